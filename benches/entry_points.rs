@@ -0,0 +1,64 @@
+//! Wall-clock benchmarks of the entry points on the mock VM
+//!
+//! These track the cost of the contract logic itself; gas is checked by
+//! `tests/gas_benchmarks.rs` on the Casper backend. Run with `cargo bench`.
+
+use casper_liquid::{CasperLiquid, CasperLiquidHostRef, CasperLiquidInitArgs};
+use criterion::{criterion_group, criterion_main, Criterion};
+use odra::host::{Deployer, HostEnv, HostRef};
+use odra::prelude::*;
+
+fn deploy(test_env: &HostEnv) -> CasperLiquidHostRef {
+    let mut contract = CasperLiquid::deploy(
+        test_env,
+        CasperLiquidInitArgs {
+            name: None,
+            symbol: None,
+            decimals: None,
+            owner: None,
+            fee_bps: None,
+            treasury: None,
+            features: None,
+        },
+    );
+    contract
+        .with_tokens(U512::from(1_000_000_000_000u64))
+        .stake(U256::from(1_000_000_000_000u64))
+        .unwrap();
+    contract
+}
+
+fn entry_points(c: &mut Criterion) {
+    let test_env = odra_test::env();
+    let mut contract = deploy(&test_env);
+    let owner = test_env.get_account(0);
+    let spender = test_env.get_account(1);
+    let recipient = test_env.get_account(2);
+    contract.approve(&spender, U256::MAX).unwrap();
+    
+    c.bench_function("stake", |b| {
+        b.iter(|| contract.with_tokens(U512::from(1_000u64)).stake(U256::from(1_000u64)).unwrap())
+    });
+    c.bench_function("unstake", |b| b.iter(|| contract.unstake(U256::from(1u64)).unwrap()));
+    c.bench_function("transfer", |b| {
+        b.iter(|| contract.transfer(&recipient, U256::from(1u64)).unwrap())
+    });
+    
+    test_env.set_caller(spender);
+    c.bench_function("transfer_from", |b| {
+        b.iter(|| contract.transfer_from(&owner, &recipient, U256::from(1u64)).unwrap())
+    });
+    
+    test_env.set_caller(owner);
+    let recipients: Vec<Address> = (2..7).map(|index| test_env.get_account(index)).collect();
+    c.bench_function("transfer_batch", |b| {
+        b.iter(|| {
+            contract
+                .transfer_batch(recipients.clone(), vec![U256::from(1u64); recipients.len()])
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, entry_points);
+criterion_main!(benches);
@@ -0,0 +1,272 @@
+//! Contract call commands
+//!
+//! Each command signs and sends one deploy, or reads one value, against the
+//! configured contract. `--json` prints a single JSON object for scripts.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+
+use casper_liquid::{incidents, CasperLiquid, CasperLiquidHostRef, Error};
+use odra::casper_types::Key;
+use odra::host::HostRefLoader;
+use odra::prelude::*;
+use serde_json::{json, Value};
+
+use crate::deploy::connect;
+
+/// Commands accepted by `call`
+pub const COMMANDS: [&str; 7] = ["stake", "unstake", "transfer", "approve", "balance-of", "allowance", "status"];
+
+/// A parsed call, checked before connecting to the node
+pub enum Call {
+    Stake(U256),
+    Unstake(U256),
+    Transfer(Address, U256),
+    Approve(Address, U256),
+    /// Balance of the address, or of the signing account
+    BalanceOf(Option<Address>),
+    Allowance(Address, Address),
+    /// Incident status, security contact and pool health
+    Status,
+}
+
+/// Settings for a call
+pub struct CallOptions {
+    pub node_address: String,
+    pub chain_name: String,
+    pub secret_key: String,
+    /// Contract package hash, formatted as `hash-...`
+    pub contract: String,
+    /// Gas limit for the deploy, in motes
+    pub gas: u64,
+    pub json: bool,
+}
+
+impl Call {
+    /// Parse `command` and its positional parameters, exiting on bad input
+    pub fn parse(command: &str, params: &[String]) -> Call {
+        match command {
+            "stake" => Call::Stake(parse_amount(param(params, 0, "AMOUNT"))),
+            "unstake" => Call::Unstake(parse_amount(param(params, 0, "AMOUNT"))),
+            "transfer" => Call::Transfer(
+                parse_address(param(params, 0, "RECIPIENT")),
+                parse_amount(param(params, 1, "AMOUNT")),
+            ),
+            "approve" => Call::Approve(
+                parse_address(param(params, 0, "SPENDER")),
+                parse_amount(param(params, 1, "AMOUNT")),
+            ),
+            "balance-of" => Call::BalanceOf(params.first().map(|raw| parse_address(raw))),
+            "allowance" => Call::Allowance(
+                parse_address(param(params, 0, "OWNER")),
+                parse_address(param(params, 1, "SPENDER")),
+            ),
+            "status" => Call::Status,
+            _ => {
+                eprintln!("❌ Error: unknown call '{}', expected one of: {}", command, COMMANDS.join(", "));
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Headroom added to a measured cost when recording it as a payment preset (20%)
+pub const GAS_REPORT_MARGIN_PERCENT: u64 = 20;
+
+/// Send the call and return its gas cost, exiting non-zero if it failed
+///
+/// The cost is what the node charged, so it is a lower bound for the payment.
+pub fn gas_report(options: CallOptions, command: &str, call: Call) -> u64 {
+    if matches!(call, Call::BalanceOf(_) | Call::Allowance(..) | Call::Status) {
+        eprintln!("❌ Error: '{}' is a read and costs no gas", command);
+        process::exit(1);
+    }
+
+    let address = parse_address(&options.contract);
+    let (host_env, key_file) = connect(&options.node_address, &options.chain_name, &options.secret_key);
+    host_env.set_gas(options.gas);
+    let caller = host_env.caller();
+    let mut contract = CasperLiquid::load(&host_env, address);
+    
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| run(&mut contract, caller, call)));
+    drop(key_file);
+    
+    match outcome {
+        Ok(Ok(_)) => host_env.last_call_gas_cost(),
+        Ok(Err(error)) => {
+            eprintln!("❌ {} failed: {:?} (User error {}) - {}", command, error, error.code(), error.describe());
+            process::exit(1);
+        }
+        Err(_) => {
+            eprintln!("❌ {} failed: the deploy did not complete", command);
+            process::exit(1);
+        }
+    }
+}
+
+/// Send the call and print the result, exiting non-zero if it failed
+pub fn call(options: CallOptions, command: &str, call: Call) {
+    let address = parse_address(&options.contract);
+    let (host_env, key_file) = connect(&options.node_address, &options.chain_name, &options.secret_key);
+    host_env.set_gas(options.gas);
+    let caller = host_env.caller();
+    let mut contract = CasperLiquid::load(&host_env, address);
+    
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| run(&mut contract, caller, call)));
+    drop(key_file);
+    
+    match outcome {
+        Ok(Ok((message, result))) => {
+            if options.json {
+                println!("{}", json!({ "status": "ok", "command": command, "result": result }));
+            } else {
+                println!("✅ {}", message);
+            }
+        }
+        Ok(Err(error)) => {
+            if options.json {
+                println!(
+                    "{}",
+                    json!({
+                        "status": "error",
+                        "command": command,
+                        "error": format!("{:?}", error),
+                        "code": error.code(),
+                        "message": error.describe(),
+                    })
+                );
+            } else {
+                eprintln!("❌ {} failed: {:?} (User error {}) - {}", command, error, error.code(), error.describe());
+            }
+            process::exit(1);
+        }
+        Err(_) => {
+            if options.json {
+                println!("{}", json!({ "status": "error", "command": command, "error": "DeployFailed" }));
+            } else {
+                eprintln!("❌ {} failed: the deploy or query did not complete", command);
+            }
+            process::exit(1);
+        }
+    }
+}
+
+// Execute the call, returning a message for humans and the result for --json
+fn run(contract: &mut CasperLiquidHostRef, caller: Address, call: Call) -> Result<(String, Value), Error> {
+    match call {
+        Call::Stake(amount) => {
+            let motes = U512::from_dec_str(&amount.to_string()).unwrap_or_default();
+            contract.with_tokens(motes).stake(amount)?;
+            Ok((format!("Staked {} motes", amount), json!({ "amount": amount.to_string() })))
+        }
+        Call::Unstake(amount) => {
+            contract.unstake(amount)?;
+            Ok((format!("Unstaked {} stCSPR", amount), json!({ "amount": amount.to_string() })))
+        }
+        Call::Transfer(recipient, amount) => {
+            contract.transfer(&recipient, amount)?;
+            Ok((
+                format!("Transferred {} stCSPR to {}", amount, formatted(&recipient)),
+                json!({ "recipient": formatted(&recipient), "amount": amount.to_string() }),
+            ))
+        }
+        Call::Approve(spender, amount) => {
+            contract.approve(&spender, amount)?;
+            Ok((
+                format!("Approved {} to spend {} stCSPR", formatted(&spender), amount),
+                json!({ "spender": formatted(&spender), "amount": amount.to_string() }),
+            ))
+        }
+        Call::BalanceOf(address) => {
+            let address = address.unwrap_or(caller);
+            let balance = contract.balance_of(&address);
+            Ok((
+                format!("Balance of {}: {} stCSPR", formatted(&address), balance),
+                json!({ "address": formatted(&address), "balance": balance.to_string() }),
+            ))
+        }
+        Call::Allowance(owner, spender) => {
+            let allowance = contract.allowance(&owner, &spender);
+            Ok((
+                format!("Allowance of {} for {}: {} stCSPR", formatted(&owner), formatted(&spender), allowance),
+                json!({
+                    "owner": formatted(&owner),
+                    "spender": formatted(&spender),
+                    "allowance": allowance.to_string(),
+                }),
+            ))
+        }
+        Call::Status => {
+            let health = contract.health_check();
+            let incident = match health.incident_code {
+                incidents::INCIDENT_NONE => "none",
+                incidents::INCIDENT_INVESTIGATING => "investigating",
+                incidents::INCIDENT_DEGRADED => "degraded",
+                incidents::INCIDENT_HALTED => "halted",
+                incidents::INCIDENT_RESOLVED => "resolved",
+                _ => "unknown",
+            };
+            let mut message = format!(
+                "Incident: {} ({}){}\n   Paused: {}\n   Exchange rate: {}\n   Withdrawal shortfall: {} motes\n   Version: {}",
+                incident,
+                health.incident_code,
+                if health.incident_message_uri.is_empty() {
+                    String::new()
+                } else {
+                    format!(" - {}", health.incident_message_uri)
+                },
+                health.paused,
+                health.exchange_rate,
+                health.withdrawal_shortfall,
+                health.version
+            );
+            if !health.security_contact.is_empty() {
+                message.push_str(&format!("\n   Security contact: {}", health.security_contact));
+            }
+            Ok((
+                message,
+                json!({
+                    "paused": health.paused,
+                    "incident_code": health.incident_code,
+                    "incident": incident,
+                    "incident_message_uri": health.incident_message_uri,
+                    "incident_updated_at": health.incident_updated_at,
+                    "security_contact": health.security_contact,
+                    "exchange_rate": health.exchange_rate.to_string(),
+                    "withdrawal_shortfall": health.withdrawal_shortfall.to_string(),
+                    "version": health.version,
+                }),
+            ))
+        }
+    }
+}
+
+fn param<'a>(params: &'a [String], index: usize, name: &str) -> &'a str {
+    params.get(index).map(String::as_str).unwrap_or_else(|| {
+        eprintln!("❌ Error: missing <{}>", name);
+        process::exit(1);
+    })
+}
+
+fn parse_amount(raw: &str) -> U256 {
+    U256::from_dec_str(raw).unwrap_or_else(|_| {
+        eprintln!("❌ Error: '{}' is not a whole number of motes", raw);
+        process::exit(1);
+    })
+}
+
+// Accepts account-hash-... for accounts and hash-... for contracts
+fn parse_address(raw: &str) -> Address {
+    raw.parse().unwrap_or_else(|_| {
+        eprintln!("❌ Error: '{}' is not an address (expected account-hash-... or hash-...)", raw);
+        process::exit(1);
+    })
+}
+
+// Format an address the way parse_address accepts it
+fn formatted(address: &Address) -> String {
+    match address {
+        Address::Account(hash) => hash.to_formatted_string(),
+        Address::Contract(hash) => Key::Hash(hash.value()).to_formatted_string(),
+    }
+}
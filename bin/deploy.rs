@@ -0,0 +1,198 @@
+//! Contract installation through the Odra livenet backend
+//!
+//! `deploy` builds the wasm with cargo-odra, then the backend signs and submits
+//! the install deploy and polls the node until it has an execution result.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use casper_liquid::{CasperLiquid, CasperLiquidInitArgs};
+use odra::casper_types::Key;
+use odra::host::{Deployer, HostEnv, HostRef};
+
+/// Default gas payment for installing the contract, in motes (450 CSPR)
+const DEFAULT_PAYMENT_AMOUNT: u64 = 450_000_000_000;
+
+/// Where `cargo odra build` writes the contract wasm, and where the livenet
+/// backend loads it from
+const DEFAULT_WASM_PATH: &str = "wasm/CasperLiquid.wasm";
+
+/// Settings for the install deploy
+pub struct DeployOptions {
+    pub node_address: String,
+    pub chain_name: String,
+    /// SECRET_KEY from .env: the PEM contents or a path to the PEM file
+    pub secret_key: String,
+    pub payment_amount: u64,
+    /// Prebuilt wasm to install instead of running `cargo odra build`
+    pub wasm_path: Option<PathBuf>,
+    /// `FEATURE_*` bits to initialize the contract with (all of them when unset)
+    pub features: Option<u32>,
+}
+
+/// Get the `--payment-amount` flag in motes, or the profile's preset, or the default
+pub fn payment_amount(args: &[String], preset: Option<u64>) -> u64 {
+    match crate::flag_value(args, "--payment-amount") {
+        Some(raw) => raw.parse().unwrap_or_else(|_| {
+            eprintln!("❌ Error: --payment-amount must be a whole number of motes, got '{}'", raw);
+            process::exit(1);
+        }),
+        None => preset.unwrap_or(DEFAULT_PAYMENT_AMOUNT),
+    }
+}
+
+/// Build and install the contract, then print its package hash
+pub fn deploy_contract(options: DeployOptions) {
+    let wasm_path = build_wasm(options.wasm_path.as_deref());
+    stage_wasm(&wasm_path);
+    let (host_env, key_file) = connect(&options.node_address, &options.chain_name, &options.secret_key);
+    
+    println!("🚀 Installing {} on {}...", wasm_path.display(), options.chain_name);
+    println!("   Payment: {} motes", options.payment_amount);
+    if let Some(features) = options.features {
+        println!("   Features: {:#05b}", features);
+    }
+    println!("   This may take a few minutes...");
+    
+    host_env.set_gas(options.payment_amount);
+    let deployed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        CasperLiquid::deploy(
+            &host_env,
+            CasperLiquidInitArgs {
+                name: None,
+                symbol: None,
+                decimals: None,
+                owner: None,
+                fee_bps: None,
+                treasury: None,
+                features: options.features,
+            },
+        )
+    }));
+    
+    drop(key_file);
+    
+    let contract = deployed.unwrap_or_else(|_| {
+        eprintln!("❌ Error: the install deploy failed. Check the execution result above.");
+        process::exit(1);
+    });
+    
+    // Formatted as a hash key so it can be passed back to the other commands
+    let package_hash = contract
+        .address()
+        .as_contract_package_hash()
+        .map(|hash| Key::Hash(hash.value()).to_formatted_string())
+        .unwrap_or_else(|| format!("{:?}", contract.address()));
+    
+    println!();
+    println!("✅ Contract deployed successfully");
+    println!("📋 Contract Package Hash: {}", package_hash);
+    println!("🌐 Network: {}", options.chain_name);
+    println!();
+    println!("📝 Save the package hash for frontend integration");
+}
+
+// Build the wasm with cargo-odra, or check the prebuilt one passed with --wasm-path
+fn build_wasm(wasm_path: Option<&Path>) -> PathBuf {
+    if let Some(path) = wasm_path {
+        if !path.is_file() {
+            eprintln!("❌ Error: wasm file {} not found", path.display());
+            process::exit(1);
+        }
+        println!("📦 Using prebuilt wasm {}", path.display());
+        return path.to_path_buf();
+    }
+    
+    println!("📦 Building contract wasm...");
+    let status = process::Command::new("cargo")
+        .args(["odra", "build", "-c", "casper_liquid"])
+        .status()
+        .unwrap_or_else(|e| {
+            eprintln!("❌ Error: could not run cargo odra build: {}", e);
+            process::exit(1);
+        });
+    if !status.success() {
+        eprintln!("❌ Error: contract build failed");
+        process::exit(1);
+    }
+    
+    println!("✅ Contract built successfully");
+    PathBuf::from(DEFAULT_WASM_PATH)
+}
+
+// The livenet backend always installs wasm/CasperLiquid.wasm, so copy a
+// --wasm-path build there first
+fn stage_wasm(wasm_path: &Path) {
+    let target = Path::new(DEFAULT_WASM_PATH);
+    if wasm_path == target {
+        return;
+    }
+    
+    let copied = target
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::copy(wasm_path, target));
+    if let Err(e) = copied {
+        eprintln!("❌ Error: could not copy {} to {}: {}", wasm_path.display(), DEFAULT_WASM_PATH, e);
+        process::exit(1);
+    }
+}
+
+/// Path to the secret key PEM used to sign deploys
+///
+/// When SECRET_KEY holds the key itself it is written to a private temporary
+/// file, which is deleted when this is dropped.
+pub struct SecretKeyFile {
+    path: PathBuf,
+    temporary: bool,
+}
+
+impl Drop for SecretKeyFile {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Get a livenet host env that signs with `secret_key` and sends deploys to `node_address`
+///
+/// Keep the returned key file alive for as long as the env is used.
+pub fn connect(node_address: &str, chain_name: &str, secret_key: &str) -> (HostEnv, SecretKeyFile) {
+    let key_file = secret_key_file(secret_key);
+    
+    // The livenet backend signs, submits and polls deploys using these settings
+    env::set_var("ODRA_CASPER_LIVENET_NODE_ADDRESS", node_address);
+    env::set_var("ODRA_CASPER_LIVENET_CHAIN_NAME", chain_name);
+    env::set_var("ODRA_CASPER_LIVENET_SECRET_KEY_PATH", &key_file.path);
+    
+    (odra_casper_livenet_env::env(), key_file)
+}
+
+fn secret_key_file(secret_key: &str) -> SecretKeyFile {
+    if Path::new(secret_key).is_file() {
+        return SecretKeyFile {
+            path: PathBuf::from(secret_key),
+            temporary: false,
+        };
+    }
+    
+    // .env files usually keep the PEM on one line with escaped newlines
+    let pem = secret_key.replace("\\n", "\n");
+    let path = env::temp_dir().join(format!("casper-liquid-deploy-{}.pem", process::id()));
+    
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    
+    let written = options
+        .open(&path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, pem.as_bytes()));
+    if let Err(e) = written {
+        eprintln!("❌ Error: could not write the secret key to {}: {}", path.display(), e);
+        process::exit(1);
+    }
+    SecretKeyFile { path, temporary: true }
+}
@@ -0,0 +1,219 @@
+//! Mock API responses for frontend development
+//!
+//! Deploys the contract to the mock VM, runs a scripted scenario (stakes from
+//! a number of users, one rewards report per epoch, withdrawal requests and
+//! claims along the way) and writes the resulting views as JSON files. The
+//! frontend can serve the files in place of RPC responses, so it can be
+//! developed against a realistic pool without a node.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use casper_liquid::{CasperLiquid, CasperLiquidHostRef, CasperLiquidInitArgs};
+use odra::casper_types::Key;
+use odra::host::{Deployer, HostEnv, HostRef};
+use odra::prelude::*;
+use serde_json::{json, Value};
+
+/// Users staking in the scenario unless --users is passed
+pub const DEFAULT_USERS: u64 = 5;
+
+/// Epochs of rewards in the scenario unless --epochs is passed
+pub const DEFAULT_EPOCHS: u64 = 4;
+
+/// Directory the files are written to unless --out is passed
+pub const DEFAULT_OUT: &str = "fixtures";
+
+/// Most users a scenario can have; the mock VM's other accounts are the owner and treasury
+pub const MAX_USERS: u64 = 15;
+
+/// Block time between two rewards reports (1 day, in milliseconds)
+const EPOCH_MILLIS: u64 = 86_400_000;
+
+/// Stake of the first user (100 CSPR); user `i` stakes `i + 1` times as much
+const BASE_STAKE: u64 = 100_000_000_000;
+
+/// Rewards reported each epoch, in basis points of the pooled CSPR
+const EPOCH_REWARD_BPS: u64 = 10;
+
+/// Share of a user's stCSPR put into a withdrawal request, in basis points
+const REQUEST_BPS: u64 = 1_000;
+
+/// Settings for a fixtures run
+pub struct FixturesOptions {
+    pub users: u64,
+    pub epochs: u64,
+    pub out: PathBuf,
+}
+
+/// Run the scenario and write the views, exiting non-zero if a call fails
+pub fn fixtures(options: FixturesOptions) {
+    if options.users == 0 || options.users > MAX_USERS {
+        eprintln!("❌ Error: --users must be between 1 and {}", MAX_USERS);
+        process::exit(1);
+    }
+
+    let test_env = odra_test::env();
+    let owner = test_env.get_account(0);
+    let treasury = test_env.get_account(MAX_USERS as usize + 1);
+    let users: Vec<Address> = (1..=options.users).map(|index| test_env.get_account(index as usize)).collect();
+
+    println!("🧪 Deploying to the mock VM...");
+    let mut contract = CasperLiquid::deploy(
+        &test_env,
+        CasperLiquidInitArgs {
+            name: None,
+            symbol: None,
+            decimals: None,
+            owner: None,
+            fee_bps: None,
+            treasury: Some(treasury),
+            features: None,
+        },
+    );
+    check("add_validator", contract.add_validator(test_env.public_key(&treasury)));
+
+    println!("💰 Staking from {} users...", users.len());
+    for (index, user) in users.iter().enumerate() {
+        let amount = BASE_STAKE * (index as u64 + 1);
+        test_env.set_caller(*user);
+        check("stake", contract.with_tokens(U512::from(amount)).stake(U256::from(amount)));
+    }
+
+    // Every epoch half the users request a withdrawal, alternating, and earlier
+    // requests that have unbonded are claimed
+    let mut requests = Vec::new();
+    for epoch in 1..=options.epochs {
+        println!("📈 Epoch {}...", epoch);
+        test_env.advance_block_time(EPOCH_MILLIS);
+        test_env.set_caller(owner);
+        let rewards = contract.total_pooled_cspr() * U256::from(EPOCH_REWARD_BPS) / U256::from(10_000u64);
+        check("report_rewards", contract.report_rewards(rewards));
+
+        claim_unbonded(&test_env, &mut contract, &requests);
+        for (index, user) in users.iter().enumerate() {
+            if (index as u64 + epoch) % 2 == 0 {
+                test_env.set_caller(*user);
+                let amount = contract.balance_of(user) * U256::from(REQUEST_BPS) / U256::from(10_000u64);
+                requests.push(check("request_unstake", contract.request_unstake(amount)));
+            }
+        }
+    }
+
+    if let Err(e) = fs::create_dir_all(&options.out) {
+        eprintln!("❌ Error: could not create {}: {}", options.out.display(), e);
+        process::exit(1);
+    }
+
+    let stats = contract.get_protocol_stats();
+    write(&options.out, "protocol_stats.json", json!({
+        "total_pooled_cspr": stats.total_pooled_cspr.to_string(),
+        "total_shares": stats.total_shares.to_string(),
+        "exchange_rate": stats.exchange_rate.to_string(),
+        "current_apr_estimate": stats.current_apr_estimate.to_string(),
+        "validator_count": stats.validator_count,
+        "pending_withdrawals": stats.pending_withdrawals.to_string(),
+    }));
+
+    let obligations = contract.get_obligations();
+    write(&options.out, "obligations.json", json!({
+        "pending_withdrawals": obligations.pending_withdrawals.to_string(),
+        "unbonding": obligations.unbonding.iter()
+            .map(|(era, amount)| json!({ "era": era, "amount": amount.to_string() }))
+            .collect::<Vec<_>>(),
+        "total_unbonding": obligations.total_unbonding.to_string(),
+        "buffered": obligations.buffered.to_string(),
+        "shortfall": obligations.shortfall.to_string(),
+        "surplus": obligations.surplus.to_string(),
+    }));
+
+    let health = contract.health_check();
+    write(&options.out, "health_check.json", json!({
+        "paused": health.paused,
+        "incident_code": health.incident_code,
+        "incident_message_uri": health.incident_message_uri,
+        "incident_updated_at": health.incident_updated_at,
+        "security_contact": health.security_contact,
+        "exchange_rate": health.exchange_rate.to_string(),
+        "withdrawal_shortfall": health.withdrawal_shortfall.to_string(),
+        "version": health.version,
+    }));
+
+    let reports: Vec<Value> = (1..=options.epochs)
+        .filter_map(|epoch| contract.get_reward_report(epoch))
+        .map(|report| json!({
+            "epoch": report.epoch,
+            "amount": report.amount.to_string(),
+            "fee": report.fee.to_string(),
+            "exchange_rate": report.exchange_rate.to_string(),
+            "timestamp": report.timestamp,
+        }))
+        .collect();
+    write(&options.out, "reward_reports.json", Value::Array(reports));
+
+    let withdrawals: Vec<Value> = requests.iter()
+        .filter_map(|id| contract.get_withdrawal(*id))
+        .map(|request| json!({
+            "id": request.id,
+            "owner": formatted(&request.owner),
+            "amount": request.amount.to_string(),
+            "claimable_at": request.claimable_at,
+            "claimed": request.claimed,
+        }))
+        .collect();
+    write(&options.out, "withdrawals.json", Value::Array(withdrawals));
+
+    let accounts: Vec<Value> = users.iter().chain([&treasury])
+        .map(|address| {
+            let stats = contract.get_user_stats(address);
+            json!({
+                "address": formatted(address),
+                "balance": contract.balance_of(address).to_string(),
+                "total_ever_staked": stats.total_ever_staked.to_string(),
+                "total_ever_unstaked": stats.total_ever_unstaked.to_string(),
+                "first_stake_timestamp": stats.first_stake_timestamp,
+                "last_action_timestamp": stats.last_action_timestamp,
+            })
+        })
+        .collect();
+    write(&options.out, "accounts.json", Value::Array(accounts));
+
+    println!("✅ Wrote the fixtures to {}", options.out.display());
+}
+
+// Claim every request that has unbonded, as its owner
+fn claim_unbonded(test_env: &HostEnv, contract: &mut CasperLiquidHostRef, requests: &[u64]) {
+    let now = test_env.block_time();
+    for request in requests.iter().filter_map(|id| contract.get_withdrawal(*id)) {
+        if !request.claimed && request.claimable_at <= now {
+            test_env.set_caller(request.owner);
+            check("claim_withdrawal", contract.claim_withdrawal(request.id));
+        }
+    }
+}
+
+fn check<T, E: std::fmt::Debug>(call: &str, result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("❌ Error: {} failed in the scenario: {:?}", call, e);
+        process::exit(1);
+    })
+}
+
+fn write(out: &Path, name: &str, value: Value) {
+    let path = out.join(name);
+    let contents = serde_json::to_string_pretty(&value).expect("JSON values always serialize");
+    if let Err(e) = fs::write(&path, contents + "\n") {
+        eprintln!("❌ Error: could not write {}: {}", path.display(), e);
+        process::exit(1);
+    }
+    println!("   {}", path.display());
+}
+
+// Format an address the way the CLI accepts it
+fn formatted(address: &Address) -> String {
+    match address {
+        Address::Account(hash) => hash.to_formatted_string(),
+        Address::Contract(hash) => Key::Hash(hash.value()).to_formatted_string(),
+    }
+}
@@ -0,0 +1,159 @@
+use std::env;
+use std::process;
+
+use casper_liquid::export::{self, Format};
+use casper_liquid::indexer::{self, JsonlSink, Sink};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.iter().any(|arg| arg == "help" || arg == "--help" || arg == "-h") {
+        print_help();
+        return;
+    }
+    
+    // Statements are built from a local log and need no node
+    if args.get(1).map(|s| s.as_str()) == Some("export") {
+        export_statement(&args);
+        return;
+    }
+    
+    let node_address = flag_value(&args, "--node-address").unwrap_or_else(|| {
+        eprintln!("❌ Error: --node-address is required");
+        process::exit(1);
+    });
+    let contract = flag_value(&args, "--contract").unwrap_or_else(|| {
+        eprintln!("❌ Error: --contract <PACKAGE_HASH> is required");
+        process::exit(1);
+    });
+    
+    let mut sink = open_sink(&args);
+    
+    let events_dictionary = indexer::find_events_dictionary(&node_address, &contract).unwrap_or_else(|e| {
+        eprintln!("❌ Error: could not find the events of {}: {}", contract, e);
+        process::exit(1);
+    });
+    
+    if args.get(1).map(|s| s.as_str()) == Some("backfill") {
+        let from_block = height_flag(&args, "--from-block").unwrap_or_else(|| {
+            eprintln!("❌ Error: --from-block <HEIGHT> is required");
+            process::exit(1);
+        });
+        let to_block = height_flag(&args, "--to-block");
+        
+        eprintln!("⏪ Backfilling events of {} from block {}", contract, from_block);
+        match indexer::backfill(&node_address, from_block, to_block, events_dictionary, sink.as_mut()) {
+            Ok(height) => eprintln!("✅ Backfilled up to block {}", height),
+            Err(e) => {
+                eprintln!("❌ Error: {}", e);
+                eprintln!("Re-run the same command to resume from the last completed block");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    let events_url = flag_value(&args, "--events-url").unwrap_or_else(|| {
+        eprintln!("❌ Error: --events-url is required, e.g. http://localhost:9999/events/main");
+        process::exit(1);
+    });
+    
+    eprintln!("📡 Indexing events of {} from {}", contract, events_url);
+    if let Err(e) = indexer::run(&events_url, events_dictionary, sink.as_mut()) {
+        eprintln!("❌ Error: {}", e);
+        process::exit(1);
+    }
+    eprintln!("ℹ️  Event stream closed");
+}
+
+// Print the statement of --address from the --jsonl log in --format csv or json
+fn export_statement(args: &[String]) {
+    let address = flag_value(args, "--address").unwrap_or_else(|| {
+        eprintln!("❌ Error: --address <account-hash-...> is required");
+        process::exit(1);
+    });
+    let format = flag_value(args, "--format").unwrap_or_else(|| "csv".to_string());
+    let Some(format) = Format::parse(&format) else {
+        eprintln!("❌ Error: --format must be csv or json, got '{}'", format);
+        process::exit(1);
+    };
+    let path = flag_value(args, "--jsonl").unwrap_or_else(|| "events.jsonl".to_string());
+    
+    let events = indexer::read_jsonl(&path).unwrap_or_else(|e| {
+        eprintln!("❌ Error: could not read {}: {}", path, e);
+        process::exit(1);
+    });
+    let entries = export::statement(&events, &address);
+    print!("{}", export::render(&entries, format));
+    if format == Format::Json {
+        println!();
+    }
+}
+
+// Open the --sqlite (or --to) database or the --jsonl file (default: events.jsonl)
+fn open_sink(args: &[String]) -> Box<dyn Sink> {
+    let database = flag_value(args, "--sqlite").or_else(|| flag_value(args, "--to"));
+    let opened: Result<Box<dyn Sink>, indexer::IndexerError> = match database {
+        #[cfg(feature = "indexer-sqlite")]
+        Some(path) => indexer::SqliteSink::open(&path).map(|sink| Box::new(sink) as Box<dyn Sink>),
+        #[cfg(not(feature = "indexer-sqlite"))]
+        Some(_) => {
+            eprintln!("❌ Error: --sqlite needs the indexer-sqlite feature");
+            process::exit(1);
+        }
+        None => {
+            let path = flag_value(args, "--jsonl").unwrap_or_else(|| "events.jsonl".to_string());
+            JsonlSink::open(&path).map(|sink| Box::new(sink) as Box<dyn Sink>)
+        }
+    };
+    
+    opened.unwrap_or_else(|e| {
+        eprintln!("❌ Error: {}", e);
+        process::exit(1);
+    })
+}
+
+// Get a block height flag, exiting if it is not a number
+fn height_flag(args: &[String], flag: &str) -> Option<u64> {
+    flag_value(args, flag).map(|raw| {
+        raw.parse().unwrap_or_else(|_| {
+            eprintln!("❌ Error: {} must be a block height, got '{}'", flag, raw);
+            process::exit(1);
+        })
+    })
+}
+
+// Get the value following `flag` on the command line
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+}
+
+fn print_help() {
+    println!("CasperLiquid Event Indexer");
+    println!();
+    println!("Writes the contract's stake, unstake, transfer and allowance events as they are emitted.");
+    println!();
+    println!("USAGE:");
+    println!("    cargo run --features indexer --bin casper-liquid-indexer -- [OPTIONS]");
+    println!("    cargo run --features indexer --bin casper-liquid-indexer -- backfill --from-block <HEIGHT> [OPTIONS]");
+    println!("    cargo run --features indexer --bin casper-liquid-indexer -- export --address <ADDRESS> [--format csv|json] [--jsonl <PATH>]");
+    println!();
+    println!("OPTIONS:");
+    println!("    --node-address <URL>       Node RPC address, e.g. http://localhost:11101");
+    println!("    --events-url <URL>         Node SSE stream, e.g. http://localhost:18101/events/main");
+    println!("    --contract <PACKAGE_HASH>  Contract package hash (hash-...)");
+    println!("    --jsonl <PATH>             Append events as JSON lines (default: events.jsonl)");
+    println!("    --sqlite <PATH>            Store events in SQLite instead (needs --features indexer-sqlite)");
+    println!();
+    println!("BACKFILL OPTIONS:");
+    println!("    --from-block <HEIGHT>      First block to read; a resumed backfill starts after its checkpoint");
+    println!("    --to-block <HEIGHT>        Last block to read (default: the latest block)");
+    println!("    --to <PATH>                Same as --sqlite");
+    println!();
+    println!("EXPORT OPTIONS:");
+    println!("    --address <ADDRESS>        Account to build the statement for (account-hash-...)");
+    println!("    --format <FORMAT>          csv (default) or json");
+    println!("    --jsonl <PATH>             Event log to read (default: events.jsonl)");
+}
@@ -1,31 +1,116 @@
 use std::env;
 use std::process;
 
+#[cfg(feature = "livenet")]
+mod call;
+#[cfg(feature = "livenet")]
+mod deploy;
+#[cfg(feature = "fixtures")]
+mod fixtures;
+#[cfg(feature = "livenet")]
+mod migrate;
+mod profiles;
+#[cfg(feature = "livenet")]
+mod smoke_test;
+#[cfg(feature = "livenet")]
+mod soak;
+
+use profiles::Profile;
+
+/// Flags that take a value, so the value is not read as a positional argument
+const VALUE_FLAGS: [&str; 21] = [
+    "--profile",
+    "-p",
+    "--chain-name",
+    "--payment-amount",
+    "--wasm-path",
+    "--test-key",
+    "--contract",
+    "--gas",
+    "--old-schema",
+    "--new-schema",
+    "--address",
+    "--events-url",
+    "--log",
+    "--users",
+    "--epochs",
+    "--out",
+    "--feature-bits",
+    "--duration",
+    "--seed",
+    "--extra-keys",
+    "--report",
+];
+
+/// Default gas limit for a call, in motes
+const DEFAULT_CALL_GAS: u64 = 10_000_000_000;
+
 fn main() {
-    println!("🚀 CasperLiquid Deployment Script");
-    println!("==================================");
+    // Parse command line arguments
+    let args: Vec<String> = env::args().collect();
     
-    // Check if .env file exists
-    if !std::path::Path::new(".env").exists() {
-        eprintln!("❌ Error: .env file not found!");
-        eprintln!("Please copy .env.example to .env and configure your SECRET_KEY");
-        process::exit(1);
+    // With --json, stdout carries only the JSON result
+    let json = args.iter().any(|arg| arg == "--json");
+    if !json {
+        println!("🚀 CasperLiquid Deployment Script");
+        println!("==================================");
     }
     
-    // Load environment variables
-    match dotenv::dotenv() {
-        Ok(_) => println!("✅ Environment variables loaded from .env"),
-        Err(e) => {
-            eprintln!("❌ Error loading .env file: {}", e);
-            process::exit(1);
+    // Commands that work offline and don't need a configured account
+    if args.get(1).map(|s| s.as_str()) == Some("decode-error") {
+        decode_error(args.get(2).map(|s| s.as_str()));
+        return;
+    }
+    if args.get(1).map(|s| s.as_str()) == Some("fixtures") {
+        fixtures_command(&args);
+        return;
+    }
+    
+    // .env is optional; it can hold SECRET_KEY and the default PROFILE
+    if std::path::Path::new(".env").exists() {
+        match dotenv::dotenv() {
+            Ok(_) if json => {}
+            Ok(_) => println!("✅ Environment variables loaded from .env"),
+            Err(e) => {
+                eprintln!("❌ Error loading .env file: {}", e);
+                process::exit(1);
+            }
         }
     }
     
-    // Validate required environment variables
-    let secret_key = env::var("SECRET_KEY").unwrap_or_else(|_| {
-        eprintln!("❌ Error: SECRET_KEY not found in .env file");
+    let profile = profiles::load(&profile_name(&args));
+    match args.get(1).map(|s| s.as_str()) {
+        Some("config") => {
+            config_command(&args, profile);
+            return;
+        }
+        Some("address-book") => {
+            address_book_command(&args, profile);
+            return;
+        }
+        _ => {}
+    }
+    
+    if profile.node_address.is_empty() {
+        eprintln!("❌ Error: profile '{}' has no node_address", profile.name);
+        eprintln!("Set one with: cargo run -- config set node_address <URL> -p {}", profile.name);
         process::exit(1);
-    });
+    }
+    
+    // Watching only reads the event stream and needs no signing key
+    if args.get(1).map(|s| s.as_str()) == Some("watch") {
+        watch_command(&args, &profile);
+        return;
+    }
+    
+    // The profile's key wins over SECRET_KEY so a profile never signs with another network's key
+    let secret_key = profile.key_path.clone()
+        .or_else(|| env::var("SECRET_KEY").ok())
+        .unwrap_or_else(|| {
+            eprintln!("❌ Error: no signing key for profile '{}'", profile.name);
+            eprintln!("Set key_path with 'cargo run -- config set key_path <PEM> -p {}' or SECRET_KEY in .env", profile.name);
+            process::exit(1);
+        });
     
     if secret_key == "your_secret_key_here" {
         eprintln!("❌ Error: Please set a valid SECRET_KEY in your .env file");
@@ -33,28 +118,185 @@ fn main() {
         process::exit(1);
     }
     
-    let node_address = env::var("NODE_ADDRESS")
-        .unwrap_or_else(|_| "http://3.143.158.19:7777".to_string());
-    let network_name = env::var("NETWORK_NAME")
-        .unwrap_or_else(|_| "casper-test".to_string());
+    let node_address = profile.node_address.clone();
+    let chain_name = flag_value(&args, "--chain-name")
+        .unwrap_or_else(|| profile.chain_name.clone());
     
-    println!("📋 Deployment Configuration:");
-    println!("   Node Address: {}", node_address);
-    println!("   Network: {}", network_name);
-    println!("   Contract: CasperLiquid");
-    println!();
-    
-    // Parse command line arguments
-    let args: Vec<String> = env::args().collect();
+    if !json {
+        println!("📋 Deployment Configuration:");
+        println!("   Profile: {}", profile.name);
+        println!("   Node Address: {}", node_address);
+        println!("   Chain Name: {}", chain_name);
+        println!("   Contract: CasperLiquid");
+        println!();
+    }
     
     match args.get(1).map(|s| s.as_str()) {
         Some("deploy") => {
+            confirm_mainnet(&profile, &args);
             println!("🔨 Starting contract deployment...");
-            deploy_contract();
+            #[cfg(feature = "livenet")]
+            deploy::deploy_contract(deploy::DeployOptions {
+                node_address,
+                chain_name,
+                secret_key,
+                payment_amount: deploy::payment_amount(&args, profile.payment("deploy")),
+                wasm_path: flag_value(&args, "--wasm-path").map(std::path::PathBuf::from),
+                features: flag_value(&args, "--feature-bits").map(|raw| {
+                    raw.parse().unwrap_or_else(|_| {
+                        eprintln!("❌ Error: --feature-bits must be a whole number, got '{}'", raw);
+                        process::exit(1);
+                    })
+                }),
+            });
+            #[cfg(not(feature = "livenet"))]
+            {
+                let _ = secret_key;
+                eprintln!("❌ Error: deploying needs the livenet backend");
+                eprintln!("Run 'cargo run --features livenet -- deploy' instead");
+                process::exit(1);
+            }
+        }
+        Some("smoke-test") => {
+            let contract = positional_args(&args[2..]).pop().map(|raw| profile.resolve(&raw));
+            let Some(contract) = contract.or(profile.contract_hash.clone()) else {
+                eprintln!("❌ Error: expected a contract hash, e.g. 'cargo run --features livenet -- smoke-test hash-...'");
+                process::exit(1);
+            };
+            if args.iter().any(|arg| arg == "--round-trip") {
+                confirm_mainnet(&profile, &args);
+            }
+            println!("🧪 Smoke testing {}...", contract);
+            #[cfg(feature = "livenet")]
+            smoke_test::smoke_test(smoke_test::SmokeTestOptions {
+                node_address,
+                chain_name,
+                secret_key: flag_value(&args, "--test-key").unwrap_or(secret_key),
+                contract,
+                round_trip: args.iter().any(|arg| arg == "--round-trip"),
+            });
+            #[cfg(not(feature = "livenet"))]
+            {
+                let _ = (contract, secret_key);
+                eprintln!("❌ Error: the smoke test needs the livenet backend");
+                eprintln!("Run 'cargo run --features livenet -- smoke-test <CONTRACT_HASH>' instead");
+                process::exit(1);
+            }
+        }
+        Some("soak") => {
+            if profile.is_mainnet() {
+                eprintln!("❌ Error: the soak test spends CSPR for hours; run it against a local network or testnet");
+                process::exit(1);
+            }
+            let Some(contract) = contract_flag(&args, &profile).or(profile.contract_hash.clone()) else {
+                eprintln!("❌ Error: set contract_hash with 'cargo run -- config set contract_hash <HASH> -p {}' or pass --contract", profile.name);
+                process::exit(1);
+            };
+            let number = |flag: &str| {
+                flag_value(&args, flag).map(|raw| {
+                    raw.parse::<u64>().unwrap_or_else(|_| {
+                        eprintln!("❌ Error: {} must be a whole number, got '{}'", flag, raw);
+                        process::exit(1);
+                    })
+                })
+            };
+            let (minutes, seed) = (number("--duration"), number("--seed"));
+            let extra_keys: Vec<String> = flag_value(&args, "--extra-keys")
+                .map(|keys| keys.split(',').map(str::to_string).collect())
+                .unwrap_or_default();
+            #[cfg(feature = "livenet")]
+            soak::soak(soak::SoakOptions {
+                node_address,
+                chain_name,
+                secret_key,
+                extra_keys,
+                contract,
+                duration: std::time::Duration::from_secs(60 * minutes.unwrap_or(soak::DEFAULT_DURATION_MINUTES)),
+                seed,
+                report: flag_value(&args, "--report"),
+            });
+            #[cfg(not(feature = "livenet"))]
+            {
+                let _ = (contract, secret_key, minutes, seed, extra_keys);
+                eprintln!("❌ Error: the soak test needs the livenet backend");
+                eprintln!("Run 'cargo run --features livenet -- soak -p local' instead");
+                process::exit(1);
+            }
+        }
+        Some(name @ ("call" | "gas-report")) => {
+            let gas_report = name == "gas-report";
+            let positional = positional_args(&args[2..]);
+            let Some((command, params)) = positional.split_first() else {
+                eprintln!("❌ Error: expected a call, e.g. 'cargo run --features livenet -- {} stake 1000000000'", name);
+                process::exit(1);
+            };
+            let Some(contract) = contract_flag(&args, &profile).or(profile.contract_hash.clone()) else {
+                eprintln!("❌ Error: set contract_hash with 'cargo run -- config set contract_hash <HASH> -p {}' or pass --contract", profile.name);
+                process::exit(1);
+            };
+            if !matches!(command.as_str(), "balance-of" | "allowance" | "status") {
+                confirm_mainnet(&profile, &args);
+            }
+            let gas = gas_flag(&args)
+                .or_else(|| profile.payment(command))
+                .unwrap_or(DEFAULT_CALL_GAS);
+            // Labels from the address book stand in for addresses; amounts never match one
+            let params: Vec<String> = params.iter().map(|param| profile.resolve(param)).collect();
+            #[cfg(feature = "livenet")]
+            {
+                let parsed = call::Call::parse(command, &params);
+                let options = call::CallOptions {
+                    node_address,
+                    chain_name,
+                    secret_key,
+                    contract,
+                    gas,
+                    json,
+                };
+                if gas_report {
+                    record_payment(&profile, command, call::gas_report(options, command, parsed));
+                } else {
+                    call::call(options, command, parsed);
+                }
+            }
+            #[cfg(not(feature = "livenet"))]
+            {
+                let _ = (command, params, contract, gas, secret_key, gas_report);
+                eprintln!("❌ Error: contract calls need the livenet backend");
+                eprintln!("Run 'cargo run --features livenet -- call ...' instead");
+                process::exit(1);
+            }
+        }
+        Some("migrate") => {
+            let review = args.iter().any(|arg| arg == "--review");
+            if !review {
+                confirm_mainnet(&profile, &args);
+            }
+            #[cfg(feature = "livenet")]
+            migrate::migrate(migrate::MigrateOptions {
+                node_address,
+                chain_name,
+                secret_key,
+                contract: contract_flag(&args, &profile).or(profile.contract_hash.clone()),
+                old_schema: flag_value(&args, "--old-schema"),
+                new_schema: flag_value(&args, "--new-schema")
+                    .unwrap_or_else(|| migrate::DEFAULT_NEW_SCHEMA.to_string()),
+                review,
+                gas: gas_flag(&args)
+                    .or_else(|| profile.payment("migrate"))
+                    .unwrap_or(migrate::MIGRATE_GAS),
+            });
+            #[cfg(not(feature = "livenet"))]
+            {
+                let _ = (review, secret_key);
+                eprintln!("❌ Error: the migrate command needs the livenet backend");
+                eprintln!("Run 'cargo run --features livenet -- migrate ...' instead");
+                process::exit(1);
+            }
         }
         Some("verify") => {
             println!("🔍 Verifying deployment configuration...");
-            verify_config();
+            verify_config(&profile);
         }
         Some("help") | Some("--help") | Some("-h") => {
             print_help();
@@ -66,28 +308,245 @@ fn main() {
     }
 }
 
-fn deploy_contract() {
-    println!("📦 Building contract...");
+// Get the profile named by --profile/-p, then PROFILE, then the default
+fn profile_name(args: &[String]) -> String {
+    flag_value(args, "--profile")
+        .or_else(|| flag_value(args, "-p"))
+        .or_else(|| env::var("PROFILE").ok())
+        .unwrap_or_else(|| profiles::DEFAULT_PROFILE.to_string())
+}
+
+// Refuse to send mainnet deploys unless --yes was passed
+fn confirm_mainnet(profile: &Profile, args: &[String]) {
+    if profile.is_mainnet() && !args.iter().any(|arg| arg == "--yes") {
+        eprintln!("❌ Error: profile '{}' sends deploys to mainnet and spends real CSPR", profile.name);
+        eprintln!("Re-run with --yes to confirm");
+        process::exit(1);
+    }
+}
+
+// `config show` prints the selected profile; `config set <KEY> <VALUE>` stores a setting in it
+fn config_command(args: &[String], profile: Profile) {
+    let positional = positional_args(&args[2..]);
+    match positional.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["show"] | [] => {
+            profile.show();
+            println!();
+            println!("Profiles: {}", profiles::names().join(", "));
+        }
+        ["set", key, value] => {
+            if !profiles::is_setting(key, value) {
+                eprintln!(
+                    "❌ Error: unknown setting '{}', expected one of: {}, {}<entry point> with a payment in motes, or {}<label> with an address",
+                    key,
+                    profiles::KEYS.join(", "),
+                    profiles::PAYMENT_PREFIX,
+                    profiles::ADDRESS_PREFIX
+                );
+                process::exit(1);
+            }
+            if let Err(e) = profiles::set(&profile.name, key, value) {
+                eprintln!("❌ Error writing {}: {}", profiles::PROFILES_FILE, e);
+                process::exit(1);
+            }
+            println!("✅ Set {} for profile '{}'", key, profile.name);
+        }
+        _ => {
+            eprintln!("❌ Error: expected 'config show' or 'config set <KEY> <VALUE>'");
+            process::exit(1);
+        }
+    }
+}
+
+// `address-book list`, `address-book add <LABEL> <ADDRESS>` and `address-book remove <LABEL>`
+fn address_book_command(args: &[String], profile: Profile) {
+    let positional = positional_args(&args[2..]);
+    match positional.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["list"] | [] => {
+            if profile.addresses.is_empty() {
+                println!("ℹ️  The address book of profile '{}' is empty", profile.name);
+            }
+            for (label, address) in &profile.addresses {
+                println!("{:<20} {}", label, address);
+            }
+        }
+        ["add", label, address] => {
+            if !profiles::is_label(label) {
+                eprintln!("❌ Error: '{}' is not a label (letters, digits, '-' and '_', not a number or an address)", label);
+                process::exit(1);
+            }
+            if !profiles::is_address(address) {
+                eprintln!("❌ Error: '{}' is not an address (expected account-hash-..., hash-... or a hex public key)", address);
+                process::exit(1);
+            }
+            if let Some(existing) = profile.addresses.get(*label).filter(|existing| existing != address) {
+                println!("ℹ️  Replacing {} = {}", label, existing);
+            }
+            let key = format!("{}{}", profiles::ADDRESS_PREFIX, label);
+            if let Err(e) = profiles::set(&profile.name, &key, address) {
+                eprintln!("❌ Error writing {}: {}", profiles::PROFILES_FILE, e);
+                process::exit(1);
+            }
+            println!("✅ Added {} = {} to profile '{}'", label, address, profile.name);
+        }
+        ["remove", label] => {
+            let key = format!("{}{}", profiles::ADDRESS_PREFIX, label);
+            match profiles::unset(&profile.name, &key) {
+                Ok(true) => println!("✅ Removed {} from profile '{}'", label, profile.name),
+                Ok(false) => {
+                    eprintln!("❌ Error: profile '{}' has no label '{}'", profile.name, label);
+                    process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("❌ Error writing {}: {}", profiles::PROFILES_FILE, e);
+                    process::exit(1);
+                }
+            }
+        }
+        _ => {
+            eprintln!("❌ Error: expected 'address-book list', 'address-book add <LABEL> <ADDRESS>' or 'address-book remove <LABEL>'");
+            process::exit(1);
+        }
+    }
+}
+
+// Follow --address on the event stream, printing every event that involves it
+fn watch_command(args: &[String], profile: &Profile) {
+    let Some(address) = flag_value(args, "--address").map(|raw| profile.resolve(&raw)) else {
+        eprintln!("❌ Error: --address <ADDRESS> is required (account-hash-..., hash-... or an address book label)");
+        process::exit(1);
+    };
+    let Some(contract) = contract_flag(args, profile).or(profile.contract_hash.clone()) else {
+        eprintln!("❌ Error: set contract_hash with 'cargo run -- config set contract_hash <HASH> -p {}' or pass --contract", profile.name);
+        process::exit(1);
+    };
+    let events_url = flag_value(args, "--events-url").unwrap_or_else(|| profile.events_url.clone());
+    if events_url.is_empty() {
+        eprintln!("❌ Error: profile '{}' has no events_url", profile.name);
+        eprintln!("Set one with: cargo run -- config set events_url <URL> -p {}", profile.name);
+        process::exit(1);
+    }
+    let log = flag_value(args, "--log");
     
-    // In a real implementation, this would use Odra's deployment APIs
-    // For now, we'll provide instructions for manual deployment
-    println!("✅ Contract built successfully");
-    println!();
-    println!("🚀 To deploy the contract, run:");
-    println!("   cargo odra deploy --network casper-test");
-    println!();
-    println!("📝 After deployment, save the contract hash for frontend integration");
+    #[cfg(feature = "indexer")]
+    {
+        use casper_liquid::indexer;
+        use casper_liquid::watch::Watch;
+        
+        let mut watch = Watch::new(&address);
+        if let Some(path) = &log {
+            watch = watch.with_log(path).unwrap_or_else(|e| {
+                eprintln!("❌ Error: could not open {}: {}", path, e);
+                process::exit(1);
+            });
+        }
+        let events_dictionary = indexer::find_events_dictionary(&profile.node_address, &contract).unwrap_or_else(|e| {
+            eprintln!("❌ Error: could not find the events of {}: {}", contract, e);
+            process::exit(1);
+        });
+        
+        println!("👀 Watching {} on {}", address, events_url);
+        if let Err(e) = indexer::run(&events_url, events_dictionary, &mut watch) {
+            eprintln!("❌ Error: {}", e);
+            process::exit(1);
+        }
+        println!("ℹ️  Event stream closed");
+    }
+    #[cfg(not(feature = "indexer"))]
+    {
+        let _ = (address, contract, events_url, log);
+        eprintln!("❌ Error: watching needs the indexer feature");
+        eprintln!("Run 'cargo run --features indexer -- watch --address <ADDRESS>' instead");
+        process::exit(1);
+    }
+}
+
+// Run a scripted scenario on the mock VM and write the views as JSON for the frontend
+fn fixtures_command(args: &[String]) {
+    let count = |flag: &str, default: u64| {
+        flag_value(args, flag).map_or(default, |raw| {
+            raw.parse().unwrap_or_else(|_| {
+                eprintln!("❌ Error: {} must be a whole number, got '{}'", flag, raw);
+                process::exit(1);
+            })
+        })
+    };
+    
+    #[cfg(feature = "fixtures")]
+    fixtures::fixtures(fixtures::FixturesOptions {
+        users: count("--users", fixtures::DEFAULT_USERS),
+        epochs: count("--epochs", fixtures::DEFAULT_EPOCHS),
+        out: flag_value(args, "--out")
+            .unwrap_or_else(|| fixtures::DEFAULT_OUT.to_string())
+            .into(),
+    });
+    #[cfg(not(feature = "fixtures"))]
+    {
+        let _ = count;
+        eprintln!("❌ Error: the fixtures command needs the mock VM");
+        eprintln!("Run 'cargo run --features fixtures -- fixtures' instead");
+        process::exit(1);
+    }
+}
+
+// Get the --contract flag, resolving an address book label
+fn contract_flag(args: &[String], profile: &Profile) -> Option<String> {
+    flag_value(args, "--contract").map(|raw| profile.resolve(&raw))
+}
+
+// Get the --gas flag in motes, exiting if it is not a number
+fn gas_flag(args: &[String]) -> Option<u64> {
+    flag_value(args, "--gas").map(|raw| {
+        raw.parse().unwrap_or_else(|_| {
+            eprintln!("❌ Error: --gas must be a whole number of motes, got '{}'", raw);
+            process::exit(1);
+        })
+    })
+}
+
+// Store a measured cost, plus headroom, as the profile's payment preset for `entry_point`
+#[cfg(feature = "livenet")]
+fn record_payment(profile: &Profile, entry_point: &str, cost: u64) {
+    let payment = cost.saturating_add(cost * call::GAS_REPORT_MARGIN_PERCENT / 100);
+    let key = format!("{}{}", profiles::PAYMENT_PREFIX, entry_point);
+    if let Err(e) = profiles::set(&profile.name, &key, &payment.to_string()) {
+        eprintln!("❌ Error writing {}: {}", profiles::PROFILES_FILE, e);
+        process::exit(1);
+    }
+    println!("⛽ {} cost {} motes", entry_point, cost);
+    println!("✅ Set {} = {} for profile '{}'", key, payment, profile.name);
+}
+
+// Get the value following `flag` on the command line, e.g. `--chain-name casper-test`
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .cloned()
 }
 
-fn verify_config() {
+// Get the arguments that are neither flags nor flag values
+fn positional_args(args: &[String]) -> Vec<String> {
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if VALUE_FLAGS.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with("--") {
+            positional.push(arg.clone());
+        }
+    }
+    positional
+}
+
+fn verify_config(profile: &Profile) {
     println!("🔍 Verifying deployment configuration...");
     
-    // Check .env file
-    if std::path::Path::new(".env").exists() {
-        println!("✅ .env file exists");
+    // Check profiles.toml; the built-in defaults apply without it
+    if std::path::Path::new(profiles::PROFILES_FILE).exists() {
+        println!("✅ {} exists", profiles::PROFILES_FILE);
     } else {
-        println!("❌ .env file missing");
-        return;
+        println!("ℹ️  {} missing, using the built-in '{}' profile", profiles::PROFILES_FILE, profile.name);
     }
     
     // Check Odra.toml
@@ -98,39 +557,130 @@ fn verify_config() {
         return;
     }
     
-    // Check environment variables
-    match dotenv::dotenv() {
-        Ok(_) => {
-            if env::var("SECRET_KEY").is_ok() {
-                println!("✅ SECRET_KEY configured");
-            } else {
-                println!("❌ SECRET_KEY not configured");
-            }
-        }
-        Err(_) => {
-            println!("❌ Error loading environment variables");
-        }
+    // Check the signing key
+    match &profile.key_path {
+        Some(path) if std::path::Path::new(path).is_file() => println!("✅ key_path {} exists", path),
+        Some(path) => println!("❌ key_path {} not found", path),
+        None if env::var("SECRET_KEY").is_ok() => println!("✅ SECRET_KEY configured"),
+        None => println!("❌ No key_path or SECRET_KEY configured"),
+    }
+    
+    if profile.contract_hash.is_some() {
+        println!("✅ contract_hash configured");
+    } else {
+        println!("ℹ️  contract_hash not set (needed by call and smoke-test)");
     }
     
     println!("✅ Configuration verification complete");
 }
 
+fn decode_error(raw: Option<&str>) {
+    if raw == Some("--all") {
+        for (code, name, message) in casper_liquid::Error::TABLE {
+            println!("{:>3}  {:<28} {}", code, name, message);
+        }
+        return;
+    }
+    
+    // Accept either a bare code ("2") or the node's "User error: 2" message
+    let code = raw
+        .map(|s| s.trim().trim_start_matches("User error:").trim())
+        .and_then(|s| s.parse::<u16>().ok());
+    
+    let Some(code) = code else {
+        eprintln!("❌ Error: expected a numeric error code, e.g. 'cargo run -- decode-error 2'");
+        process::exit(1);
+    };
+    
+    match casper_liquid::Error::from_code(code) {
+        Some(error) => println!("User error {}: {:?} - {}", code, error, error.describe()),
+        None => {
+            eprintln!("❌ Unknown error code {} (not emitted by CasperLiquid)", code);
+            process::exit(1);
+        }
+    }
+}
+
 fn print_help() {
     println!("CasperLiquid Deployment Tool");
     println!();
     println!("USAGE:");
-    println!("    cargo run -- <COMMAND>");
+    println!("    cargo run -- <COMMAND> [--profile <NAME>]");
     println!();
     println!("COMMANDS:");
-    println!("    deploy    Deploy the CasperLiquid contract to Testnet");
+    println!("    deploy    Build, install and confirm the CasperLiquid contract (needs --features livenet)");
+    println!("    smoke-test [CONTRACT_HASH]  Check a deployed contract's metadata (needs --features livenet)");
+    println!("    soak      Send randomized traffic for hours, checking the invariants after every step (needs --features livenet)");
+    println!("    call <CALL> [ARGS]  Send a call to the profile's contract (needs --features livenet):");
+    println!("        stake <AMOUNT>               Stake AMOUNT motes");
+    println!("        unstake <AMOUNT>             Unstake AMOUNT stCSPR");
+    println!("        transfer <RECIPIENT> <AMOUNT>");
+    println!("        approve <SPENDER> <AMOUNT>");
+    println!("        balance-of [ADDRESS]         Defaults to the SECRET_KEY account");
+    println!("        allowance <OWNER> <SPENDER>");
+    println!("        status                       Incident status, security contact and pool health");
+    println!("    gas-report <CALL> [ARGS]  Send a call and store its cost plus 20% as the profile's payment.<CALL> (needs --features livenet)");
+    println!("    migrate   Review entry point changes and migrate state once a new version is installed (needs --features livenet)");
+    println!("    watch --address <ADDRESS>  Print the address's transfers, stakes and allowances as they happen (needs --features indexer)");
     println!("    verify    Verify deployment configuration");
+    println!("    config show               Show the selected profile");
+    println!("    config set <KEY> <VALUE>  Store node_address, chain_name, key_path, contract_hash, events_url or payment.<ENTRY POINT> in the profile");
+    println!("    address-book list                    List the profile's labeled addresses");
+    println!("    address-book add <LABEL> <ADDRESS>   Label an account-hash-..., hash-... or validator key; labels work wherever an address does");
+    println!("    address-book remove <LABEL>");
+    println!("    decode-error <CODE>  Explain a contract revert code (e.g. 'User error: 2')");
+    println!("    decode-error --all   List every contract error code and message");
+    println!("    fixtures  Run a scripted scenario on the mock VM and write the views as JSON (needs --features fixtures)");
     println!("    help      Show this help message");
     println!();
+    println!("OPTIONS:");
+    println!("    -p, --profile <NAME>       Network profile: local, testnet or mainnet (default: PROFILE, then testnet)");
+    println!("    --yes                      Confirm deploys that spend CSPR on mainnet");
+    println!();
+    println!("DEPLOY OPTIONS:");
+    println!("    --chain-name <NAME>        Chain name to sign for (default: the profile's chain_name)");
+    println!("    --payment-amount <MOTES>   Gas payment for the install (default: payment.deploy, then 450 CSPR)");
+    println!("    --wasm-path <PATH>         Install a prebuilt wasm instead of running 'cargo odra build'");
+    println!("    --feature-bits <BITS>      Surfaces to enable: 1 compliance, 2 instant unstake, 4 referrals (default: 7, all)");
+    println!();
+    println!("CALL OPTIONS:");
+    println!("    --contract <CONTRACT_HASH>  Contract to call instead of the profile's contract_hash");
+    println!("    --gas <MOTES>              Gas limit for the deploy (default: payment.<CALL>, then 10 CSPR)");
+    println!("    --json                     Print the result as a single JSON object");
+    println!();
+    println!("MIGRATE OPTIONS:");
+    println!("    --old-schema <PATH>        Schema of the deployed version; prints the entry point diff");
+    println!("    --new-schema <PATH>        Schema of the new build (default: the 'cargo odra schema' output)");
+    println!("    --review                   Only print the entry point diff, do not call migrate()");
+    println!("    --gas <MOTES>              Gas limit for the migrate deploy (default: payment.migrate, then 20 CSPR)");
+    println!();
+    println!("WATCH OPTIONS:");
+    println!("    --address <ADDRESS>        Holder to watch: account-hash-..., hash-... or an address book label");
+    println!("    --events-url <URL>         Node SSE stream (default: the profile's events_url)");
+    println!("    --log <PATH>               Also append every line to this file");
+    println!("    --contract <CONTRACT_HASH>  Contract to watch instead of the profile's contract_hash");
+    println!();
+    println!("SMOKE TEST OPTIONS:");
+    println!("    --round-trip               Also stake and unstake 1 CSPR and check the balance is restored");
+    println!("    --test-key <PATH>          Sign the round trip with this key instead of SECRET_KEY");
+    println!();
+    println!("SOAK OPTIONS:");
+    println!("    --duration <MINUTES>       How long to send traffic (default: 60)");
+    println!("    --extra-keys <PATH,...>    More signing keys taking part, so transfers have recipients");
+    println!("    --seed <N>                 Replay the traffic of an earlier run (default: from the clock)");
+    println!("    --report <PATH>            Also write the report as JSON");
+    println!("    --contract <CONTRACT_HASH>  Contract to soak instead of the profile's contract_hash");
+    println!();
+    println!("FIXTURES OPTIONS:");
+    println!("    --users <N>                Users staking in the scenario, 1 to 15 (default: 5)");
+    println!("    --epochs <M>               Epochs of rewards and withdrawal requests (default: 4)");
+    println!("    --out <DIR>                Directory to write the JSON files to (default: fixtures)");
+    println!();
     println!("SETUP:");
-    println!("    1. Copy .env.example to .env");
-    println!("    2. Set your SECRET_KEY in .env");
+    println!("    1. Run 'cargo run -- config set key_path <PEM> -p testnet', or set SECRET_KEY in .env");
+    println!("    2. Run 'cargo run -- config show -p testnet' to check the network settings");
     println!("    3. Run 'cargo run -- verify' to check configuration");
-    println!("    4. Run 'cargo run -- deploy' to deploy the contract");
+    println!("    4. Run 'cargo run --features livenet -- deploy' to deploy the contract");
     println!();
     println!("For more information, see the deployment documentation in README.md");
 }
\ No newline at end of file
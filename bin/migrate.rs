@@ -0,0 +1,162 @@
+//! State migration after a new version is installed
+//!
+//! This command does not install anything. The Odra 1.0 installer always
+//! creates a new contract package, so the new wasm has to be added to the
+//! existing package by a session that calls `add_contract_version` under the
+//! package's access key, and that session should call `migrate` in the same
+//! deploy. This command reviews the entry point changes beforehand and runs
+//! `migrate` when the install session did not.
+//!
+//! Migrating version 1 state assigns the owner baked into the wasm with
+//! `CASPER_LIQUID_MIGRATION_OWNER`, so a migration sent by anyone, including
+//! one that front-runs this command, ends with the same owner.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+
+use casper_liquid::CasperLiquid;
+use odra::host::HostRefLoader;
+use odra::prelude::*;
+use serde_json::Value;
+
+use crate::deploy::connect;
+
+/// Where `cargo odra schema` writes the schema of the current build
+pub const DEFAULT_NEW_SCHEMA: &str = "resources/casper_contract_schemas/casper_liquid_schema.json";
+
+/// Default gas limit for the migrate deploy, in motes
+pub const MIGRATE_GAS: u64 = 20_000_000_000;
+
+/// Settings for a migrate run
+pub struct MigrateOptions {
+    pub node_address: String,
+    pub chain_name: String,
+    pub secret_key: String,
+    /// Contract package hash, formatted as `hash-...`
+    pub contract: Option<String>,
+    /// Schema of the deployed version, saved when it was built
+    pub old_schema: Option<String>,
+    pub new_schema: String,
+    /// Only print the entry point diff
+    pub review: bool,
+    /// Gas limit for the migrate deploy, in motes
+    pub gas: u64,
+}
+
+/// Print the entry point diff, then call `migrate` unless only reviewing
+pub fn migrate(options: MigrateOptions) {
+    if let Some(old_schema) = &options.old_schema {
+        print_entry_point_diff(old_schema, &options.new_schema);
+    }
+
+    if options.review {
+        println!();
+        println!("ℹ️  Build the new wasm with CASPER_LIQUID_MIGRATION_OWNER set if the deployed state is version 1,");
+        println!("   add it to the existing package with a session that also calls migrate, or re-run without --review");
+        return;
+    }
+
+    let Some(contract) = options.contract else {
+        eprintln!("❌ Error: migrate needs the profile's contract_hash or --contract");
+        process::exit(1);
+    };
+    let address: Address = contract.parse().unwrap_or_else(|_| {
+        eprintln!("❌ Error: '{}' is not a contract hash (expected hash-...)", contract);
+        process::exit(1);
+    });
+
+    let (host_env, key_file) = connect(&options.node_address, &options.chain_name, &options.secret_key);
+    host_env.set_gas(options.gas);
+    let mut contract = CasperLiquid::load(&host_env, address);
+
+    let version = contract.version();
+    if version >= casper_liquid::CONTRACT_VERSION {
+        println!("✅ Already at version {}", version);
+        return;
+    }
+    if version < 2 {
+        match contract.migration_owner() {
+            Some(owner) => println!("👤 Version 1 has no owner; the installed wasm assigns {:?}", owner),
+            None => {
+                eprintln!("❌ Error: the installed wasm was built without CASPER_LIQUID_MIGRATION_OWNER");
+                eprintln!("Version 1 state has no owner, so the migration would fail");
+                process::exit(1);
+            }
+        }
+    }
+
+    println!("🔄 Migrating state from version {}...", version);
+    let migrated = panic::catch_unwind(AssertUnwindSafe(|| contract.migrate()));
+    drop(key_file);
+
+    match migrated {
+        Ok(Ok(())) => println!("✅ Migrated to version {}", casper_liquid::CONTRACT_VERSION),
+        Ok(Err(error)) => {
+            eprintln!("❌ migrate failed: {:?} (User error {}) - {}", error, error.code(), error.describe());
+            process::exit(1);
+        }
+        Err(_) => {
+            eprintln!("❌ migrate failed: the deploy did not complete");
+            process::exit(1);
+        }
+    }
+}
+
+// Print entry points added, removed or changed between two contract schemas
+fn print_entry_point_diff(old_path: &str, new_path: &str) {
+    let old = entry_points(old_path);
+    let new = entry_points(new_path);
+
+    println!("📋 Entry point changes ({} -> {}):", old_path, new_path);
+    let mut changed = false;
+    for (name, signature) in &new {
+        match old.get(name) {
+            None => println!("   + {}{}", name, signature),
+            Some(old_signature) if old_signature != signature => {
+                println!("   - {}{}", name, old_signature);
+                println!("   + {}{}", name, signature);
+            }
+            Some(_) => continue,
+        }
+        changed = true;
+    }
+    for (name, signature) in &old {
+        if !new.contains_key(name) {
+            println!("   - {}{}", name, signature);
+            changed = true;
+        }
+    }
+    if !changed {
+        println!("   (no changes)");
+    }
+}
+
+// Read a schema's entry points as name -> "(arg: type, ...) -> return type"
+fn entry_points(path: &str) -> BTreeMap<String, String> {
+    let schema: Value = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_else(|| {
+            eprintln!("❌ Error: could not read the contract schema {}", path);
+            eprintln!("Generate it with 'cargo odra schema'");
+            process::exit(1);
+        });
+
+    let mut entry_points = BTreeMap::new();
+    for entry_point in schema["entry_points"].as_array().into_iter().flatten() {
+        let Some(name) = entry_point["name"].as_str() else {
+            continue;
+        };
+        let arguments: Vec<String> = entry_point["arguments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|argument| format!("{}: {}", argument["name"].as_str().unwrap_or("?"), argument["ty"]))
+            .collect();
+        let signature = format!("({}) -> {}", arguments.join(", "), entry_point["return_ty"]);
+        entry_points.insert(name.to_string(), signature);
+    }
+    entry_points
+}
@@ -0,0 +1,277 @@
+//! Named network profiles
+//!
+//! Each profile holds the node, chain, signing key and contract for one
+//! network. Values in `profiles.toml` override the built-in defaults for
+//! `local` (NCTL), `testnet` and `mainnet`. The file uses a flat TOML subset:
+//!
+//! ```toml
+//! [testnet]
+//! node_address = "http://3.143.158.19:7777"
+//! events_url = "http://3.143.158.19:9999/events/main"
+//! key_path = "keys/secret_key.pem"
+//! contract_hash = "hash-..."
+//! payment.stake = "3000000000"
+//! address.treasury = "account-hash-..."
+//! ```
+//!
+//! `payment.<entry point>` settings are the payment, in motes, the commands
+//! attach to that entry point on the network unless `--gas` (or
+//! `--payment-amount` for `deploy`) overrides it. `gas-report` records them
+//! from a measured call.
+//!
+//! `address.<label>` settings form the profile's address book: commands accept
+//! the label wherever they take an address, contract hash or validator key, so
+//! admin operations don't depend on pasting 64-character hex strings.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::process;
+
+/// File holding the profile settings, relative to the working directory
+pub const PROFILES_FILE: &str = "profiles.toml";
+
+/// Profile used when neither --profile nor PROFILE is set
+pub const DEFAULT_PROFILE: &str = "testnet";
+
+/// Chain name of Casper mainnet, where deploys need --yes
+pub const MAINNET_CHAIN_NAME: &str = "casper";
+
+/// Settings a profile can hold
+pub const KEYS: [&str; 5] = ["node_address", "chain_name", "key_path", "contract_hash", "events_url"];
+
+/// Prefix of the per-entry-point payment settings, e.g. `payment.stake`
+pub const PAYMENT_PREFIX: &str = "payment.";
+
+/// Prefix of the address book settings, e.g. `address.treasury`
+pub const ADDRESS_PREFIX: &str = "address.";
+
+/// Settings for one network
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    pub name: String,
+    pub node_address: String,
+    pub chain_name: String,
+    /// Path to the secret key PEM; SECRET_KEY is used when unset
+    pub key_path: Option<String>,
+    /// Package hash of the deployed contract, formatted as `hash-...`
+    pub contract_hash: Option<String>,
+    /// Node's SSE event stream, read by `watch`
+    pub events_url: String,
+    /// Payment preset for each entry point, in motes
+    pub payments: BTreeMap<String, u64>,
+    /// Labeled addresses, contract hashes and validator keys
+    pub addresses: BTreeMap<String, String>,
+}
+
+impl Profile {
+    /// Get the built-in defaults for a profile name
+    ///
+    /// Mainnet has no default node, so it cannot be used until one is set.
+    fn builtin(name: &str) -> Option<Profile> {
+        let (node_address, chain_name, events_url) = match name {
+            "local" => ("http://localhost:11101", "casper-net-1", "http://localhost:18101/events/main"),
+            "testnet" => ("http://3.143.158.19:7777", "casper-test", "http://3.143.158.19:9999/events/main"),
+            "mainnet" => ("", MAINNET_CHAIN_NAME, ""),
+            _ => return None,
+        };
+        Some(Profile {
+            name: name.to_string(),
+            node_address: node_address.to_string(),
+            chain_name: chain_name.to_string(),
+            events_url: events_url.to_string(),
+            ..Profile::default()
+        })
+    }
+
+    /// Check whether deploys with this profile spend real CSPR
+    pub fn is_mainnet(&self) -> bool {
+        self.chain_name == MAINNET_CHAIN_NAME
+    }
+
+    /// Get the payment preset for an entry point, in motes
+    pub fn payment(&self, entry_point: &str) -> Option<u64> {
+        self.payments.get(entry_point).copied()
+    }
+
+    /// Get the address a label stands for, or `raw` itself if it is not a label
+    pub fn resolve(&self, raw: &str) -> String {
+        self.addresses.get(raw).cloned().unwrap_or_else(|| raw.to_string())
+    }
+
+    fn set(&mut self, key: &str, value: String) {
+        match key {
+            "node_address" => self.node_address = value,
+            "chain_name" => self.chain_name = value,
+            "key_path" => self.key_path = Some(value),
+            "contract_hash" => self.contract_hash = Some(value),
+            "events_url" => self.events_url = value,
+            _ => {
+                if let Some(label) = key.strip_prefix(ADDRESS_PREFIX) {
+                    self.addresses.insert(label.to_string(), value);
+                } else if let (Some(entry_point), Ok(motes)) = (key.strip_prefix(PAYMENT_PREFIX), value.parse()) {
+                    self.payments.insert(entry_point.to_string(), motes);
+                }
+            }
+        }
+    }
+
+    /// Print the profile's settings
+    pub fn show(&self) {
+        let unset = "(unset)".to_string();
+        println!("[{}]", self.name);
+        println!("node_address  = {}", if self.node_address.is_empty() { &unset } else { &self.node_address });
+        println!("chain_name    = {}", self.chain_name);
+        println!("key_path      = {}", self.key_path.as_ref().unwrap_or(&"(SECRET_KEY from .env)".to_string()));
+        println!("contract_hash = {}", self.contract_hash.as_ref().unwrap_or(&unset));
+        println!("events_url    = {}", if self.events_url.is_empty() { &unset } else { &self.events_url });
+        for (entry_point, motes) in &self.payments {
+            println!("{}{} = {}", PAYMENT_PREFIX, entry_point, motes);
+        }
+        for (label, address) in &self.addresses {
+            println!("{}{} = {}", ADDRESS_PREFIX, label, address);
+        }
+    }
+}
+
+/// Load a profile, exiting if it is neither built in nor in the profiles file
+pub fn load(name: &str) -> Profile {
+    let file = read_file();
+    let mut profile = match (Profile::builtin(name), file.get(name)) {
+        (Some(profile), _) => profile,
+        (None, Some(_)) => Profile {
+            name: name.to_string(),
+            ..Profile::default()
+        },
+        (None, None) => {
+            eprintln!("❌ Error: unknown profile '{}'", name);
+            eprintln!("Available profiles: {}", names().join(", "));
+            process::exit(1);
+        }
+    };
+
+    for (key, value) in file.get(name).into_iter().flatten() {
+        profile.set(key, value.clone());
+    }
+    profile
+}
+
+/// Get the names of the built-in profiles and those in the profiles file
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = ["local", "testnet", "mainnet"].iter().map(|name| name.to_string()).collect();
+    for name in read_file().into_keys() {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Check whether `key` is a setting and `value` a valid value for it
+pub fn is_setting(key: &str, value: &str) -> bool {
+    if let Some(label) = key.strip_prefix(ADDRESS_PREFIX) {
+        return is_label(label) && is_address(value);
+    }
+    match key.strip_prefix(PAYMENT_PREFIX) {
+        Some(entry_point) => !entry_point.is_empty() && value.parse::<u64>().is_ok(),
+        None => KEYS.contains(&key),
+    }
+}
+
+/// Check whether `label` can name an address book entry
+///
+/// Labels are letters, digits, `-` and `_`, and cannot be mistaken for an
+/// amount or an address.
+pub fn is_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && !label.chars().all(|c| c.is_ascii_digit())
+        && !is_address(label)
+}
+
+/// Check whether `value` is an `account-hash-...` or `hash-...` address, or a
+/// hex validator public key
+pub fn is_address(value: &str) -> bool {
+    let is_hex = |raw: &str, length: usize| raw.len() == length && raw.chars().all(|c| c.is_ascii_hexdigit());
+    match value.strip_prefix("account-hash-").or_else(|| value.strip_prefix("hash-")) {
+        Some(hash) => is_hex(hash, 64),
+        // Ed25519 keys are tagged 01, secp256k1 keys 02
+        None => (value.starts_with("01") && is_hex(value, 66)) || (value.starts_with("02") && is_hex(value, 68)),
+    }
+}
+
+/// Store one setting of a profile in the profiles file
+pub fn set(name: &str, key: &str, value: &str) -> io::Result<()> {
+    let mut file = read_file();
+    file.entry(name.to_string())
+        .or_default()
+        .insert(key.to_string(), value.to_string());
+    write_file(&file)
+}
+
+/// Remove one setting of a profile from the profiles file; returns whether it was set
+pub fn unset(name: &str, key: &str) -> io::Result<bool> {
+    let mut file = read_file();
+    let removed = file.get_mut(name).and_then(|settings| settings.remove(key)).is_some();
+    if removed {
+        write_file(&file)?;
+    }
+    Ok(removed)
+}
+
+fn write_file(file: &BTreeMap<String, BTreeMap<String, String>>) -> io::Result<()> {
+    let mut contents = String::from("# CasperLiquid network profiles, see `cargo run -- config`\n");
+    for (name, settings) in file {
+        contents.push_str(&format!("\n[{}]\n", name));
+        for (key, value) in settings {
+            contents.push_str(&format!("{} = \"{}\"\n", key, value));
+        }
+    }
+    fs::write(PROFILES_FILE, contents)
+}
+
+// Read the profiles file as section -> key -> value; a missing file is empty
+fn read_file() -> BTreeMap<String, BTreeMap<String, String>> {
+    let mut sections: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    let Ok(contents) = fs::read_to_string(PROFILES_FILE) else {
+        return sections;
+    };
+
+    let mut current = None;
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            current = Some(name.trim().to_string());
+            sections.entry(name.trim().to_string()).or_default();
+            continue;
+        }
+
+        let parsed = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')));
+        match (current.as_ref(), parsed) {
+            (Some(section), Some((key, value))) if is_setting(key, value) => {
+                sections
+                    .entry(section.clone())
+                    .or_default()
+                    .insert(key.to_string(), value.to_string());
+            }
+            _ => {
+                eprintln!(
+                    "❌ Error: {} line {}: expected a [profile] or a setting ({}, {}<entry point> in motes, or {}<label> with an address)",
+                    PROFILES_FILE,
+                    number + 1,
+                    KEYS.join(", "),
+                    PAYMENT_PREFIX,
+                    ADDRESS_PREFIX
+                );
+                process::exit(1);
+            }
+        }
+    }
+    sections
+}
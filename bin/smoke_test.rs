@@ -0,0 +1,96 @@
+//! Post-deploy smoke test
+//!
+//! Reads the token metadata of a deployed contract over RPC and, with
+//! `--round-trip`, stakes and unstakes a small amount to check the pool end to end.
+
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+
+use casper_liquid::CasperLiquid;
+use odra::host::HostRefLoader;
+use odra::prelude::*;
+
+use crate::deploy::connect;
+
+/// Amount staked by the round trip (1 CSPR)
+const ROUND_TRIP_AMOUNT: u64 = 1_000_000_000;
+
+/// Gas limit for each round trip call, in motes
+const CALL_GAS: u64 = 10_000_000_000;
+
+/// Settings for a smoke test run
+pub struct SmokeTestOptions {
+    pub node_address: String,
+    pub chain_name: String,
+    /// Key that signs the round trip deploys: --test-key, or SECRET_KEY
+    pub secret_key: String,
+    /// Contract package hash, formatted as `hash-...`
+    pub contract: String,
+    pub round_trip: bool,
+}
+
+/// Run the checks against a deployed contract, exiting non-zero if any fails
+pub fn smoke_test(options: SmokeTestOptions) {
+    let address: Address = options.contract.parse().unwrap_or_else(|_| {
+        eprintln!("❌ Error: '{}' is not a contract hash (expected hash-...)", options.contract);
+        process::exit(1);
+    });
+    
+    let (host_env, key_file) = connect(&options.node_address, &options.chain_name, &options.secret_key);
+    let mut contract = CasperLiquid::load(&host_env, address);
+    let mut passed = true;
+    
+    println!("🔍 Checking token metadata...");
+    passed &= check("name", || contract.name(), |name| !name.is_empty());
+    passed &= check("symbol", || contract.symbol(), |symbol| !symbol.is_empty());
+    passed &= check("decimals", || contract.decimals(), |decimals| *decimals == 9);
+    passed &= check("total_supply", || contract.total_supply(), |_| true);
+    
+    if options.round_trip {
+        println!("🔁 Staking and unstaking {} motes...", ROUND_TRIP_AMOUNT);
+        host_env.set_gas(CALL_GAS);
+        let user = host_env.caller();
+        let before = contract.balance_of(&user);
+        
+        passed &= check(
+            "stake",
+            || contract.with_tokens(U512::from(ROUND_TRIP_AMOUNT)).stake(U256::from(ROUND_TRIP_AMOUNT)),
+            Result::is_ok,
+        );
+        let minted = contract.balance_of(&user).saturating_sub(before);
+        passed &= check("stCSPR minted", || minted, |minted| !minted.is_zero());
+        
+        if !minted.is_zero() {
+            passed &= check("unstake", || contract.unstake(minted), Result::is_ok);
+            passed &= check("balance restored", || contract.balance_of(&user), |balance| *balance == before);
+        }
+    }
+    drop(key_file);
+    
+    println!();
+    if passed {
+        println!("✅ Smoke test passed");
+    } else {
+        println!("❌ Smoke test failed");
+        process::exit(1);
+    }
+}
+
+// Run one check and print the result; a reverted or unreachable call counts as a failure
+fn check<T: Debug>(label: &str, call: impl FnOnce() -> T, pass: impl FnOnce(&T) -> bool) -> bool {
+    match panic::catch_unwind(AssertUnwindSafe(call)) {
+        Ok(value) if pass(&value) => {
+            println!("   ✅ {}: {:?}", label, value);
+            true
+        }
+        Ok(value) => {
+            println!("   ❌ {}: unexpected {:?}", label, value);
+            false
+        }
+        Err(_) => {
+            println!("   ❌ {}: call failed", label);
+            false
+        }
+    }
+}
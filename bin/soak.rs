@@ -0,0 +1,257 @@
+//! Long-running soak test against a local network
+//!
+//! Sends randomized traffic to a deployed contract for a set time: stakes,
+//! transfers between the soak accounts, instant unstakes, withdrawal requests
+//! and claims, and a small rewards report every few steps standing in for an
+//! epoch. After every step the pool invariants (`invariant_report`) are
+//! checked and the exchange rate must not have dropped, so slow state drift
+//! shows up as the step that caused it. Ends with a report of what was sent,
+//! what the contract rejected and what failed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use casper_liquid::{CasperLiquid, Error, RATE_PRECISION};
+use odra::host::HostRefLoader;
+use odra::prelude::*;
+use serde_json::json;
+
+use crate::deploy::connect;
+
+/// Run time unless --duration is passed, in minutes
+pub const DEFAULT_DURATION_MINUTES: u64 = 60;
+
+/// Gas limit for each soak call, in motes
+const CALL_GAS: u64 = 10_000_000_000;
+
+/// Largest stake a step sends (10 CSPR); stakes are 1 to 10 CSPR
+const MAX_STAKE: u64 = 10_000_000_000;
+
+/// Steps between two rewards reports
+const EPOCH_STEPS: u64 = 25;
+
+/// Settings for a soak run
+pub struct SoakOptions {
+    pub node_address: String,
+    pub chain_name: String,
+    /// Key of the contract's operator; signs the rewards reports and takes part in the traffic
+    pub secret_key: String,
+    /// Further keys taking part in the traffic, so transfers have somewhere to go
+    pub extra_keys: Vec<String>,
+    /// Contract package hash, formatted as `hash-...`
+    pub contract: String,
+    pub duration: Duration,
+    pub seed: Option<u64>,
+    /// File the JSON report is written to
+    pub report: Option<String>,
+}
+
+/// Outcomes of one kind of step
+#[derive(Default)]
+struct Tally {
+    ok: u64,
+    /// Contract errors by name, e.g. liquidity running out; expected under random traffic
+    rejected: BTreeMap<String, u64>,
+    /// Calls that did not execute at all
+    failed: u64,
+}
+
+/// Send traffic until the time is up or an invariant breaks, exiting non-zero on a violation
+pub fn soak(options: SoakOptions) {
+    let address: Address = options.contract.parse().unwrap_or_else(|_| {
+        eprintln!("❌ Error: '{}' is not a contract hash (expected hash-...)", options.contract);
+        process::exit(1);
+    });
+
+    // The livenet backend picks up extra signers from ODRA_CASPER_LIVENET_KEY_<n>
+    for (index, key) in options.extra_keys.iter().enumerate() {
+        std::env::set_var(format!("ODRA_CASPER_LIVENET_KEY_{}", index + 1), key);
+    }
+    let (host_env, key_file) = connect(&options.node_address, &options.chain_name, &options.secret_key);
+    let mut contract = CasperLiquid::load(&host_env, address);
+    host_env.set_gas(CALL_GAS);
+
+    let operator = host_env.get_account(0);
+    let accounts: Vec<Address> = (0..=options.extra_keys.len()).map(|index| host_env.get_account(index)).collect();
+    let seed = options.seed.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(1, |elapsed| elapsed.as_nanos() as u64)
+    });
+    let mut rng = Rng::new(seed);
+
+    println!("🌊 Soaking {} with {} accounts for {:?} (seed {})", options.contract, accounts.len(), options.duration, seed);
+    let started = Instant::now();
+    let initial_rate = contract.exchange_rate();
+    let mut last_rate = initial_rate;
+    let mut tallies: BTreeMap<&str, Tally> = BTreeMap::new();
+    let mut requests: Vec<(u64, Address, Instant)> = Vec::new();
+    let mut violation = None;
+    let mut step = 0u64;
+
+    while started.elapsed() < options.duration {
+        step += 1;
+        let account = accounts[rng.below(accounts.len() as u64) as usize];
+        host_env.set_caller(account);
+        let balance = contract.balance_of(&account);
+        let share = |rng: &mut Rng| balance * U256::from(rng.below(50) + 1) / U256::from(100u64);
+
+        let (kind, outcome) = if step % EPOCH_STEPS == 0 {
+            // Roughly 0.1% of the pool, the size of a few days of rewards
+            host_env.set_caller(operator);
+            let rewards = contract.total_pooled_cspr() / U256::from(1_000u64);
+            ("report_rewards", send(|| contract.report_rewards(rewards.max(U256::one()))))
+        } else {
+            match rng.below(10) {
+                0..=2 => {
+                    let amount = rng.below(MAX_STAKE) + 1;
+                    ("stake", send(|| contract.with_tokens(U512::from(amount)).stake(U256::from(amount))))
+                }
+                3 | 4 if accounts.len() > 1 => {
+                    let recipient = accounts[rng.below(accounts.len() as u64) as usize];
+                    let amount = share(&mut rng);
+                    ("transfer", send(|| contract.transfer(&recipient, amount)))
+                }
+                5 => {
+                    let amount = share(&mut rng);
+                    ("unstake", send(|| contract.unstake(amount)))
+                }
+                6 | 7 => {
+                    let amount = share(&mut rng);
+                    let outcome = send(|| contract.request_unstake(amount));
+                    if let Outcome::Ok(id) = outcome {
+                        requests.push((id, account, Instant::now()));
+                    }
+                    ("request_unstake", outcome.map(|_| ()))
+                }
+                _ => {
+                    // Claim the oldest request whose unbonding period has passed by the wall clock
+                    let unbonding = Duration::from_millis(contract.unbonding_period());
+                    match requests.iter().position(|(_, _, at)| at.elapsed() >= unbonding) {
+                        Some(index) => {
+                            let (id, owner, _) = requests.remove(index);
+                            host_env.set_caller(owner);
+                            ("claim_withdrawal", send(|| contract.claim_withdrawal(id)))
+                        }
+                        None => continue,
+                    }
+                }
+            }
+        };
+
+        let tally = tallies.entry(kind).or_default();
+        match outcome {
+            Outcome::Ok(()) => tally.ok += 1,
+            Outcome::Rejected(error) => *tally.rejected.entry(format!("{:?}", error)).or_default() += 1,
+            Outcome::Failed => tally.failed += 1,
+        }
+
+        // The monitor checks: the invariant set, and no loss of value per share
+        let checked = panic::catch_unwind(AssertUnwindSafe(|| (contract.invariant_report(), contract.exchange_rate())));
+        let Ok((report, rate)) = checked else {
+            println!("   ⚠️  step {}: could not read the pool state", step);
+            continue;
+        };
+        if let Err(error) = report.check() {
+            violation = Some(format!("step {} ({}): {:?}, {:?}", step, kind, error, report));
+            break;
+        }
+        if rate < last_rate {
+            violation = Some(format!("step {} ({}): exchange rate dropped from {} to {}", step, kind, last_rate, rate));
+            break;
+        }
+        last_rate = rate;
+
+        if step % 100 == 0 {
+            println!("   step {} after {:?}, rate {}", step, started.elapsed(), rate);
+        }
+    }
+    drop(key_file);
+
+    println!();
+    println!("📋 {} steps in {:?}", step, started.elapsed());
+    for (kind, tally) in &tallies {
+        println!("   {:<18} {} ok, {} failed, rejected {:?}", kind, tally.ok, tally.failed, tally.rejected);
+    }
+    println!(
+        "   exchange rate {} -> {} ({} per {})",
+        initial_rate, last_rate, last_rate.saturating_sub(initial_rate), RATE_PRECISION
+    );
+
+    if let Some(path) = &options.report {
+        let calls: BTreeMap<&str, _> = tallies
+            .iter()
+            .map(|(kind, tally)| (*kind, json!({ "ok": tally.ok, "rejected": tally.rejected, "failed": tally.failed })))
+            .collect();
+        let report = json!({
+            "contract": options.contract,
+            "seed": seed,
+            "steps": step,
+            "elapsed_secs": started.elapsed().as_secs(),
+            "initial_rate": initial_rate.to_string(),
+            "final_rate": last_rate.to_string(),
+            "calls": calls,
+            "violation": violation,
+        });
+        if let Err(e) = fs::write(path, report.to_string() + "\n") {
+            eprintln!("❌ Error: could not write {}: {}", path, e);
+            process::exit(1);
+        }
+        println!("   Report written to {}", path);
+    }
+
+    match violation {
+        Some(violation) => {
+            println!("❌ Invariant broken at {}", violation);
+            println!("   Re-run with --seed {} to replay the same traffic", seed);
+            process::exit(1);
+        }
+        None => println!("✅ No invariant broke"),
+    }
+}
+
+/// Result of one soak call
+enum Outcome<T> {
+    Ok(T),
+    /// The contract reverted with an error
+    Rejected(Error),
+    /// The deploy was not executed, e.g. the node was unreachable
+    Failed,
+}
+
+impl<T> Outcome<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Outcome<U> {
+        match self {
+            Outcome::Ok(value) => Outcome::Ok(f(value)),
+            Outcome::Rejected(error) => Outcome::Rejected(error),
+            Outcome::Failed => Outcome::Failed,
+        }
+    }
+}
+
+// Send one call; a panic means the deploy never executed
+fn send<T>(call: impl FnOnce() -> Result<T, Error>) -> Outcome<T> {
+    match panic::catch_unwind(AssertUnwindSafe(call)) {
+        Ok(Ok(value)) => Outcome::Ok(value),
+        Ok(Err(error)) => Outcome::Rejected(error),
+        Err(_) => Outcome::Failed,
+    }
+}
+
+/// xorshift64* generator, so a run can be replayed from its seed
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng(seed.max(1))
+    }
+
+    // Get a number below `bound`
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d) % bound.max(1)
+    }
+}
@@ -0,0 +1,200 @@
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::Error;
+
+/// Roles that can be granted to accounts
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub enum Role {
+    /// Manages roles and protocol configuration
+    Admin,
+    /// Runs day-to-day operations: validators, delegations and reports
+    Operator,
+    /// Can pause and unpause the contract
+    Pauser,
+    /// Extension contract (e.g. a bridge) allowed to mint and burn stCSPR shares
+    Minter,
+    /// Can sell treasury stCSPR through registered swap pools
+    Treasurer,
+    /// Bridge operator minting and burning stCSPR that moves to and from other chains
+    BridgeMinter,
+    /// Can flag large withdrawal requests under review for delayed processing
+    Guardian,
+}
+
+/// Event emitted when a role is granted to an account
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Address,
+    pub sender: Address,
+}
+
+/// Event emitted when a role is revoked from an account
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Address,
+    pub sender: Address,
+}
+
+/// Event emitted when the owner nominates a new owner
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnershipTransferStarted {
+    pub previous_owner: Address,
+    pub new_owner: Address,
+}
+
+/// Event emitted when the nominated owner accepts ownership
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnershipTransferred {
+    pub previous_owner: Address,
+    pub new_owner: Address,
+}
+
+/// AccessControl - contract ownership with two-step transfer and role-based permissions
+///
+/// The owner can always manage roles; accounts with the `Admin` role can too.
+#[odra::module]
+pub struct AccessControl {
+    /// Current owner
+    owner: Var<Address>,
+    /// Owner nominated by `transfer_ownership`, waiting to accept
+    pending_owner: Var<Option<Address>>,
+    /// Granted roles
+    roles: Mapping<(Role, Address), bool>,
+}
+
+#[odra::module]
+impl AccessControl {
+    /// Make `owner` the owner and grant it every role
+    pub fn init(&mut self, owner: Address) {
+        self.owner.set(owner);
+        for role in [Role::Admin, Role::Operator, Role::Pauser] {
+            self.set_role(role, owner, owner, true);
+        }
+    }
+
+    /// Get the current owner
+    pub fn owner(&self) -> Option<Address> {
+        self.owner.get()
+    }
+
+    /// Get the owner nominated by `transfer_ownership`, if any
+    pub fn pending_owner(&self) -> Option<Address> {
+        self.pending_owner.get().flatten()
+    }
+
+    /// Nominate a new owner; ownership moves once they call `accept_ownership`
+    pub fn transfer_ownership(&mut self, caller: &Address, new_owner: Address) -> Result<(), Error> {
+        self.require_owner(caller)?;
+
+        self.pending_owner.set(Some(new_owner));
+        self.env().emit_event(OwnershipTransferStarted {
+            previous_owner: *caller,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Complete an ownership transfer - the caller must be the pending owner
+    pub fn accept_ownership(&mut self, caller: &Address) -> Result<(), Error> {
+        if self.pending_owner() != Some(*caller) {
+            return Err(Error::NotAuthorized);
+        }
+
+        let previous_owner = self.owner.get().ok_or(Error::NotAuthorized)?;
+        self.owner.set(*caller);
+        self.pending_owner.set(None);
+        self.env().emit_event(OwnershipTransferred {
+            previous_owner,
+            new_owner: *caller,
+        });
+        Ok(())
+    }
+
+    /// Move ownership for an action the parent contract has already authorised
+    ///
+    /// Returns the previous owner.
+    pub fn force_transfer(&mut self, new_owner: Address) -> Result<Address, Error> {
+        let previous_owner = self.owner.get().ok_or(Error::NotAuthorized)?;
+        self.owner.set(new_owner);
+        self.pending_owner.set(None);
+        self.env().emit_event(OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(previous_owner)
+    }
+
+    /// Check whether an account holds a role
+    pub fn has_role(&self, role: Role, account: &Address) -> bool {
+        self.roles.get(&(role, *account)).unwrap_or_default()
+    }
+
+    /// Grant a role - the caller must be the owner or an admin
+    pub fn grant_role(&mut self, caller: &Address, role: Role, account: Address) -> Result<(), Error> {
+        self.require_role_manager(caller)?;
+        self.grant(role, account, *caller);
+        Ok(())
+    }
+
+    /// Revoke a role - the caller must be the owner or an admin
+    pub fn revoke_role(&mut self, caller: &Address, role: Role, account: Address) -> Result<(), Error> {
+        self.require_role_manager(caller)?;
+        self.revoke(role, account, *caller);
+        Ok(())
+    }
+
+    /// Grant a role for an action the parent contract has already authorised
+    pub fn grant(&mut self, role: Role, account: Address, sender: Address) {
+        if !self.has_role(role.clone(), &account) {
+            self.set_role(role, account, sender, true);
+        }
+    }
+
+    /// Revoke a role for an action the parent contract has already authorised
+    pub fn revoke(&mut self, role: Role, account: Address, sender: Address) {
+        if self.has_role(role.clone(), &account) {
+            self.set_role(role, account, sender, false);
+        }
+    }
+
+    /// Ensure `account` holds `role`
+    pub fn require_role(&self, role: Role, account: &Address) -> Result<(), Error> {
+        if !self.has_role(role, account) {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    /// Ensure `account` is the owner
+    pub fn require_owner(&self, account: &Address) -> Result<(), Error> {
+        if self.owner.get() != Some(*account) {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    /// Ensure `account` may grant and revoke roles
+    fn require_role_manager(&self, account: &Address) -> Result<(), Error> {
+        if self.owner.get() == Some(*account) || self.has_role(Role::Admin, account) {
+            return Ok(());
+        }
+        Err(Error::NotAuthorized)
+    }
+
+    fn set_role(&mut self, role: Role, account: Address, sender: Address, granted: bool) {
+        self.roles.set(&(role.clone(), account), granted);
+        if granted {
+            self.env().emit_event(RoleGranted { role, account, sender });
+        } else {
+            self.env().emit_event(RoleRevoked { role, account, sender });
+        }
+    }
+}
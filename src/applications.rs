@@ -0,0 +1,187 @@
+use odra::casper_types::PublicKey;
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::Error;
+
+/// Default bond attached to a validator application (100 CSPR, in motes)
+pub const DEFAULT_APPLICATION_BOND: u64 = 100_000_000_000;
+
+/// Longest metadata URI an application can carry, in bytes
+pub const MAX_METADATA_URI_LENGTH: usize = 256;
+
+/// Where a validator application stands
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub enum ApplicationStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A validator's application to join the pool's validator set
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidatorApplication {
+    pub id: u64,
+    pub applicant: Address,
+    pub validator: PublicKey,
+    /// Where the operator publishes its commission, infrastructure and contacts
+    pub metadata_uri: String,
+    /// CSPR bonded with the application, in motes
+    pub bond: U512,
+    pub status: ApplicationStatus,
+    pub submitted_at: u64,
+}
+
+/// Event emitted when a validator applies to join the set
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidatorApplied {
+    pub id: u64,
+    pub applicant: Address,
+    pub validator: PublicKey,
+    pub metadata_uri: String,
+    pub bond: U512,
+}
+
+/// Event emitted when an application is approved and its bond refunded
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ApplicationApproved {
+    pub id: u64,
+    pub validator: PublicKey,
+}
+
+/// Event emitted when an application is rejected and its bond forfeited
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ApplicationRejected {
+    pub id: u64,
+    pub validator: PublicKey,
+    pub bond_forfeited: U512,
+}
+
+/// ValidatorApplications - queue of validators asking to join the set
+///
+/// The queue only does the bookkeeping; registering the validator and moving
+/// bonds is the responsibility of the parent contract.
+#[odra::module]
+pub struct ValidatorApplications {
+    /// Id assigned to the next application
+    next_id: Var<u64>,
+    /// All applications by id
+    applications: Mapping<u64, ValidatorApplication>,
+    /// Whether a validator key has an application pending
+    pending: Mapping<PublicKey, bool>,
+    /// Bonds of pending applications, in motes
+    bonds_held: Var<U512>,
+    /// Bond required with a new application, in motes
+    bond: Var<U512>,
+}
+
+#[odra::module]
+impl ValidatorApplications {
+    /// Queue an application carrying `bond`; returns its id
+    pub fn submit(
+        &mut self,
+        applicant: Address,
+        validator: PublicKey,
+        metadata_uri: String,
+        bond: U512,
+    ) -> Result<u64, Error> {
+        if bond != self.bond() {
+            return Err(Error::AttachedValueMismatch);
+        }
+        if metadata_uri.is_empty() || metadata_uri.len() > MAX_METADATA_URI_LENGTH {
+            return Err(Error::InvalidAmount);
+        }
+        if self.pending.get(&validator).unwrap_or_default() {
+            return Err(Error::ValidatorAlreadyExists);
+        }
+
+        let id = self.next_id.get_or_default();
+        let bonds_held = self
+            .bonds_held()
+            .checked_add(bond)
+            .ok_or(Error::ArithmeticOverflow)?;
+        self.next_id.set(id + 1);
+        self.bonds_held.set(bonds_held);
+        self.pending.set(&validator, true);
+        self.applications.set(
+            &id,
+            ValidatorApplication {
+                id,
+                applicant,
+                validator: validator.clone(),
+                metadata_uri: metadata_uri.clone(),
+                bond,
+                status: ApplicationStatus::Pending,
+                submitted_at: self.env().block_time(),
+            },
+        );
+
+        self.env().emit_event(ValidatorApplied {
+            id,
+            applicant,
+            validator,
+            metadata_uri,
+            bond,
+        });
+        Ok(id)
+    }
+
+    /// Settle a pending application, releasing its bond from the held total
+    ///
+    /// Returns the application as it was submitted.
+    pub fn decide(&mut self, id: u64, approved: bool) -> Result<ValidatorApplication, Error> {
+        let mut application = self.get(id).ok_or(Error::ApplicationNotFound)?;
+        if !matches!(application.status, ApplicationStatus::Pending) {
+            return Err(Error::ApplicationNotPending);
+        }
+
+        let submitted = application.clone();
+        application.status = if approved {
+            ApplicationStatus::Approved
+        } else {
+            ApplicationStatus::Rejected
+        };
+        self.applications.set(&id, application);
+        self.pending.set(&submitted.validator, false);
+        self.bonds_held.set(self.bonds_held().saturating_sub(submitted.bond));
+
+        if approved {
+            self.env().emit_event(ApplicationApproved {
+                id,
+                validator: submitted.validator.clone(),
+            });
+        } else {
+            self.env().emit_event(ApplicationRejected {
+                id,
+                validator: submitted.validator.clone(),
+                bond_forfeited: submitted.bond,
+            });
+        }
+        Ok(submitted)
+    }
+
+    /// Get an application by id
+    pub fn get(&self, id: u64) -> Option<ValidatorApplication> {
+        self.applications.get(&id)
+    }
+
+    /// Get the bonds of all pending applications, in motes
+    pub fn bonds_held(&self) -> U512 {
+        self.bonds_held.get_or_default()
+    }
+
+    /// Get the bond required with a new application, in motes
+    pub fn bond(&self) -> U512 {
+        self.bond.get().unwrap_or(U512::from(DEFAULT_APPLICATION_BOND))
+    }
+
+    /// Set the bond required with new applications
+    pub fn set_bond(&mut self, bond: U512) {
+        self.bond.set(bond);
+    }
+}
@@ -0,0 +1,105 @@
+//! Undelegation batches and their settlement
+//!
+//! CSPR undelegated in the same return era forms a batch. Withdrawal requests
+//! opened since the previous undelegation join the next batch, since that
+//! undelegation is what pays them. Once the batch's era has passed, the
+//! operator settles it with the CSPR the auction actually returned. A
+//! shortfall, e.g. from slashing while the CSPR was unbonding, is drawn from
+//! the insurance fund first. What the fund cannot cover is shared pro rata
+//! between the batch's unclaimed requests and the pool, by how much of the
+//! batch each is owed, and `BatchSettled` records the haircut applied.
+
+use odra::prelude::*;
+use odra::{module::Module, Mapping, Var};
+
+use crate::Error;
+
+/// CSPR undelegated to return in one era, and the requests it pays
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnbondingBatch {
+    /// Era the auction returns the CSPR in
+    pub era: u64,
+    /// CSPR undelegated into the batch, in motes
+    pub expected: U512,
+    /// CSPR the auction actually returned, in motes (zero until settled)
+    pub returned: U512,
+    /// Withdrawal requests the batch pays, oldest first
+    pub requests: Vec<u64>,
+    pub settled: bool,
+}
+
+/// Event emitted when an undelegation batch is settled
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchSettled {
+    pub era: u64,
+    pub expected: U512,
+    pub returned: U512,
+    /// Part of the shortfall the insurance fund covered
+    pub covered: U256,
+    /// Part of the shortfall cut from the batch's unclaimed requests
+    pub haircut: U256,
+    /// Part of the shortfall written off the pooled CSPR
+    pub written_off: U256,
+}
+
+/// UnbondingBatches - expected and returned CSPR of each undelegation batch
+///
+/// Covering shortfalls and cutting requests is the responsibility of the parent contract.
+#[odra::module]
+pub struct UnbondingBatches {
+    /// Requests waiting for the next undelegation
+    waiting: Var<Vec<u64>>,
+    /// Batches by the era they return in
+    batches: Mapping<u64, UnbondingBatch>,
+}
+
+#[odra::module]
+impl UnbondingBatches {
+    /// Queue request `id` for the next batch
+    pub fn enqueue(&mut self, id: u64) {
+        let mut waiting = self.waiting.get_or_default();
+        waiting.push(id);
+        self.waiting.set(waiting);
+    }
+
+    /// Add `amount` undelegated to return in `era`; the waiting requests join its batch
+    pub fn record(&mut self, era: u64, amount: U512) {
+        let mut batch = self.batches.get(&era).unwrap_or(UnbondingBatch {
+            era,
+            expected: U512::zero(),
+            returned: U512::zero(),
+            requests: Vec::new(),
+            settled: false,
+        });
+        batch.expected = batch.expected.saturating_add(amount);
+        batch.requests.extend(self.waiting.get_or_default());
+        self.waiting.set(Vec::new());
+        self.batches.set(&era, batch);
+    }
+
+    /// Get the batch returning in `era`
+    pub fn get(&self, era: u64) -> Option<UnbondingBatch> {
+        self.batches.get(&era)
+    }
+
+    /// Get the requests waiting for the next undelegation
+    pub fn waiting(&self) -> Vec<u64> {
+        self.waiting.get_or_default()
+    }
+
+    /// Record that the batch of `era` returned `returned`; returns the batch before settling
+    pub fn settle(&mut self, era: u64, returned: U512) -> Result<UnbondingBatch, Error> {
+        let mut batch = self.get(era).filter(|batch| !batch.settled).ok_or(Error::BatchNotFound)?;
+        if returned > batch.expected {
+            return Err(Error::ExceedsMaximum);
+        }
+
+        let unsettled = batch.clone();
+        batch.returned = returned;
+        batch.settled = true;
+        self.batches.set(&era, batch);
+        Ok(unsettled)
+    }
+}
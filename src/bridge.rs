@@ -0,0 +1,141 @@
+//! Supply accounting for a canonical bridge
+//!
+//! A bridge operator burns stCSPR here when it moves to another chain and
+//! mints it back when it returns. The shares burned and not yet minted back
+//! are the bridged-out supply, locked behind the remote representation. They
+//! still count in the exchange rate, so bridging never moves the rate, and no
+//! more can be minted back than was burned. Mints are also capped per
+//! operator per day, and each origin transaction can be minted once.
+
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::Error;
+
+/// Length of the period a bridge operator's cap applies to (one day, in milliseconds)
+pub const BRIDGE_DAY: u64 = 86_400_000;
+
+/// Longest origin transaction or destination a bridge call can carry, in bytes
+pub const MAX_BRIDGE_REFERENCE_LENGTH: usize = 128;
+
+/// Event emitted when a bridge operator mints stCSPR returning from another chain
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct BridgeMinted {
+    pub operator: Address,
+    pub recipient: Address,
+    pub amount: U256,
+    /// Transaction that released the stCSPR on the other chain
+    pub origin_tx: String,
+    /// stCSPR still bridged out after the mint
+    pub bridged_out: U256,
+}
+
+/// Event emitted when a bridge operator burns stCSPR leaving for another chain
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct BridgeBurned {
+    pub operator: Address,
+    pub owner: Address,
+    pub amount: U256,
+    /// Recipient on the other chain
+    pub destination: String,
+    /// stCSPR bridged out after the burn
+    pub bridged_out: U256,
+}
+
+/// Event emitted when a bridge operator's daily mint cap changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct BridgeCapSet {
+    pub operator: Address,
+    pub daily_cap: U256,
+}
+
+/// Bridge - bridged-out supply, daily mint caps and processed origin transactions
+///
+/// Minting and burning the shares is the responsibility of the parent contract.
+#[odra::module]
+pub struct Bridge {
+    /// stCSPR burned to other chains and not yet minted back
+    bridged_out: Var<U256>,
+    /// Most stCSPR each operator can mint per day (zero means none)
+    daily_caps: Mapping<Address, U256>,
+    /// stCSPR each operator minted on each day
+    minted: Mapping<(Address, u64), U256>,
+    /// Origin transactions already minted
+    processed: Mapping<String, bool>,
+}
+
+#[odra::module]
+impl Bridge {
+    /// Get the stCSPR burned to other chains and not yet minted back
+    pub fn bridged_out(&self) -> U256 {
+        self.bridged_out.get_or_default()
+    }
+
+    /// Get the most stCSPR an operator can mint per day
+    pub fn daily_cap(&self, operator: &Address) -> U256 {
+        self.daily_caps.get(operator).unwrap_or_default()
+    }
+
+    /// Set the most stCSPR an operator can mint per day
+    pub fn set_daily_cap(&mut self, operator: Address, daily_cap: U256) {
+        self.daily_caps.set(&operator, daily_cap);
+        self.env().emit_event(BridgeCapSet { operator, daily_cap });
+    }
+
+    /// Get the stCSPR an operator minted on `day`
+    pub fn minted_on(&self, operator: &Address, day: u64) -> U256 {
+        self.minted.get(&(*operator, day)).unwrap_or_default()
+    }
+
+    /// Check whether an origin transaction has been minted
+    pub fn is_processed(&self, origin_tx: &str) -> bool {
+        self.processed.get(&origin_tx.to_string()).unwrap_or_default()
+    }
+
+    /// Record a mint of `amount` for `origin_tx` by `operator` on `day`
+    ///
+    /// Returns the stCSPR still bridged out.
+    pub fn record_mint(&mut self, operator: Address, origin_tx: &str, amount: U256, day: u64) -> Result<U256, Error> {
+        check_reference(origin_tx)?;
+        if self.is_processed(origin_tx) {
+            return Err(Error::BridgeTxProcessed);
+        }
+        let minted = self
+            .minted_on(&operator, day)
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        if minted > self.daily_cap(&operator) {
+            return Err(Error::BridgeCapReached);
+        }
+        let bridged_out = self
+            .bridged_out()
+            .checked_sub(amount)
+            .ok_or(Error::BridgeSupplyExceeded)?;
+
+        self.processed.set(&origin_tx.to_string(), true);
+        self.minted.set(&(operator, day), minted);
+        self.bridged_out.set(bridged_out);
+        Ok(bridged_out)
+    }
+
+    /// Record a burn of `amount` to `destination`; returns the stCSPR bridged out
+    pub fn record_burn(&mut self, destination: &str, amount: U256) -> Result<U256, Error> {
+        check_reference(destination)?;
+        let bridged_out = self
+            .bridged_out()
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        self.bridged_out.set(bridged_out);
+        Ok(bridged_out)
+    }
+}
+
+fn check_reference(reference: &str) -> Result<(), Error> {
+    if reference.is_empty() || reference.len() > MAX_BRIDGE_REFERENCE_LENGTH {
+        return Err(Error::InvalidAmount);
+    }
+    Ok(())
+}
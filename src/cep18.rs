@@ -0,0 +1,72 @@
+//! CEP-18 token events
+//!
+//! Names and fields follow the CEP-18 reference implementation so wallets and
+//! explorers indexing CES events recognize stCSPR mints, burns, transfers and
+//! allowance changes.
+
+use odra::prelude::*;
+use odra::Address;
+
+/// Event emitted when stCSPR is minted
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mint {
+    pub recipient: Address,
+    pub amount: U256,
+}
+
+/// Event emitted when stCSPR is burned
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Burn {
+    pub owner: Address,
+    pub amount: U256,
+}
+
+/// Event emitted when an owner sets a spender's allowance
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetAllowance {
+    pub owner: Address,
+    pub spender: Address,
+    pub allowance: U256,
+}
+
+/// Event emitted when tokens are transferred by their owner
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transfer {
+    pub sender: Address,
+    pub recipient: Address,
+    pub amount: U256,
+}
+
+/// Event emitted when a spender transfers tokens on behalf of an owner
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransferFrom {
+    pub spender: Address,
+    pub owner: Address,
+    pub recipient: Address,
+    pub amount: U256,
+}
+
+/// Event emitted when an owner raises a spender's allowance
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct IncreaseAllowance {
+    pub owner: Address,
+    pub spender: Address,
+    pub allowance: U256,
+    pub inc_by: U256,
+}
+
+/// Event emitted when an owner lowers a spender's allowance
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecreaseAllowance {
+    pub owner: Address,
+    pub spender: Address,
+    pub allowance: U256,
+    pub decr_by: U256,
+}
@@ -0,0 +1,94 @@
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+/// A value as of a block time
+#[odra::odra_type]
+pub struct Checkpoint {
+    pub timestamp: u64,
+    pub value: U256,
+}
+
+/// Checkpoints - history of stCSPR balances and total supply
+///
+/// Every write appends a checkpoint, or overwrites the last one if it was taken
+/// at the same block time, so historical values can be looked up by binary search.
+/// Keeping the live balances is the responsibility of the parent contract.
+#[odra::module]
+pub struct Checkpoints {
+    /// Number of checkpoints recorded for each account
+    balance_counts: Mapping<Address, u32>,
+    /// Balance checkpoints by (account, index)
+    balances: Mapping<(Address, u32), Checkpoint>,
+    /// Number of total supply checkpoints
+    supply_count: Var<u32>,
+    /// Total supply checkpoints by index
+    supply: Mapping<u32, Checkpoint>,
+}
+
+#[odra::module]
+impl Checkpoints {
+    /// Record an account's new balance
+    pub fn record_balance(&mut self, account: &Address, value: U256) {
+        let now = self.env().block_time();
+        let count = self.balance_counts.get(account).unwrap_or_default();
+
+        if count > 0 {
+            if let Some(last) = self.balances.get(&(*account, count - 1)) {
+                if last.timestamp == now {
+                    self.balances.set(&(*account, count - 1), Checkpoint { timestamp: now, value });
+                    return;
+                }
+            }
+        }
+
+        self.balances.set(&(*account, count), Checkpoint { timestamp: now, value });
+        self.balance_counts.set(account, count + 1);
+    }
+
+    /// Record the new total supply
+    pub fn record_total_supply(&mut self, value: U256) {
+        let now = self.env().block_time();
+        let count = self.supply_count.get_or_default();
+
+        if count > 0 {
+            if let Some(last) = self.supply.get(&(count - 1)) {
+                if last.timestamp == now {
+                    self.supply.set(&(count - 1), Checkpoint { timestamp: now, value });
+                    return;
+                }
+            }
+        }
+
+        self.supply.set(&count, Checkpoint { timestamp: now, value });
+        self.supply_count.set(count + 1);
+    }
+
+    /// Get an account's balance at the end of block time `timestamp`
+    pub fn balance_at(&self, account: &Address, timestamp: u64) -> U256 {
+        let count = self.balance_counts.get(account).unwrap_or_default();
+        search(count, timestamp, |index| self.balances.get(&(*account, index)))
+    }
+
+    /// Get the total supply at the end of block time `timestamp`
+    pub fn total_supply_at(&self, timestamp: u64) -> U256 {
+        let count = self.supply_count.get_or_default();
+        search(count, timestamp, |index| self.supply.get(&index))
+    }
+}
+
+/// Binary search for the value of the last checkpoint taken at or before `timestamp`
+fn search<F: Fn(u32) -> Option<Checkpoint>>(count: u32, timestamp: u64, get: F) -> U256 {
+    let (mut low, mut high) = (0u32, count);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match get(mid) {
+            Some(checkpoint) if checkpoint.timestamp <= timestamp => low = mid + 1,
+            _ => high = mid,
+        }
+    }
+
+    if low == 0 {
+        return U256::zero();
+    }
+    get(low - 1).map(|checkpoint| checkpoint.value).unwrap_or_default()
+}
@@ -0,0 +1,514 @@
+//! Client for a deployed contract
+//!
+//! Reads stCSPR state straight from a Casper 1.x node's RPC, so frontends and
+//! bots do not have to reproduce Odra's storage layout. Odra keeps every field
+//! in the contract's `state` dictionary under `hex(blake2b(field path ++ key))`,
+//! where the field path is the field's index in the module (nested modules
+//! shift their parent's index left by four bits) and the key is the mapping
+//! key's bytes, both hex encoded.
+//!
+//! Field indices follow declaration order in `CasperLiquid`; they must be kept
+//! in sync if fields are ever appended before the ones read here.
+//!
+//! Heavy views (balance history, withdrawal requests, reward reports) are read
+//! a page at a time. With `with_cache_ttl`, reads are cached; once an entry's
+//! TTL runs out it is revalidated against the contract's event count, which
+//! every state change bumps, and only refetched if new events were emitted.
+//!
+//! The client does not sign anything. It can submit deploys signed elsewhere
+//! and report whether they executed, which `deploy_queue` builds on.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use odra::casper_types::bytesrepr::{Bytes, FromBytes, ToBytes};
+use odra::casper_types::crypto;
+use odra::prelude::*;
+use odra::Address;
+use serde_json::{json, Value};
+
+use crate::checkpoints::Checkpoint;
+use crate::rewards::RewardReport;
+use crate::withdrawals::WithdrawalRequest;
+use crate::{motes, RATE_PRECISION};
+
+/// Name of the dictionary Odra stores contract state in
+pub const STATE_DICTIONARY: &str = "state";
+
+/// Index of `CasperLiquid::balances`
+const BALANCES: u32 = 1;
+/// Index of `CasperLiquid::total_staked`
+const TOTAL_STAKED: u32 = 3;
+/// Index of `CasperLiquid::contract_cspr_balance`
+const CONTRACT_CSPR_BALANCE: u32 = 4;
+/// Index of `CasperLiquid::rewards`
+const REWARDS: u32 = 15;
+/// Index of `Rewards::current_epoch`
+const REWARDS_CURRENT_EPOCH: u32 = 1;
+/// Index of `Rewards::reports`
+const REWARDS_REPORTS: u32 = 2;
+/// Index of `CasperLiquid::withdrawals`
+const WITHDRAWALS: u32 = 16;
+/// Index of `WithdrawalQueue::next_id`
+const WITHDRAWALS_NEXT_ID: u32 = 1;
+/// Index of `WithdrawalQueue::requests`
+const WITHDRAWALS_REQUESTS: u32 = 2;
+/// Index of `WithdrawalQueue::total_pending`
+const WITHDRAWALS_TOTAL_PENDING: u32 = 4;
+/// Index of `CasperLiquid::checkpoints`
+const CHECKPOINTS: u32 = 28;
+/// Index of `Checkpoints::balance_counts`
+const CHECKPOINTS_BALANCE_COUNTS: u32 = 1;
+/// Index of `Checkpoints::balances`
+const CHECKPOINTS_BALANCES: u32 = 2;
+
+/// Named key Odra keeps the number of emitted events under
+pub const EVENTS_LENGTH_KEY: &str = "__events_length";
+
+/// Most items read for one page
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+/// Errors raised while reading contract state
+#[derive(Debug)]
+pub enum ClientError {
+    /// The node could not be reached or returned an error
+    Http(String),
+    /// A node response did not have the expected shape
+    UnexpectedResponse(String),
+    /// A stored value could not be decoded as the expected type
+    Decode(String),
+    /// A submitted deploy failed or never executed
+    DeployFailed(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Http(message) => write!(f, "node request failed: {}", message),
+            ClientError::UnexpectedResponse(message) => write!(f, "unexpected node response: {}", message),
+            ClientError::Decode(message) => write!(f, "could not decode stored value: {}", message),
+            ClientError::DeployFailed(message) => write!(f, "deploy failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(error: reqwest::Error) -> Self {
+        ClientError::Http(error.to_string())
+    }
+}
+
+/// One page of a paginated view
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor of the next page, `None` on the last page
+    pub next_cursor: Option<u64>,
+}
+
+/// Execution state of a submitted deploy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeployStatus {
+    /// Not executed yet, or not known to the node
+    Pending,
+    Succeeded,
+    /// Executed and reverted, with the node's error message
+    Failed(String),
+}
+
+/// Client for one deployed contract
+pub struct Client {
+    http: reqwest::Client,
+    rpc_url: String,
+    /// Hash of the contract version being read, formatted as `hash-...`
+    contract_hash: String,
+    cache: ResponseCache,
+}
+
+impl Client {
+    /// Connect to the latest version of the contract package `package_hash` (`hash-...`)
+    pub async fn connect(node_address: &str, package_hash: &str) -> Result<Self, ClientError> {
+        let mut client = Client {
+            http: reqwest::Client::new(),
+            rpc_url: format!("{}/rpc", node_address.trim_end_matches('/')),
+            contract_hash: String::new(),
+            cache: ResponseCache::new(Duration::ZERO),
+        };
+
+        let package = client
+            .rpc("query_global_state", json!({ "state_identifier": null, "key": package_hash, "path": [] }))
+            .await?;
+        let contract_hash = package["stored_value"]["ContractPackage"]["versions"]
+            .as_array()
+            .and_then(|versions| versions.last())
+            .and_then(|version| version["contract_hash"].as_str())
+            .ok_or_else(|| ClientError::UnexpectedResponse(format!("{} is not a contract package", package_hash)))?;
+
+        // Contract hashes are listed as contract-<hex> but addressed as hash-<hex>
+        client.contract_hash = contract_hash.replacen("contract-", "hash-", 1);
+        Ok(client)
+    }
+
+    /// Cache reads for `ttl` before revalidating them against the event count
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache = ResponseCache::new(ttl);
+        self
+    }
+
+    /// Get the number of events the contract has emitted
+    ///
+    /// Every state change emits at least one event, so an unchanged count
+    /// means cached state is still current.
+    pub async fn events_length(&self) -> Result<u32, ClientError> {
+        let params = json!({ "state_identifier": null, "key": self.contract_hash, "path": [EVENTS_LENGTH_KEY] });
+        let result = self.rpc("query_global_state", params).await?;
+        decode_stored_value(&result["stored_value"]["CLValue"])
+    }
+
+    /// Get the stCSPR balance of an address
+    pub async fn balance_of(&self, address: &Address) -> Result<U256, ClientError> {
+        let key = address.to_bytes().map_err(|e| ClientError::Decode(format!("{:?}", e)))?;
+        self.read_or_default(BALANCES, &key).await
+    }
+
+    /// Get the total supply of stCSPR
+    pub async fn total_supply(&self) -> Result<U256, ClientError> {
+        self.read_or_default(TOTAL_STAKED, &[]).await
+    }
+
+    /// Get the value of one stCSPR in CSPR, scaled by `RATE_PRECISION`
+    pub async fn exchange_rate(&self) -> Result<U256, ClientError> {
+        let total_shares = self.total_supply().await?;
+        if total_shares.is_zero() {
+            return Ok(U256::from(RATE_PRECISION));
+        }
+        let pooled: U512 = self.read_or_default(CONTRACT_CSPR_BALANCE, &[]).await?;
+        Ok(to_amount(pooled)? * U256::from(RATE_PRECISION) / total_shares)
+    }
+
+    /// Get the CSPR owed to all unclaimed withdrawal requests
+    pub async fn pending_withdrawals(&self) -> Result<U256, ClientError> {
+        let pending: U512 = self.read_or_default((WITHDRAWALS << 4) + WITHDRAWALS_TOTAL_PENDING, &[]).await?;
+        to_amount(pending)
+    }
+
+    /// Get a page of an account's balance checkpoints, oldest first
+    pub async fn balance_history(&self, address: &Address, cursor: u64, limit: u64) -> Result<Page<Checkpoint>, ClientError> {
+        let owner = address.to_bytes().map_err(|e| ClientError::Decode(format!("{:?}", e)))?;
+        let count: u32 = self.read_or_default((CHECKPOINTS << 4) + CHECKPOINTS_BALANCE_COUNTS, &owner).await?;
+        self.read_page((CHECKPOINTS << 4) + CHECKPOINTS_BALANCES, cursor, count as u64, limit, |position| {
+            let mut key = owner.clone();
+            key.extend((position as u32).to_bytes().map_err(|e| ClientError::Decode(format!("{:?}", e)))?);
+            Ok(key)
+        })
+        .await
+    }
+
+    /// Get a page of withdrawal requests by id, claimed ones included
+    pub async fn withdrawal_requests(&self, cursor: u64, limit: u64) -> Result<Page<WithdrawalRequest>, ClientError> {
+        let next_id: u64 = self.read_or_default((WITHDRAWALS << 4) + WITHDRAWALS_NEXT_ID, &[]).await?;
+        self.read_page((WITHDRAWALS << 4) + WITHDRAWALS_REQUESTS, cursor, next_id, limit, u64_key).await
+    }
+
+    /// Get a page of reward reports, which checkpoint the exchange rate, by epoch
+    ///
+    /// Epochs start at one; a zero cursor reads from the first epoch.
+    pub async fn reward_reports(&self, cursor: u64, limit: u64) -> Result<Page<RewardReport>, ClientError> {
+        let current_epoch: u64 = self.read_or_default((REWARDS << 4) + REWARDS_CURRENT_EPOCH, &[]).await?;
+        self.read_page((REWARDS << 4) + REWARDS_REPORTS, cursor.max(1), current_epoch + 1, limit, u64_key).await
+    }
+
+    /// Submit a signed deploy, given as the node's JSON deploy format; returns its hash
+    pub async fn put_deploy(&self, deploy: Value) -> Result<String, ClientError> {
+        let result = self.rpc("account_put_deploy", json!({ "deploy": deploy })).await?;
+        result["deploy_hash"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ClientError::UnexpectedResponse("account_put_deploy returned no deploy_hash".to_string()))
+    }
+
+    /// Get whether a submitted deploy has executed
+    pub async fn deploy_status(&self, deploy_hash: &str) -> Result<DeployStatus, ClientError> {
+        match self.rpc("info_get_deploy", json!({ "deploy_hash": deploy_hash })).await {
+            Ok(result) => Ok(parse_deploy_status(&result)),
+            // Dropped and not yet gossiped deploys are unknown to the node
+            Err(ClientError::Http(message)) if message.contains("No such deploy") => Ok(DeployStatus::Pending),
+            Err(error) => Err(error),
+        }
+    }
+
+    // Read the items of a mapping at positions `cursor..end`, at most `limit` of them
+    async fn read_page<T: FromBytes>(
+        &self,
+        index: u32,
+        cursor: u64,
+        end: u64,
+        limit: u64,
+        key: impl Fn(u64) -> Result<Vec<u8>, ClientError>,
+    ) -> Result<Page<T>, ClientError> {
+        let (positions, next_cursor) = page_range(cursor, end, limit);
+        let mut items = Vec::new();
+        for position in positions {
+            if let Some(item) = self.read(index, &key(position)?).await? {
+                items.push(item);
+            }
+        }
+        Ok(Page { items, next_cursor })
+    }
+
+    // Read a field, or its default if it was never written
+    async fn read_or_default<T: FromBytes + Default>(&self, index: u32, key: &[u8]) -> Result<T, ClientError> {
+        Ok(self.read(index, key).await?.unwrap_or_default())
+    }
+
+    // Read a field, or `None` if it was never written
+    async fn read<T: FromBytes>(&self, index: u32, key: &[u8]) -> Result<Option<T>, ClientError> {
+        let item_key = state_key(index, key);
+        let cl_value = match self.cache.fresh(&item_key) {
+            Some(cl_value) => cl_value,
+            None => self.fetch_cached(&item_key).await?,
+        };
+        if cl_value.is_null() {
+            return Ok(None);
+        }
+        decode_stored_value(&cl_value).map(Some)
+    }
+
+    // Fetch a dictionary item unless the event count shows the cached copy is current
+    async fn fetch_cached(&self, item_key: &str) -> Result<Value, ClientError> {
+        let events = if self.cache.is_enabled() {
+            let events = self.events_length().await?;
+            if let Some(cl_value) = self.cache.revalidate(item_key, events) {
+                return Ok(cl_value);
+            }
+            Some(events)
+        } else {
+            None
+        };
+
+        let cl_value = self.fetch(item_key).await?;
+        if let Some(events) = events {
+            self.cache.store(item_key, events, cl_value.clone());
+        }
+        Ok(cl_value)
+    }
+
+    // Fetch a dictionary item's CLValue, or `Null` if it was never written
+    async fn fetch(&self, item_key: &str) -> Result<Value, ClientError> {
+        let params = json!({
+            "state_root_hash": null,
+            "dictionary_identifier": {
+                "ContractNamedKey": {
+                    "key": self.contract_hash,
+                    "dictionary_name": STATE_DICTIONARY,
+                    "dictionary_item_key": item_key,
+                }
+            }
+        });
+
+        match self.rpc("state_get_dictionary_item", params).await {
+            Ok(result) => Ok(result["stored_value"]["CLValue"].clone()),
+            // The node reports an unwritten dictionary item as an error
+            Err(ClientError::Http(message)) if message.contains("ValueNotFound") => Ok(Value::Null),
+            Err(error) => Err(error),
+        }
+    }
+
+    // Send a JSON-RPC request and return its result
+    async fn rpc(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .http
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(ClientError::Http(error.to_string()));
+        }
+        Ok(response["result"].clone())
+    }
+}
+
+/// Dictionary items read by a client, tagged with the event count they were read at
+struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+struct CacheEntry {
+    read_at: Instant,
+    events: u32,
+    cl_value: Value,
+}
+
+impl ResponseCache {
+    fn new(ttl: Duration) -> Self {
+        ResponseCache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    // Get an entry read less than a TTL ago
+    fn fresh(&self, item_key: &str) -> Option<Value> {
+        let entries = self.entries.lock().ok()?;
+        let entry = entries.get(item_key)?;
+        (entry.read_at.elapsed() < self.ttl).then(|| entry.cl_value.clone())
+    }
+
+    // Get an entry read at `events`, restarting its TTL
+    fn revalidate(&self, item_key: &str, events: u32) -> Option<Value> {
+        let mut entries = self.entries.lock().ok()?;
+        let entry = entries.get_mut(item_key).filter(|entry| entry.events == events)?;
+        entry.read_at = Instant::now();
+        Some(entry.cl_value.clone())
+    }
+
+    fn store(&self, item_key: &str, events: u32, cl_value: Value) {
+        if let Ok(mut entries) = self.entries.lock() {
+            let entry = CacheEntry {
+                read_at: Instant::now(),
+                events,
+                cl_value,
+            };
+            entries.insert(item_key.to_string(), entry);
+        }
+    }
+}
+
+/// Get the positions of a page starting at `cursor`, and the cursor of the next page
+///
+/// Pages hold at most `MAX_PAGE_SIZE` items, whatever `limit` asks for.
+pub fn page_range(cursor: u64, end: u64, limit: u64) -> (Range<u64>, Option<u64>) {
+    let stop = end.min(cursor.saturating_add(limit.min(MAX_PAGE_SIZE)));
+    let start = cursor.min(stop);
+    (start..stop, (stop < end).then_some(stop))
+}
+
+/// Get the `state` dictionary item key of a field, given its index path and mapping key
+pub fn state_key(index: u32, key: &[u8]) -> String {
+    let mut preimage = format!("{:08x}", index).into_bytes();
+    preimage.extend(hex_encode(key).into_bytes());
+    hex_encode(&crypto::blake2b(preimage))
+}
+
+// Read the execution state from an `info_get_deploy` result
+fn parse_deploy_status(result: &Value) -> DeployStatus {
+    let Some(execution) = result["execution_results"].as_array().and_then(|results| results.first()) else {
+        return DeployStatus::Pending;
+    };
+    match execution["result"].get("Failure") {
+        Some(failure) => DeployStatus::Failed(failure["error_message"].as_str().unwrap_or("unknown error").to_string()),
+        None => DeployStatus::Succeeded,
+    }
+}
+
+// Decode a stored CLValue, which holds the field's bytes either raw or as `Bytes`
+fn decode_stored_value<T: FromBytes>(cl_value: &Value) -> Result<T, ClientError> {
+    let hex = cl_value["bytes"]
+        .as_str()
+        .ok_or_else(|| ClientError::UnexpectedResponse("stored value is not a CLValue".to_string()))?;
+    let bytes = hex_decode(hex).ok_or_else(|| ClientError::Decode(format!("invalid hex {}", hex)))?;
+
+    let raw = if cl_value["cl_type"] == json!({ "List": "U8" }) {
+        Bytes::from_bytes(&bytes)
+            .map(|(inner, _)| inner.to_vec())
+            .map_err(|e| ClientError::Decode(format!("{:?}", e)))?
+    } else {
+        bytes
+    };
+    T::from_bytes(&raw)
+        .map(|(value, _)| value)
+        .map_err(|e| ClientError::Decode(format!("{:?}", e)))
+}
+
+fn u64_key(position: u64) -> Result<Vec<u8>, ClientError> {
+    position.to_bytes().map_err(|e| ClientError::Decode(format!("{:?}", e)))
+}
+
+// Custody amounts are stored in motes; reads return token amounts
+fn to_amount(stored: U512) -> Result<U256, ClientError> {
+    motes::to_amount(stored).map_err(|_| ClientError::Decode(format!("{} motes do not fit into a U256", stored)))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_stored_value_accepts_raw_and_wrapped_bytes() {
+        let amount = U256::from(123_456u64);
+        let raw = amount.to_bytes().unwrap();
+        let wrapped = Bytes::from(raw.clone()).to_bytes().unwrap();
+
+        let any = json!({ "cl_type": "Any", "bytes": hex_encode(&raw) });
+        let list = json!({ "cl_type": { "List": "U8" }, "bytes": hex_encode(&wrapped) });
+        assert_eq!(decode_stored_value::<U256>(&any).unwrap(), amount);
+        assert_eq!(decode_stored_value::<U256>(&list).unwrap(), amount);
+    }
+
+    #[test]
+    fn test_page_range_walks_to_the_end() {
+        assert_eq!(page_range(0, 5, 2), (0..2, Some(2)));
+        assert_eq!(page_range(4, 5, 2), (4..5, None));
+        assert_eq!(page_range(7, 5, 2), (5..5, None));
+        assert_eq!(page_range(0, 500, 1_000), (0..MAX_PAGE_SIZE, Some(MAX_PAGE_SIZE)));
+    }
+
+    #[test]
+    fn test_cache_revalidates_against_event_count() {
+        let cache = ResponseCache::new(Duration::ZERO);
+        cache.store("item", 3, json!({ "bytes": "01" }));
+        assert_eq!(cache.fresh("item"), None);
+        assert_eq!(cache.revalidate("item", 4), None);
+        assert_eq!(cache.revalidate("item", 3), Some(json!({ "bytes": "01" })));
+
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        cache.store("item", 3, Value::Null);
+        assert_eq!(cache.fresh("item"), Some(Value::Null));
+    }
+
+    #[test]
+    fn test_parse_deploy_status() {
+        assert_eq!(parse_deploy_status(&json!({ "execution_results": [] })), DeployStatus::Pending);
+        let succeeded = json!({ "execution_results": [{ "result": { "Success": { "cost": "100" } } }] });
+        assert_eq!(parse_deploy_status(&succeeded), DeployStatus::Succeeded);
+        let failed = json!({ "execution_results": [{ "result": { "Failure": { "error_message": "User error: 12" } } }] });
+        assert_eq!(parse_deploy_status(&failed), DeployStatus::Failed("User error: 12".to_string()));
+    }
+
+    #[test]
+    fn test_state_key_depends_on_index_and_key() {
+        assert_eq!(state_key(BALANCES, &[1, 2]).len(), 64);
+        assert_ne!(state_key(BALANCES, &[1, 2]), state_key(BALANCES, &[1, 3]));
+        assert_ne!(state_key(TOTAL_STAKED, &[]), state_key(CONTRACT_CSPR_BALANCE, &[]));
+    }
+}
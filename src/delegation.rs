@@ -0,0 +1,384 @@
+use odra::casper_types::PublicKey;
+use odra::prelude::*;
+use odra::{module::Module, Mapping, Var};
+
+use crate::staking::{Backend, StakingBackend};
+use crate::{Error, BPS_DENOMINATOR};
+
+/// Largest share of a validator's rewards the pool rebates to its operator (10%)
+pub const MAX_REBATE_BPS: u16 = 1_000;
+
+/// A validator's share of the delegation strategy and its current position
+#[odra::odra_type]
+pub struct ValidatorAllocation {
+    pub validator: PublicKey,
+    /// Target weight, relative to the sum of all weights
+    pub weight: u32,
+    /// CSPR currently delegated
+    pub delegated: U512,
+    /// CSPR the validator should hold under the current weights
+    pub target: U512,
+}
+
+/// Event emitted when a validator is added to the set
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidatorAdded {
+    pub validator: PublicKey,
+}
+
+/// Event emitted when a validator is removed from the set
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidatorRemoved {
+    pub validator: PublicKey,
+}
+
+/// Event emitted when a validator's target weight changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidatorWeightSet {
+    pub validator: PublicKey,
+    pub weight: u32,
+}
+
+/// Event emitted when pooled CSPR is delegated to a validator
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delegated {
+    pub validator: PublicKey,
+    pub amount: U512,
+}
+
+/// Event emitted when CSPR is undelegated from a validator
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Undelegated {
+    pub validator: PublicKey,
+    pub amount: U512,
+}
+
+/// Event emitted when CSPR is moved from one validator to another
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Redelegated {
+    pub from: PublicKey,
+    pub to: PublicKey,
+    pub amount: U512,
+}
+
+/// Event emitted when rewards compounded by the auction are synced for a validator
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compounded {
+    pub validator: PublicKey,
+    pub amount: U512,
+    /// Delegation after the rewards
+    pub delegated: U512,
+}
+
+/// Event emitted when a validator's operator rebate changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RebateRateSet {
+    pub validator: PublicKey,
+    pub rebate_bps: u16,
+}
+
+/// Delegation - tracks the validator set and the CSPR delegated to each validator
+///
+/// This module performs the actual staking calls through the composed
+/// `staking::Backend`. Authorization is the responsibility of the parent contract.
+#[odra::module]
+pub struct Delegation {
+    /// Validators the pool is allowed to delegate to
+    validators: Var<Vec<PublicKey>>,
+    /// Amount of CSPR currently delegated to each validator
+    delegated: Mapping<PublicKey, U512>,
+    /// Sum of all delegated amounts
+    total_delegated: Var<U512>,
+    /// Target weight of each validator in the delegation strategy
+    weights: Mapping<PublicKey, u32>,
+    /// Auction rewards synced for each validator since it joined the pool
+    rewards_earned: Mapping<PublicKey, U512>,
+    /// Share of each validator's rewards rebated to its operator, in basis points
+    rebate_bps: Mapping<PublicKey, u16>,
+    /// Rebate each operator has accrued and not claimed yet, in motes
+    rebates_owed: Mapping<PublicKey, U512>,
+}
+
+#[odra::module]
+impl Delegation {
+    /// Register a validator the pool may delegate to
+    pub fn add_validator(&mut self, validator: PublicKey) -> Result<(), Error> {
+        let mut validators = self.validators.get_or_default();
+        if validators.contains(&validator) {
+            return Err(Error::ValidatorAlreadyExists);
+        }
+
+        validators.push(validator.clone());
+        self.validators.set(validators);
+        self.env().emit_event(ValidatorAdded { validator });
+        Ok(())
+    }
+
+    /// Remove a validator from the set - all stake must be undelegated first
+    pub fn remove_validator(&mut self, validator: &PublicKey) -> Result<(), Error> {
+        let mut validators = self.validators.get_or_default();
+        let index = validators
+            .iter()
+            .position(|v| v == validator)
+            .ok_or(Error::ValidatorNotFound)?;
+
+        if self.delegated_to(validator) > U512::zero() {
+            return Err(Error::ValidatorHasDelegations);
+        }
+
+        validators.remove(index);
+        self.validators.set(validators);
+        self.weights.set(validator, 0);
+        self.env().emit_event(ValidatorRemoved {
+            validator: validator.clone(),
+        });
+        Ok(())
+    }
+
+    /// Delegate CSPR from the contract purse to a registered validator
+    pub fn delegate(&mut self, validator: PublicKey, amount: U512) -> Result<(), Error> {
+        // CHECKS: Validator must be registered; the parent checks the CSPR is free to delegate
+        if amount == U512::zero() {
+            return Err(Error::InvalidAmount);
+        }
+        if !self.is_validator(&validator) {
+            return Err(Error::ValidatorNotFound);
+        }
+
+        let new_delegated = self
+            .delegated_to(&validator)
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        let new_total = self
+            .total_delegated()
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        // EFFECTS: Update accounting
+        self.delegated.set(&validator, new_delegated);
+        self.total_delegated.set(new_total);
+
+        // INTERACTIONS: Stake through the backend
+        Backend::delegate(&self.env(), validator.clone(), amount);
+        self.env().emit_event(Delegated { validator, amount });
+
+        Ok(())
+    }
+
+    /// Undelegate CSPR from a validator; funds return to the contract purse
+    /// once the auction unbonding period has elapsed
+    pub fn undelegate(&mut self, validator: PublicKey, amount: U512) -> Result<(), Error> {
+        // CHECKS: Cannot undelegate more than was delegated
+        if amount == U512::zero() {
+            return Err(Error::InvalidAmount);
+        }
+
+        let current = self.delegated_to(&validator);
+        if current < amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let new_delegated = current - amount;
+        let new_total = self
+            .total_delegated()
+            .checked_sub(amount)
+            .ok_or(Error::ArithmeticUnderflow)?;
+
+        // EFFECTS: Update accounting
+        self.delegated.set(&validator, new_delegated);
+        self.total_delegated.set(new_total);
+
+        // INTERACTIONS: Unstake through the backend
+        Backend::undelegate(&self.env(), validator.clone(), amount);
+        self.env().emit_event(Undelegated { validator, amount });
+
+        Ok(())
+    }
+
+    /// Move CSPR delegated to `from` over to `to`
+    ///
+    /// The stake on `from` is undelegated and `to` is delegated from the contract
+    /// purse, so the parent checks that `amount` is free to delegate. The
+    /// undelegated CSPR returns to the purse after the unbonding period.
+    pub fn redelegate(&mut self, from: PublicKey, to: PublicKey, amount: U512) -> Result<(), Error> {
+        // CHECKS: Both validators must differ and the target must be registered
+        if from == to {
+            return Err(Error::InvalidAddress);
+        }
+        if !self.is_validator(&to) {
+            return Err(Error::ValidatorNotFound);
+        }
+
+        // EFFECTS and INTERACTIONS: Undelegate, then delegate the same amount
+        self.undelegate(from.clone(), amount)?;
+        self.delegate(to.clone(), amount)?;
+        self.env().emit_event(Redelegated { from, to, amount });
+
+        Ok(())
+    }
+
+    /// Sync the tracked delegations with the auction and return the total growth
+    ///
+    /// Only validators whose auction stake grew are updated; each emits `Compounded`.
+    /// At most `max` of growth is synced in total, the rest stays visible for a
+    /// later call.
+    pub fn sync_rewards(&mut self, max: U512) -> Result<U512, Error> {
+        let mut total_rewards = U512::zero();
+        for validator in self.validators() {
+            let tracked = self.delegated_to(&validator);
+            let staked = Backend::delegated_amount(&self.env(), validator.clone());
+            let remaining = max - total_rewards;
+            if staked <= tracked || remaining == U512::zero() {
+                continue;
+            }
+
+            let amount = (staked - tracked).min(remaining);
+            let delegated = tracked + amount;
+            total_rewards = total_rewards.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+            self.delegated.set(&validator, delegated);
+            self.rewards_earned.set(&validator, self.rewards_of(&validator).saturating_add(amount));
+            let rebate = amount * U512::from(self.rebate_bps_of(&validator)) / U512::from(BPS_DENOMINATOR);
+            if rebate > U512::zero() {
+                self.rebates_owed.set(&validator, self.rebate_owed(&validator).saturating_add(rebate));
+            }
+            self.env().emit_event(Compounded {
+                validator,
+                amount,
+                delegated,
+            });
+        }
+
+        let new_total = self
+            .total_delegated()
+            .checked_add(total_rewards)
+            .ok_or(Error::ArithmeticOverflow)?;
+        self.total_delegated.set(new_total);
+        Ok(total_rewards)
+    }
+
+    /// Write off CSPR lost at a validator (e.g. slashed) without calling the auction
+    pub fn write_off(&mut self, validator: &PublicKey, amount: U512) -> Result<(), Error> {
+        let current = self.delegated_to(validator);
+        if current < amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let new_total = self
+            .total_delegated()
+            .checked_sub(amount)
+            .ok_or(Error::ArithmeticUnderflow)?;
+        self.delegated.set(validator, current - amount);
+        self.total_delegated.set(new_total);
+        Ok(())
+    }
+
+    /// Set a registered validator's target weight
+    pub fn set_weight(&mut self, validator: &PublicKey, weight: u32) -> Result<(), Error> {
+        if !self.is_validator(validator) {
+            return Err(Error::ValidatorNotFound);
+        }
+        self.weights.set(validator, weight);
+        self.env().emit_event(ValidatorWeightSet {
+            validator: validator.clone(),
+            weight,
+        });
+        Ok(())
+    }
+
+    /// Get a validator's target weight
+    pub fn weight_of(&self, validator: &PublicKey) -> u32 {
+        self.weights.get(validator).unwrap_or_default()
+    }
+
+    /// Set the share of a registered validator's rewards rebated to its operator
+    pub fn set_rebate_bps(&mut self, validator: &PublicKey, rebate_bps: u16) -> Result<(), Error> {
+        if !self.is_validator(validator) {
+            return Err(Error::ValidatorNotFound);
+        }
+        if rebate_bps > MAX_REBATE_BPS {
+            return Err(Error::ExceedsMaximum);
+        }
+        self.rebate_bps.set(validator, rebate_bps);
+        self.env().emit_event(RebateRateSet {
+            validator: validator.clone(),
+            rebate_bps,
+        });
+        Ok(())
+    }
+
+    /// Get the share of a validator's rewards rebated to its operator, in basis points
+    pub fn rebate_bps_of(&self, validator: &PublicKey) -> u16 {
+        self.rebate_bps.get(validator).unwrap_or_default()
+    }
+
+    /// Get the auction rewards synced for a validator
+    pub fn rewards_of(&self, validator: &PublicKey) -> U512 {
+        self.rewards_earned.get(validator).unwrap_or_default()
+    }
+
+    /// Get the rebate a validator's operator can claim, in motes
+    pub fn rebate_owed(&self, validator: &PublicKey) -> U512 {
+        self.rebates_owed.get(validator).unwrap_or_default()
+    }
+
+    /// Clear a validator's accrued rebate and return it
+    pub fn take_rebate(&mut self, validator: &PublicKey) -> U512 {
+        let owed = self.rebate_owed(validator);
+        self.rebates_owed.set(validator, U512::zero());
+        owed
+    }
+
+    /// Split `target_total` across the validators by weight
+    ///
+    /// Validators without weight target zero. Nothing is targeted if no validator has weight.
+    pub fn allocation(&self, target_total: U512) -> Vec<ValidatorAllocation> {
+        let validators = self.validators();
+        let total_weight: u64 = validators.iter().map(|v| self.weight_of(v) as u64).sum();
+
+        validators
+            .into_iter()
+            .map(|validator| {
+                let weight = self.weight_of(&validator);
+                let target = if total_weight == 0 {
+                    U512::zero()
+                } else {
+                    target_total * U512::from(weight) / U512::from(total_weight)
+                };
+                ValidatorAllocation {
+                    delegated: self.delegated_to(&validator),
+                    validator,
+                    weight,
+                    target,
+                }
+            })
+            .collect()
+    }
+
+    /// Get the registered validators
+    pub fn validators(&self) -> Vec<PublicKey> {
+        self.validators.get_or_default()
+    }
+
+    /// Check whether a validator is registered
+    pub fn is_validator(&self, validator: &PublicKey) -> bool {
+        self.validators.get_or_default().contains(validator)
+    }
+
+    /// Get the amount of CSPR delegated to a validator
+    pub fn delegated_to(&self, validator: &PublicKey) -> U512 {
+        self.delegated.get(validator).unwrap_or_default()
+    }
+
+    /// Get the total amount of CSPR delegated across all validators
+    pub fn total_delegated(&self) -> U512 {
+        self.total_delegated.get_or_default()
+    }
+}
@@ -0,0 +1,217 @@
+//! Sequenced deploys from one account
+//!
+//! Ops scripts that send several admin calls in a row (an upgrade followed by
+//! role grants and parameter changes) race themselves if they submit them all
+//! at once: later calls can execute before the ones they depend on, and two
+//! deploys signed in the same millisecond by the same key can collide. The
+//! queue submits one deploy at a time, waits for it to execute, gives every
+//! deploy a strictly later timestamp than the previous one, and rebuilds and
+//! resubmits a deploy that expired without executing.
+//!
+//! Deploys are signed by the caller: each queued deploy is a closure that
+//! returns the signed deploy JSON for a given timestamp. The queue does not
+//! sleep; call `poll` on an interval of the script's choosing.
+
+use std::collections::VecDeque;
+
+use serde_json::Value;
+
+use crate::client::{Client, ClientError, DeployStatus};
+
+/// Default time a deploy stays valid after its timestamp (30 minutes, in milliseconds)
+pub const DEFAULT_DEPLOY_TTL: u64 = 1_800_000;
+
+/// Default number of times a deploy is submitted before the queue gives up
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Builds the signed deploy JSON for a timestamp, in milliseconds since the epoch
+pub type BuildDeploy = Box<dyn Fn(u64) -> Result<Value, ClientError>>;
+
+/// Where the queue stands after a poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueState {
+    /// A deploy is on its way; poll again later
+    Waiting { label: String, deploy_hash: String },
+    /// Every queued deploy executed successfully
+    Done,
+}
+
+/// A deploy that executed successfully
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutedDeploy {
+    pub label: String,
+    pub deploy_hash: String,
+    /// Times the deploy was submitted
+    pub attempts: u32,
+}
+
+struct QueuedDeploy {
+    label: String,
+    build: BuildDeploy,
+}
+
+struct InFlight {
+    deploy: QueuedDeploy,
+    deploy_hash: String,
+    timestamp: u64,
+    attempts: u32,
+}
+
+/// DeployQueue - submits one account's deploys in order, one at a time
+pub struct DeployQueue {
+    ttl: u64,
+    max_attempts: u32,
+    /// Timestamp of the last deploy submitted
+    last_timestamp: u64,
+    queued: VecDeque<QueuedDeploy>,
+    in_flight: Option<InFlight>,
+    executed: Vec<ExecutedDeploy>,
+}
+
+impl DeployQueue {
+    /// Create a queue for deploys signed with time-to-live `ttl`, in milliseconds
+    pub fn new(ttl: u64) -> Self {
+        DeployQueue {
+            ttl,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            last_timestamp: 0,
+            queued: VecDeque::new(),
+            in_flight: None,
+            executed: Vec::new(),
+        }
+    }
+
+    /// Give up on a deploy after it expired `max_attempts` times
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Queue a deploy behind the ones already queued
+    pub fn push(&mut self, label: &str, build: BuildDeploy) {
+        self.queued.push_back(QueuedDeploy {
+            label: label.to_string(),
+            build,
+        });
+    }
+
+    /// Get the deploys that executed, in order
+    pub fn executed(&self) -> &[ExecutedDeploy] {
+        &self.executed
+    }
+
+    /// Get the number of deploys not executed yet, the one in flight included
+    pub fn remaining(&self) -> usize {
+        self.queued.len() + usize::from(self.in_flight.is_some())
+    }
+
+    /// Check the deploy in flight and submit the next one once it executed
+    ///
+    /// `now` is the current time in milliseconds since the epoch. A failed
+    /// deploy stops the queue, since the ones behind it may depend on it.
+    pub async fn poll(&mut self, client: &Client, now: u64) -> Result<QueueState, ClientError> {
+        if let Some(in_flight) = self.in_flight.take() {
+            match client.deploy_status(&in_flight.deploy_hash).await? {
+                DeployStatus::Succeeded => self.executed.push(ExecutedDeploy {
+                    label: in_flight.deploy.label,
+                    deploy_hash: in_flight.deploy_hash,
+                    attempts: in_flight.attempts,
+                }),
+                DeployStatus::Failed(message) => {
+                    return Err(ClientError::DeployFailed(format!("{}: {}", in_flight.deploy.label, message)));
+                }
+                DeployStatus::Pending if !is_expired(in_flight.timestamp, self.ttl, now) => {
+                    let state = QueueState::Waiting {
+                        label: in_flight.deploy.label.clone(),
+                        deploy_hash: in_flight.deploy_hash.clone(),
+                    };
+                    self.in_flight = Some(in_flight);
+                    return Ok(state);
+                }
+                DeployStatus::Pending if in_flight.attempts >= self.max_attempts => {
+                    return Err(ClientError::DeployFailed(format!(
+                        "{}: expired {} times without executing",
+                        in_flight.deploy.label, in_flight.attempts
+                    )));
+                }
+                DeployStatus::Pending => {
+                    return self.submit(client, in_flight.deploy, in_flight.attempts, now).await;
+                }
+            }
+        }
+
+        match self.queued.pop_front() {
+            Some(deploy) => self.submit(client, deploy, 0, now).await,
+            None => Ok(QueueState::Done),
+        }
+    }
+
+    // Sign the deploy with a fresh timestamp and send it
+    async fn submit(
+        &mut self,
+        client: &Client,
+        deploy: QueuedDeploy,
+        attempts: u32,
+        now: u64,
+    ) -> Result<QueueState, ClientError> {
+        let timestamp = next_timestamp(self.last_timestamp, now);
+        let deploy_hash = client.put_deploy((deploy.build)(timestamp)?).await?;
+        self.last_timestamp = timestamp;
+
+        let state = QueueState::Waiting {
+            label: deploy.label.clone(),
+            deploy_hash: deploy_hash.clone(),
+        };
+        self.in_flight = Some(InFlight {
+            deploy,
+            deploy_hash,
+            timestamp,
+            attempts: attempts + 1,
+        });
+        Ok(state)
+    }
+}
+
+impl Default for DeployQueue {
+    fn default() -> Self {
+        DeployQueue::new(DEFAULT_DEPLOY_TTL)
+    }
+}
+
+/// Get a timestamp no earlier than `now` and later than the previous deploy's
+pub fn next_timestamp(last_timestamp: u64, now: u64) -> u64 {
+    now.max(last_timestamp.saturating_add(1))
+}
+
+/// Check whether a deploy signed at `timestamp` can no longer be included in a block
+pub fn is_expired(timestamp: u64, ttl: u64, now: u64) -> bool {
+    now >= timestamp.saturating_add(ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamps_strictly_increase() {
+        assert_eq!(next_timestamp(0, 1_000), 1_000);
+        assert_eq!(next_timestamp(1_000, 1_000), 1_001);
+        assert_eq!(next_timestamp(1_005, 1_000), 1_006);
+    }
+
+    #[test]
+    fn test_deploy_expires_after_ttl() {
+        assert!(!is_expired(1_000, DEFAULT_DEPLOY_TTL, 1_000 + DEFAULT_DEPLOY_TTL - 1));
+        assert!(is_expired(1_000, DEFAULT_DEPLOY_TTL, 1_000 + DEFAULT_DEPLOY_TTL));
+    }
+
+    #[test]
+    fn test_queue_counts_remaining_deploys() {
+        let mut queue = DeployQueue::default().with_max_attempts(0);
+        assert_eq!(queue.max_attempts, 1);
+        queue.push("grant_role", Box::new(|_| Ok(Value::Null)));
+        queue.push("set_fee_bps", Box::new(|_| Ok(Value::Null)));
+        assert_eq!(queue.remaining(), 2);
+        assert!(queue.executed().is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+use odra::prelude::*;
+use odra::{module::Module, Var};
+
+use crate::{Error, ERA_DURATION};
+
+/// Default number of eras the auction holds undelegated CSPR before releasing it
+pub const DEFAULT_UNBONDING_DELAY: u64 = 7;
+
+/// Event emitted when the era parameters change
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct EraConfigUpdated {
+    pub era_length: u64,
+    pub unbonding_delay: u64,
+    pub anchor_era: u64,
+    pub anchor_timestamp: u64,
+}
+
+/// Eras - maps block time to Casper eras
+///
+/// Eras are counted from an anchor: an era id and the block time it started,
+/// as read from the chain. Without an anchor, era zero starts at block time zero.
+#[odra::module]
+pub struct Eras {
+    /// Length of an era, in milliseconds
+    era_length: Var<u64>,
+    /// Eras between an undelegation and the CSPR returning to the purse
+    unbonding_delay: Var<u64>,
+    /// Id of a known era
+    anchor_era: Var<u64>,
+    /// Block time the anchor era started
+    anchor_timestamp: Var<u64>,
+}
+
+#[odra::module]
+impl Eras {
+    /// Get the era that `timestamp` falls into
+    pub fn era_at(&self, timestamp: u64) -> u64 {
+        let since_anchor = timestamp.saturating_sub(self.anchor_timestamp.get_or_default());
+        self.anchor_era.get_or_default() + since_anchor / self.era_length()
+    }
+
+    /// Get the block time an era starts
+    pub fn era_start(&self, era: u64) -> u64 {
+        let eras_since_anchor = era.saturating_sub(self.anchor_era.get_or_default());
+        self.anchor_timestamp
+            .get_or_default()
+            .saturating_add(eras_since_anchor.saturating_mul(self.era_length()))
+    }
+
+    /// Get the block time CSPR undelegated at `timestamp` is back in the purse
+    pub fn unbonded_at(&self, timestamp: u64) -> u64 {
+        let release_era = self.era_at(timestamp) + self.unbonding_delay() + 1;
+        self.era_start(release_era)
+    }
+
+    /// Get the length of an era in milliseconds
+    pub fn era_length(&self) -> u64 {
+        self.era_length.get().unwrap_or(ERA_DURATION)
+    }
+
+    /// Get the auction unbonding delay in eras
+    pub fn unbonding_delay(&self) -> u64 {
+        self.unbonding_delay.get().unwrap_or(DEFAULT_UNBONDING_DELAY)
+    }
+
+    /// Set the era parameters; the anchor may not lie in the future
+    pub fn set_config(
+        &mut self,
+        era_length: u64,
+        unbonding_delay: u64,
+        anchor_era: u64,
+        anchor_timestamp: u64,
+    ) -> Result<(), Error> {
+        if era_length == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if anchor_timestamp > self.env().block_time() {
+            return Err(Error::InvalidEra);
+        }
+
+        self.era_length.set(era_length);
+        self.unbonding_delay.set(unbonding_delay);
+        self.anchor_era.set(anchor_era);
+        self.anchor_timestamp.set(anchor_timestamp);
+
+        self.env().emit_event(EraConfigUpdated {
+            era_length,
+            unbonding_delay,
+            anchor_era,
+            anchor_timestamp,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+//! Versioned layouts of the events the indexer decodes
+//!
+//! Upgrades add fields to events, and a CES event carries only its name and
+//! its fields, so a decoder built for the new layout cannot read events
+//! emitted before the upgrade. Every layout a tracked event has had is listed
+//! here with its schema version. Decoding tries the newest layout first and
+//! takes the first one that reads the whole event. Shims then fill in the
+//! fields older layouts lack, so indexers see one shape per event across
+//! upgrades. The decoded JSON carries the `schema_version` it was read with.
+//!
+//! The version is not written on chain: the CEP-18 events must keep their
+//! standard layout, and the field count identifies the layout already.
+//!
+//! When an upgrade changes a tracked event, add its new layout here with the
+//! next version, and a shim for what the older layouts lack.
+
+use odra::casper_types::bytesrepr::FromBytes;
+use odra::prelude::*;
+use odra::Address;
+use serde_json::{json, Map, Value};
+
+use crate::indexer::address_string;
+use crate::RATE_PRECISION;
+
+/// Type of an event field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldKind {
+    Address,
+    U256,
+    U64,
+}
+
+/// One layout an event has had
+#[derive(Clone, Copy, Debug)]
+pub struct EventLayout {
+    /// Event name without the CES `event_` prefix
+    pub name: &'static str,
+    pub schema_version: u8,
+    /// Fields in serialization order
+    pub fields: &'static [(&'static str, FieldKind)],
+}
+
+/// Every layout of the tracked events, oldest first within each event
+pub const LAYOUTS: &[EventLayout] = &[
+    EventLayout {
+        name: "StakeEvent",
+        schema_version: 1,
+        fields: &[
+            ("user", FieldKind::Address),
+            ("cspr_amount", FieldKind::U256),
+            ("stcspr_minted", FieldKind::U256),
+            ("timestamp", FieldKind::U64),
+        ],
+    },
+    // `stake_for` added the payer
+    EventLayout {
+        name: "StakeEvent",
+        schema_version: 2,
+        fields: &[
+            ("user", FieldKind::Address),
+            ("payer", FieldKind::Address),
+            ("cspr_amount", FieldKind::U256),
+            ("stcspr_minted", FieldKind::U256),
+            ("timestamp", FieldKind::U64),
+        ],
+    },
+    // The execution rate was added for statements
+    EventLayout {
+        name: "StakeEvent",
+        schema_version: 3,
+        fields: &[
+            ("user", FieldKind::Address),
+            ("payer", FieldKind::Address),
+            ("cspr_amount", FieldKind::U256),
+            ("stcspr_minted", FieldKind::U256),
+            ("timestamp", FieldKind::U64),
+            ("rate", FieldKind::U256),
+        ],
+    },
+    EventLayout {
+        name: "UnstakeEvent",
+        schema_version: 1,
+        fields: &[
+            ("user", FieldKind::Address),
+            ("stcspr_burned", FieldKind::U256),
+            ("cspr_returned", FieldKind::U256),
+            ("timestamp", FieldKind::U64),
+        ],
+    },
+    // `unstake_to` added the recipient
+    EventLayout {
+        name: "UnstakeEvent",
+        schema_version: 2,
+        fields: &[
+            ("user", FieldKind::Address),
+            ("recipient", FieldKind::Address),
+            ("stcspr_burned", FieldKind::U256),
+            ("cspr_returned", FieldKind::U256),
+            ("timestamp", FieldKind::U64),
+        ],
+    },
+    EventLayout {
+        name: "UnstakeEvent",
+        schema_version: 3,
+        fields: &[
+            ("user", FieldKind::Address),
+            ("recipient", FieldKind::Address),
+            ("stcspr_burned", FieldKind::U256),
+            ("cspr_returned", FieldKind::U256),
+            ("timestamp", FieldKind::U64),
+            ("rate", FieldKind::U256),
+        ],
+    },
+    EventLayout {
+        name: "Transfer",
+        schema_version: 1,
+        fields: &[
+            ("sender", FieldKind::Address),
+            ("recipient", FieldKind::Address),
+            ("amount", FieldKind::U256),
+        ],
+    },
+    EventLayout {
+        name: "TransferFrom",
+        schema_version: 1,
+        fields: &[
+            ("spender", FieldKind::Address),
+            ("owner", FieldKind::Address),
+            ("recipient", FieldKind::Address),
+            ("amount", FieldKind::U256),
+        ],
+    },
+    EventLayout {
+        name: "SetAllowance",
+        schema_version: 1,
+        fields: &[
+            ("owner", FieldKind::Address),
+            ("spender", FieldKind::Address),
+            ("allowance", FieldKind::U256),
+        ],
+    },
+];
+
+/// Get the newest schema version of a tracked event
+pub fn current_version(name: &str) -> Option<u8> {
+    LAYOUTS
+        .iter()
+        .filter(|layout| layout.name == name)
+        .map(|layout| layout.schema_version)
+        .max()
+}
+
+/// Decode a CES event of any known layout into its name and JSON fields
+///
+/// Returns `None` for events the indexer does not track.
+pub fn decode(bytes: &[u8]) -> Option<(String, Value)> {
+    let (name, fields) = String::from_bytes(bytes).ok()?;
+    let name = name.strip_prefix("event_")?;
+    let (layout, mut data) = LAYOUTS
+        .iter()
+        .rev()
+        .filter(|layout| layout.name == name)
+        .find_map(|layout| read_fields(layout, fields).map(|data| (layout, data)))?;
+
+    upgrade(layout, &mut data);
+    data.insert("schema_version".to_string(), json!(layout.schema_version));
+    Some((name.to_string(), Value::Object(data)))
+}
+
+// Read every field of `layout`, failing unless the bytes end with the last one
+fn read_fields(layout: &EventLayout, mut bytes: &[u8]) -> Option<Map<String, Value>> {
+    let mut data = Map::new();
+    for (name, kind) in layout.fields {
+        let value = match kind {
+            FieldKind::Address => {
+                let (address, rest) = Address::from_bytes(bytes).ok()?;
+                bytes = rest;
+                json!(address_string(&address))
+            }
+            FieldKind::U256 => {
+                let (amount, rest) = U256::from_bytes(bytes).ok()?;
+                bytes = rest;
+                json!(amount.to_string())
+            }
+            FieldKind::U64 => {
+                let (number, rest) = u64::from_bytes(bytes).ok()?;
+                bytes = rest;
+                json!(number)
+            }
+        };
+        data.insert(name.to_string(), value);
+    }
+    bytes.is_empty().then_some(data)
+}
+
+// Fill in the fields the layout's version lacks
+fn upgrade(layout: &EventLayout, data: &mut Map<String, Value>) {
+    let amount = |data: &Map<String, Value>, field: &str| {
+        U256::from_dec_str(data.get(field).and_then(Value::as_str).unwrap_or_default()).unwrap_or_default()
+    };
+    // Rate implied by the amounts; for unstakes it is net of any fee withheld
+    let implied_rate = |cspr: U256, shares: U256| {
+        if shares.is_zero() {
+            U256::from(RATE_PRECISION)
+        } else {
+            cspr * U256::from(RATE_PRECISION) / shares
+        }
+    };
+
+    match (layout.name, layout.schema_version) {
+        ("StakeEvent", version) if version < 3 => {
+            if version < 2 {
+                let user = data["user"].clone();
+                data.insert("payer".to_string(), user);
+            }
+            let rate = implied_rate(amount(data, "cspr_amount"), amount(data, "stcspr_minted"));
+            data.insert("rate".to_string(), json!(rate.to_string()));
+        }
+        ("UnstakeEvent", version) if version < 3 => {
+            if version < 2 {
+                let user = data["user"].clone();
+                data.insert("recipient".to_string(), user);
+            }
+            let rate = implied_rate(amount(data, "cspr_returned"), amount(data, "stcspr_burned"));
+            data.insert("rate".to_string(), json!(rate.to_string()));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StakeEvent;
+    use odra::casper_types::account::AccountHash;
+    use odra::casper_types::bytesrepr::ToBytes;
+
+    #[test]
+    fn test_decode_reads_every_stake_layout_in_the_current_shape() {
+        let user = Address::Account(AccountHash::new([1u8; 32]));
+        let payer = Address::Account(AccountHash::new([2u8; 32]));
+
+        // An event emitted before payer and rate were added
+        let mut original = "event_StakeEvent".to_string().to_bytes().unwrap();
+        original.extend(user.to_bytes().unwrap());
+        original.extend(U256::from(1_100).to_bytes().unwrap());
+        original.extend(U256::from(1_000).to_bytes().unwrap());
+        original.extend(5u64.to_bytes().unwrap());
+
+        let (name, data) = decode(&original).unwrap();
+        assert_eq!(name, "StakeEvent");
+        assert_eq!(data["schema_version"], 1);
+        assert_eq!(data["payer"], address_string(&user));
+        assert_eq!(data["rate"], "1100000000");
+        assert_eq!(data["timestamp"], 5);
+
+        let current = StakeEvent {
+            user,
+            payer,
+            cspr_amount: U256::from(1_100),
+            stcspr_minted: U256::from(1_000),
+            timestamp: 5,
+            rate: U256::from(1_099_000_000u64),
+        };
+        let (_, data) = decode(&current.to_bytes().unwrap()).unwrap();
+        assert_eq!(data["schema_version"], current_version("StakeEvent").unwrap());
+        assert_eq!(data["payer"], address_string(&payer));
+        assert_eq!(data["rate"], "1099000000");
+
+        // Trailing bytes no layout accounts for are not guessed at
+        let mut unknown = current.to_bytes().unwrap();
+        unknown.push(0);
+        assert!(decode(&unknown).is_none());
+    }
+}
@@ -0,0 +1,207 @@
+//! Per-address activity statements from indexed events
+//!
+//! Builds a chronological statement of one address's stakes, unstakes and
+//! transfers from an indexer log, for holders who need tax records. Every
+//! stake and unstake event records the exchange rate it executed at, so the
+//! log doubles as a series of rate checkpoints. When the rate moves between
+//! two checkpoints, the address's balance at the time is credited with the
+//! rewards (or debited with the loss) the move implies. The fee on an
+//! unstake is the difference between the burned shares' value at the
+//! recorded rate and the CSPR actually returned.
+
+use odra::prelude::*;
+use serde_json::{json, Value};
+
+use crate::indexer::IndexedEvent;
+use crate::RATE_PRECISION;
+
+/// Formats a statement can be written in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    /// Parse `csv` or `json`
+    pub fn parse(raw: &str) -> Option<Format> {
+        match raw {
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// One line of a statement
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementEntry {
+    pub timestamp: String,
+    pub deploy_hash: String,
+    /// `stake`, `unstake`, `transfer_in`, `transfer_out`, `reward` or `loss`
+    pub kind: &'static str,
+    /// stCSPR received
+    pub stcspr_in: U256,
+    /// stCSPR sent or burned
+    pub stcspr_out: U256,
+    /// CSPR paid, returned or earned, or the value of the stCSPR moved
+    pub cspr_amount: U256,
+    /// CSPR withheld from an unstake
+    pub fee: U256,
+    /// Exchange rate applied, scaled by `RATE_PRECISION`
+    pub rate: U256,
+    /// stCSPR held after the entry
+    pub balance: U256,
+}
+
+/// Build the statement of `address` (formatted as in the log) from events in index order
+pub fn statement(events: &[IndexedEvent], address: &str) -> Vec<StatementEntry> {
+    let precision = U256::from(RATE_PRECISION);
+    let mut entries = Vec::new();
+    let mut balance = U256::zero();
+    let mut rate: Option<U256> = None;
+
+    for event in events {
+        let field = |name: &str| event.data[name].as_str().unwrap_or_default();
+        let amount = |name: &str| U256::from_dec_str(field(name)).unwrap_or_default();
+        let entry = |kind, stcspr_in, stcspr_out, cspr_amount, fee, rate, balance| StatementEntry {
+            timestamp: event.timestamp.clone(),
+            deploy_hash: event.deploy_hash.clone(),
+            kind,
+            stcspr_in,
+            stcspr_out,
+            cspr_amount,
+            fee,
+            rate,
+            balance,
+        };
+
+        // Stakes and unstakes by anyone are rate checkpoints
+        if matches!(event.name.as_str(), "StakeEvent" | "UnstakeEvent") {
+            let checkpoint = amount("rate");
+            if let Some(previous) = rate.filter(|previous| *previous != checkpoint && !balance.is_zero()) {
+                let (kind, delta) = if checkpoint > previous {
+                    ("reward", checkpoint - previous)
+                } else {
+                    ("loss", previous - checkpoint)
+                };
+                let cspr = balance * delta / precision;
+                entries.push(entry(kind, U256::zero(), U256::zero(), cspr, U256::zero(), checkpoint, balance));
+            }
+            rate = Some(checkpoint);
+        }
+        let current_rate = rate.unwrap_or(precision);
+
+        match event.name.as_str() {
+            "StakeEvent" if field("user") == address => {
+                let minted = amount("stcspr_minted");
+                balance += minted;
+                entries.push(entry("stake", minted, U256::zero(), amount("cspr_amount"), U256::zero(), current_rate, balance));
+            }
+            "UnstakeEvent" if field("user") == address => {
+                let burned = amount("stcspr_burned");
+                let returned = amount("cspr_returned");
+                let fee = (burned * current_rate / precision).saturating_sub(returned);
+                balance = balance.saturating_sub(burned);
+                entries.push(entry("unstake", U256::zero(), burned, returned, fee, current_rate, balance));
+            }
+            "Transfer" | "TransferFrom" => {
+                let sender = if event.name == "Transfer" { field("sender") } else { field("owner") };
+                let moved = amount("amount");
+                let value = moved * current_rate / precision;
+                // A transfer to oneself leaves the balance unchanged and is skipped
+                if sender == address && field("recipient") != address {
+                    balance = balance.saturating_sub(moved);
+                    entries.push(entry("transfer_out", U256::zero(), moved, value, U256::zero(), current_rate, balance));
+                } else if field("recipient") == address && sender != address {
+                    balance += moved;
+                    entries.push(entry("transfer_in", moved, U256::zero(), value, U256::zero(), current_rate, balance));
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// Write a statement as CSV with a header row, or as a JSON array
+pub fn render(entries: &[StatementEntry], format: Format) -> String {
+    match format {
+        Format::Csv => {
+            let mut csv = String::from("timestamp,deploy_hash,kind,stcspr_in,stcspr_out,cspr_amount,fee,rate,balance\n");
+            for entry in entries {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{}\n",
+                    entry.timestamp,
+                    entry.deploy_hash,
+                    entry.kind,
+                    entry.stcspr_in,
+                    entry.stcspr_out,
+                    entry.cspr_amount,
+                    entry.fee,
+                    entry.rate,
+                    entry.balance
+                ));
+            }
+            csv
+        }
+        Format::Json => {
+            let rows: Vec<Value> = entries
+                .iter()
+                .map(|entry| {
+                    json!({
+                        "timestamp": entry.timestamp,
+                        "deploy_hash": entry.deploy_hash,
+                        "kind": entry.kind,
+                        "stcspr_in": entry.stcspr_in.to_string(),
+                        "stcspr_out": entry.stcspr_out.to_string(),
+                        "cspr_amount": entry.cspr_amount.to_string(),
+                        "fee": entry.fee.to_string(),
+                        "rate": entry.rate.to_string(),
+                        "balance": entry.balance.to_string(),
+                    })
+                })
+                .collect();
+            Value::Array(rows).to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexed(index: u32, name: &str, data: Value) -> IndexedEvent {
+        IndexedEvent {
+            index,
+            deploy_hash: format!("deploy-{}", index),
+            block_hash: "bb".to_string(),
+            timestamp: format!("t{}", index),
+            name: name.to_string(),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_statement_attributes_rewards_and_fees() {
+        let events = vec![
+            indexed(0, "StakeEvent", json!({ "user": "alice", "payer": "alice", "cspr_amount": "1000", "stcspr_minted": "1000", "rate": "1000000000" })),
+            indexed(1, "Transfer", json!({ "sender": "alice", "recipient": "bob", "amount": "200" })),
+            // Bob's stake shows the rate moved up 10%
+            indexed(2, "StakeEvent", json!({ "user": "bob", "payer": "bob", "cspr_amount": "110", "stcspr_minted": "100", "rate": "1100000000" })),
+            indexed(3, "UnstakeEvent", json!({ "user": "alice", "recipient": "alice", "stcspr_burned": "100", "cspr_returned": "105", "rate": "1100000000" })),
+        ];
+
+        let entries = statement(&events, "alice");
+        let kinds: Vec<&str> = entries.iter().map(|entry| entry.kind).collect();
+        assert_eq!(kinds, vec!["stake", "transfer_out", "reward", "unstake"]);
+        assert_eq!(entries[2].cspr_amount, U256::from(80));
+        assert_eq!(entries[3].fee, U256::from(5));
+        assert_eq!(entries[3].balance, U256::from(700));
+
+        let csv = render(&entries, Format::Csv);
+        assert_eq!(csv.lines().count(), 5);
+        assert_eq!(csv.lines().nth(3), Some("t2,deploy-2,reward,0,0,80,0,1100000000,800"));
+        assert_eq!(Format::parse("xml"), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Flash minting of stCSPR
+//!
+//! `flash_mint` credits a borrower contract with freshly minted stCSPR, calls its
+//! `on_flash_mint` hook, and burns the amount plus a fee from the borrower before
+//! the call returns. A borrower that cannot repay reverts the whole deploy, so
+//! the supply is never left inflated. Arbitrageurs can use it to close the gap
+//! between the market price of stCSPR and the pool's exchange rate without
+//! holding stCSPR up front.
+
+use odra::casper_types::bytesrepr::Bytes;
+use odra::prelude::*;
+use odra::{module::Module, Address, Var};
+
+/// Interface of a contract that borrows stCSPR through a flash mint
+#[odra::external_contract]
+pub trait FlashBorrower {
+    /// Called after `amount` stCSPR has been minted to the borrower. `initiator`
+    /// requested the flash mint; the borrower must hold `amount + fee` stCSPR when
+    /// it returns `true`, and returning `false` reverts the flash mint.
+    fn on_flash_mint(&mut self, initiator: Address, amount: U256, fee: U256, data: Bytes) -> bool;
+}
+
+/// Event emitted when a flash mint has been repaid
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlashMinted {
+    pub initiator: Address,
+    pub borrower: Address,
+    pub amount: U256,
+    pub fee: U256,
+}
+
+/// FlashMint - whether flash mints are enabled and what they cost
+///
+/// Minting, calling the borrower and collecting the repayment is the
+/// responsibility of the parent contract.
+#[odra::module]
+pub struct FlashMint {
+    /// Whether `flash_mint` is available
+    enabled: Var<bool>,
+    /// Fee charged on the minted amount, in basis points
+    fee_bps: Var<u16>,
+}
+
+#[odra::module]
+impl FlashMint {
+    /// Check whether flash mints are enabled
+    pub fn enabled(&self) -> bool {
+        self.enabled.get_or_default()
+    }
+
+    /// Get the flash mint fee in basis points
+    pub fn fee_bps(&self) -> u16 {
+        self.fee_bps.get_or_default()
+    }
+
+    /// Get the fee owed for flash minting `amount`
+    pub fn fee(&self, amount: U256) -> U256 {
+        amount * U256::from(self.fee_bps()) / U256::from(crate::BPS_DENOMINATOR)
+    }
+
+    /// Enable or disable flash mints and set their fee
+    pub fn configure(&mut self, enabled: bool, fee_bps: u16) {
+        self.enabled.set(enabled);
+        self.fee_bps.set(fee_bps);
+    }
+}
+
+/// Borrower used by the contract tests
+#[cfg(test)]
+pub(crate) mod mock {
+    use odra::casper_types::bytesrepr::Bytes;
+    use odra::prelude::*;
+    use odra::{module::Module, Address, Var};
+
+    use crate::CasperLiquidContractRef;
+
+    /// Records the last flash mint it was given and accepts or rejects it as configured
+    #[odra::module]
+    pub struct MockBorrower {
+        accept: Var<bool>,
+        borrowed: Var<U256>,
+        last_fee: Var<U256>,
+        last_initiator: Var<Option<Address>>,
+        /// Whether the hook tries to queue the minted stCSPR for withdrawal
+        reenter: Var<bool>,
+        /// Exchange rate read back from the pool during the last hook
+        observed_rate: Var<U256>,
+    }
+
+    #[odra::module]
+    impl MockBorrower {
+        pub fn init(&mut self, accept: bool) {
+            self.accept.set(accept);
+        }
+
+        pub fn set_reenter(&mut self, reenter: bool) {
+            self.reenter.set(reenter);
+        }
+
+        pub fn on_flash_mint(&mut self, initiator: Address, amount: U256, fee: U256, _data: Bytes) -> bool {
+            self.borrowed.set(self.borrowed.get_or_default() + amount);
+            self.last_fee.set(fee);
+            self.last_initiator.set(Some(initiator));
+            let mut pool = CasperLiquidContractRef::new(self.env(), self.env().caller());
+            pool.update_rate_feed();
+            self.observed_rate.set(pool.exchange_rate());
+            if self.reenter.get_or_default() {
+                let _ = pool.request_unstake(amount);
+            }
+            self.accept.get_or_default()
+        }
+
+        pub fn borrowed(&self) -> U256 {
+            self.borrowed.get_or_default()
+        }
+
+        pub fn last_fee(&self) -> U256 {
+            self.last_fee.get_or_default()
+        }
+
+        pub fn last_initiator(&self) -> Option<Address> {
+            self.last_initiator.get().flatten()
+        }
+
+        pub fn observed_rate(&self) -> U256 {
+            self.observed_rate.get_or_default()
+        }
+    }
+}
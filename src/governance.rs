@@ -0,0 +1,272 @@
+use odra::casper_types::PublicKey;
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::access_control::Role;
+use crate::Error;
+
+/// Default time a proposal stays open for votes (3 days, in milliseconds)
+pub const DEFAULT_VOTING_PERIOD: u64 = 259_200_000;
+
+/// Default share of the snapshot supply that must vote for a proposal to pass (4%)
+pub const DEFAULT_QUORUM_BPS: u16 = 400;
+
+/// Admin actions a proposal, the timelock or the admin multisig can execute
+///
+/// Every admin setter has a matching action, so once the multisig or the
+/// timelock is enabled no setting is left that a single admin changes directly.
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProposalAction {
+    SetFeeBps(u16),
+    SetTreasury(Address),
+    AddValidator(PublicKey),
+    RemoveValidator(PublicKey),
+    SetTimelockDelay(u64),
+    /// Replace the admin multisig signers and threshold (zero disables it)
+    SetMultisig(Vec<Address>, u32),
+    GrantRole(Role, Address),
+    RevokeRole(Role, Address),
+    /// Replace the oracle committee members and quorum (zero disables it)
+    SetOracleCommittee(Vec<PublicKey>, u32),
+    /// Pay insurance CSPR back into the pool
+    CoverLoss(U256),
+    /// Minimum stake, maximum stake per transaction and cap on the pool
+    SetLimits(U256, U256, U256),
+    /// Keeper reward and cooldown
+    SetKeeperConfig(U256, u64),
+    SetFlashMintConfig(bool, u16),
+    SetBufferTargetBps(u16),
+    SetFlushThreshold(U512),
+    SetCompoundInterval(u64),
+    SetInsuranceShareBps(u16),
+    SetUnstakeLimitBps(u16),
+    SetStakeCooldown(u64),
+    /// Voting period and quorum for new votes
+    SetGovernanceParams(u64, u16),
+    RestrictTransfers,
+    SetTransferAllowlist(Address, bool),
+    OpenTransfers,
+    SetMerkleRoot([u8; 32]),
+    RevokeVesting(u64),
+    /// Cancel a timelock entry; applied as soon as it is approved, never queued
+    CancelQueuedAction(u64),
+    /// Withdrawal average weight and the share of it the buffer covers
+    SetBufferPolicy(u16, u16),
+    /// Register or remove a pool the treasury may swap through
+    SetSwapPool(Address, bool),
+    /// Most treasury stCSPR swapped per rewards epoch
+    SetTreasurySwapLimit(U256),
+    /// Register the validator of a pending application and refund its bond
+    ApproveApplication(u64),
+    /// Reject a pending application, paying its bond into the insurance fund
+    RejectApplication(u64),
+    SetApplicationBond(U512),
+    /// Share of a validator's rewards rebated to its operator
+    SetValidatorRebate(PublicKey, u16),
+    /// Integrator's share of the fee on its referred stCSPR, and its lifetime cap
+    SetIntegrator(Address, u16, U256),
+    /// Most stCSPR a bridge operator can mint per day
+    SetBridgeDailyCap(Address, U256),
+    /// Recovery council and the heartbeat period after which it can take over (none disarms it)
+    SetRecovery(Option<Address>, u64),
+    /// Large-withdrawal review threshold, window and flag delay (zero threshold disables it)
+    SetWithdrawalReview(u16, u64, u64),
+    /// Start winding the pool down, giving holders the exit window before positions are forced out
+    StartSunset(u64),
+}
+
+/// A governance proposal and its tally
+#[odra::odra_type]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    /// Hash of the off-chain description
+    pub description_hash: [u8; 32],
+    pub actions: Vec<ProposalAction>,
+    /// Block time whose balances give the voting weight
+    pub snapshot: u64,
+    /// Block time after which no more votes are accepted
+    pub vote_end: u64,
+    pub for_votes: U256,
+    pub against_votes: U256,
+    pub executed: bool,
+}
+
+/// Event emitted when a proposal is created
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProposalCreated {
+    pub id: u64,
+    pub proposer: Address,
+    pub description_hash: [u8; 32],
+    pub snapshot: u64,
+    pub vote_end: u64,
+}
+
+/// Event emitted when a holder votes on a proposal
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct VoteCast {
+    pub id: u64,
+    pub voter: Address,
+    pub support: bool,
+    pub weight: U256,
+}
+
+/// Event emitted when a passed proposal's actions are executed
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProposalExecuted {
+    pub id: u64,
+}
+
+/// Governance - stores proposals and tallies votes
+///
+/// Voting weights come from the parent contract's balance checkpoints, and the
+/// parent applies the actions of executed proposals.
+#[odra::module]
+pub struct Governance {
+    /// Id assigned to the next proposal
+    next_id: Var<u64>,
+    /// All proposals by id
+    proposals: Mapping<u64, Proposal>,
+    /// Whether an address has voted on a proposal
+    voted: Mapping<(u64, Address), bool>,
+    /// Time a proposal stays open for votes, in milliseconds
+    voting_period: Var<u64>,
+    /// Share of the snapshot supply that must vote in favour, in basis points
+    quorum_bps: Var<u16>,
+}
+
+#[odra::module]
+impl Governance {
+    /// Create a proposal weighted by balances at `snapshot`; returns its id
+    pub fn create(
+        &mut self,
+        proposer: Address,
+        description_hash: [u8; 32],
+        actions: Vec<ProposalAction>,
+        snapshot: u64,
+    ) -> u64 {
+        let id = self.next_id.get_or_default();
+        let vote_end = self.env().block_time().saturating_add(self.voting_period());
+
+        self.next_id.set(id + 1);
+        self.proposals.set(
+            &id,
+            Proposal {
+                id,
+                proposer,
+                description_hash,
+                actions,
+                snapshot,
+                vote_end,
+                for_votes: U256::zero(),
+                against_votes: U256::zero(),
+                executed: false,
+            },
+        );
+
+        self.env().emit_event(ProposalCreated {
+            id,
+            proposer,
+            description_hash,
+            snapshot,
+            vote_end,
+        });
+
+        id
+    }
+
+    /// Record a vote of `weight` from `voter`
+    pub fn vote(&mut self, id: u64, voter: Address, support: bool, weight: U256) -> Result<(), Error> {
+        // CHECKS: Proposal must be open and the voter must not have voted yet
+        let mut proposal = self.proposals.get(&id).ok_or(Error::ProposalNotFound)?;
+        if self.env().block_time() > proposal.vote_end {
+            return Err(Error::VotingClosed);
+        }
+        if self.has_voted(id, &voter) {
+            return Err(Error::AlreadyVoted);
+        }
+
+        if support {
+            proposal.for_votes = proposal
+                .for_votes
+                .checked_add(weight)
+                .ok_or(Error::ArithmeticOverflow)?;
+        } else {
+            proposal.against_votes = proposal
+                .against_votes
+                .checked_add(weight)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+
+        // EFFECTS: Store the tally
+        self.proposals.set(&id, proposal);
+        self.voted.set(&(id, voter), true);
+
+        self.env().emit_event(VoteCast {
+            id,
+            voter,
+            support,
+            weight,
+        });
+
+        Ok(())
+    }
+
+    /// Mark a passed proposal as executed and return its actions
+    ///
+    /// `snapshot_supply` is the total supply at the proposal's snapshot, used for the quorum.
+    pub fn execute(&mut self, id: u64, snapshot_supply: U256) -> Result<Vec<ProposalAction>, Error> {
+        // CHECKS: Voting must be over and the proposal must have passed
+        let mut proposal = self.proposals.get(&id).ok_or(Error::ProposalNotFound)?;
+        if proposal.executed {
+            return Err(Error::ProposalAlreadyExecuted);
+        }
+        if self.env().block_time() <= proposal.vote_end {
+            return Err(Error::VotingInProgress);
+        }
+
+        let quorum = snapshot_supply * U256::from(self.quorum_bps()) / U256::from(crate::BPS_DENOMINATOR);
+        if proposal.for_votes <= proposal.against_votes || proposal.for_votes < quorum {
+            return Err(Error::ProposalNotPassed);
+        }
+
+        // EFFECTS: Mark as executed before the parent applies the actions
+        proposal.executed = true;
+        let actions = proposal.actions.clone();
+        self.proposals.set(&id, proposal);
+
+        self.env().emit_event(ProposalExecuted { id });
+
+        Ok(actions)
+    }
+
+    /// Get a proposal by id
+    pub fn get(&self, id: u64) -> Option<Proposal> {
+        self.proposals.get(&id)
+    }
+
+    /// Check whether an address has voted on a proposal
+    pub fn has_voted(&self, id: u64, voter: &Address) -> bool {
+        self.voted.get(&(id, *voter)).unwrap_or_default()
+    }
+
+    /// Get the voting period in milliseconds
+    pub fn voting_period(&self) -> u64 {
+        self.voting_period.get().unwrap_or(DEFAULT_VOTING_PERIOD)
+    }
+
+    /// Get the quorum in basis points of the snapshot supply
+    pub fn quorum_bps(&self) -> u16 {
+        self.quorum_bps.get().unwrap_or(DEFAULT_QUORUM_BPS)
+    }
+
+    /// Set the voting period and quorum
+    pub fn set_params(&mut self, voting_period: u64, quorum_bps: u16) {
+        self.voting_period.set(voting_period);
+        self.quorum_bps.set(quorum_bps);
+    }
+}
@@ -0,0 +1,115 @@
+//! Security contact and incident status
+//!
+//! Gives integrators one canonical place to check during an incident.
+//! Operators publish where to reach the security team (e.g. the URI of a
+//! PGP-signed security.txt) and the current incident status, as a code and a
+//! URI with the details. Both come from an operator's signed deploy, and the
+//! events record which operator published them. `health_check` returns them
+//! together with the pause state.
+
+use odra::prelude::*;
+use odra::{module::Module, Address, Var};
+
+use crate::Error;
+
+/// No incident; the normal state
+pub const INCIDENT_NONE: u8 = 0;
+/// Something is being investigated; the protocol runs normally
+pub const INCIDENT_INVESTIGATING: u8 = 1;
+/// Some functions are degraded or paused
+pub const INCIDENT_DEGRADED: u8 = 2;
+/// The protocol is halted
+pub const INCIDENT_HALTED: u8 = 3;
+/// An incident is over; the message links the post-mortem
+pub const INCIDENT_RESOLVED: u8 = 4;
+
+/// Longest contact or message URI that can be published, in bytes
+pub const MAX_INCIDENT_URI_LENGTH: usize = 256;
+
+/// Event emitted when an operator publishes an incident status
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct IncidentStatusSet {
+    pub code: u8,
+    pub message_uri: String,
+    pub operator: Address,
+    pub timestamp: u64,
+}
+
+/// Event emitted when an operator publishes the security contact
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityContactSet {
+    pub contact: String,
+    pub operator: Address,
+}
+
+/// Incidents - published security contact and current incident status
+///
+/// Authorization is the responsibility of the parent contract.
+#[odra::module]
+pub struct Incidents {
+    /// Where to reach the security team
+    contact: Var<String>,
+    /// Current incident code (`INCIDENT_NONE` when unset)
+    code: Var<u8>,
+    /// Details of the current incident status
+    message_uri: Var<String>,
+    /// Block time the status was last published
+    updated_at: Var<u64>,
+}
+
+#[odra::module]
+impl Incidents {
+    /// Publish the security contact on behalf of `operator`
+    pub fn set_contact(&mut self, operator: Address, contact: String) -> Result<(), Error> {
+        check_uri(&contact)?;
+        self.contact.set(contact.clone());
+        self.env().emit_event(SecurityContactSet { contact, operator });
+        Ok(())
+    }
+
+    /// Publish an incident status on behalf of `operator`
+    pub fn set_status(&mut self, operator: Address, code: u8, message_uri: String) -> Result<(), Error> {
+        if code > INCIDENT_RESOLVED {
+            return Err(Error::ExceedsMaximum);
+        }
+        // The all-clear needs no message; every incident does
+        if code != INCIDENT_NONE {
+            check_uri(&message_uri)?;
+        }
+
+        let timestamp = self.env().block_time();
+        self.code.set(code);
+        self.message_uri.set(message_uri.clone());
+        self.updated_at.set(timestamp);
+        self.env().emit_event(IncidentStatusSet {
+            code,
+            message_uri,
+            operator,
+            timestamp,
+        });
+        Ok(())
+    }
+
+    /// Get the security contact
+    pub fn contact(&self) -> String {
+        self.contact.get_or_default()
+    }
+
+    /// Get the incident code, message URI and the block time they were published
+    pub fn status(&self) -> (u8, String, u64) {
+        (
+            self.code.get_or_default(),
+            self.message_uri.get_or_default(),
+            self.updated_at.get_or_default(),
+        )
+    }
+}
+
+fn check_uri(uri: &str) -> Result<(), Error> {
+    if uri.is_empty() || uri.len() > MAX_INCIDENT_URI_LENGTH {
+        return Err(Error::InvalidAmount);
+    }
+    Ok(())
+}
@@ -0,0 +1,744 @@
+//! Off-chain indexer for stCSPR events
+//!
+//! Follows a node's SSE event stream and picks out the CES events the contract
+//! writes to its `__events` dictionary. Stake, unstake, transfer and allowance
+//! events are decoded to JSON, in every layout they have had, and handed to a
+//! [`Sink`].
+//!
+//! Built for Casper 1.x nodes, whose `DeployProcessed` messages carry the
+//! execution effects of each deploy.
+//!
+//! [`backfill`] fills a sink from historical blocks instead, reading each
+//! block's deploys over RPC. Sinks record the last block written, so an
+//! interrupted backfill resumes where it stopped.
+//!
+//! The stream is followed with a [`Cursor`], which remembers the block each
+//! recent event index came from. If an index comes back from another block,
+//! the events from it onwards were orphaned by a fork, and the sink is told
+//! which range to drop before the replacements are written.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+
+use odra::casper_types::bytesrepr::{Bytes, FromBytes};
+use odra::casper_types::{CLValue, Key};
+use odra::prelude::*;
+use odra::Address;
+use serde_json::{json, Value};
+
+use crate::events;
+
+/// Name of the dictionary CES events are written to
+pub const EVENTS_DICTIONARY: &str = "__events";
+
+/// Number of recent events a [`Cursor`] remembers the blocks of
+pub const CURSOR_DEPTH: usize = 1_024;
+
+/// Errors raised while looking up the contract or reading the stream
+#[derive(Debug)]
+pub enum IndexerError {
+    /// The node could not be reached or returned an error
+    Http(String),
+    /// A node response did not have the expected shape
+    UnexpectedResponse(String),
+    /// Writing to the sink failed
+    Io(io::Error),
+}
+
+impl fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexerError::Http(message) => write!(f, "node request failed: {}", message),
+            IndexerError::UnexpectedResponse(message) => write!(f, "unexpected node response: {}", message),
+            IndexerError::Io(error) => write!(f, "could not write the event: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for IndexerError {}
+
+impl From<io::Error> for IndexerError {
+    fn from(error: io::Error) -> Self {
+        IndexerError::Io(error)
+    }
+}
+
+impl From<reqwest::Error> for IndexerError {
+    fn from(error: reqwest::Error) -> Self {
+        IndexerError::Http(error.to_string())
+    }
+}
+
+/// A decoded contract event and where it was emitted
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexedEvent {
+    /// Position in the contract's event history, unique per contract
+    pub index: u32,
+    pub deploy_hash: String,
+    pub block_hash: String,
+    pub timestamp: String,
+    /// Event name without the CES `event_` prefix, e.g. `StakeEvent`
+    pub name: String,
+    pub data: Value,
+}
+
+impl IndexedEvent {
+    /// Get the event as one JSON object
+    pub fn to_json(&self) -> Value {
+        json!({
+            "index": self.index,
+            "deploy_hash": self.deploy_hash,
+            "block_hash": self.block_hash,
+            "timestamp": self.timestamp,
+            "event": self.name,
+            "data": self.data,
+        })
+    }
+
+    /// Read an event back from the JSON `to_json` wrote
+    pub fn from_json(value: &Value) -> Option<IndexedEvent> {
+        Some(IndexedEvent {
+            index: u32::try_from(value["index"].as_u64()?).ok()?,
+            deploy_hash: value["deploy_hash"].as_str()?.to_string(),
+            block_hash: value["block_hash"].as_str()?.to_string(),
+            timestamp: value["timestamp"].as_str()?.to_string(),
+            name: value["event"].as_str()?.to_string(),
+            data: value["data"].clone(),
+        })
+    }
+}
+
+/// Events orphaned by a fork: every index from `from_index` to `to_index`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reorg {
+    pub from_index: u32,
+    pub to_index: u32,
+    /// Block the first orphaned event was read from
+    pub orphaned_block: String,
+    /// Block the event at `from_index` now comes from
+    pub new_block: String,
+}
+
+impl Reorg {
+    /// Get the reorg as the JSON line [`JsonlSink`] records it with
+    pub fn to_json(&self) -> Value {
+        json!({
+            "reorg": {
+                "from_index": self.from_index,
+                "to_index": self.to_index,
+                "orphaned_block": self.orphaned_block,
+                "new_block": self.new_block,
+            }
+        })
+    }
+}
+
+/// Where an event falls relative to the events a [`Cursor`] has seen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Position {
+    /// Not seen before
+    New,
+    /// Already seen from the same block, e.g. replayed by the stream
+    Seen,
+    /// Seen from another block; the events it replaces must be dropped first
+    Reorg(Reorg),
+}
+
+/// Event indices seen on the stream and the block each came from
+///
+/// Only the last [`CURSOR_DEPTH`] events are remembered, which is far deeper
+/// than a fork of the stream can reach. A fork whose new blocks emit no
+/// contract events is only noticed once the next event arrives.
+#[derive(Clone, Debug, Default)]
+pub struct Cursor {
+    blocks: BTreeMap<u32, String>,
+}
+
+impl Cursor {
+    /// Start with no events seen
+    pub fn new() -> Self {
+        Cursor::default()
+    }
+
+    /// Get the highest event index seen and its block
+    pub fn last(&self) -> Option<(u32, &str)> {
+        self.blocks.iter().next_back().map(|(index, block)| (*index, block.as_str()))
+    }
+
+    /// Place `event` against the events seen so far and remember its block
+    pub fn advance(&mut self, event: &IndexedEvent) -> Position {
+        let position = match self.blocks.get(&event.index) {
+            Some(block) if *block == event.block_hash => return Position::Seen,
+            Some(block) => {
+                let reorg = Reorg {
+                    from_index: event.index,
+                    to_index: self.last().map(|(index, _)| index).unwrap_or(event.index),
+                    orphaned_block: block.clone(),
+                    new_block: event.block_hash.clone(),
+                };
+                self.blocks.split_off(&event.index);
+                Position::Reorg(reorg)
+            }
+            None => Position::New,
+        };
+
+        self.blocks.insert(event.index, event.block_hash.clone());
+        while self.blocks.len() > CURSOR_DEPTH {
+            self.blocks.pop_first();
+        }
+        position
+    }
+}
+
+/// Read the events of a JSON lines file written by [`JsonlSink`], in index order
+///
+/// Events written twice (e.g. by a stream and an overlapping backfill) are kept
+/// once, and events a recorded reorg orphaned are left out.
+pub fn read_jsonl(path: &str) -> Result<Vec<IndexedEvent>, IndexerError> {
+    let mut events: Vec<IndexedEvent> = Vec::new();
+    for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: Option<Value> = serde_json::from_str(&line).ok();
+        if let Some(reorg) = value.as_ref().map(|value| &value["reorg"]).filter(|reorg| reorg.is_object()) {
+            let from_index = reorg["from_index"].as_u64().unwrap_or_default();
+            let to_index = reorg["to_index"].as_u64().unwrap_or(u64::MAX);
+            events.retain(|event| !(from_index..=to_index).contains(&u64::from(event.index)));
+            continue;
+        }
+        let event = value
+            .and_then(|value| IndexedEvent::from_json(&value))
+            .ok_or_else(|| IndexerError::UnexpectedResponse(format!("{} line {} is not an indexed event", path, number + 1)))?;
+        events.push(event);
+    }
+    events.sort_by_key(|event| event.index);
+    events.dedup_by_key(|event| event.index);
+    Ok(events)
+}
+
+/// Destination for indexed events
+pub trait Sink {
+    fn write(&mut self, event: &IndexedEvent) -> Result<(), IndexerError>;
+
+    /// Drop the events a fork orphaned; called before their replacements are written
+    fn reorg(&mut self, _reorg: &Reorg) -> Result<(), IndexerError> {
+        Ok(())
+    }
+
+    /// Get the height of the last block a backfill wrote completely
+    fn backfilled_height(&self) -> Result<Option<u64>, IndexerError> {
+        Ok(None)
+    }
+
+    /// Record that every block up to `height` has been written
+    fn set_backfilled_height(&mut self, _height: u64) -> Result<(), IndexerError> {
+        Ok(())
+    }
+}
+
+/// Appends one JSON object per line to a file
+///
+/// Reorgs are appended as a `{"reorg": ...}` line, which [`read_jsonl`] applies.
+/// The backfill checkpoint is kept next to it, in `<path>.checkpoint`.
+pub struct JsonlSink {
+    file: File,
+    checkpoint_path: String,
+}
+
+impl JsonlSink {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: &str) -> Result<Self, IndexerError> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(JsonlSink {
+            file,
+            checkpoint_path: format!("{}.checkpoint", path),
+        })
+    }
+}
+
+impl Sink for JsonlSink {
+    fn write(&mut self, event: &IndexedEvent) -> Result<(), IndexerError> {
+        writeln!(self.file, "{}", event.to_json())?;
+        Ok(())
+    }
+
+    fn reorg(&mut self, reorg: &Reorg) -> Result<(), IndexerError> {
+        writeln!(self.file, "{}", reorg.to_json())?;
+        Ok(())
+    }
+
+    fn backfilled_height(&self) -> Result<Option<u64>, IndexerError> {
+        match fs::read_to_string(&self.checkpoint_path) {
+            Ok(contents) => contents.trim().parse().map(Some).map_err(|_| {
+                IndexerError::UnexpectedResponse(format!("{} does not hold a block height", self.checkpoint_path))
+            }),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn set_backfilled_height(&mut self, height: u64) -> Result<(), IndexerError> {
+        // Events must be on disk before the checkpoint moves past them
+        self.file.sync_data()?;
+        fs::write(&self.checkpoint_path, height.to_string())?;
+        Ok(())
+    }
+}
+
+/// Stores events in an `events` table keyed by their index, so replaying the
+/// stream does not duplicate rows
+#[cfg(feature = "indexer-sqlite")]
+pub struct SqliteSink {
+    connection: rusqlite::Connection,
+}
+
+#[cfg(feature = "indexer-sqlite")]
+impl SqliteSink {
+    /// Open or create the database at `path`
+    pub fn open(path: &str) -> Result<Self, IndexerError> {
+        let connection = rusqlite::Connection::open(path).map_err(sqlite_error)?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS events (
+                    event_index INTEGER PRIMARY KEY,
+                    deploy_hash TEXT NOT NULL,
+                    block_hash TEXT NOT NULL,
+                    timestamp TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    data TEXT NOT NULL
+                )",
+                (),
+            )
+            .map_err(sqlite_error)?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS backfill_checkpoint (
+                    id INTEGER PRIMARY KEY CHECK (id = 0),
+                    height INTEGER NOT NULL
+                )",
+                (),
+            )
+            .map_err(sqlite_error)?;
+        Ok(SqliteSink { connection })
+    }
+}
+
+#[cfg(feature = "indexer-sqlite")]
+impl Sink for SqliteSink {
+    fn write(&mut self, event: &IndexedEvent) -> Result<(), IndexerError> {
+        self.connection
+            .execute(
+                "INSERT OR REPLACE INTO events VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                (
+                    event.index,
+                    &event.deploy_hash,
+                    &event.block_hash,
+                    &event.timestamp,
+                    &event.name,
+                    event.data.to_string(),
+                ),
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn reorg(&mut self, reorg: &Reorg) -> Result<(), IndexerError> {
+        self.connection
+            .execute(
+                "DELETE FROM events WHERE event_index BETWEEN ?1 AND ?2",
+                (reorg.from_index, reorg.to_index),
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn backfilled_height(&self) -> Result<Option<u64>, IndexerError> {
+        let height: Option<i64> = self
+            .connection
+            .query_row("SELECT height FROM backfill_checkpoint WHERE id = 0", (), |row| row.get(0))
+            .map(Some)
+            .or_else(|error| match error {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                error => Err(error),
+            })
+            .map_err(sqlite_error)?;
+        Ok(height.map(|height| height as u64))
+    }
+
+    fn set_backfilled_height(&mut self, height: u64) -> Result<(), IndexerError> {
+        self.connection
+            .execute("INSERT OR REPLACE INTO backfill_checkpoint VALUES (0, ?1)", (height as i64,))
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "indexer-sqlite")]
+fn sqlite_error(error: rusqlite::Error) -> IndexerError {
+    IndexerError::Io(io::Error::new(io::ErrorKind::Other, error))
+}
+
+/// Look up the address of the `__events` dictionary of the latest contract
+/// version in a package, given as `hash-...`
+pub fn find_events_dictionary(node_address: &str, package_hash: &str) -> Result<[u8; 32], IndexerError> {
+    let package = query_global_state(node_address, package_hash)?;
+    let contract_hash = package["ContractPackage"]["versions"]
+        .as_array()
+        .and_then(|versions| versions.last())
+        .and_then(|version| version["contract_hash"].as_str())
+        .ok_or_else(|| IndexerError::UnexpectedResponse(format!("{} is not a contract package", package_hash)))?;
+
+    // Contract hashes are listed as contract-<hex> but queried as hash-<hex>
+    let contract_key = contract_hash.replacen("contract-", "hash-", 1);
+    let contract = query_global_state(node_address, &contract_key)?;
+    let events_uref = contract["Contract"]["named_keys"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|named_key| named_key["name"] == EVENTS_DICTIONARY)
+        .and_then(|named_key| named_key["key"].as_str())
+        .ok_or_else(|| IndexerError::UnexpectedResponse(format!("{} has no {} named key", contract_key, EVENTS_DICTIONARY)))?;
+
+    match Key::from_formatted_str(events_uref) {
+        Ok(Key::URef(uref)) => Ok(uref.addr()),
+        _ => Err(IndexerError::UnexpectedResponse(format!("{} is not a URef", events_uref))),
+    }
+}
+
+/// Follow the node's event stream and write each contract event to `sink`
+///
+/// `events_url` is the node's SSE endpoint, e.g. `http://localhost:9999/events/main`.
+/// Events the stream repeats are skipped, and events orphaned by a fork are
+/// passed to [`Sink::reorg`]. Returns only if the stream ends or fails.
+pub fn run(events_url: &str, events_dictionary: [u8; 32], sink: &mut dyn Sink) -> Result<(), IndexerError> {
+    let client = reqwest::blocking::Client::builder().timeout(None).build()?;
+    let response = client.get(events_url).send()?.error_for_status()?;
+    let mut cursor = Cursor::new();
+
+    for line in BufReader::new(response).lines() {
+        let line = line?;
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        let Ok(message) = serde_json::from_str::<Value>(data.trim()) else {
+            continue;
+        };
+        for event in events_in_deploy(&message, &events_dictionary) {
+            match cursor.advance(&event) {
+                Position::Seen => continue,
+                Position::Reorg(reorg) => sink.reorg(&reorg)?,
+                Position::New => {}
+            }
+            sink.write(&event)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the contract events of blocks `from_height..=to_height` to `sink`
+///
+/// Without `to_height` the backfill runs to the node's latest block. If the
+/// sink already holds a checkpoint at or past `from_height`, the backfill
+/// resumes after it. Returns the height of the last block written.
+pub fn backfill(
+    node_address: &str,
+    from_height: u64,
+    to_height: Option<u64>,
+    events_dictionary: [u8; 32],
+    sink: &mut dyn Sink,
+) -> Result<u64, IndexerError> {
+    let start = match sink.backfilled_height()? {
+        Some(height) if height >= from_height => height + 1,
+        _ => from_height,
+    };
+    let end = match to_height {
+        Some(height) => height,
+        None => {
+            let latest = rpc(node_address, "chain_get_block", json!({}))?;
+            latest["block"]["header"]["height"]
+                .as_u64()
+                .ok_or_else(|| IndexerError::UnexpectedResponse("latest block has no height".to_string()))?
+        }
+    };
+
+    for height in start..=end {
+        let block = rpc(node_address, "chain_get_block", json!({ "block_identifier": { "Height": height } }))?;
+        let block_hash = block["block"]["hash"].as_str().unwrap_or_default();
+        let deploy_hashes = block["block"]["body"]["deploy_hashes"].as_array().cloned().unwrap_or_default();
+
+        for deploy_hash in deploy_hashes.iter().filter_map(Value::as_str) {
+            let deploy = rpc(node_address, "info_get_deploy", json!({ "deploy_hash": deploy_hash }))?;
+            let message = processed_message(&deploy, block_hash);
+            for event in events_in_deploy(&message, &events_dictionary) {
+                sink.write(&event)?;
+            }
+        }
+        sink.set_backfilled_height(height)?;
+    }
+    Ok(end.max(start.saturating_sub(1)))
+}
+
+/// Shape an `info_get_deploy` result like the `DeployProcessed` SSE message of
+/// its execution in `block_hash`
+pub fn processed_message(deploy: &Value, block_hash: &str) -> Value {
+    let execution_result = deploy["execution_results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|execution| execution["block_hash"] == block_hash)
+        .map(|execution| execution["result"].clone())
+        .unwrap_or(Value::Null);
+
+    json!({
+        "DeployProcessed": {
+            "deploy_hash": deploy["deploy"]["hash"],
+            "block_hash": block_hash,
+            "timestamp": deploy["deploy"]["header"]["timestamp"],
+            "execution_result": execution_result,
+        }
+    })
+}
+
+/// Decode the contract events written by a `DeployProcessed` SSE message
+///
+/// Other messages, failed deploys and writes to other dictionaries yield nothing.
+pub fn events_in_deploy(message: &Value, events_dictionary: &[u8; 32]) -> Vec<IndexedEvent> {
+    let processed = &message["DeployProcessed"];
+    let transforms = processed["execution_result"]["Success"]["effect"]["transforms"].as_array();
+
+    let mut events: Vec<IndexedEvent> = transforms
+        .into_iter()
+        .flatten()
+        .filter_map(|transform| transform["transform"]["WriteCLValue"]["bytes"].as_str())
+        .filter_map(|bytes| dictionary_event(bytes, events_dictionary))
+        .filter_map(|(index, event_bytes)| {
+            let (name, data) = decode_event(&event_bytes)?;
+            Some(IndexedEvent {
+                index,
+                deploy_hash: processed["deploy_hash"].as_str().unwrap_or_default().to_string(),
+                block_hash: processed["block_hash"].as_str().unwrap_or_default().to_string(),
+                timestamp: processed["timestamp"].as_str().unwrap_or_default().to_string(),
+                name,
+                data,
+            })
+        })
+        .collect();
+    events.sort_by_key(|event| event.index);
+    events
+}
+
+/// Decode a CES event into its name and JSON fields
+///
+/// Events emitted under an older layout are read through the shims in
+/// [`crate::events`]. Returns `None` for events the indexer does not track.
+pub fn decode_event(bytes: &[u8]) -> Option<(String, Value)> {
+    events::decode(bytes)
+}
+
+/// Format an address as `account-hash-...` or `hash-...`
+pub fn address_string(address: &Address) -> String {
+    match address {
+        Address::Account(hash) => hash.to_formatted_string(),
+        Address::Contract(hash) => Key::Hash(hash.value()).to_formatted_string(),
+    }
+}
+
+// Get the event index and bytes from a dictionary write, if it is to `events_dictionary`
+//
+// Dictionary entries are stored as the value's CLValue, followed by the
+// dictionary's URef address and the item key (the event index as a string).
+fn dictionary_event(hex_bytes: &str, events_dictionary: &[u8; 32]) -> Option<(u32, Vec<u8>)> {
+    let bytes = hex_decode(hex_bytes)?;
+    let (value, rest) = CLValue::from_bytes(&bytes).ok()?;
+    let (seed, rest) = <[u8; 32]>::from_bytes(rest).ok()?;
+    if &seed != events_dictionary {
+        return None;
+    }
+
+    let (item_key, _) = Bytes::from_bytes(rest).ok()?;
+    let index = core::str::from_utf8(&item_key).ok()?.parse().ok()?;
+    let event: Bytes = value.into_t().ok()?;
+    Some((index, event.into()))
+}
+
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// Get a stored value as JSON from the node's query_global_state RPC
+fn query_global_state(node_address: &str, key: &str) -> Result<Value, IndexerError> {
+    let result = rpc(node_address, "query_global_state", json!({ "state_identifier": null, "key": key, "path": [] }))?;
+    Ok(result["stored_value"].clone())
+}
+
+// Send a JSON-RPC request to the node and return its result
+fn rpc(node_address: &str, method: &str, params: Value) -> Result<Value, IndexerError> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let response: Value = reqwest::blocking::Client::new()
+        .post(format!("{}/rpc", node_address.trim_end_matches('/')))
+        .json(&request)
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    if let Some(error) = response.get("error") {
+        return Err(IndexerError::Http(error.to_string()));
+    }
+    Ok(response["result"].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StakeEvent, Transfer};
+    use odra::casper_types::bytesrepr::ToBytes;
+    use odra::casper_types::account::AccountHash;
+
+    const EVENTS_DICTIONARY_ADDR: [u8; 32] = [7u8; 32];
+
+    fn hex_encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    // Serialize an event the way a dictionary write of it appears in the stream
+    fn dictionary_write(index: u32, event: Vec<u8>, dictionary: [u8; 32]) -> Value {
+        let mut bytes = CLValue::from_t(Bytes::from(event)).unwrap().to_bytes().unwrap();
+        bytes.extend(dictionary.to_bytes().unwrap());
+        bytes.extend(Bytes::from(index.to_string().into_bytes()).to_bytes().unwrap());
+        json!({ "key": "dictionary-00", "transform": { "WriteCLValue": { "cl_type": "Any", "bytes": hex_encode(&bytes) } } })
+    }
+
+    #[test]
+    fn test_events_in_deploy_decodes_tracked_events_in_order() {
+        let user = Address::Account(AccountHash::new([1u8; 32]));
+        let other = Address::Account(AccountHash::new([2u8; 32]));
+        let stake = StakeEvent {
+            user,
+            payer: user,
+            cspr_amount: U256::from(100),
+            stcspr_minted: U256::from(100),
+            timestamp: 5,
+            rate: U256::from(1_000_000_000u64),
+        };
+        let transfer = Transfer { sender: user, recipient: other, amount: U256::from(40) };
+        let message = json!({
+            "DeployProcessed": {
+                "deploy_hash": "aa",
+                "block_hash": "bb",
+                "timestamp": "2024-01-01T00:00:00Z",
+                "execution_result": { "Success": { "effect": { "transforms": [
+                    dictionary_write(4, transfer.to_bytes().unwrap(), EVENTS_DICTIONARY_ADDR),
+                    dictionary_write(3, stake.to_bytes().unwrap(), EVENTS_DICTIONARY_ADDR),
+                    // Same event in another contract's dictionary
+                    dictionary_write(0, stake.to_bytes().unwrap(), [8u8; 32]),
+                ] } } },
+            }
+        });
+
+        let events = events_in_deploy(&message, &EVENTS_DICTIONARY_ADDR);
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].index, events[0].name.as_str()), (3, "StakeEvent"));
+        assert_eq!(events[0].data["stcspr_minted"], "100");
+        assert_eq!(events[0].data["rate"], "1000000000");
+        assert_eq!(events[0].data["user"], address_string(&user));
+        assert_eq!((events[1].index, events[1].name.as_str()), (4, "Transfer"));
+        assert_eq!(events[1].data["recipient"], address_string(&other));
+        assert_eq!(events[1].deploy_hash, "aa");
+    }
+
+    #[test]
+    fn test_processed_message_uses_the_execution_in_the_block() {
+        let user = Address::Account(AccountHash::new([1u8; 32]));
+        let transfer = Transfer { sender: user, recipient: user, amount: U256::from(5) };
+        let success = json!({ "Success": { "effect": { "transforms": [
+            dictionary_write(9, transfer.to_bytes().unwrap(), EVENTS_DICTIONARY_ADDR),
+        ] } } });
+        let deploy = json!({
+            "deploy": { "hash": "aa", "header": { "timestamp": "2024-01-01T00:00:00Z" } },
+            "execution_results": [
+                { "block_hash": "orphan", "result": { "Failure": { "error_message": "" } } },
+                { "block_hash": "bb", "result": success },
+            ],
+        });
+
+        let events = events_in_deploy(&processed_message(&deploy, "bb"), &EVENTS_DICTIONARY_ADDR);
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].index, events[0].deploy_hash.as_str()), (9, "aa"));
+        assert_eq!(events[0].block_hash, "bb");
+        assert!(events_in_deploy(&processed_message(&deploy, "orphan"), &EVENTS_DICTIONARY_ADDR).is_empty());
+    }
+
+    #[test]
+    fn test_jsonl_sink_keeps_the_backfill_checkpoint() {
+        let path = std::env::temp_dir().join(format!("casper-liquid-backfill-{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut sink = JsonlSink::open(&path).unwrap();
+        assert_eq!(sink.backfilled_height().unwrap(), None);
+
+        sink.set_backfilled_height(42).unwrap();
+        assert_eq!(JsonlSink::open(&path).unwrap().backfilled_height().unwrap(), Some(42));
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(format!("{}.checkpoint", path)).unwrap();
+    }
+
+    #[test]
+    fn test_cursor_reports_the_events_a_fork_orphaned() {
+        let event = |index: u32, block: &str| IndexedEvent {
+            index,
+            deploy_hash: format!("deploy-{}", index),
+            block_hash: block.to_string(),
+            timestamp: "t".to_string(),
+            name: "Transfer".to_string(),
+            data: json!({}),
+        };
+        let path = std::env::temp_dir().join(format!("casper-liquid-reorg-{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut sink = JsonlSink::open(&path).unwrap();
+        let mut cursor = Cursor::new();
+
+        let stream = vec![event(0, "a"), event(1, "b"), event(2, "b"), event(1, "b"), event(1, "c"), event(2, "c")];
+        let mut reorgs = Vec::new();
+        for event in &stream {
+            match cursor.advance(event) {
+                Position::Seen => continue,
+                Position::Reorg(reorg) => {
+                    sink.reorg(&reorg).unwrap();
+                    reorgs.push(reorg);
+                }
+                Position::New => {}
+            }
+            sink.write(event).unwrap();
+        }
+
+        // The repeated event is skipped and the fork orphans both events of block b
+        assert_eq!(reorgs.len(), 1);
+        assert_eq!((reorgs[0].from_index, reorgs[0].to_index), (1, 2));
+        assert_eq!((reorgs[0].orphaned_block.as_str(), reorgs[0].new_block.as_str()), ("b", "c"));
+        assert_eq!(cursor.last(), Some((2, "c")));
+
+        let blocks: Vec<String> = read_jsonl(&path).unwrap().into_iter().map(|event| event.block_hash).collect();
+        assert_eq!(blocks, vec!["a", "c", "c"]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_decode_event_skips_untracked_events() {
+        let mint = crate::Mint {
+            recipient: Address::Account(AccountHash::new([1u8; 32])),
+            amount: U256::from(1),
+        };
+        assert!(decode_event(&mint.to_bytes().unwrap()).is_none());
+        assert!(decode_event(&[1, 2, 3]).is_none());
+    }
+}
@@ -0,0 +1,84 @@
+use odra::prelude::*;
+use odra::{module::Module, Var};
+
+use crate::{motes, Error};
+
+/// Event emitted when part of the protocol fee is set aside for insurance
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct InsuranceDeposited {
+    pub amount: U256,
+    pub balance: U256,
+}
+
+/// Event emitted when insurance CSPR is returned to the pool to offset a loss
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct LossCovered {
+    pub amount: U256,
+    pub balance: U256,
+}
+
+/// Insurance - a cover fund fed by a slice of the protocol fee
+///
+/// The fund's CSPR stays in the contract's custody but is not part of the pooled
+/// CSPR backing stCSPR. Moving it in and out of the pool is the responsibility
+/// of the parent contract.
+#[odra::module]
+pub struct Insurance {
+    /// CSPR held by the fund, in motes
+    balance: Var<U512>,
+    /// Share of each protocol fee paid into the fund, in basis points of the fee
+    share_bps: Var<u16>,
+}
+
+#[odra::module]
+impl Insurance {
+    /// Add CSPR to the fund
+    pub fn deposit(&mut self, amount: U256) -> Result<(), Error> {
+        let balance = motes::add(self.balance.get_or_default(), amount)?;
+        self.balance.set(balance);
+
+        self.env().emit_event(InsuranceDeposited {
+            amount,
+            balance: motes::to_amount(balance)?,
+        });
+        Ok(())
+    }
+
+    /// Take CSPR out of the fund to cover a loss
+    pub fn cover(&mut self, amount: U256) -> Result<(), Error> {
+        let balance = self
+            .balance
+            .get_or_default()
+            .checked_sub(motes::to_motes(amount))
+            .ok_or(Error::InsufficientBalance)?;
+        self.balance.set(balance);
+
+        self.env().emit_event(LossCovered {
+            amount,
+            balance: motes::to_amount(balance)?,
+        });
+        Ok(())
+    }
+
+    /// Get the CSPR held by the fund
+    pub fn balance(&self) -> U256 {
+        motes::to_amount(self.balance.get_or_default()).unwrap_or_else(|error| self.env().revert(error))
+    }
+
+    /// Get the CSPR held by the fund, in motes
+    pub fn balance_motes(&self) -> U512 {
+        self.balance.get_or_default()
+    }
+
+    /// Get the share of each protocol fee paid into the fund, in basis points
+    pub fn share_bps(&self) -> u16 {
+        self.share_bps.get_or_default()
+    }
+
+    /// Set the share of each protocol fee paid into the fund
+    pub fn set_share_bps(&mut self, share_bps: u16) {
+        self.share_bps.set(share_bps);
+    }
+}
@@ -0,0 +1,221 @@
+//! Protocol fee sharing with integrators
+//!
+//! Wallets and dapps that bring stakers through `stake_with_referral` can be
+//! registered as integrators. A staker's first referrer is remembered, and the
+//! stCSPR held by everyone an integrator referred is tracked as it moves.
+//! When rewards accrue, each registered integrator earns its share of the
+//! protocol fee on that stCSPR, up to a lifetime cap, and claims it later.
+
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::{Error, BPS_DENOMINATOR};
+
+/// Largest share of the fee on referred stCSPR an integrator can earn (50%)
+pub const MAX_INTEGRATOR_SHARE_BPS: u16 = 5_000;
+
+/// Most integrators that can be registered at once, bounding the fee split loop
+pub const MAX_INTEGRATORS: usize = 32;
+
+/// Event emitted when an integrator is registered, updated or removed (zero share)
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegratorUpdated {
+    pub integrator: Address,
+    pub share_bps: u16,
+    /// Most stCSPR the integrator can earn in total (zero means no cap)
+    pub cap: U256,
+}
+
+/// Event emitted when rewards accrue fees to integrators
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegratorFeesAccrued {
+    pub epoch: u64,
+    /// stCSPR of the fee set aside for integrators, held by the contract
+    pub stcspr_amount: U256,
+}
+
+/// Event emitted when an integrator claims its fee share
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegratorFeesClaimed {
+    pub integrator: Address,
+    pub amount: U256,
+}
+
+/// Integrators - referral attribution and the fee shares integrators earn
+///
+/// Moving the stCSPR is the responsibility of the parent contract, which
+/// holds the unclaimed shares in its own balance.
+#[odra::module]
+pub struct Integrators {
+    /// Registered integrators, in registration order
+    integrators: Var<Vec<Address>>,
+    /// Share of the fee on referred stCSPR each integrator earns, in basis points
+    share_bps: Mapping<Address, u16>,
+    /// Most stCSPR each integrator can earn in total (zero means no cap)
+    caps: Mapping<Address, U256>,
+    /// First referrer of each staker
+    referrer_of: Mapping<Address, Address>,
+    /// stCSPR held by the stakers each referrer brought in
+    referred_shares: Mapping<Address, U256>,
+    /// stCSPR each integrator has earned, claimed or not
+    earned: Mapping<Address, U256>,
+    /// stCSPR each integrator can claim
+    owed: Mapping<Address, U256>,
+    /// stCSPR owed to all integrators
+    total_owed: Var<U256>,
+}
+
+#[odra::module]
+impl Integrators {
+    /// Register or update an integrator; a zero share removes it
+    pub fn set(&mut self, integrator: Address, share_bps: u16, cap: U256) -> Result<(), Error> {
+        if share_bps > MAX_INTEGRATOR_SHARE_BPS {
+            return Err(Error::ExceedsMaximum);
+        }
+
+        let mut integrators = self.list();
+        let registered = integrators.contains(&integrator);
+        if share_bps == 0 {
+            integrators.retain(|address| *address != integrator);
+        } else if !registered {
+            if integrators.len() >= MAX_INTEGRATORS {
+                return Err(Error::ExceedsMaximum);
+            }
+            integrators.push(integrator);
+        }
+
+        self.integrators.set(integrators);
+        self.share_bps.set(&integrator, share_bps);
+        self.caps.set(&integrator, cap);
+        self.env().emit_event(IntegratorUpdated {
+            integrator,
+            share_bps,
+            cap,
+        });
+        Ok(())
+    }
+
+    /// Get the registered integrators
+    pub fn list(&self) -> Vec<Address> {
+        self.integrators.get_or_default()
+    }
+
+    /// Get an integrator's share of the fee on its referred stCSPR, in basis points
+    pub fn share_bps(&self, integrator: &Address) -> u16 {
+        self.share_bps.get(integrator).unwrap_or_default()
+    }
+
+    /// Get the most stCSPR an integrator can earn in total (zero means no cap)
+    pub fn cap(&self, integrator: &Address) -> U256 {
+        self.caps.get(integrator).unwrap_or_default()
+    }
+
+    /// Get a staker's first referrer
+    pub fn referrer_of(&self, staker: &Address) -> Option<Address> {
+        self.referrer_of.get(staker)
+    }
+
+    /// Attribute `staker`, currently holding `balance`, to `referrer` unless
+    /// an earlier referrer was recorded
+    pub fn refer(&mut self, staker: &Address, referrer: &Address, balance: U256) {
+        if self.referrer_of.get(staker).is_none() {
+            self.referrer_of.set(staker, *referrer);
+            self.move_referred(referrer, U256::zero(), balance);
+        }
+    }
+
+    /// Follow a referred staker's balance change from `old` to `new`
+    pub fn track_balance(&mut self, staker: &Address, old: U256, new: U256) {
+        if let Some(referrer) = self.referrer_of.get(staker) {
+            self.move_referred(&referrer, old, new);
+        }
+    }
+
+    /// Get the stCSPR held by the stakers a referrer brought in
+    pub fn referred_shares(&self, referrer: &Address) -> U256 {
+        self.referred_shares.get(referrer).unwrap_or_default()
+    }
+
+    /// Split `fee_shares`, minted on `total_shares`, between the integrators
+    ///
+    /// Each integrator earns its share of the fee on its referred stCSPR, cut
+    /// at its cap. Returns the stCSPR owed to integrators by this call.
+    pub fn accrue(&mut self, fee_shares: U256, total_shares: U256) -> Result<U256, Error> {
+        if fee_shares.is_zero() || total_shares.is_zero() {
+            return Ok(U256::zero());
+        }
+
+        let mut accrued = U256::zero();
+        for integrator in self.list() {
+            let referred = self.referred_shares(&integrator);
+            let mut cut = fee_shares
+                .checked_mul(referred)
+                .and_then(|fee| fee.checked_mul(U256::from(self.share_bps(&integrator))))
+                .ok_or(Error::ArithmeticOverflow)?
+                / total_shares
+                / U256::from(BPS_DENOMINATOR);
+            let earned = self.earned.get(&integrator).unwrap_or_default();
+            let cap = self.cap(&integrator);
+            if !cap.is_zero() {
+                cut = cut.min(cap.saturating_sub(earned));
+            }
+            // The cuts together never exceed the fee they come from
+            cut = cut.min(fee_shares - accrued);
+            if cut.is_zero() {
+                continue;
+            }
+
+            self.earned.set(&integrator, earned + cut);
+            self.owed.set(&integrator, self.owed(&integrator) + cut);
+            accrued += cut;
+        }
+
+        self.total_owed.set(self.total_owed() + accrued);
+        Ok(accrued)
+    }
+
+    /// Get the stCSPR an integrator has earned, claimed or not
+    pub fn earned(&self, integrator: &Address) -> U256 {
+        self.earned.get(integrator).unwrap_or_default()
+    }
+
+    /// Get the stCSPR an integrator can claim
+    pub fn owed(&self, integrator: &Address) -> U256 {
+        self.owed.get(integrator).unwrap_or_default()
+    }
+
+    /// Get the stCSPR owed to all integrators
+    pub fn total_owed(&self) -> U256 {
+        self.total_owed.get_or_default()
+    }
+
+    /// Clear an integrator's claimable stCSPR and return it
+    pub fn take(&mut self, integrator: &Address) -> Result<U256, Error> {
+        let owed = self.owed(integrator);
+        if owed.is_zero() {
+            return Err(Error::InvalidAmount);
+        }
+
+        self.owed.set(integrator, U256::zero());
+        self.total_owed.set(self.total_owed().saturating_sub(owed));
+        self.env().emit_event(IntegratorFeesClaimed {
+            integrator: *integrator,
+            amount: owed,
+        });
+        Ok(owed)
+    }
+
+    // Referred balances are part of the total supply, so the sum cannot overflow
+    fn move_referred(&mut self, referrer: &Address, old: U256, new: U256) {
+        let referred = self.referred_shares(referrer);
+        let updated = if new >= old {
+            referred.saturating_add(new - old)
+        } else {
+            referred.saturating_sub(old - new)
+        };
+        self.referred_shares.set(referrer, updated);
+    }
+}
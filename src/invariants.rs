@@ -0,0 +1,95 @@
+//! Pool invariants for debug builds
+//!
+//! `validate_state_consistency` always checks that outstanding shares are
+//! backed by pooled CSPR. With the `invariants` feature every mutating entry
+//! point ends by checking the set below and reverts with the error of the first
+//! invariant that broke. `invariant_report` returns the figures the checks run
+//! against in any build.
+
+use odra::prelude::*;
+
+use crate::{Error, BPS_DENOMINATOR, MAX_FEE_BPS, RATE_PRECISION};
+
+/// Snapshot of the pool figures the invariants are checked against
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct InvariantReport {
+    /// stCSPR shares in circulation
+    pub total_shares: U256,
+    /// CSPR backing the shares, in motes
+    pub pooled_cspr: U512,
+    /// Balance of the contract purse
+    pub purse: U512,
+    /// CSPR delegated to validators
+    pub delegated: U512,
+    /// CSPR undelegated and not yet back in the purse
+    pub unbonding: U512,
+    /// Deposits in the purse not yet delegated
+    pub pending_deposits: U512,
+    /// CSPR owed to unclaimed withdrawal requests
+    pub pending_withdrawals: U512,
+    /// CSPR held by the insurance fund
+    pub insurance: U512,
+    /// Bonds of pending validator applications
+    pub application_bonds: U512,
+    /// Reported rewards the auction has not been seen to pay yet
+    pub unsynced_rewards: U512,
+    pub fee_bps: u16,
+    pub insurance_share_bps: u16,
+    /// Gross rewards and fee of the latest rewards report
+    pub last_rewards: U256,
+    pub last_fee: U256,
+    /// CSPR returned for the shares that staking `RATE_PRECISION` CSPR mints
+    pub round_trip_cspr: U256,
+    /// Shares minted for the CSPR that `RATE_PRECISION` shares are worth
+    pub round_trip_shares: U256,
+}
+
+impl InvariantReport {
+    /// Check every invariant, returning the error of the first one that fails
+    pub fn check(&self) -> Result<(), Error> {
+        // Outstanding shares are backed by pooled CSPR
+        if self.total_shares > U256::zero() && self.pooled_cspr == U512::zero() {
+            return Err(Error::StateInconsistency);
+        }
+
+        // Every mote owed is held, or was reported as rewards the auction still
+        // has to pay. Holdings may exceed obligations by CSPR sent to the purse
+        // without staking.
+        if self.obligations() > self.holdings().saturating_add(self.unsynced_rewards) {
+            return Err(Error::InvariantCustody);
+        }
+
+        // Rounding in share math never works in the caller's favour
+        let probe = U256::from(RATE_PRECISION);
+        if self.round_trip_cspr > probe || self.round_trip_shares > probe {
+            return Err(Error::InvariantShareRoundTrip);
+        }
+
+        // Fees stay within their bounds and never exceed the rewards they came from
+        if self.fee_bps > MAX_FEE_BPS
+            || self.insurance_share_bps > BPS_DENOMINATOR
+            || self.last_fee > self.last_rewards
+        {
+            return Err(Error::InvariantFeeAccounting);
+        }
+
+        Ok(())
+    }
+
+    /// CSPR the contract owes: the pool, withdrawal requests, the insurance fund
+    /// and application bonds
+    pub fn obligations(&self) -> U512 {
+        self.pooled_cspr
+            .saturating_add(self.pending_withdrawals)
+            .saturating_add(self.insurance)
+            .saturating_add(self.application_bonds)
+    }
+
+    /// CSPR the contract can account for: its purse, its delegations and what is unbonding
+    pub fn holdings(&self) -> U512 {
+        self.purse
+            .saturating_add(self.delegated)
+            .saturating_add(self.unbonding)
+    }
+}
@@ -1,90 +1,956 @@
+use odra::casper_types::bytesrepr::{Bytes, ToBytes};
+use odra::casper_types::PublicKey;
 use odra::prelude::*;
-use odra::{module::Module, Address, Mapping, UnwrapOrRevert, Var};
-
-/// Custom error types for the CasperLiquid contract
-#[odra::odra_error]
-pub enum Error {
-    /// Insufficient balance for the operation
-    InsufficientBalance = 1,
-    /// Insufficient allowance for the operation
-    InsufficientAllowance = 2,
-    /// Invalid amount (e.g., zero when non-zero required)
-    InvalidAmount = 3,
-    /// Transfer to self is not allowed
-    SelfTransfer = 4,
-    /// Arithmetic overflow detected
-    ArithmeticOverflow = 5,
-    /// Arithmetic underflow detected
-    ArithmeticUnderflow = 6,
-    /// Invalid address provided
-    InvalidAddress = 7,
-    /// Operation would exceed maximum allowed value
-    ExceedsMaximum = 8,
+use odra::{module::Module, Address, Mapping, SubModule, UnwrapOrRevert, Var};
+
+pub mod access_control;
+pub mod applications;
+pub mod batches;
+pub mod bridge;
+pub mod cep18;
+pub mod checkpoints;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+pub mod client;
+pub mod delegation;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+pub mod deploy_queue;
+pub mod eras;
+#[cfg(feature = "indexer")]
+pub mod events;
+#[cfg(feature = "indexer")]
+pub mod export;
+pub mod flash;
+pub mod governance;
+pub mod incidents;
+#[cfg(feature = "indexer")]
+pub mod indexer;
+pub mod insurance;
+pub mod integrators;
+pub mod invariants;
+pub mod merkle_drop;
+pub mod motes;
+pub mod multisig;
+pub mod oracle;
+pub mod receiver;
+pub mod recovery;
+pub mod review;
+#[cfg(test)]
+mod replay;
+pub mod rewards;
+pub mod rounding;
+pub mod staking;
+pub mod sunset;
+pub mod swaps;
+#[cfg(test)]
+mod test_support;
+pub mod timelock;
+pub mod vesting;
+#[cfg(feature = "indexer")]
+pub mod watch;
+pub mod withdrawals;
+pub mod wst_cspr;
+
+use access_control::{AccessControl, Role};
+use applications::{ValidatorApplication, ValidatorApplications};
+use batches::{BatchSettled, UnbondingBatch, UnbondingBatches};
+pub use cep18::{
+    Burn, DecreaseAllowance, IncreaseAllowance, Mint, SetAllowance, Transfer, TransferFrom,
+};
+use bridge::{Bridge, BridgeBurned, BridgeMinted, BRIDGE_DAY};
+use checkpoints::Checkpoints;
+use delegation::{Delegation, ValidatorAllocation};
+use eras::Eras;
+use flash::{FlashBorrowerContractRef, FlashMint, FlashMinted};
+use governance::{Governance, Proposal, ProposalAction};
+use incidents::Incidents;
+use insurance::Insurance;
+use integrators::{IntegratorFeesAccrued, Integrators};
+use invariants::InvariantReport;
+use merkle_drop::MerkleDrop;
+use multisig::{Multisig, MultisigAction};
+use oracle::{OracleCommittee, OracleReport};
+use receiver::TokenReceiverContractRef;
+use recovery::{AdminRecovered, Recovery};
+use review::{WithdrawalFlagged, WithdrawalReview};
+use rewards::{RewardReport, Rewards};
+use rounding::RoundingPolicy;
+use sunset::{ForcedExit, Sunset};
+use swaps::{SwapPoolContractRef, TreasurySwapped, TreasurySwaps};
+use timelock::{QueuedAction, Timelock};
+use vesting::{Vesting, VestingSchedule};
+use withdrawals::{WithdrawalQueue, WithdrawalRequest};
+
+/// Declares the contract error enum together with its code and message tables.
+///
+/// Each variant is written once as `Variant = code => "message"`, so the enum,
+/// `from_code`, `code`, `describe` and `Error::TABLE` are generated from the same
+/// list and cannot drift apart.
+macro_rules! contract_errors {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[doc = $doc:literal])*
+                $variant:ident = $code:literal => $message:literal,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        pub enum $name {
+            $(
+                $(#[doc = $doc])*
+                $variant = $code,
+            )*
+        }
+
+        impl $name {
+            /// Every error this contract can revert with, as `(code, variant, message)`
+            /// in code order. This is the exported form of the table used by
+            /// `decode-error` and client tooling.
+            pub const TABLE: &'static [(u16, &'static str, &'static str)] = &[
+                $(($code, stringify!($variant), $message),)*
+            ];
+
+            /// Look up an error by the numeric code reported in a failed deploy
+            /// (`User error: <code>`). Returns `None` for codes this contract never emits.
+            pub fn from_code(code: u16) -> Option<Self> {
+                match code {
+                    $($code => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// The stable numeric code this error reverts with
+            pub fn code(&self) -> u16 {
+                match self {
+                    $($name::$variant => $code,)*
+                }
+            }
+
+            /// Human-readable explanation of the error, suitable for showing to end users.
+            /// The wording is part of the public interface and should not change once released.
+            pub fn describe(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $message,)*
+                }
+            }
+        }
+    };
+}
+
+contract_errors! {
+    /// Custom error types for the CasperLiquid contract
+    ///
+    /// A reverted deploy reports `User error: <code>` with the numeric value below.
+    /// Codes are part of the public interface: never renumber or reuse them, only
+    /// append new variants.
+    #[odra::odra_error]
+    #[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Error {
+        /// Insufficient balance for the operation
+        InsufficientBalance = 1 => "Insufficient stCSPR balance",
+        /// Insufficient allowance for the operation
+        InsufficientAllowance = 2 => "Insufficient allowance",
+        /// Invalid amount (e.g., zero when non-zero required)
+        InvalidAmount = 3 => "Amount must be greater than zero",
+        /// Transfer to self is not allowed
+        SelfTransfer = 4 => "Cannot transfer or approve to the same account",
+        /// Arithmetic overflow detected
+        ArithmeticOverflow = 5 => "Arithmetic overflow",
+        /// Arithmetic underflow detected
+        ArithmeticUnderflow = 6 => "Arithmetic underflow",
+        /// Invalid address provided
+        InvalidAddress = 7 => "Invalid address",
+        /// Operation would exceed maximum allowed value
+        ExceedsMaximum = 8 => "Amount exceeds the maximum allowed value",
+        /// Faucet already claimed by this address within the current period
+        FaucetCooldown = 9 => "Faucet already claimed, try again later",
+        /// CSPR attached to the call does not match the requested amount
+        AttachedValueMismatch = 10 => "Attached CSPR does not match the stake amount",
+        // 11 is retired: a failed native transfer reverts in the host, and a short
+        // purse is reported as InsufficientLiquidity before any transfer
+        /// Caller is not allowed to perform this operation
+        NotAuthorized = 12 => "Caller is not authorized for this operation",
+        /// Validator is already registered
+        ValidatorAlreadyExists = 13 => "Validator is already registered",
+        /// Validator is not registered
+        ValidatorNotFound = 14 => "Validator is not registered",
+        /// Validator still has CSPR delegated to it
+        ValidatorHasDelegations = 15 => "Validator still has delegated CSPR",
+        /// Not enough liquid CSPR in the contract purse
+        InsufficientLiquidity = 16 => "Not enough liquid CSPR in the contract",
+        /// No withdrawal request exists with the given id
+        WithdrawalNotFound = 17 => "Withdrawal request not found",
+        /// Withdrawal request has already been paid out
+        WithdrawalAlreadyClaimed = 18 => "Withdrawal request already claimed",
+        /// Withdrawal request is still within its unbonding period
+        UnbondingNotFinished = 19 => "Withdrawal is still unbonding",
+        /// Contract is paused
+        ContractPaused = 20 => "Contract is paused",
+        /// Entry point re-entered while a guarded call is in progress
+        Reentrancy = 21 => "Reentrant call",
+        /// Stake amount is below the configured minimum
+        BelowMinimumStake = 22 => "Stake amount is below the minimum",
+        /// Stake would exceed the pool's staking cap
+        StakingCapReached = 23 => "Staking cap reached",
+        /// Stored state already matches the installed contract version
+        MigrationNotNeeded = 24 => "State is already at the current version",
+        /// Batch argument vectors have different lengths
+        LengthMismatch = 25 => "Batch argument lengths do not match",
+        /// Permit deadline has passed
+        PermitExpired = 26 => "Permit has expired",
+        /// Permit signature does not match the owner's key
+        InvalidSignature = 27 => "Invalid signature",
+        /// Proposal id does not exist
+        ProposalNotFound = 28 => "Proposal not found",
+        /// Voter has already voted on the proposal
+        AlreadyVoted = 29 => "Already voted on this proposal",
+        /// Voting period of the proposal has ended
+        VotingClosed = 30 => "Voting has closed",
+        /// Voting period of the proposal has not ended yet
+        VotingInProgress = 31 => "Voting is still in progress",
+        /// Proposal did not reach quorum or a majority
+        ProposalNotPassed = 32 => "Proposal did not pass",
+        /// Proposal has already been executed
+        ProposalAlreadyExecuted = 33 => "Proposal already executed",
+        /// Account held no stCSPR at the proposal snapshot
+        NoVotingPower = 34 => "No voting power at the snapshot",
+        /// Unstake exceeds the per-era rate limit
+        UnstakeRateLimited = 35 => "Unstake exceeds the per-era rate limit",
+        /// Pending deposits are below the flush threshold
+        BelowFlushThreshold = 36 => "Pending deposits are below the flush threshold",
+        /// Maintenance task ran too recently
+        KeeperCooldown = 37 => "Maintenance task ran too recently",
+        /// Stored accounting violates a pool invariant
+        StateInconsistency = 38 => "Pool accounting is inconsistent",
+        /// Era is in the future or otherwise out of range
+        InvalidEra = 39 => "Invalid era",
+        /// Receiving contract rejected the stCSPR
+        TransferRejected = 40 => "Receiver rejected the transfer",
+        /// Queued action's eta is sooner than the timelock delay allows
+        TimelockEtaTooEarly = 41 => "Eta is earlier than the timelock delay",
+        /// Queued action's eta has not been reached
+        TimelockNotReady = 42 => "Timelocked action is not ready",
+        /// No queued action exists with the given id
+        TimelockActionNotFound = 43 => "Timelocked action not found",
+        /// Queued action was already executed or cancelled
+        TimelockActionNotPending = 44 => "Timelocked action is not pending",
+        /// Action must be queued through the timelock
+        TimelockRequired = 45 => "Action must go through the timelock",
+        /// Action must be approved through the admin multisig
+        MultisigRequired = 46 => "Action must go through the multisig",
+        /// No multisig action exists with the given id
+        MultisigActionNotFound = 47 => "Multisig action not found",
+        /// Multisig action was already executed
+        MultisigActionNotPending = 48 => "Multisig action is not pending",
+        /// Multisig action expired before reaching its threshold
+        MultisigActionExpired = 49 => "Multisig action expired",
+        /// Compound interval since the last compound has not passed
+        CompoundTooSoon = 50 => "Compounded too recently",
+        /// Transfers are restricted and the recipient is not allowlisted
+        RecipientNotAllowlisted = 51 => "Recipient is not allowlisted",
+        /// Transfers were opened permanently
+        TransfersAlreadyOpen = 52 => "Transfers are already open",
+        /// No merkle root is set or the proof does not match it
+        InvalidMerkleProof = 53 => "Merkle proof does not match the drop root",
+        /// The drop allocation at this index was already claimed
+        AlreadyClaimed = 54 => "Drop allocation already claimed",
+        /// No vesting schedule exists with the given id
+        VestingNotFound = 55 => "Vesting schedule not found",
+        /// Vesting duration is zero or shorter than the cliff
+        InvalidVestingSchedule = 56 => "Vesting duration is zero or shorter than the cliff",
+        /// Vesting schedule is not revocable or was already revoked
+        VestingNotRevocable = 57 => "Vesting schedule is not revocable or was already revoked",
+        /// Flash mints are not enabled
+        FlashMintDisabled = 58 => "Flash mints are not enabled",
+        /// Flash mint borrower did not hold the amount plus fee when its hook returned
+        FlashMintNotRepaid = 59 => "Flash mint was not repaid with its fee",
+        /// Native motes amount does not fit into a token amount
+        MotesOutOfRange = 60 => "Motes amount out of range",
+        /// Share math rounding would create value (invariants feature)
+        InvariantShareRoundTrip = 61 => "Share math round trip creates value",
+        /// Fee settings or the latest fee are out of bounds (invariants feature)
+        InvariantFeeAccounting = 62 => "Fee accounting out of bounds",
+        /// Reports must be signed by the oracle committee
+        OracleReportRequired = 63 => "Report must be signed by the oracle committee",
+        /// Oracle report nonce is not above the last accepted one
+        OracleNonceUsed = 64 => "Oracle report nonce already used",
+        /// Too few committee members signed the oracle report
+        OracleQuorumNotReached = 65 => "Oracle report lacks a quorum of signatures",
+        /// Staked stCSPR is still within the stake cooldown
+        CooldownActive = 66 => "Stake cooldown has not elapsed",
+        /// Version 1 state has no owner and the wasm names none
+        MigrationOwnerNotSet = 67 => "The wasm was built without a migration owner",
+        /// Signer already approved this multisig action
+        AlreadyApproved = 68 => "Already approved this action",
+        /// Pool obligations exceed the CSPR held and reported (invariants feature)
+        InvariantCustody = 69 => "Pool obligations exceed custody",
+        /// Swap pool is not registered for treasury swaps
+        SwapPoolNotRegistered = 70 => "Swap pool is not registered",
+        /// Treasury swap exceeds the per-epoch limit
+        SwapLimitReached = 71 => "Treasury swap limit reached for this epoch",
+        /// Swap pool paid less than the minimum output
+        SlippageExceeded = 72 => "Swap output is below the minimum",
+        /// No validator application exists with the given id
+        ApplicationNotFound = 73 => "Validator application not found",
+        /// Validator application was already approved or rejected
+        ApplicationNotPending = 74 => "Validator application was already decided",
+        /// Bridge operator's daily mint cap reached
+        BridgeCapReached = 75 => "Bridge operator's daily mint cap reached",
+        /// Bridge mint exceeds the bridged-out supply
+        BridgeSupplyExceeded = 76 => "Bridge mint exceeds the bridged-out supply",
+        /// Origin transaction already minted
+        BridgeTxProcessed = 77 => "Origin transaction already minted",
+        /// Admin heartbeat period has not run out
+        RecoveryNotDue = 78 => "Admin heartbeats have not stopped for the recovery period",
+        /// Unstake is above the withdrawal review threshold
+        ReviewRequired = 79 => "Unstake is above the review threshold; use request_unstake",
+        /// Withdrawal request is not in an open review window
+        NotUnderReview = 80 => "Withdrawal request is not in an open review window",
+        /// No unsettled undelegation batch returns in the era
+        BatchNotFound = 81 => "No unsettled undelegation batch returns in that era",
+        /// The entry point belongs to a feature this deployment was initialized without
+        FeatureDisabled = 82 => "This feature is disabled on this deployment",
+        /// A sunset has started, so the pool takes no new stake
+        SunsetActive = 83 => "The pool is winding down and takes no new stake",
+        /// No sunset has started, or its exit window is still open
+        SunsetNotDue = 84 => "The sunset has not reached that step",
+        /// stCSPR or unclaimed withdrawal requests remain
+        SupplyOutstanding = 85 => "stCSPR or withdrawal requests are still outstanding",
+        /// The contract was retired at the end of its sunset
+        ContractRetired = 86 => "The contract has been retired",
+    }
+}
+
+/// Lifetime staking statistics of a single address
+#[odra::odra_type]
+pub struct UserStats {
+    /// CSPR staked over the address's lifetime
+    pub total_ever_staked: U256,
+    /// CSPR returned by unstakes and withdrawal requests over the address's lifetime
+    pub total_ever_unstaked: U256,
+    /// Block time of the first stake (zero if the address never staked)
+    pub first_stake_timestamp: u64,
+    /// Block time of the last stake, unstake or withdrawal request
+    pub last_action_timestamp: u64,
+}
+
+/// Pool-wide figures for dashboards, returned by `get_protocol_stats`
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProtocolStats {
+    /// CSPR pooled by the contract (liquid and delegated)
+    pub total_pooled_cspr: U256,
+    /// stCSPR shares in circulation
+    pub total_shares: U256,
+    /// CSPR per stCSPR, scaled by `RATE_PRECISION`
+    pub exchange_rate: U256,
+    /// Annualized return over the last two reward reports, in basis points
+    pub current_apr_estimate: U256,
+    pub validator_count: u32,
+    /// CSPR owed to unclaimed withdrawal requests
+    pub pending_withdrawals: U256,
+}
+
+/// Exchange rate with its freshness, returned by `rate_with_meta`
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RateWithMeta {
+    /// CSPR per stCSPR, scaled by `RATE_PRECISION`
+    pub rate: U256,
+    /// Block time of the last rewards report (zero before the first)
+    pub updated_at: u64,
+    /// Number of the last reported rewards epoch
+    pub epoch: u64,
+}
+
+/// Whether pending withdrawals are covered, returned by `get_obligations`
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obligations {
+    /// CSPR owed to unclaimed withdrawal requests
+    pub pending_withdrawals: U512,
+    /// CSPR still unbonding at the auction, as `(era it returns in, amount)`, oldest first
+    pub unbonding: Vec<(u64, U512)>,
+    pub total_unbonding: U512,
+    /// CSPR free for instant unstakes and delegation: the purse outside the
+    /// insurance fund, less pending withdrawals
+    pub buffered: U512,
+    /// Pending withdrawals not covered by the purse (outside insurance) and unbonding CSPR
+    pub shortfall: U512,
+    /// Purse (outside insurance) and unbonding CSPR left over once pending withdrawals are paid
+    pub surplus: U512,
+}
+
+/// What integrators check during an incident, returned by `health_check`
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct HealthCheck {
+    pub paused: bool,
+    /// Incident code published by an operator (see `incidents`), zero if none
+    pub incident_code: u8,
+    pub incident_message_uri: String,
+    /// Block time the incident status was last published
+    pub incident_updated_at: u64,
+    /// Where to reach the security team
+    pub security_contact: String,
+    /// CSPR per stCSPR, scaled by `RATE_PRECISION`
+    pub exchange_rate: U256,
+    /// Pending withdrawals not covered by the purse and unbonding CSPR
+    pub withdrawal_shortfall: U512,
+    pub version: u32,
 }
 
 /// Event emitted when a user stakes CSPR tokens
 #[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
 pub struct StakeEvent {
+    /// Address the stCSPR was minted to
     pub user: Address,
+    /// Address that paid the CSPR (same as `user` for a plain stake)
+    pub payer: Address,
     pub cspr_amount: U256,
     pub stcspr_minted: U256,
     pub timestamp: u64,
+    /// Exchange rate the stake executed at, scaled by `RATE_PRECISION`
+    pub rate: U256,
 }
 
 /// Event emitted when a user unstakes stCSPR tokens
 #[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnstakeEvent {
+    /// Address whose stCSPR was burned
     pub user: Address,
+    /// Address the CSPR is paid or owed to
+    pub recipient: Address,
     pub stcspr_burned: U256,
     pub cspr_returned: U256,
     pub timestamp: u64,
+    /// Exchange rate the unstake executed at, scaled by `RATE_PRECISION`
+    pub rate: U256,
+}
+
+/// Event emitted when the protocol fee is minted to the treasury
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeCollected {
+    pub epoch: u64,
+    pub treasury: Address,
+    pub cspr_amount: U256,
+    pub stcspr_minted: U256,
+}
+
+/// Event emitted when a validator's operator claims its rebate
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RebateClaimed {
+    pub validator: PublicKey,
+    pub operator: Address,
+    pub cspr_amount: U256,
+    pub stcspr_minted: U256,
 }
 
-/// Event emitted when tokens are transferred (CEP-18 standard)
+/// Event emitted when an owner sets an operator's unstake budget
 #[odra::event]
-pub struct Transfer {
-    pub from: Address,
-    pub to: Address,
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnstakeApproval {
+    pub owner: Address,
+    pub operator: Address,
     pub amount: U256,
 }
 
-/// Event emitted when an approval is set (CEP-18 standard)
+/// Event emitted when a spender's cumulative usage of an allowance crosses
+/// a multiple of the threshold configured by the owner
 #[odra::event]
-pub struct Approval {
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct AllowanceUsageThresholdCrossed {
     pub owner: Address,
     pub spender: Address,
+    pub threshold: U256,
+    pub cumulative_usage: U256,
+}
+
+/// Event emitted when a stake is attributed to a referrer
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReferralRecorded {
+    pub referrer: Address,
+    pub staker: Address,
+    pub cspr_amount: U256,
+    pub total_referred: U256,
+}
+
+/// Event emitted when lost CSPR is written off the pool
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct LossReported {
+    pub validator: PublicKey,
+    pub amount: U256,
+    pub exchange_rate: U256,
+}
+
+/// Event emitted when a numeric protocol setting changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigChanged {
+    /// Name of the setting, e.g. "fee_bps"
+    pub key: String,
+    pub old_value: U256,
+    pub new_value: U256,
+    pub changed_by: Address,
+}
+
+/// Event emitted when the fee treasury changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreasuryChanged {
+    pub previous_treasury: Option<Address>,
+    pub new_treasury: Address,
+    pub changed_by: Address,
+}
+
+/// Event emitted when the contract is paused
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Paused {
+    pub account: Address,
+}
+
+/// Event emitted when the contract is unpaused
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Unpaused {
+    pub account: Address,
+}
+
+/// Event emitted when the staking limits change (zero means no limit)
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct LimitsUpdated {
+    pub min_stake: U256,
+    pub max_stake_per_tx: U256,
+    pub max_total_staked: U256,
+}
+
+/// Event emitted when stored state is migrated to a new contract version
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Migrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+/// Event emitted for each validator whose delegation `rebalance` moves towards its target
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RebalancePlanned {
+    pub validator: PublicKey,
+    pub current: U512,
+    pub target: U512,
+}
+
+/// Event emitted when a stake adds CSPR to the pending deposits
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct DepositBuffered {
+    pub staker: Address,
+    pub amount: U512,
+    pub pending: U512,
+}
+
+/// Event emitted when pending deposits are delegated across the validators
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct DepositsFlushed {
+    pub amount: U512,
+    /// Pending deposits left over, e.g. rounding dust
+    pub pending: U512,
+}
+
+/// Event emitted when a minter extension mints stCSPR shares
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtensionMinted {
+    pub minter: Address,
+    pub recipient: Address,
+    pub shares: U256,
+}
+
+/// Event emitted when a minter extension burns stCSPR shares
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtensionBurned {
+    pub minter: Address,
+    pub owner: Address,
+    pub shares: U256,
+}
+
+/// Event emitted when an address is added to or removed from the transfer allowlist
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransferAllowlistUpdated {
+    pub account: Address,
+    pub allowed: bool,
+}
+
+/// Event emitted when the launch-phase transfer restriction is switched on
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransfersRestricted {
+    pub account: Address,
+}
+
+/// Event emitted when transfers are opened to everyone for good
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransfersOpened {
+    pub account: Address,
+}
+
+/// Event emitted when a keeper is paid for running a maintenance task
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeeperRewarded {
+    pub keeper: Address,
+    /// Name of the entry point, e.g. "flush_deposits"
+    pub task: String,
     pub amount: U256,
 }
 
+/// Event emitted when CSPR moves between the liquidity buffer and a validator
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct BufferRebalanced {
+    pub validator: PublicKey,
+    pub delegated: U512,
+    pub undelegated: U512,
+    pub buffer: U512,
+}
+
+/// Event emitted when the published exchange rate changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RateUpdated {
+    /// CSPR per stCSPR, scaled by `RATE_PRECISION` (zero if nothing was published before)
+    pub old_rate: U256,
+    pub new_rate: U256,
+    pub timestamp: u64,
+}
+
+/// Fixed-point precision of the exchange rate (matches the 9 token decimals)
+pub const RATE_PRECISION: u64 = 1_000_000_000;
+
+/// Named key holding the published `(exchange rate, block time)` pair
+///
+/// The value is a `Tuple2(U256, U64)` with the rate scaled by `RATE_PRECISION`,
+/// so it can be read by key without calling into the contract.
+pub const RATE_FEED_KEY: &str = "stcspr_rate";
+
+/// Storage layout version of this build; bumped whenever `migrate` gains a step
+pub const CONTRACT_VERSION: u32 = 2;
+
+/// Account made owner when version 1 state (which had no owner) is migrated
+///
+/// Set `CASPER_LIQUID_MIGRATION_OWNER` to a formatted address (`account-hash-...`)
+/// when building the upgrade wasm. Only the holder of the package's access key
+/// can install that wasm, so the owner it names is as trusted as the install.
+pub const MIGRATION_OWNER: Option<&str> = option_env!("CASPER_LIQUID_MIGRATION_OWNER");
+
+/// Domain tag prefixed to every permit message so signatures cannot be replayed elsewhere
+pub const PERMIT_DOMAIN: &str = "CasperLiquid:permit:v1";
+
+/// Default length of an era (2 hours, in milliseconds)
+pub const ERA_DURATION: u64 = 7_200_000;
+
+/// Smallest delegation the auction contract accepts (500 CSPR, in motes)
+pub const MIN_DELEGATION: u64 = 500_000_000_000;
+
+/// Default weight of the latest era in the withdrawal average (20%)
+pub const DEFAULT_EWMA_ALPHA_BPS: u16 = 2_000;
+
+/// Most completed eras folded into the withdrawal average by one upkeep
+pub const MAX_EWMA_ERAS: u64 = 64;
+
+/// Denominator for basis point values
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+/// Highest protocol fee that can be configured (20% of rewards)
+pub const MAX_FEE_BPS: u16 = 2_000;
+
+/// Feature bit: admins can restrict transfers to an allowlist
+pub const FEATURE_COMPLIANCE: u32 = 1 << 0;
+
+/// Feature bit: `unstake`, `unstake_to` and `unstake_from` pay out from the buffer
+pub const FEATURE_INSTANT_UNSTAKE: u32 = 1 << 1;
+
+/// Feature bit: `stake_with_referral` records referrers
+pub const FEATURE_REFERRALS: u32 = 1 << 2;
+
+/// Features enabled when `init` is not given a bitmap, and on deployments from before the bitmap
+pub const DEFAULT_FEATURES: u32 = FEATURE_COMPLIANCE | FEATURE_INSTANT_UNSTAKE | FEATURE_REFERRALS;
+
+/// Amount of stCSPR handed out per faucet claim (1,000 stCSPR)
+#[cfg(feature = "testnet-faucet")]
+pub const FAUCET_AMOUNT: u64 = 1_000_000_000_000;
+
+/// Minimum time between two faucet claims by the same address (one day, in milliseconds)
+#[cfg(feature = "testnet-faucet")]
+pub const FAUCET_PERIOD: u64 = 86_400_000;
+
 /// CasperLiquid - A liquid staking contract for Casper Network
 /// 
-/// This contract allows users to stake CSPR tokens and receive stCSPR tokens
-/// in return, maintaining a 1:1 ratio. Users can unstake to get their CSPR back.
+/// This contract allows users to stake CSPR tokens and receive stCSPR shares
+/// in return. Each share represents a proportional claim on the pooled CSPR, so
+/// the exchange rate appreciates as staking rewards are added to the pool.
+/// Users can unstake to get their share of the pool back.
+///
+/// Storage keys are derived from field order, so a new wasm installed under the
+/// same package can only read existing state if fields are appended at the end
+/// and never reordered or removed. Changes to the meaning of stored values are
+/// applied by `migrate`.
 #[odra::module]
 pub struct CasperLiquid {
     /// Token balances for each address
     balances: Mapping<Address, U256>,
     /// Allowances for spending tokens on behalf of others
     allowances: Mapping<(Address, Address), U256>,
-    /// Total amount of CSPR currently staked
+    /// Total stCSPR shares in circulation
     total_staked: Var<U256>,
-    /// Total CSPR held in custody by the contract
-    contract_cspr_balance: Var<U256>,
+    /// Total CSPR pooled by the contract (liquid and delegated), in motes
+    contract_cspr_balance: Var<U512>,
     /// Token metadata
     name: Var<String>,
     symbol: Var<String>,
     decimals: Var<u8>,
+    /// CSPR credited to the pool by the original unbacked faucet; no longer written
+    faucet_minted: Var<U256>,
+    /// Block time of each address's last faucet claim
+    faucet_last_claim: Mapping<Address, u64>,
+    /// Cumulative amount each spender has moved on behalf of each owner
+    allowance_usage: Mapping<(Address, Address), U256>,
+    /// Owner-configured usage alert threshold per (owner, spender) pair
+    allowance_usage_thresholds: Mapping<(Address, Address), U256>,
+    /// Unstake budgets granted by owners to operators
+    unstake_allowances: Mapping<(Address, Address), U256>,
+    /// Owner and role assignments
+    access: SubModule<AccessControl>,
+    /// Validator set and per-validator delegated amounts
+    delegation: SubModule<Delegation>,
+    /// History of reward reports
+    rewards: SubModule<Rewards>,
+    /// Withdrawal requests waiting for the unbonding period
+    withdrawals: SubModule<WithdrawalQueue>,
+    /// Protocol fee taken from reported rewards, in basis points
+    fee_bps: Var<u16>,
+    /// Address receiving protocol fees as stCSPR
+    treasury: Var<Address>,
+    /// Emergency stop for staking, unstaking and token movements
+    paused: Var<bool>,
+    /// Reentrancy guard, set while a guarded entry point is executing
+    locked: Var<bool>,
+    /// Smallest CSPR amount accepted by `stake` (zero means no minimum)
+    min_stake: Var<U256>,
+    /// Largest CSPR amount accepted by a single `stake` (zero means no limit)
+    max_stake_per_tx: Var<U256>,
+    /// Cap on the total pooled CSPR (zero means no cap)
+    max_total_staked: Var<U256>,
+    /// Storage layout version (unset means version 1, the original 1:1 layout)
+    version: Var<u32>,
+    /// Lifetime staking statistics per address
+    user_stats: Mapping<Address, UserStats>,
+    /// Share of the pooled CSPR kept liquid for instant unstakes, in basis points
+    buffer_target_bps: Var<u16>,
+    /// Number of permits used by each owner, part of the next permit message
+    nonces: Mapping<Address, u64>,
+    /// History of balances and total supply for snapshot lookups
+    checkpoints: SubModule<Checkpoints>,
+    /// Proposals and votes of stCSPR holders
+    governance: SubModule<Governance>,
+    /// Cumulative CSPR staked through each referrer
+    referred_volume: Mapping<Address, U256>,
+    /// Cover fund fed by a slice of the protocol fee
+    insurance: SubModule<Insurance>,
+    /// Most of the pool that can be unstaked per era, in basis points (zero means no limit)
+    unstake_limit_bps: Var<u16>,
+    /// CSPR unstaked or queued for withdrawal in each era
+    unstaked_per_era: Mapping<u64, U256>,
+    /// CSPR staked since the last flush that has not been delegated yet
+    pending_deposits: Var<U512>,
+    /// Pending deposits needed before `flush_deposits` delegates them (defaults to `MIN_DELEGATION`)
+    flush_threshold: Var<U512>,
+    /// stCSPR paid from the treasury to whoever runs a maintenance task
+    keeper_reward: Var<U256>,
+    /// Time between two paid runs of the same maintenance task, in milliseconds
+    keeper_cooldown: Var<u64>,
+    /// Block time each maintenance task last ran
+    keeper_last_run: Mapping<String, u64>,
+    /// Delays every admin change and executed proposal once enabled
+    timelock: SubModule<Timelock>,
+    /// m-of-n approval of admin actions once enabled
+    multisig: SubModule<Multisig>,
+    /// Block time of the last `compound` call
+    last_compound_timestamp: Var<u64>,
+    /// Minimum time between two `compound` calls (defaults to one era)
+    compound_interval: Var<u64>,
+    /// Whether only allowlisted addresses can receive transfers
+    transfers_restricted: Var<bool>,
+    /// Set once `open_transfers` lifts the restriction for good
+    transfers_opened: Var<bool>,
+    /// Addresses allowed to receive transfers while restricted
+    transfer_allowlist: Mapping<Address, bool>,
+    /// Era length, unbonding delay and era anchor
+    eras: SubModule<Eras>,
+    /// Merkle root and claim flags of the current stCSPR drop
+    merkle_drop: SubModule<MerkleDrop>,
+    /// stCSPR vesting schedules for team and partner allocations
+    vesting: SubModule<Vesting>,
+    /// Flash mint switch and fee
+    flash: SubModule<FlashMint>,
+    /// Oracle committee that signs reward and loss reports once enabled
+    oracle: SubModule<OracleCommittee>,
+    /// Minimum time between staking and unstaking, in milliseconds (zero disables it)
+    stake_cooldown: Var<u64>,
+    /// Block time each address last staked, or received stCSPR still cooling down
+    last_stake_time: Mapping<Address, u64>,
+    /// CSPR undelegated and still held by the auction, by the era it returns in
+    unbonding_per_era: Mapping<u64, U512>,
+    /// Return eras with CSPR recorded in `unbonding_per_era`, oldest first
+    unbonding_eras: Var<Vec<u64>>,
+    /// stCSPR each address staked, or received from a staker, within the cooldown
+    cooling_shares: Mapping<Address, U256>,
+    /// stCSPR held by the contract for the testnet faucet
+    faucet_balance: Var<U256>,
+    /// stCSPR flash minted and not yet repaid, left out of the exchange rate
+    flash_outstanding: Var<U256>,
+    /// Reported rewards not yet seen in the tracked delegations, in motes
+    unsynced_rewards: Var<U512>,
+    /// Exponentially weighted average of the CSPR withdrawn per era, in motes
+    withdrawal_ewma: Var<U512>,
+    /// Last era folded into `withdrawal_ewma`
+    withdrawal_ewma_era: Var<u64>,
+    /// Weight of the latest era in `withdrawal_ewma`, in basis points
+    ewma_alpha_bps: Var<u16>,
+    /// Share of the average era withdrawals kept liquid, in basis points (zero disables it)
+    withdrawal_cover_bps: Var<u16>,
+    /// Swap pools and per-epoch budget for selling treasury stCSPR
+    swaps: SubModule<TreasurySwaps>,
+    /// Validators applying to join the set, and their bonds
+    applications: SubModule<ValidatorApplications>,
+    /// Integrators earning a share of the fee on the stCSPR of stakers they referred
+    integrators: SubModule<Integrators>,
+    /// stCSPR bridged to other chains and the bridge operators' daily caps
+    bridge: SubModule<Bridge>,
+    /// Admin heartbeats and the council that takes over when they stop
+    recovery: SubModule<Recovery>,
+    /// Review windows and guardian flags of large withdrawal requests
+    review: SubModule<WithdrawalReview>,
+    /// Published security contact and incident status
+    incidents: SubModule<Incidents>,
+    /// Expected and returned CSPR of each undelegation batch, and the requests it pays
+    batches: SubModule<UnbondingBatches>,
+    /// `FEATURE_*` bits fixed at init (`DEFAULT_FEATURES` when unset)
+    features: Var<u32>,
+    /// Wind-down schedule of a sunset and the retired flag
+    sunset: SubModule<Sunset>,
 }
 
 #[odra::module]
 impl CasperLiquid {
-    /// Initialize the contract with metadata
-    pub fn init(&mut self) {
-        self.name.set("Staked CSPR".to_string());
-        self.symbol.set("stCSPR".to_string());
-        self.decimals.set(9u8); // Same as CSPR
+    /// Initialize the contract with metadata and configuration
+    ///
+    /// Every argument is optional so the same wasm can be deployed with different
+    /// settings: metadata defaults to "Staked CSPR" / "stCSPR" / 9 decimals, the
+    /// owner defaults to the deployer and no protocol fee or treasury is set.
+    pub fn init(
+        &mut self,
+        name: Option<String>,
+        symbol: Option<String>,
+        decimals: Option<u8>,
+        owner: Option<Address>,
+        fee_bps: Option<u16>,
+        treasury: Option<Address>,
+        features: Option<u32>,
+    ) {
+        let fee_bps = fee_bps.unwrap_or_default();
+        if fee_bps > MAX_FEE_BPS {
+            self.env().revert(Error::ExceedsMaximum);
+        }
+        let features = features.unwrap_or(DEFAULT_FEATURES);
+        if features & !DEFAULT_FEATURES != 0 {
+            self.env().revert(Error::ExceedsMaximum);
+        }
+        
+        self.name.set(name.unwrap_or_else(|| "Staked CSPR".to_string()));
+        self.symbol.set(symbol.unwrap_or_else(|| "stCSPR".to_string()));
+        self.decimals.set(decimals.unwrap_or(9u8)); // Same as CSPR by default
         self.total_staked.set(U256::zero());
-        self.contract_cspr_balance.set(U256::zero());
+        self.contract_cspr_balance.set(U512::zero());
+        self.fee_bps.set(fee_bps);
+        if let Some(treasury) = treasury {
+            self.treasury.set(treasury);
+        }
+        self.access.init(owner.unwrap_or_else(|| self.env().caller()));
+        self.version.set(CONTRACT_VERSION);
+        self.features.set(features);
+    }
+
+    /// Get the `FEATURE_*` bits this deployment was initialized with
+    pub fn features(&self) -> u32 {
+        self.features.get().unwrap_or(DEFAULT_FEATURES)
+    }
+
+    /// Check whether every bit of `feature` is enabled on this deployment
+    pub fn feature_enabled(&self, feature: u32) -> bool {
+        self.features() & feature == feature
+    }
+
+    /// Ensure the caller holds a role
+    fn require_role(&self, role: Role) -> Result<(), Error> {
+        self.access.require_role(role, &self.env().caller())
+    }
+
+    /// Emit a ConfigChanged event for a numeric setting
+    fn emit_config_changed(&self, key: &str, old_value: U256, new_value: U256) {
+        self.env().emit_event(ConfigChanged {
+            key: key.to_string(),
+            old_value,
+            new_value,
+            changed_by: self.env().caller(),
+        });
+    }
+
+    /// Write the exchange rate to the rate feed and emit RateUpdated if it moved
+    fn publish_rate(&mut self) {
+        let old_rate = self.rate_feed().map(|(rate, _)| rate).unwrap_or_default();
+        let new_rate = self.exchange_rate();
+        if new_rate == old_rate {
+            return;
+        }
+        
+        let timestamp = self.env().block_time();
+        self.env().set_named_value(RATE_FEED_KEY, (new_rate, timestamp));
+        self.env().emit_event(RateUpdated {
+            old_rate,
+            new_rate,
+            timestamp,
+        });
+    }
+
+    /// Ensure this deployment was initialized with `feature`
+    fn require_feature(&self, feature: u32) -> Result<(), Error> {
+        if !self.feature_enabled(feature) {
+            return Err(Error::FeatureDisabled);
+        }
+        Ok(())
+    }
+
+    /// Ensure the contract is not paused
+    fn require_not_paused(&self) -> Result<(), Error> {
+        if self.paused() {
+            return Err(Error::ContractPaused);
+        }
+        Ok(())
     }
 
     /// Validate that an amount is non-zero and within reasonable bounds
@@ -103,6 +969,39 @@ impl CasperLiquid {
         Ok(())
     }
 
+    /// Store the pooled CSPR, kept in motes like the purse that holds it
+    fn set_pooled_cspr(&mut self, amount: U256) {
+        self.contract_cspr_balance.set(motes::to_motes(amount));
+    }
+
+    /// Validate that the CSPR attached to the call matches the requested amount
+    fn validate_attached_value(&self, amount: U256) -> Result<(), Error> {
+        if self.env().attached_value() != motes::to_motes(amount) {
+            return Err(Error::AttachedValueMismatch);
+        }
+        Ok(())
+    }
+
+    /// Shares backed by pooled CSPR: the supply minus any flash mint in progress
+    fn backed_shares(&self) -> U256 {
+        self.total_staked
+            .get_or_default()
+            .saturating_sub(self.flash_outstanding.get_or_default())
+            .saturating_add(self.bridge.bridged_out())
+    }
+
+    /// Convert a CSPR amount into shares at the current exchange rate (rounds per `MINT_ROUNDING`)
+    fn shares_for_cspr(&self, amount: U256) -> Result<U256, Error> {
+        rounding::shares_for_cspr(amount, self.backed_shares(), self.contract_cspr_balance())
+            .ok_or(Error::ArithmeticOverflow)
+    }
+
+    /// Convert shares into CSPR at the current exchange rate (rounds against `BURN_ROUNDING`)
+    fn cspr_for_shares(&self, shares: U256) -> Result<U256, Error> {
+        rounding::cspr_for_shares(shares, self.backed_shares(), self.contract_cspr_balance())
+            .ok_or(Error::ArithmeticOverflow)
+    }
+
     /// Validate that an address is not the zero address
     fn validate_address(&self, address: &Address) -> Result<(), Error> {
         // In Odra/Casper, we can't easily check for zero address, but we can validate
@@ -128,6 +1027,25 @@ impl CasperLiquid {
         Ok(())
     }
 
+    /// Validate a stake amount against the configured limits
+    fn validate_stake_limits(&self, amount: U256) -> Result<(), Error> {
+        if amount < self.min_stake() {
+            return Err(Error::BelowMinimumStake);
+        }
+        
+        let max_per_tx = self.max_stake_per_tx();
+        if max_per_tx > U256::zero() && amount > max_per_tx {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        let cap = self.max_total_staked();
+        if cap > U256::zero() && self.safe_add(self.contract_cspr_balance(), amount)? > cap {
+            return Err(Error::StakingCapReached);
+        }
+        
+        Ok(())
+    }
+
     /// Validate that an allowance is sufficient for an operation
     fn validate_sufficient_allowance(&self, allowance: U256, required: U256) -> Result<(), Error> {
         if allowance < required {
@@ -138,27 +1056,50 @@ impl CasperLiquid {
 
     /// Reentrancy guard state
     fn is_locked(&self) -> bool {
-        // In Odra, we can use a simple state variable to track reentrancy
-        // For this implementation, we'll rely on the inherent atomicity of blockchain transactions
-        // and proper state management patterns
-        false
+        self.locked.get_or_default()
+    }
+
+    /// Enter a guarded section - fails if one is already executing
+    ///
+    /// Every error reverts the whole call, which also rolls the flag back, so
+    /// only the success path needs to call `unlock`.
+    fn lock(&mut self) -> Result<(), Error> {
+        if self.is_locked() {
+            return Err(Error::Reentrancy);
+        }
+        self.locked.set(true);
+        Ok(())
+    }
+
+    /// Leave a guarded section
+    fn unlock(&mut self) {
+        self.locked.set(false);
     }
 
     /// Validate state consistency before critical operations
     fn validate_state_consistency(&self) -> Result<(), Error> {
-        // Ensure total supply equals contract CSPR balance (1:1 ratio maintained)
+        // Outstanding shares must always be backed by pooled CSPR
         let total_supply = self.total_supply();
-        let contract_balance = self.contract_cspr_balance();
+        let pooled = self.contract_cspr_balance();
         
-        if total_supply != contract_balance {
+        if total_supply > U256::zero() && pooled == U256::zero() {
             // This should never happen in a properly functioning contract
             // If it does, it indicates a critical state inconsistency
-            return Err(Error::ArithmeticOverflow); // Using overflow as a general state error
+            return Err(Error::StateInconsistency);
         }
         
         Ok(())
     }
 
+    /// Check the full invariant set at the end of a mutating entry point
+    ///
+    /// Compiles to a no-op unless the `invariants` feature is enabled.
+    fn check_invariants(&self) -> Result<(), Error> {
+        #[cfg(feature = "invariants")]
+        self.invariant_report().check()?;
+        Ok(())
+    }
+
     /// Get the token name
     pub fn name(&self) -> String {
         self.name.get_or_default()
@@ -180,8 +1121,8 @@ impl CasperLiquid {
     }
 
     /// Get the balance of a specific address
-    pub fn balance_of(&self, owner: &Address) -> U256 {
-        self.balances.get(owner).unwrap_or_default()
+    pub fn balance_of(&self, address: &Address) -> U256 {
+        self.balances.get(address).unwrap_or_default()
     }
 
     /// Transfer tokens from the caller to another address
@@ -191,12 +1132,64 @@ impl CasperLiquid {
         self.validate_address(recipient)?;
         
         let caller = self.env().caller();
-        self._transfer(&caller, recipient, amount)
+        self._transfer(&caller, recipient, amount)?;
+        
+        self.env().emit_event(Transfer {
+            sender: caller,
+            recipient: *recipient,
+            amount,
+        });
+        
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Transfer tokens and notify the recipient if it is a contract
+    ///
+    /// Contract recipients must implement `on_token_received` and accept the
+    /// transfer, otherwise the whole call reverts. Transfers to accounts behave
+    /// like `transfer`, with `data` ignored.
+    pub fn transfer_and_call(&mut self, recipient: &Address, amount: U256, data: Bytes) -> Result<(), Error> {
+        self.transfer(recipient, amount)?;
+        if recipient.is_contract() {
+            let caller = self.env().caller();
+            self.notify_receiver(recipient, &caller, amount, data)?;
+        }
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Transfer tokens from the caller to several recipients in one call
+    ///
+    /// Either every leg succeeds or the whole call reverts. One Transfer event is
+    /// emitted per leg.
+    pub fn transfer_batch(&mut self, recipients: Vec<Address>, amounts: Vec<U256>) -> Result<(), Error> {
+        // CHECKS: Arguments must pair up
+        if recipients.len() != amounts.len() {
+            return Err(Error::LengthMismatch);
+        }
+        
+        // EFFECTS: Each leg is validated by `_transfer`; any failure reverts all legs
+        let caller = self.env().caller();
+        for (recipient, amount) in recipients.iter().zip(amounts.iter()) {
+            self.validate_address(recipient)?;
+            self._transfer(&caller, recipient, *amount)?;
+            
+            self.env().emit_event(Transfer {
+                sender: caller,
+                recipient: *recipient,
+                amount: *amount,
+            });
+        }
+        
+        self.check_invariants()?;
+        Ok(())
     }
 
     /// Approve another address to spend tokens on behalf of the caller
     pub fn approve(&mut self, spender: &Address, amount: U256) -> Result<(), Error> {
         // Comprehensive input validation
+        self.require_not_paused()?;
         self.validate_address(spender)?;
         // Note: amount can be zero for approve (to reset allowance)
         
@@ -207,16 +1200,145 @@ impl CasperLiquid {
             return Err(Error::SelfTransfer);
         }
         
-        // Set the allowance
-        self.allowances.set(&(caller, *spender), amount);
+        self._approve(&caller, spender, amount);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Raise a spender's allowance by `amount`
+    ///
+    /// Unlike `approve`, the change is relative to the current allowance, so a
+    /// spender cannot use the old allowance and then the new one by front-running.
+    pub fn increase_allowance(&mut self, spender: &Address, amount: U256) -> Result<(), Error> {
+        self.require_not_paused()?;
+        self.validate_address(spender)?;
+        
+        let caller = self.env().caller();
+        if caller == *spender {
+            return Err(Error::SelfTransfer);
+        }
+        
+        let new_allowance = self.safe_add(self.allowance(&caller, spender), amount)?;
+        self.allowances.set(&(caller, *spender), new_allowance);
         
-        // Emit approval event
-        self.env().emit_event(Approval {
+        self.env().emit_event(IncreaseAllowance {
             owner: caller,
             spender: *spender,
-            amount,
+            allowance: new_allowance,
+            inc_by: amount,
+        });
+        
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Lower a spender's allowance by `amount` - fails if the allowance is smaller
+    pub fn decrease_allowance(&mut self, spender: &Address, amount: U256) -> Result<(), Error> {
+        self.require_not_paused()?;
+        self.validate_address(spender)?;
+        
+        let caller = self.env().caller();
+        let current_allowance = self.allowance(&caller, spender);
+        self.validate_sufficient_allowance(current_allowance, amount)?;
+        
+        let new_allowance = self.safe_sub(current_allowance, amount)?;
+        self.allowances.set(&(caller, *spender), new_allowance);
+        
+        self.env().emit_event(DecreaseAllowance {
+            owner: caller,
+            spender: *spender,
+            allowance: new_allowance,
+            decr_by: amount,
         });
         
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Set an allowance from an owner's off-chain signature
+    ///
+    /// The owner signs `permit_message(owner, spender, amount, deadline)` with the
+    /// key behind their account; anyone can then submit the permit before
+    /// `deadline` (block time, in milliseconds). Each permit consumes the owner's
+    /// nonce, so a signature can only be used once.
+    pub fn permit(
+        &mut self,
+        owner: PublicKey,
+        spender: Address,
+        amount: U256,
+        deadline: u64,
+        signature: Bytes,
+    ) -> Result<(), Error> {
+        // CHECKS: Permit must be live and signed by the owner
+        self.require_not_paused()?;
+        self.validate_address(&spender)?;
+        if self.env().block_time() > deadline {
+            return Err(Error::PermitExpired);
+        }
+        
+        let owner_address = Address::from(owner.clone());
+        if owner_address == spender {
+            return Err(Error::SelfTransfer);
+        }
+        
+        let message = self.permit_message(owner_address, spender, amount, deadline);
+        if !self.env().verify_signature(&message, &signature, &owner) {
+            return Err(Error::InvalidSignature);
+        }
+        
+        // EFFECTS: Consume the nonce and set the allowance
+        self.nonces.set(&owner_address, self.nonces(owner_address) + 1);
+        self._approve(&owner_address, &spender, amount);
+        self.check_invariants()?;
+        
+        Ok(())
+    }
+
+    /// Get the nonce the owner's next permit must be signed with
+    pub fn nonces(&self, owner: Address) -> u64 {
+        self.nonces.get(&owner).unwrap_or_default()
+    }
+
+    /// Build the message an owner signs to permit `spender` to use `amount`
+    ///
+    /// The message binds the domain tag, this contract, the owner's current nonce
+    /// and every permit argument.
+    pub fn permit_message(&self, owner: Address, spender: Address, amount: U256, deadline: u64) -> Bytes {
+        let fields = (
+            PERMIT_DOMAIN.to_string(),
+            self.env().self_address(),
+            owner,
+            spender,
+            amount,
+            self.nonces(owner),
+            deadline,
+        );
+        // Serializing these types cannot fail; an empty message would never verify anyway
+        Bytes::from(fields.to_bytes().unwrap_or_default())
+    }
+
+    /// Set allowances for several spenders in one call
+    ///
+    /// Either every entry is applied or none is: all spenders are validated before
+    /// any allowance changes. One SetAllowance event is emitted per entry.
+    pub fn set_allowances(&mut self, entries: Vec<(Address, U256)>) -> Result<(), Error> {
+        self.require_not_paused()?;
+        let caller = self.env().caller();
+        
+        // CHECKS: Validate every entry before touching state
+        for (spender, _) in entries.iter() {
+            self.validate_address(spender)?;
+            if caller == *spender {
+                return Err(Error::SelfTransfer);
+            }
+        }
+        
+        // EFFECTS: Apply all allowances
+        for (spender, amount) in entries.iter() {
+            self._approve(&caller, spender, *amount);
+        }
+        
+        self.check_invariants()?;
         Ok(())
     }
 
@@ -240,6 +1362,17 @@ impl CasperLiquid {
         let new_allowance = self.safe_sub(current_allowance, amount)?;
         self.allowances.set(&(*owner, caller), new_allowance);
         
+        // Track how much of the owner's approvals this spender has used
+        self.record_allowance_usage(owner, &caller, amount)?;
+        
+        self.env().emit_event(TransferFrom {
+            spender: caller,
+            owner: *owner,
+            recipient: *recipient,
+            amount,
+        });
+        
+        self.check_invariants()?;
         Ok(())
     }
 
@@ -248,666 +1381,7123 @@ impl CasperLiquid {
         self.allowances.get(&(*owner, *spender)).unwrap_or_default()
     }
 
-    /// Stake CSPR tokens and receive stCSPR tokens in return
-    /// 
-    /// This function accepts CSPR deposits and mints equivalent stCSPR tokens
-    /// at a 1:1 ratio. The CSPR is held in custody by the contract.
-    /// Follows checks-effects-interactions pattern for atomic execution.
+    /// Get the cumulative amount a spender has transferred on behalf of an owner
+    pub fn allowance_usage(&self, owner: &Address, spender: &Address) -> U256 {
+        self.allowance_usage.get(&(*owner, *spender)).unwrap_or_default()
+    }
+
+    /// Get the usage alert threshold the owner configured for a spender
+    pub fn allowance_usage_threshold(&self, owner: &Address, spender: &Address) -> U256 {
+        self.allowance_usage_thresholds.get(&(*owner, *spender)).unwrap_or_default()
+    }
+
+    /// Configure a usage alert for a spender of the caller's tokens
+    ///
+    /// An `AllowanceUsageThresholdCrossed` event is emitted every time the spender's
+    /// cumulative usage crosses a multiple of `threshold`. Zero disables the alert.
+    pub fn set_allowance_usage_threshold(&mut self, spender: &Address, threshold: U256) -> Result<(), Error> {
+        self.validate_address(spender)?;
+        
+        let caller = self.env().caller();
+        if caller == *spender {
+            return Err(Error::SelfTransfer);
+        }
+        
+        self.allowance_usage_thresholds.set(&(caller, *spender), threshold);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Add to the cumulative allowance usage of a spender and emit an alert
+    /// if the owner's threshold was crossed
+    fn record_allowance_usage(&mut self, owner: &Address, spender: &Address, amount: U256) -> Result<(), Error> {
+        let key = (*owner, *spender);
+        let previous_usage = self.allowance_usage.get(&key).unwrap_or_default();
+        let new_usage = self.safe_add(previous_usage, amount)?;
+        self.allowance_usage.set(&key, new_usage);
+        
+        let threshold = self.allowance_usage_thresholds.get(&key).unwrap_or_default();
+        if threshold > U256::zero() && new_usage / threshold > previous_usage / threshold {
+            self.env().emit_event(AllowanceUsageThresholdCrossed {
+                owner: *owner,
+                spender: *spender,
+                threshold,
+                cumulative_usage: new_usage,
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Stake CSPR tokens and receive stCSPR tokens in return
+    /// 
+    /// This function accepts CSPR deposits and mints stCSPR shares at the current
+    /// exchange rate: `shares = amount * total_shares / total_pooled_cspr`.
+    /// The caller must attach exactly `amount` motes; the attached CSPR lands in
+    /// the contract's main purse and is held in custody there.
+    /// Follows checks-effects-interactions pattern for atomic execution.
+    #[odra(payable)]
     pub fn stake(&mut self, amount: U256) -> Result<(), Error> {
-        // CHECKS: Comprehensive input validation and state checks
-        self.validate_amount(amount)?;
-        self.validate_state_consistency()?;
+        let caller = self.env().caller();
+        self._stake(&caller, amount)?;
+        self.check_invariants()
+    }
 
+    /// Stake the attached CSPR and move the minted stCSPR into `target` in one deploy
+    ///
+    /// The shares are minted to the caller and then transferred to `target`, e.g. a
+    /// lending market. If `payload` is non-empty, `target` must be a contract
+    /// implementing `on_token_received`, which is called with the payload and can
+    /// reject the deposit. Returns the stCSPR minted.
+    #[odra(payable)]
+    pub fn stake_and_transfer(&mut self, target: &Address, amount: U256, payload: Bytes) -> Result<U256, Error> {
+        self.validate_address(target)?;
         let caller = self.env().caller();
+        if caller == *target {
+            return Err(Error::SelfTransfer);
+        }
         
-        // Get current state values
-        let current_balance = self.balances.get(&caller).unwrap_or_default();
-        let current_total_supply = self.total_staked.get_or_default();
-        let current_contract_balance = self.contract_cspr_balance.get_or_default();
+        let balance_before = self.balance_of(&caller);
+        self._stake(&caller, amount)?;
+        let shares = self.safe_sub(self.balance_of(&caller), balance_before)?;
         
-        // Pre-calculate all new values to ensure they're valid before any state changes
-        let new_balance = self.safe_add(current_balance, amount)?;
-        let new_total_supply = self.safe_add(current_total_supply, amount)?;
-        let new_contract_balance = self.safe_add(current_contract_balance, amount)?;
+        self._transfer(&caller, target, shares)?;
+        self.env().emit_event(Transfer {
+            sender: caller,
+            recipient: *target,
+            amount: shares,
+        });
         
-        // EFFECTS: Update all state variables atomically
-        // All state changes happen together - if any fail, the entire transaction reverts
-        self.balances.set(&caller, new_balance);
-        self.total_staked.set(new_total_supply);
-        self.contract_cspr_balance.set(new_contract_balance);
+        if !payload.is_empty() {
+            self.notify_receiver(target, &caller, shares, payload)?;
+        }
+        self.check_invariants()?;
+        Ok(shares)
+    }
+
+    /// Stake the attached CSPR and attribute the deposit to `referrer`
+    ///
+    /// Behaves exactly like `stake`; the referrer's cumulative volume is recorded
+    /// so partners can verify on-chain how much stake they brought in.
+    #[odra(payable)]
+    pub fn stake_with_referral(&mut self, amount: U256, referrer: &Address) -> Result<(), Error> {
+        self.require_feature(FEATURE_REFERRALS)?;
+        self.validate_address(referrer)?;
+        let caller = self.env().caller();
+        if caller == *referrer {
+            return Err(Error::SelfTransfer);
+        }
         
-        // Validate state consistency after changes
-        self.validate_state_consistency()?;
+        // The first referrer is kept; the stCSPR minted below is followed from here on
+        self.integrators.refer(&caller, referrer, self.balance_of(&caller));
+        self._stake(&caller, amount)?;
         
-        // INTERACTIONS: External effects (events) happen last
-        let timestamp = self.env().block_time();
-        self.env().emit_event(StakeEvent {
-            user: caller,
+        let total_referred = self.safe_add(self.referred_volume(referrer), amount)?;
+        self.referred_volume.set(referrer, total_referred);
+        self.env().emit_event(ReferralRecorded {
+            referrer: *referrer,
+            staker: caller,
             cspr_amount: amount,
-            stcspr_minted: amount, // 1:1 ratio
-            timestamp,
+            total_referred,
         });
         
-        // Emit Transfer event for minting (from zero address concept)
-        // In Odra, we'll use the contract's own address as the "from" for minting
-        let contract_address = self.env().self_address();
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Get the cumulative CSPR staked through a referrer
+    pub fn referred_volume(&self, referrer: &Address) -> U256 {
+        self.referred_volume.get(referrer).unwrap_or_default()
+    }
+
+    /// Register an integrator, or update or remove one with a zero share (admin only)
+    ///
+    /// A registered integrator earns `share_bps` of the protocol fee on the
+    /// stCSPR held by the stakers it referred, at most `MAX_INTEGRATOR_SHARE_BPS`,
+    /// until it has earned `cap` stCSPR in total (zero means no cap).
+    pub fn set_integrator(&mut self, integrator: Address, share_bps: u16, cap: U256) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self.validate_address(&integrator)?;
+        self.integrators.set(integrator, share_bps, cap)?;
+        self.check_invariants()
+    }
+
+    /// Get the registered integrators
+    pub fn integrators(&self) -> Vec<Address> {
+        self.integrators.list()
+    }
+
+    /// Get an integrator's share of the fee on its referred stCSPR, in basis points
+    pub fn integrator_share_bps(&self, integrator: Address) -> u16 {
+        self.integrators.share_bps(&integrator)
+    }
+
+    /// Get the most stCSPR an integrator can earn in total (zero means no cap)
+    pub fn integrator_cap(&self, integrator: Address) -> U256 {
+        self.integrators.cap(&integrator)
+    }
+
+    /// Get the referrer a staker is attributed to
+    pub fn referrer_of(&self, staker: Address) -> Option<Address> {
+        self.integrators.referrer_of(&staker)
+    }
+
+    /// Get the stCSPR currently held by the stakers a referrer brought in
+    pub fn referred_shares(&self, referrer: Address) -> U256 {
+        self.integrators.referred_shares(&referrer)
+    }
+
+    /// Get the stCSPR an integrator has earned, claimed or not
+    pub fn integrator_fees_earned(&self, integrator: Address) -> U256 {
+        self.integrators.earned(&integrator)
+    }
+
+    /// Get the stCSPR an integrator can claim
+    pub fn integrator_fees_owed(&self, integrator: Address) -> U256 {
+        self.integrators.owed(&integrator)
+    }
+
+    /// Pay the caller's accrued integrator fees out of the contract's stCSPR
+    pub fn claim_integrator_fees(&mut self) -> Result<U256, Error> {
+        self.lock()?;
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+        
+        // CHECKS and EFFECTS: Clear what is owed, then pay it out
+        let amount = self.integrators.take(&caller)?;
+        self._transfer(&self_address, &caller, amount)?;
+        
         self.env().emit_event(Transfer {
-            from: contract_address,
-            to: caller,
+            sender: self_address,
+            recipient: caller,
             amount,
         });
-        
-        Ok(())
+        self.unlock();
+        self.check_invariants()?;
+        Ok(amount)
+    }
+
+    /// Stake the attached CSPR and mint the stCSPR to `beneficiary`
+    ///
+    /// Lets a payer such as an exchange or onboarding service fund a position
+    /// owned by another address. The StakeEvent records both.
+    #[odra(payable)]
+    pub fn stake_for(&mut self, beneficiary: &Address, amount: U256) -> Result<(), Error> {
+        self.validate_address(beneficiary)?;
+        self._stake(beneficiary, amount)?;
+        self.check_invariants()
     }
 
     /// Unstake stCSPR tokens and receive CSPR tokens back
     /// 
-    /// This function burns stCSPR tokens and returns equivalent CSPR tokens
-    /// at a 1:1 ratio. The CSPR is transferred back from the contract's custody.
+    /// This function burns `amount` stCSPR shares and returns their value in CSPR
+    /// at the current exchange rate. The CSPR is transferred from the contract's
+    /// purse to the caller's main purse.
     /// Follows checks-effects-interactions pattern for atomic execution.
     pub fn unstake(&mut self, amount: U256) -> Result<(), Error> {
-        // CHECKS: Comprehensive input validation and state checks
-        self.validate_amount(amount)?;
-        self.validate_state_consistency()?;
+        let caller = self.env().caller();
+        self._unstake(&caller, &caller, amount)?;
+        self.check_invariants()
+    }
 
+    /// Unstake the caller's stCSPR and send the CSPR to `recipient`
+    pub fn unstake_to(&mut self, recipient: &Address, amount: U256) -> Result<(), Error> {
+        self.validate_address(recipient)?;
         let caller = self.env().caller();
+        self._unstake(&caller, recipient, amount)?;
+        self.check_invariants()
+    }
+
+    /// Allow an operator (e.g. an automated vault) to unstake up to `amount`
+    /// of the caller's stCSPR. This budget is separate from transfer allowances.
+    pub fn approve_unstake(&mut self, operator: &Address, amount: U256) -> Result<(), Error> {
+        self.validate_address(operator)?;
         
-        // Get current state values and validate sufficient balance
-        let current_balance = self.balances.get(&caller).unwrap_or_default();
-        self.validate_sufficient_balance(current_balance, amount)?;
+        let caller = self.env().caller();
+        if caller == *operator {
+            return Err(Error::SelfTransfer);
+        }
         
-        let current_total_supply = self.total_staked.get_or_default();
-        let current_contract_balance = self.contract_cspr_balance.get_or_default();
+        self.unstake_allowances.set(&(caller, *operator), amount);
         
-        // Pre-calculate all new values to ensure they're valid before any state changes
-        let new_balance = self.safe_sub(current_balance, amount)?;
-        let new_total_supply = self.safe_sub(current_total_supply, amount)?;
-        let new_contract_balance = self.safe_sub(current_contract_balance, amount)?;
+        self.env().emit_event(UnstakeApproval {
+            owner: caller,
+            operator: *operator,
+            amount,
+        });
         
-        // EFFECTS: Update all state variables atomically
-        // All state changes happen together - if any fail, the entire transaction reverts
-        self.balances.set(&caller, new_balance);
-        self.total_staked.set(new_total_supply);
-        self.contract_cspr_balance.set(new_contract_balance);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Get the remaining unstake budget an owner granted to an operator
+    pub fn unstake_allowance(&self, owner: &Address, operator: &Address) -> U256 {
+        self.unstake_allowances.get(&(*owner, *operator)).unwrap_or_default()
+    }
+
+    /// Unstake on behalf of an owner within the budget granted via `approve_unstake`
+    ///
+    /// The owner's stCSPR is burned and the position is exited exactly as if the
+    /// owner had called `unstake` - the operator never takes custody of the tokens.
+    pub fn unstake_from(&mut self, owner: &Address, amount: U256) -> Result<(), Error> {
+        self.validate_amount(amount)?;
+        self.validate_address(owner)?;
         
-        // Validate state consistency after changes
-        self.validate_state_consistency()?;
+        let caller = self.env().caller();
         
-        // INTERACTIONS: External effects (events) happen last
-        let timestamp = self.env().block_time();
-        self.env().emit_event(UnstakeEvent {
-            user: caller,
-            stcspr_burned: amount,
-            cspr_returned: amount, // 1:1 ratio
-            timestamp,
-        });
+        // Check unstake budget with proper validation
+        let current_allowance = self.unstake_allowances.get(&(*owner, caller)).unwrap_or_default();
+        self.validate_sufficient_allowance(current_allowance, amount)?;
         
-        // Emit Transfer event for burning (to zero address concept)
-        // In Odra, we'll use the contract's own address as the "to" for burning
-        let contract_address = self.env().self_address();
-        self.env().emit_event(Transfer {
-            from: caller,
-            to: contract_address,
-            amount,
-        });
+        // Perform the unstake for the owner
+        self._unstake(owner, owner, amount)?;
+        
+        // Update unstake budget with safe arithmetic
+        let new_allowance = self.safe_sub(current_allowance, amount)?;
+        self.unstake_allowances.set(&(*owner, caller), new_allowance);
         
+        self.check_invariants()?;
         Ok(())
     }
 
-    /// Get the total CSPR held in custody by the contract
-    pub fn contract_cspr_balance(&self) -> U256 {
-        self.contract_cspr_balance.get_or_default()
+    /// Request a withdrawal of `amount` stCSPR shares through the unbonding queue
+    ///
+    /// The shares are burned immediately and their CSPR value at the current
+    /// exchange rate is owed to the caller. It can be claimed with
+    /// `claim_withdrawal` once the unbonding period has elapsed. Returns the request id.
+    pub fn request_unstake(&mut self, amount: U256) -> Result<u64, Error> {
+        let caller = self.env().caller();
+        let id = self._request_unstake(&caller, amount)?;
+        self.check_invariants()?;
+        Ok(id)
     }
 
-    /// Internal transfer function with validation
-    /// Follows checks-effects-interactions pattern for atomic execution.
-    fn _transfer(&mut self, from: &Address, to: &Address, amount: U256) -> Result<(), Error> {
-        // CHECKS: Comprehensive input validation
+    /// Request a withdrawal on behalf of an owner within the budget granted via `approve_unstake`
+    ///
+    /// The request belongs to the owner, so only the owner can claim the CSPR.
+    pub fn request_unstake_from(&mut self, owner: &Address, amount: U256) -> Result<u64, Error> {
         self.validate_amount(amount)?;
-        self.validate_address(from)?;
-        self.validate_address(to)?;
+        self.validate_address(owner)?;
         
-        if from == to {
-            return Err(Error::SelfTransfer);
+        let caller = self.env().caller();
+        
+        // Check unstake budget with proper validation
+        let current_allowance = self.unstake_allowances.get(&(*owner, caller)).unwrap_or_default();
+        self.validate_sufficient_allowance(current_allowance, amount)?;
+        
+        let id = self._request_unstake(owner, amount)?;
+        
+        // Update unstake budget with safe arithmetic
+        let new_allowance = self.safe_sub(current_allowance, amount)?;
+        self.unstake_allowances.set(&(*owner, caller), new_allowance);
+        
+        self.check_invariants()?;
+        Ok(id)
+    }
+
+    /// Claim the CSPR of an unlocked withdrawal request
+    pub fn claim_withdrawal(&mut self, id: u64) -> Result<(), Error> {
+        self.lock()?;
+        let caller = self.env().caller();
+        
+        // CHECKS: Payout must be covered by the contract purse outside the insurance
+        // fund (undelegated funds return there once unbonded)
+        let request = self.withdrawals.get(id).ok_or(Error::WithdrawalNotFound)?;
+        let payout = motes::to_motes(request.amount);
+        if self.withdrawable_cspr() < payout {
+            return Err(Error::InsufficientLiquidity);
         }
         
-        // Check sender balance with proper validation
-        let from_balance = self.balances.get(from).unwrap_or_default();
-        self.validate_sufficient_balance(from_balance, amount)?;
+        // EFFECTS: Settle the request
+        self.withdrawals.claim(id, &caller)?;
         
-        // Pre-calculate new balances to ensure they're valid before any state changes
-        let new_from_balance = self.safe_sub(from_balance, amount)?;
-        let to_balance = self.balances.get(to).unwrap_or_default();
-        let new_to_balance = self.safe_add(to_balance, amount)?;
+        // INTERACTIONS: Pay out CSPR
+        self.env().transfer_tokens(&caller, &payout);
         
-        // EFFECTS: Update balances atomically
-        // Both balance updates happen together - if any fail, the entire transaction reverts
-        self.balances.set(from, new_from_balance);
-        self.balances.set(to, new_to_balance);
+        self.validate_state_consistency()?;
+        self.unlock();
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Get a withdrawal request by id
+    pub fn get_withdrawal(&self, id: u64) -> Option<WithdrawalRequest> {
+        self.withdrawals.get(id)
+    }
+
+    /// Transfer an unclaimed withdrawal request to `new_owner`
+    ///
+    /// The caller must own the request or be approved for it. The new owner
+    /// claims the CSPR once the unbonding period is over, so pending positions
+    /// can be sold instead of waited out.
+    pub fn transfer_withdrawal(&mut self, id: u64, new_owner: Address) -> Result<(), Error> {
+        self.require_not_paused()?;
+        let caller = self.env().caller();
+        self.withdrawals.transfer(id, &caller, new_owner)?;
+        self.check_invariants()
+    }
+
+    /// Approve `approved` to transfer the caller's withdrawal request, or clear it with `None`
+    ///
+    /// The approval is cleared whenever the request changes owner.
+    pub fn approve_withdrawal(&mut self, id: u64, approved: Option<Address>) -> Result<(), Error> {
+        self.require_not_paused()?;
+        let caller = self.env().caller();
+        self.withdrawals.approve(id, &caller, approved)?;
+        self.check_invariants()
+    }
+
+    /// Get the address approved to transfer a withdrawal request
+    pub fn withdrawal_approved(&self, id: u64) -> Option<Address> {
+        self.withdrawals.approved(id)
+    }
+
+    /// Get all unclaimed withdrawal requests of an owner
+    pub fn list_withdrawals(&self, owner: &Address) -> Vec<WithdrawalRequest> {
+        self.withdrawals.pending_of(owner)
+    }
+
+    /// Get up to `limit` unclaimed withdrawal requests of an owner, oldest first, skipping `offset`
+    ///
+    /// Owners with many open requests should page through them with this instead
+    /// of `list_withdrawals`.
+    pub fn list_withdrawals_page(&self, owner: &Address, offset: u32, limit: u32) -> Vec<WithdrawalRequest> {
+        self.withdrawals.pending_page_of(owner, offset, limit)
+    }
+
+    /// Get the block time from which a withdrawal request can be claimed
+    pub fn claimable_at(&self, id: u64) -> Option<u64> {
+        self.withdrawals.get(id).map(|request| request.claimable_at)
+    }
+
+    /// Get the number of unclaimed withdrawal requests ahead of request `id`
+    ///
+    /// Returns `None` if the request does not exist or was claimed.
+    pub fn queue_position(&self, id: u64) -> Option<u64> {
+        let request = self.withdrawals.get(id).filter(|request| !request.claimed)?;
+        Some(self.withdrawals.ahead_of(request.id).0)
+    }
+
+    /// Estimate the block time from which withdrawal request `id` can be paid
+    ///
+    /// The request and every unclaimed one ahead of it are set against the purse
+    /// outside the insurance fund, then against undelegated CSPR in the order it
+    /// returns from the auction. If that is still not enough, the operator has to
+    /// undelegate more, which takes the full auction unbonding delay from now. The
+    /// estimate is never earlier than the request's own `claimable_at`. Returns
+    /// `None` if the request does not exist or was claimed.
+    pub fn withdrawal_eta(&self, id: u64) -> Option<u64> {
+        let request = self.withdrawals.get(id).filter(|request| !request.claimed)?;
+        let (_, owed_ahead) = self.withdrawals.ahead_of(request.id);
+        let owed = owed_ahead.saturating_add(motes::to_motes(request.amount));
         
-        // INTERACTIONS: Emit transfer event
-        self.env().emit_event(Transfer {
-            from: *from,
-            to: *to,
-            amount,
-        });
+        let mut available = self.withdrawable_cspr();
+        if available >= owed {
+            return Some(request.claimable_at);
+        }
+        for (era, amount) in self.unbonding() {
+            available = available.saturating_add(amount);
+            if available >= owed {
+                return Some(request.claimable_at.max(self.eras.era_start(era)));
+            }
+        }
+        Some(request.claimable_at.max(self.eras.unbonded_at(self.env().block_time())))
+    }
+
+    /// Get the CSPR owed to all unclaimed withdrawal requests
+    pub fn pending_withdrawals(&self) -> U256 {
+        self.withdrawals.total_pending()
+    }
+
+    /// Get the unbonding period applied to new withdrawal requests, in milliseconds
+    pub fn unbonding_period(&self) -> u64 {
+        self.withdrawals.unbonding_period()
+    }
+
+    /// Set the unbonding period for new withdrawal requests (operator only)
+    pub fn set_unbonding_period(&mut self, period: u64) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        let old_period = self.withdrawals.unbonding_period();
         
+        self.withdrawals.set_unbonding_period(period);
+        self.emit_config_changed("unbonding_period", U256::from(old_period), U256::from(period));
+        self.check_invariants()?;
         Ok(())
     }
 
-    /// Validate supply consistency - ensures total supply equals sum of all balances
-    /// This is a view function that performs internal consistency checks
-    pub fn validate_supply_consistency(&self) -> bool {
-        // In a real implementation, we would iterate through all balances
-        // For this simplified version, we check that total_supply equals contract_cspr_balance
-        // since we maintain a 1:1 ratio between stCSPR tokens and CSPR custody
-        let total_supply = self.total_supply();
-        let contract_balance = self.contract_cspr_balance();
+    /// Get the total CSPR held in custody by the contract
+    pub fn contract_cspr_balance(&self) -> U256 {
+        motes::to_amount(self.contract_cspr_motes()).unwrap_or_else(|error| self.env().revert(error))
+    }
+
+    /// Get the total CSPR held in custody by the contract, in motes
+    pub fn contract_cspr_motes(&self) -> U512 {
+        self.contract_cspr_balance.get_or_default()
+    }
+
+    /// Get the total CSPR backing all stCSPR shares (alias of `contract_cspr_balance`)
+    pub fn total_pooled_cspr(&self) -> U256 {
+        self.contract_cspr_balance()
+    }
+
+    /// Get the value of one stCSPR in CSPR, scaled by `RATE_PRECISION`
+    pub fn exchange_rate(&self) -> U256 {
+        let total_shares = self.backed_shares();
+        if total_shares == U256::zero() {
+            return U256::from(RATE_PRECISION);
+        }
         
-        // Supply consistency: total stCSPR supply should equal CSPR in custody
-        total_supply == contract_balance
+        self.contract_cspr_balance() * U256::from(RATE_PRECISION) / total_shares
     }
 
-    /// Test-only method to set balances directly (for testing purposes)
-    #[cfg(test)]
-    pub fn set_balance_for_testing(&mut self, address: &Address, amount: U256) {
-        self.balances.set(address, amount);
+    /// Publish the current exchange rate under the `RATE_FEED_KEY` named key
+    ///
+    /// Rewards, losses and loss cover publish the rate automatically; anyone can
+    /// call this to pick up smaller moves such as rounding from stakes and unstakes.
+    pub fn update_rate_feed(&mut self) {
+        self.publish_rate();
+        self.check_invariants().unwrap_or_else(|error| self.env().revert(error));
+    }
+
+    /// Get the published `(exchange rate, block time)` pair, if any
+    pub fn rate_feed(&self) -> Option<(U256, u64)> {
+        self.env().get_named_value(RATE_FEED_KEY)
+    }
+
+    /// Get the exchange rate, the block time of the last rewards report and its epoch in one call
+    ///
+    /// Meant for other contracts: reading all three atomically lets a lending
+    /// market apply its own staleness policy, e.g. refuse the rate once no
+    /// rewards have been reported for a day, instead of trusting a bare number.
+    pub fn rate_with_meta(&self) -> RateWithMeta {
+        let epoch = self.rewards.current_epoch();
+        RateWithMeta {
+            rate: self.exchange_rate(),
+            updated_at: self.rewards.report(epoch).map(|report| report.timestamp).unwrap_or_default(),
+            epoch,
+        }
+    }
+
+    /// Preview how many stCSPR shares staking `amount` CSPR would mint
+    pub fn preview_stake(&self, amount: U256) -> U256 {
+        self.shares_for_cspr(amount).unwrap_or_default()
+    }
+
+    /// Preview how much CSPR unstaking `shares` stCSPR would return
+    pub fn preview_unstake(&self, shares: U256) -> U256 {
+        self.cspr_for_shares(shares).unwrap_or_default()
+    }
+
+    /// Preview how many stCSPR must be unstaked to receive `amount` CSPR (rounds per `BURN_ROUNDING`)
+    ///
+    /// Unstaking the returned shares pays at least `amount`, before unstake fees.
+    pub fn preview_withdraw(&self, amount: U256) -> U256 {
+        rounding::shares_to_burn(amount, self.backed_shares(), self.contract_cspr_balance()).unwrap_or_default()
+    }
+
+    /// Get the rounding directions of the share conversions this build was compiled with
+    pub fn rounding_policy(&self) -> RoundingPolicy {
+        rounding::policy()
+    }
+
+    /// Get the contract owner
+    pub fn owner(&self) -> Option<Address> {
+        self.access.owner()
+    }
+
+    /// Get the account nominated to become owner, if any
+    pub fn pending_owner(&self) -> Option<Address> {
+        self.access.pending_owner()
+    }
+
+    /// Nominate a new owner (owner only); takes effect once they accept
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Error> {
+        self.validate_address(&new_owner)?;
+        let caller = self.env().caller();
+        self.access.transfer_ownership(&caller, new_owner)?;
+        self.check_invariants()
+    }
+
+    /// Accept a pending ownership transfer (pending owner only)
+    pub fn accept_ownership(&mut self) -> Result<(), Error> {
+        let caller = self.env().caller();
+        self.access.accept_ownership(&caller)?;
+        self.check_invariants()
+    }
+
+    /// Check whether an account holds a role
+    pub fn has_role(&self, role: Role, account: Address) -> bool {
+        self.access.has_role(role, &account)
+    }
+
+    /// Grant a role to an account (owner or admin only)
+    ///
+    /// Once the multisig or the timelock is enabled, roles are granted through a
+    /// `GrantRole` action instead.
+    pub fn grant_role(&mut self, role: Role, account: Address) -> Result<(), Error> {
+        self.validate_address(&account)?;
+        self.require_direct_admin_change()?;
+        let caller = self.env().caller();
+        self.access.grant_role(&caller, role, account)?;
+        self.check_invariants()
+    }
+
+    /// Revoke a role from an account (owner or admin only)
+    ///
+    /// Once the multisig or the timelock is enabled, roles are revoked through a
+    /// `RevokeRole` action instead.
+    pub fn revoke_role(&mut self, role: Role, account: Address) -> Result<(), Error> {
+        self.require_direct_admin_change()?;
+        let caller = self.env().caller();
+        self.access.revoke_role(&caller, role, account)?;
+        self.check_invariants()
+    }
+
+    /// Record that the admin keys are still in use (admin only)
+    pub fn heartbeat(&mut self) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        let caller = self.env().caller();
+        self.recovery.beat(caller);
+        self.check_invariants()
+    }
+
+    /// Arm the dead-man's switch with a recovery council and period (admin only)
+    ///
+    /// Once no admin has called `heartbeat` for `period`, the council can take
+    /// over with `claim_admin`. Arming counts as a heartbeat; `None` disarms it.
+    pub fn set_recovery(&mut self, council: Option<Address>, period: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        let caller = self.env().caller();
+        self.recovery.configure(caller, council, period)?;
+        self.check_invariants()
+    }
+
+    /// Take ownership and the admin role once the heartbeats have stopped (recovery council only)
+    ///
+    /// The previous owner loses the admin role, as its key is presumed lost.
+    /// While the multisig or the timelock is enabled, admin changes still go
+    /// through them.
+    pub fn claim_admin(&mut self) -> Result<(), Error> {
+        let council = self.env().caller();
+        let last_heartbeat = self.recovery.take_over(&council)?;
+        let previous_owner = self.access.force_transfer(council)?;
+        self.access.revoke(Role::Admin, previous_owner, council);
+        self.access.grant(Role::Admin, council, council);
+
+        self.env().emit_event(AdminRecovered {
+            council,
+            previous_owner,
+            last_heartbeat,
+        });
+        self.check_invariants()
+    }
+
+    /// Get the recovery council, if the dead-man's switch is armed
+    pub fn recovery_council(&self) -> Option<Address> {
+        self.recovery.council()
+    }
+
+    /// Get the time without a heartbeat after which the council can take over
+    pub fn recovery_period(&self) -> u64 {
+        self.recovery.period()
+    }
+
+    /// Get the block time of the last admin heartbeat
+    pub fn last_heartbeat(&self) -> u64 {
+        self.recovery.last_heartbeat()
+    }
+
+    /// Get the block time from which the council can take over, if the switch is armed
+    pub fn recovery_available_at(&self) -> Option<u64> {
+        self.recovery.available_at()
+    }
+
+    /// Check whether the contract is paused
+    pub fn paused(&self) -> bool {
+        self.paused.get_or_default()
+    }
+
+    /// Pause staking, unstaking, transfers and approvals (pauser only)
+    pub fn pause(&mut self) -> Result<(), Error> {
+        self.require_role(Role::Pauser)?;
+        self.require_not_paused()?;
+        
+        self.paused.set(true);
+        self.env().emit_event(Paused {
+            account: self.env().caller(),
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Resume normal operation (pauser only)
+    pub fn unpause(&mut self) -> Result<(), Error> {
+        self.require_role(Role::Pauser)?;
+        if self.sunset.is_retired() {
+            return Err(Error::ContractRetired);
+        }
+        
+        self.paused.set(false);
+        self.env().emit_event(Unpaused {
+            account: self.env().caller(),
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Register a validator the pool may delegate to (operator only)
+    pub fn add_validator(&mut self, validator: PublicKey) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        self.delegation.add_validator(validator)?;
+        self.check_invariants()
+    }
+
+    /// Remove a validator with no remaining delegation (operator only)
+    pub fn remove_validator(&mut self, validator: PublicKey) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        self.require_direct_admin_change()?;
+        self.delegation.remove_validator(&validator)?;
+        self.check_invariants()
+    }
+
+    /// Apply to join the validator set, with the application bond attached
+    ///
+    /// `metadata_uri` points to the operator's public details. The bond is
+    /// refunded when the application is approved and paid into the insurance
+    /// fund when it is rejected. Returns the application id.
+    #[odra(payable)]
+    pub fn apply_as_validator(&mut self, validator: PublicKey, metadata_uri: String) -> Result<u64, Error> {
+        self.require_not_paused()?;
+        if self.delegation.is_validator(&validator) {
+            return Err(Error::ValidatorAlreadyExists);
+        }
+        
+        let applicant = self.env().caller();
+        let bond = self.env().attached_value();
+        let id = self.applications.submit(applicant, validator, metadata_uri, bond)?;
+        self.check_invariants()?;
+        Ok(id)
+    }
+
+    /// Approve a pending application: register the validator and refund the bond (admin only)
+    pub fn approve_application(&mut self, id: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._approve_application(id)?;
+        self.check_invariants()
+    }
+
+    fn _approve_application(&mut self, id: u64) -> Result<(), Error> {
+        let application = self.applications.decide(id, true)?;
+        self.delegation.add_validator(application.validator)?;
+        if application.bond > U512::zero() {
+            self.env().transfer_tokens(&application.applicant, &application.bond);
+        }
+        Ok(())
+    }
+
+    /// Reject a pending application and pay its bond into the insurance fund (admin only)
+    pub fn reject_application(&mut self, id: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._reject_application(id)?;
+        self.check_invariants()
+    }
+
+    fn _reject_application(&mut self, id: u64) -> Result<(), Error> {
+        let application = self.applications.decide(id, false)?;
+        if application.bond > U512::zero() {
+            self.insurance.deposit(motes::to_amount(application.bond)?)?;
+        }
+        Ok(())
+    }
+
+    /// Get a validator application by id
+    pub fn get_validator_application(&self, id: u64) -> Option<ValidatorApplication> {
+        self.applications.get(id)
+    }
+
+    /// Get the bond required with a validator application, in motes
+    pub fn application_bond(&self) -> U512 {
+        self.applications.bond()
+    }
+
+    /// Set the bond required with new validator applications (admin only)
+    pub fn set_application_bond(&mut self, bond: U512) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_application_bond(bond)?;
+        self.check_invariants()
+    }
+
+    fn _set_application_bond(&mut self, bond: U512) -> Result<(), Error> {
+        let old_bond = self.applications.bond();
+        self.applications.set_bond(bond);
+        self.emit_config_changed("application_bond", motes::to_amount(old_bond)?, motes::to_amount(bond)?);
+        Ok(())
+    }
+
+    /// Delegate custodied CSPR to a registered validator (operator only)
+    ///
+    /// Delegated CSPR stays part of the custody balance but is no longer
+    /// available in the contract purse for instant unstaking. CSPR reserved for
+    /// withdrawal requests or held by the insurance fund cannot be delegated.
+    pub fn delegate_to(&mut self, validator: PublicKey, amount: U512) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        self._delegate(validator, amount)?;
+        self.check_invariants()
+    }
+
+    /// Undelegate CSPR from a validator (operator only)
+    pub fn undelegate_from(&mut self, validator: PublicKey, amount: U512) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        self._undelegate(validator, amount)?;
+        self.check_invariants()
+    }
+
+    /// Move delegated CSPR from one validator to another (operator only)
+    ///
+    /// `to` is delegated from the purse while `from` unbonds, so the move needs as
+    /// much free CSPR as it shifts, just like `delegate_to`. The CSPR undelegated
+    /// from `from` is back in the purse after the unbonding period.
+    pub fn redelegate(&mut self, from: PublicKey, to: PublicKey, amount: U512) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        if self.available_liquidity() < amount {
+            return Err(Error::InsufficientLiquidity);
+        }
+        self.delegation.redelegate(from, to, amount)?;
+        self.track_unbonding(amount);
+        self.check_invariants()
+    }
+
+    /// Get the share of pooled CSPR kept liquid for instant unstakes, in basis points
+    pub fn buffer_target_bps(&self) -> u16 {
+        self.buffer_target_bps.get_or_default()
+    }
+
+    /// Set the liquidity buffer target (admin only, at most `BPS_DENOMINATOR`)
+    pub fn set_buffer_target_bps(&mut self, buffer_target_bps: u16) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_buffer_target_bps(buffer_target_bps)?;
+        self.check_invariants()
+    }
+
+    fn _set_buffer_target_bps(&mut self, buffer_target_bps: u16) -> Result<(), Error> {
+        if buffer_target_bps > BPS_DENOMINATOR {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        let old_target = self.buffer_target_bps();
+        self.buffer_target_bps.set(buffer_target_bps);
+        self.emit_config_changed("buffer_target_bps", U256::from(old_target), U256::from(buffer_target_bps));
+        Ok(())
+    }
+
+    /// Get the CSPR available for instant unstakes (purse balance not reserved for withdrawals or insurance)
+    pub fn current_buffer(&self) -> U512 {
+        self.available_liquidity()
+    }
+
+    /// Get the buffer size `rebalance` and `flush_deposits` aim for
+    ///
+    /// This is the larger of the `buffer_target_bps` share of the pool and
+    /// `withdrawal_cover_bps` of the average CSPR withdrawn per era, so the buffer
+    /// grows with withdrawal demand.
+    pub fn target_buffer(&self) -> U512 {
+        let ratio_target = self.contract_cspr_balance() * U256::from(self.buffer_target_bps())
+            / U256::from(BPS_DENOMINATOR);
+        let demand_target = self.withdrawal_ewma() * U512::from(self.withdrawal_cover_bps())
+            / U512::from(BPS_DENOMINATOR);
+        motes::to_motes(ratio_target).max(demand_target)
+    }
+
+    /// Get the average CSPR withdrawn per era as of the last upkeep, in motes
+    pub fn withdrawal_ewma(&self) -> U512 {
+        self.withdrawal_ewma.get_or_default()
+    }
+
+    /// Get the weight of the latest era in the withdrawal average, in basis points
+    pub fn ewma_alpha_bps(&self) -> u16 {
+        self.ewma_alpha_bps.get().unwrap_or(DEFAULT_EWMA_ALPHA_BPS)
+    }
+
+    /// Get the share of the average era withdrawals kept liquid, in basis points
+    pub fn withdrawal_cover_bps(&self) -> u16 {
+        self.withdrawal_cover_bps.get_or_default()
+    }
+
+    /// Set how the buffer follows withdrawal demand (admin only)
+    ///
+    /// `ewma_alpha_bps` (at most `BPS_DENOMINATOR`) is how far each completed era
+    /// moves the average towards its own volume; `withdrawal_cover_bps` is how much
+    /// of the average the buffer holds, e.g. 20,000 for two eras of withdrawals.
+    pub fn set_buffer_policy(&mut self, ewma_alpha_bps: u16, withdrawal_cover_bps: u16) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_buffer_policy(ewma_alpha_bps, withdrawal_cover_bps)?;
+        self.check_invariants()
+    }
+
+    fn _set_buffer_policy(&mut self, ewma_alpha_bps: u16, withdrawal_cover_bps: u16) -> Result<(), Error> {
+        if ewma_alpha_bps > BPS_DENOMINATOR {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        let old_alpha = self.ewma_alpha_bps();
+        let old_cover = self.withdrawal_cover_bps();
+        self.ewma_alpha_bps.set(ewma_alpha_bps);
+        self.withdrawal_cover_bps.set(withdrawal_cover_bps);
+        self.emit_config_changed("ewma_alpha_bps", U256::from(old_alpha), U256::from(ewma_alpha_bps));
+        self.emit_config_changed("withdrawal_cover_bps", U256::from(old_cover), U256::from(withdrawal_cover_bps));
+        Ok(())
+    }
+
+    /// Fold the withdrawals of every era completed since the last upkeep into the average
+    ///
+    /// Each era moves the average `ewma_alpha_bps` of the way towards its volume.
+    /// At most `MAX_EWMA_ERAS` eras are folded; older ones have decayed away.
+    fn update_withdrawal_ewma(&mut self) {
+        let current_era = self.current_era();
+        let first_era = self
+            .withdrawal_ewma_era
+            .get()
+            .map_or(current_era.saturating_sub(1), |era| era + 1)
+            .max(current_era.saturating_sub(MAX_EWMA_ERAS));
+        
+        let alpha = U512::from(self.ewma_alpha_bps());
+        let denominator = U512::from(BPS_DENOMINATOR);
+        let mut ewma = self.withdrawal_ewma();
+        for era in first_era..current_era {
+            let volume = motes::to_motes(self.unstaked_per_era.get(&era).unwrap_or_default());
+            ewma = (ewma * (denominator - alpha) + volume * alpha) / denominator;
+        }
+        
+        if current_era > 0 {
+            self.withdrawal_ewma.set(ewma);
+            self.withdrawal_ewma_era.set(current_era - 1);
+        }
+    }
+
+    /// Move the liquidity buffer towards its target using one validator (operator only)
+    ///
+    /// CSPR above the target is delegated to `validator`. A shortfall is undelegated
+    /// from it, up to its delegation. Undelegated CSPR only reaches the purse after
+    /// the auction unbonding period, so wait for it before rebalancing again.
+    pub fn rebalance_buffer(&mut self, validator: PublicKey) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        
+        let buffer = self.current_buffer();
+        let target = self.target_buffer();
+        let mut delegated = U512::zero();
+        let mut undelegated = U512::zero();
+        
+        if buffer > target {
+            delegated = buffer - target;
+            self._delegate(validator.clone(), delegated)?;
+        } else if buffer < target {
+            undelegated = (target - buffer).min(self.delegation.delegated_to(&validator));
+            if undelegated > U512::zero() {
+                self._undelegate(validator.clone(), undelegated)?;
+            }
+        }
+        
+        self.env().emit_event(BufferRebalanced {
+            validator,
+            delegated,
+            undelegated,
+            buffer: self.current_buffer(),
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Get the CSPR staked since the last flush that has not been delegated yet
+    pub fn pending_deposits(&self) -> U512 {
+        self.pending_deposits.get_or_default()
+    }
+
+    /// Get the pending deposits needed before `flush_deposits` delegates them
+    pub fn flush_threshold(&self) -> U512 {
+        self.flush_threshold.get().unwrap_or(U512::from(MIN_DELEGATION))
+    }
+
+    /// Set the flush threshold (admin only)
+    pub fn set_flush_threshold(&mut self, threshold: U512) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_flush_threshold(threshold)?;
+        self.check_invariants()
+    }
+
+    fn _set_flush_threshold(&mut self, threshold: U512) -> Result<(), Error> {
+        let old_threshold = self.flush_threshold();
+        self.flush_threshold.set(threshold);
+        self.emit_config_changed("flush_threshold", motes::to_amount(old_threshold)?, motes::to_amount(threshold)?);
+        Ok(())
+    }
+
+    /// Delegate the pending deposits across the validators by weight (keeper task)
+    ///
+    /// Stakes only add CSPR to the purse, so delegations are batched here instead
+    /// of paying for an auction call on every stake. The withdrawal average is
+    /// updated first; only CSPR above the resulting buffer target is delegated,
+    /// and nothing is done below the flush threshold.
+    pub fn flush_deposits(&mut self) -> Result<(), Error> {
+        self.run_keeper_task("flush_deposits")?;
+        self.update_withdrawal_ewma();
+        
+        let pending = self.pending_deposits();
+        let available = self.current_buffer().saturating_sub(self.target_buffer());
+        let amount = pending.min(available);
+        if amount == U512::zero() || amount < self.flush_threshold() {
+            return Err(Error::BelowFlushThreshold);
+        }
+        
+        let mut flushed = Vec::new();
+        for entry in self.delegation.allocation(amount) {
+            if entry.target > U512::zero() {
+                self._delegate(entry.validator.clone(), entry.target)?;
+                flushed.push((entry.validator, entry.target));
+            }
+        }
+        // No validator has weight to delegate to
+        if flushed.is_empty() {
+            return Err(Error::ValidatorNotFound);
+        }
+        
+        let total = flushed
+            .iter()
+            .fold(U512::zero(), |total, (_, delegated)| total + *delegated);
+        let remaining = pending - total;
+        self.pending_deposits.set(remaining);
+        let buffer = self.current_buffer();
+        for (validator, delegated) in flushed {
+            self.env().emit_event(BufferRebalanced {
+                validator,
+                delegated,
+                undelegated: U512::zero(),
+                buffer,
+            });
+        }
+        self.env().emit_event(DepositsFlushed {
+            amount: total,
+            pending: remaining,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Set a validator's target weight in the delegation strategy (operator only)
+    pub fn set_validator_weight(&mut self, validator: PublicKey, weight: u32) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        self.delegation.set_weight(&validator, weight)?;
+        self.check_invariants()
+    }
+
+    /// Set the share of a validator's rewards rebated to its operator (admin only)
+    ///
+    /// Rebates accrue as `compound` syncs the validator's auction rewards, so a
+    /// lower pool-specific commission can be paid back in stCSPR.
+    pub fn set_validator_rebate(&mut self, validator: PublicKey, rebate_bps: u16) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self.delegation.set_rebate_bps(&validator, rebate_bps)?;
+        self.check_invariants()
+    }
+
+    /// Get the share of a validator's rewards rebated to its operator, in basis points
+    pub fn validator_rebate_bps(&self, validator: PublicKey) -> u16 {
+        self.delegation.rebate_bps_of(&validator)
+    }
+
+    /// Get the auction rewards the pool has synced for a validator, in motes
+    pub fn validator_rewards(&self, validator: PublicKey) -> U512 {
+        self.delegation.rewards_of(&validator)
+    }
+
+    /// Get the rebate a validator's operator can claim, in motes
+    pub fn rebate_owed(&self, validator: PublicKey) -> U512 {
+        self.delegation.rebate_owed(&validator)
+    }
+
+    /// Claim a validator's accrued rebate as stCSPR (the validator's account only)
+    ///
+    /// Mints shares worth exactly the rebate at the post-mint rate, so the
+    /// rebate is paid by diluting holders like the protocol fee.
+    pub fn claim_rebate(&mut self, validator: PublicKey) -> Result<U256, Error> {
+        self.lock()?;
+        let operator = Address::from(validator.clone());
+        
+        // CHECKS: Only the validator's own account claims, and something must be owed
+        if self.env().caller() != operator {
+            return Err(Error::NotAuthorized);
+        }
+        let owed = motes::to_amount(self.delegation.rebate_owed(&validator))?;
+        if owed == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+        
+        // shares = owed * total_shares / (pooled - owed)
+        let numerator = owed.checked_mul(self.backed_shares()).ok_or(Error::ArithmeticOverflow)?;
+        let shares = numerator / self.safe_sub(self.contract_cspr_balance(), owed)?;
+        
+        // EFFECTS: Clear the rebate and mint the shares
+        self.delegation.take_rebate(&validator);
+        self._mint(&operator, shares)?;
+        self.publish_rate();
+        
+        self.env().emit_event(RebateClaimed {
+            validator,
+            operator,
+            cspr_amount: owed,
+            stcspr_minted: shares,
+        });
+        
+        self.validate_state_consistency()?;
+        self.unlock();
+        self.check_invariants()?;
+        Ok(shares)
+    }
+
+    /// Get every validator's weight, current delegation and target delegation
+    ///
+    /// Targets split everything above the liquidity buffer target by weight.
+    /// CSPR still unbonding counts as buffer already on its way, so an
+    /// undelegation does not lower the targets of the next rebalance.
+    pub fn current_allocation(&self) -> Vec<ValidatorAllocation> {
+        let stake_total = self
+            .total_delegated()
+            .saturating_add(self.current_buffer())
+            .saturating_add(self.total_unbonding());
+        let target_delegated = stake_total.saturating_sub(self.target_buffer());
+        self.delegation.allocation(target_delegated)
+    }
+
+    /// Move every validator's delegation towards its weighted target (operator only)
+    ///
+    /// Validators above target are undelegated first. Validators below target are
+    /// then topped up from the CSPR above the buffer target. Undelegated CSPR only
+    /// returns after the auction unbonding period, so shortfalls may take a second
+    /// rebalance to close.
+    pub fn rebalance(&mut self) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        
+        let allocation = self.current_allocation();
+        for entry in allocation.iter().filter(|entry| entry.delegated != entry.target) {
+            self.env().emit_event(RebalancePlanned {
+                validator: entry.validator.clone(),
+                current: entry.delegated,
+                target: entry.target,
+            });
+        }
+        
+        for entry in allocation.iter().filter(|entry| entry.delegated > entry.target) {
+            self._undelegate(entry.validator.clone(), entry.delegated - entry.target)?;
+        }
+        
+        let mut available = self.current_buffer().saturating_sub(self.target_buffer());
+        for entry in allocation.iter().filter(|entry| entry.delegated < entry.target) {
+            let amount = (entry.target - entry.delegated).min(available);
+            if amount == U512::zero() {
+                break;
+            }
+            self._delegate(entry.validator.clone(), amount)?;
+            available -= amount;
+        }
+        
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Get the registered validators
+    pub fn validators(&self) -> Vec<PublicKey> {
+        self.delegation.validators()
+    }
+
+    /// Get the amount of CSPR delegated to a validator
+    pub fn delegated_amount(&self, validator: PublicKey) -> U512 {
+        self.delegation.delegated_to(&validator)
+    }
+
+    /// Get the total amount of CSPR delegated across all validators
+    pub fn total_delegated(&self) -> U512 {
+        self.delegation.total_delegated()
+    }
+
+    /// Report staking rewards earned by the pool's delegations (operator only)
+    ///
+    /// The rewards are added to the pooled CSPR without minting shares, so the
+    /// exchange rate of every stCSPR appreciates. Each report opens a new epoch.
+    /// Once the oracle committee is enabled, reports go through `submit_oracle_report`.
+    pub fn report_rewards(&mut self, amount: U256) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        if self.oracle.is_enabled() {
+            return Err(Error::OracleReportRequired);
+        }
+        self.validate_amount(amount)?;
+        self.run_keeper_task("report_rewards")?;
+        self.accrue_reported_rewards(amount)?;
+        self.check_invariants()
+    }
+
+    /// Sync rewards the auction has compounded into the delegations (keeper task)
+    ///
+    /// The auction re-delegates rewards automatically, so each validator's stake
+    /// grows on its own. This reads the stake back, records the growth as rewards
+    /// on the same path as `report_rewards` and emits `Compounded` per validator.
+    /// Growth already credited by `report_rewards` or an oracle report is only
+    /// synced into the delegations, never credited twice; while the oracle
+    /// committee is enabled nothing beyond the reported rewards is credited.
+    /// Stakes below the tracked delegation are left for `report_loss`. Runs at most
+    /// once per `compound_interval`.
+    pub fn compound(&mut self) -> Result<(), Error> {
+        let now = self.env().block_time();
+        if let Some(last_compound) = self.last_compound_timestamp.get() {
+            if now < last_compound.saturating_add(self.compound_interval()) {
+                return Err(Error::CompoundTooSoon);
+            }
+        }
+        self.run_keeper_task("compound")?;
+        self.last_compound_timestamp.set(now);
+        
+        let reported = self.unsynced_rewards();
+        let max = if self.oracle.is_enabled() { reported } else { U512::MAX };
+        let growth = self.delegation.sync_rewards(max)?;
+        let already_credited = growth.min(reported);
+        self.unsynced_rewards.set(reported - already_credited);
+        
+        let rewards = growth - already_credited;
+        if rewards > U512::zero() {
+            self.accrue_rewards(motes::to_amount(rewards)?)?;
+        }
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Get the rewards credited by reports that `compound` has not synced into the delegations yet
+    pub fn unsynced_rewards(&self) -> U512 {
+        self.unsynced_rewards.get_or_default()
+    }
+
+    /// Get the block time of the last `compound` call
+    pub fn last_compound_timestamp(&self) -> Option<u64> {
+        self.last_compound_timestamp.get()
+    }
+
+    /// Get the minimum time between two `compound` calls, in milliseconds
+    pub fn compound_interval(&self) -> u64 {
+        self.compound_interval.get().unwrap_or(ERA_DURATION)
+    }
+
+    /// Set the minimum time between two `compound` calls (admin only)
+    pub fn set_compound_interval(&mut self, interval: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_compound_interval(interval);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    fn _set_compound_interval(&mut self, interval: u64) {
+        let old_interval = self.compound_interval();
+        self.compound_interval.set(interval);
+        self.emit_config_changed("compound_interval", U256::from(old_interval), U256::from(interval));
+    }
+
+    /// Accrue reported rewards and remember them until `compound` sees them in the auction
+    fn accrue_reported_rewards(&mut self, amount: U256) -> Result<(), Error> {
+        let unsynced = self
+            .unsynced_rewards()
+            .checked_add(motes::to_motes(amount))
+            .ok_or(Error::ArithmeticOverflow)?;
+        self.unsynced_rewards.set(unsynced);
+        self.accrue_rewards(amount)
+    }
+
+    /// Add `amount` of rewards to the pool, taking the protocol fee and insurance cut
+    fn accrue_rewards(&mut self, amount: U256) -> Result<(), Error> {
+        // Rewards can only accrue to existing holders
+        if self.total_supply() == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+        
+        let total_shares = self.backed_shares();
+        
+        // Protocol fee is only taken once a treasury has been configured, and never during a sunset
+        let treasury = self.treasury.get();
+        let fee = match treasury {
+            Some(_) if !self.sunset.is_active() => {
+                amount * U256::from(self.fee_bps()) / U256::from(BPS_DENOMINATOR)
+            }
+            _ => U256::zero(),
+        };
+        
+        // Part of the fee goes to the insurance fund and stays out of the pool
+        let insurance_cut = fee * U256::from(self.insurance.share_bps()) / U256::from(BPS_DENOMINATOR);
+        let treasury_fee = fee - insurance_cut;
+        let new_pooled = self.safe_add(
+            self.contract_cspr_balance(),
+            self.safe_sub(amount, insurance_cut)?,
+        )?;
+        
+        // Mint shares worth exactly `treasury_fee` at the post-reward rate:
+        // fee_shares = treasury_fee * total_shares / (new_pooled - treasury_fee)
+        let fee_shares = if treasury_fee > U256::zero() {
+            let numerator = treasury_fee.checked_mul(total_shares).ok_or(Error::ArithmeticOverflow)?;
+            numerator / self.safe_sub(new_pooled, treasury_fee)?
+        } else {
+            U256::zero()
+        };
+        
+        // Integrators' shares of the fee are held by the contract until claimed
+        let integrator_shares = self.integrators.accrue(fee_shares, total_shares)?;
+        
+        // EFFECTS: Add rewards to the pool, fund the insurance, then mint the fee
+        self.set_pooled_cspr(new_pooled);
+        if insurance_cut > U256::zero() {
+            self.insurance.deposit(insurance_cut)?;
+        }
+        if let (Some(treasury), true) = (treasury, fee_shares > integrator_shares) {
+            self._mint(&treasury, fee_shares - integrator_shares)?;
+        }
+        if integrator_shares > U256::zero() {
+            let self_address = self.env().self_address();
+            self._mint(&self_address, integrator_shares)?;
+        }
+        
+        let exchange_rate = self.exchange_rate();
+        let epoch = self.rewards.record(amount, fee, exchange_rate);
+        self.publish_rate();
+        
+        if let (Some(treasury), true) = (treasury, fee_shares > U256::zero()) {
+            self.env().emit_event(FeeCollected {
+                epoch,
+                treasury,
+                cspr_amount: treasury_fee,
+                stcspr_minted: fee_shares,
+            });
+        }
+        if integrator_shares > U256::zero() {
+            self.env().emit_event(IntegratorFeesAccrued {
+                epoch,
+                stcspr_amount: integrator_shares,
+            });
+        }
+        
+        self.validate_state_consistency()
+
+    }
+
+    /// Get the CSPR held by the insurance fund
+    pub fn insurance_balance(&self) -> U256 {
+        self.insurance.balance()
+    }
+
+    /// Get the insurance fund as a share of the pooled CSPR, in basis points
+    pub fn coverage_ratio(&self) -> U256 {
+        let pooled = self.contract_cspr_balance();
+        if pooled == U256::zero() {
+            return U256::zero();
+        }
+        self.insurance.balance() * U256::from(BPS_DENOMINATOR) / pooled
+    }
+
+    /// Get the share of each protocol fee paid into the insurance fund, in basis points
+    pub fn insurance_share_bps(&self) -> u16 {
+        self.insurance.share_bps()
+    }
+
+    /// Set the share of each protocol fee paid into the insurance fund (admin only)
+    pub fn set_insurance_share_bps(&mut self, share_bps: u16) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_insurance_share_bps(share_bps)?;
+        self.check_invariants()
+    }
+
+    fn _set_insurance_share_bps(&mut self, share_bps: u16) -> Result<(), Error> {
+        if share_bps > BPS_DENOMINATOR {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        let old_share_bps = self.insurance.share_bps();
+        self.insurance.set_share_bps(share_bps);
+        self.emit_config_changed("insurance_share_bps", U256::from(old_share_bps), U256::from(share_bps));
+        Ok(())
+    }
+
+    /// Return insurance CSPR to the pool to offset a reported loss (admin only)
+    ///
+    /// Raises the exchange rate back towards where it was before the loss.
+    pub fn cover_loss(&mut self, amount: U256) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._cover_loss(amount)?;
+        self.check_invariants()
+    }
+
+    fn _cover_loss(&mut self, amount: U256) -> Result<(), Error> {
+        self.validate_amount(amount)?;
+        
+        let new_pooled = self.safe_add(self.contract_cspr_balance(), amount)?;
+        self.insurance.cover(amount)?;
+        self.set_pooled_cspr(new_pooled);
+        self.publish_rate();
+        self.validate_state_consistency()
+    }
+
+    /// Report CSPR lost at a validator, e.g. through slashing (operator only)
+    ///
+    /// The loss is written off the validator's delegation. The insurance fund
+    /// absorbs as much of it as it holds; only the remainder is written off the
+    /// pooled CSPR and shared by all holders through a lower exchange rate. The
+    /// pool cannot be written down to zero while shares are outstanding. Once the
+    /// oracle committee is enabled, losses go through `submit_oracle_report`.
+    pub fn report_loss(&mut self, amount: U256, validator: PublicKey) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        if self.oracle.is_enabled() {
+            return Err(Error::OracleReportRequired);
+        }
+        self._report_loss(amount, validator)?;
+        self.check_invariants()
+    }
+
+    /// Write a loss off the validator's delegation, the insurance fund and the pool
+    fn _report_loss(&mut self, amount: U256, validator: PublicKey) -> Result<(), Error> {
+        self.validate_amount(amount)?;
+        
+        // Insurance pays first; holders only bear what it cannot cover
+        let covered = amount.min(self.insurance.balance());
+        let uncovered = amount - covered;
+        let pooled = self.contract_cspr_balance();
+        if uncovered > U256::zero() && uncovered >= pooled {
+            return Err(Error::ExceedsMaximum);
+        }
+        let new_pooled = self.safe_sub(pooled, uncovered)?;
+        
+        // EFFECTS: Write the loss off the validator, the insurance fund and the pool
+        self.delegation.write_off(&validator, motes::to_motes(amount))?;
+        if covered > U256::zero() {
+            self.insurance.cover(covered)?;
+        }
+        self.set_pooled_cspr(new_pooled);
+        self.publish_rate();
+        
+        self.env().emit_event(LossReported {
+            validator,
+            amount,
+            exchange_rate: self.exchange_rate(),
+        });
+        
+        self.validate_state_consistency()
+    }
+
+    /// Settle the undelegation batch of `era` with the CSPR the auction returned (operator only)
+    ///
+    /// The era must have passed. A shortfall against the CSPR undelegated, e.g.
+    /// from slashing while it was unbonding, is drawn from the insurance fund
+    /// first. The rest is shared pro rata by the batch's unclaimed withdrawal
+    /// requests and the pooled CSPR, by how much of the batch each is owed;
+    /// requests already paid out keep their full amount. `BatchSettled` records
+    /// the split and `WithdrawalHaircut` each request cut.
+    pub fn settle_unbonding_batch(&mut self, era: u64, returned: U512) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        if era > self.current_era() {
+            return Err(Error::UnbondingNotFinished);
+        }
+        let batch = self.batches.settle(era, returned)?;
+        let expected = motes::to_amount(batch.expected)?;
+        let shortfall = motes::to_amount(batch.expected - returned)?;
+        
+        // Insurance pays first; requests and holders share the rest
+        let covered = shortfall.min(self.insurance.balance());
+        let uncovered = shortfall - covered;
+        let requests: Vec<WithdrawalRequest> = batch
+            .requests
+            .iter()
+            .filter_map(|id| self.withdrawals.get(*id))
+            .filter(|request| !request.claimed)
+            .collect();
+        let owed = requests
+            .iter()
+            .fold(U256::zero(), |total, request| total.saturating_add(request.amount));
+        let requests_share = if expected.is_zero() {
+            U256::zero()
+        } else {
+            (uncovered * owed / expected).min(owed)
+        };
+        
+        // Each request's cut rounds down; the remainder is written off the pool
+        let cuts: Vec<(u64, U256)> = if requests_share.is_zero() {
+            Vec::new()
+        } else {
+            requests
+                .iter()
+                .map(|request| (request.id, requests_share * request.amount / owed))
+                .filter(|(_, cut)| *cut > U256::zero())
+                .collect()
+        };
+        let haircut = cuts.iter().fold(U256::zero(), |total, (_, cut)| total + *cut);
+        let written_off = uncovered - haircut;
+        let pooled = self.contract_cspr_balance();
+        if written_off > U256::zero() && written_off >= pooled {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        // EFFECTS: Cover, cut and write off the shortfall
+        if covered > U256::zero() {
+            self.insurance.cover(covered)?;
+        }
+        for (id, cut) in cuts {
+            self.withdrawals.haircut(id, cut)?;
+        }
+        if written_off > U256::zero() {
+            self.set_pooled_cspr(pooled - written_off);
+            self.publish_rate();
+        }
+        
+        self.env().emit_event(BatchSettled {
+            era,
+            expected: batch.expected,
+            returned,
+            covered,
+            haircut,
+            written_off,
+        });
+        self.check_invariants()
+    }
+
+    /// Get the undelegation batch returning in `era`
+    pub fn unbonding_batch(&self, era: u64) -> Option<UnbondingBatch> {
+        self.batches.get(era)
+    }
+
+    /// Move all of `holder`'s stCSPR into a withdrawal request once the sunset's exit window has passed
+    ///
+    /// Callable by anyone, so keepers can empty the pool on schedule. The request
+    /// belongs to the holder, who claims the CSPR as usual. Positions beyond the
+    /// era's unstake limit are forced out in later eras. stCSPR the contract holds
+    /// itself, e.g. unclaimed integrator fees, has to be claimed out first.
+    pub fn force_exit(&mut self, holder: &Address) -> Result<u64, Error> {
+        self.sunset.require_forced_exit_open()?;
+        if *holder == self.env().self_address() {
+            return Err(Error::InvalidAddress);
+        }
+        let shares = self.balance_of(holder);
+        let id = self._request_unstake(holder, shares)?;
+        
+        self.env().emit_event(ForcedExit {
+            holder: *holder,
+            request_id: id,
+            shares,
+            keeper: self.env().caller(),
+        });
+        self.check_invariants()?;
+        Ok(id)
+    }
+
+    /// Retire the contract at the end of its sunset (operator only)
+    ///
+    /// Every stCSPR must have been burned and every withdrawal request claimed.
+    /// The contract is paused for good: `unpause` refuses once it is retired.
+    pub fn retire(&mut self) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        if !self.sunset.is_active() {
+            return Err(Error::SunsetNotDue);
+        }
+        if self.total_supply() > U256::zero() || self.pending_withdrawals() > U256::zero() {
+            return Err(Error::SupplyOutstanding);
+        }
+        
+        let operator = self.env().caller();
+        self.sunset.retire(operator)?;
+        if !self.paused() {
+            self.paused.set(true);
+            self.env().emit_event(Paused {
+                account: operator,
+            });
+        }
+        self.check_invariants()
+    }
+
+    /// Get the block time the sunset started at, if governance started one
+    pub fn sunset_started_at(&self) -> Option<u64> {
+        self.sunset.started_at()
+    }
+
+    /// Get the block time from which remaining positions can be forced into the queue
+    pub fn forced_exit_at(&self) -> Option<u64> {
+        self.sunset.forced_exit_at()
+    }
+
+    /// Check whether the contract was retired
+    pub fn retired(&self) -> bool {
+        self.sunset.is_retired()
+    }
+
+    /// Apply a reward or loss report signed by a quorum of the oracle committee
+    ///
+    /// Anyone can submit the report. `nonce` must be above the last accepted one,
+    /// and `signatures` pairs each member's public key with its signature over
+    /// `oracle_report_message(report, nonce)`; duplicates and non-members are
+    /// ignored.
+    pub fn submit_oracle_report(
+        &mut self,
+        report: OracleReport,
+        nonce: u64,
+        signatures: Vec<(PublicKey, Bytes)>,
+    ) -> Result<(), Error> {
+        if !self.oracle.is_enabled() {
+            return Err(Error::NotAuthorized);
+        }
+        
+        self.oracle.accept(report.clone(), nonce, signatures)?;
+        match report {
+            OracleReport::Rewards(amount) => {
+                self.validate_amount(amount)?;
+                self.accrue_reported_rewards(amount)?;
+            }
+            OracleReport::Loss(amount, validator) => self._report_loss(amount, validator)?,
+        }
+        self.check_invariants()
+    }
+
+    /// Build the message committee members sign for a report
+    pub fn oracle_report_message(&self, report: OracleReport, nonce: u64) -> Bytes {
+        self.oracle.message(&report, nonce)
+    }
+
+    /// Replace the oracle committee (admin only)
+    ///
+    /// A quorum above zero makes signed reports mandatory: `report_rewards` and
+    /// `report_loss` are then rejected. A quorum of zero returns to operator reports.
+    pub fn set_oracle_committee(&mut self, members: Vec<PublicKey>, quorum: u32) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self.oracle.configure(members, quorum)?;
+        self.check_invariants()
+    }
+
+    /// Get the oracle committee members
+    pub fn oracle_members(&self) -> Vec<PublicKey> {
+        self.oracle.members()
+    }
+
+    /// Get the member signatures an oracle report needs (zero means disabled)
+    pub fn oracle_quorum(&self) -> u32 {
+        self.oracle.quorum()
+    }
+
+    /// Get the nonce of the last accepted oracle report
+    pub fn oracle_nonce(&self) -> u64 {
+        self.oracle.nonce()
+    }
+
+    /// Get the protocol fee in basis points
+    pub fn fee_bps(&self) -> u16 {
+        self.fee_bps.get_or_default()
+    }
+
+    /// Set the protocol fee taken from rewards (admin only, at most `MAX_FEE_BPS`)
+    pub fn set_fee_bps(&mut self, fee_bps: u16) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_fee_bps(fee_bps)?;
+        self.check_invariants()
+    }
+
+    /// Internal fee setter shared by the admin entry point and governance
+    fn _set_fee_bps(&mut self, fee_bps: u16) -> Result<(), Error> {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        let old_fee_bps = self.fee_bps();
+        self.fee_bps.set(fee_bps);
+        self.emit_config_changed("fee_bps", U256::from(old_fee_bps), U256::from(fee_bps));
+        Ok(())
+    }
+
+    /// Get the address receiving protocol fees
+    pub fn treasury(&self) -> Option<Address> {
+        self.treasury.get()
+    }
+
+    /// Set the address receiving protocol fees (admin only)
+    pub fn set_treasury(&mut self, treasury: Address) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_treasury(treasury)?;
+        self.check_invariants()
+    }
+
+    /// Internal treasury setter shared by the admin entry point and governance
+    fn _set_treasury(&mut self, treasury: Address) -> Result<(), Error> {
+        self.validate_address(&treasury)?;
+        
+        let previous_treasury = self.treasury();
+        self.treasury.set(treasury);
+        self.env().emit_event(TreasuryChanged {
+            previous_treasury,
+            new_treasury: treasury,
+            changed_by: self.env().caller(),
+        });
+        Ok(())
+    }
+
+    /// Sell `amount` of the treasury's stCSPR through a registered pool (treasurer only)
+    ///
+    /// The stCSPR moves from the treasury to `pool`, which pays its output to the
+    /// treasury and returns the amount paid. Swaps are capped per rewards epoch by
+    /// the treasury swap limit, and revert if the pool pays less than `min_out`.
+    pub fn treasury_swap(&mut self, pool: Address, amount: U256, min_out: U256) -> Result<U256, Error> {
+        // CHECKS: Only registered pools, within the epoch budget
+        self.lock()?;
+        self.require_role(Role::Treasurer)?;
+        if !self.swaps.is_pool(&pool) {
+            return Err(Error::SwapPoolNotRegistered);
+        }
+        let treasury = self.treasury.get().ok_or(Error::InvalidAddress)?;
+        let epoch = self.current_epoch();
+        
+        // EFFECTS: Spend the budget and hand the stCSPR to the pool
+        self.swaps.record(epoch, amount)?;
+        self._transfer(&treasury, &pool, amount)?;
+        self.env().emit_event(Transfer {
+            sender: treasury,
+            recipient: pool,
+            amount,
+        });
+        
+        // INTERACTIONS: The pool pays the treasury
+        let amount_out = SwapPoolContractRef::new(self.env(), pool).swap(amount, min_out, treasury);
+        if amount_out < min_out {
+            return Err(Error::SlippageExceeded);
+        }
+        
+        self.env().emit_event(TreasurySwapped {
+            pool,
+            treasurer: self.env().caller(),
+            amount_in: amount,
+            amount_out,
+            epoch,
+        });
+        self.unlock();
+        self.check_invariants()?;
+        Ok(amount_out)
+    }
+
+    /// Check whether the treasury may swap through a pool
+    pub fn is_swap_pool(&self, pool: Address) -> bool {
+        self.swaps.is_pool(&pool)
+    }
+
+    /// Get the most treasury stCSPR that can be swapped per rewards epoch
+    pub fn treasury_swap_limit(&self) -> U256 {
+        self.swaps.epoch_limit()
+    }
+
+    /// Get the treasury stCSPR swapped in a rewards epoch
+    pub fn treasury_swapped(&self, epoch: u64) -> U256 {
+        self.swaps.swapped_in(epoch)
+    }
+
+    /// Register or remove a pool the treasury may swap through (admin only)
+    pub fn set_swap_pool(&mut self, pool: Address, registered: bool) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self.swaps.set_pool(pool, registered)?;
+        self.check_invariants()
+    }
+
+    /// Set the most treasury stCSPR swapped per rewards epoch (admin only, zero disables swaps)
+    pub fn set_treasury_swap_limit(&mut self, limit: U256) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_treasury_swap_limit(limit);
+        self.check_invariants()
+    }
+
+    fn _set_treasury_swap_limit(&mut self, limit: U256) {
+        let old_limit = self.swaps.epoch_limit();
+        self.swaps.set_epoch_limit(limit);
+        self.emit_config_changed("treasury_swap_limit", old_limit, limit);
+    }
+
+    /// Get the smallest CSPR amount accepted by `stake` (zero means no minimum)
+    pub fn min_stake(&self) -> U256 {
+        self.min_stake.get_or_default()
+    }
+
+    /// Get the largest CSPR amount accepted by a single `stake` (zero means no limit)
+    pub fn max_stake_per_tx(&self) -> U256 {
+        self.max_stake_per_tx.get_or_default()
+    }
+
+    /// Get the cap on the total pooled CSPR (zero means no cap)
+    pub fn max_total_staked(&self) -> U256 {
+        self.max_total_staked.get_or_default()
+    }
+
+    /// Set the staking limits (admin only); zero disables a limit
+    pub fn set_limits(&mut self, min_stake: U256, max_stake_per_tx: U256, max_total_staked: U256) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_limits(min_stake, max_stake_per_tx, max_total_staked)?;
+        self.check_invariants()
+    }
+
+    fn _set_limits(&mut self, min_stake: U256, max_stake_per_tx: U256, max_total_staked: U256) -> Result<(), Error> {
+        if max_stake_per_tx > U256::zero() && min_stake > max_stake_per_tx {
+            return Err(Error::InvalidAmount);
+        }
+        
+        self.min_stake.set(min_stake);
+        self.max_stake_per_tx.set(max_stake_per_tx);
+        self.max_total_staked.set(max_total_staked);
+        
+        self.env().emit_event(LimitsUpdated {
+            min_stake,
+            max_stake_per_tx,
+            max_total_staked,
+        });
+        Ok(())
+    }
+
+    /// Get the stCSPR paid to keepers per maintenance run
+    pub fn keeper_reward(&self) -> U256 {
+        self.keeper_reward.get_or_default()
+    }
+
+    /// Get the time between two paid runs of the same maintenance task, in milliseconds
+    pub fn keeper_cooldown(&self) -> u64 {
+        self.keeper_cooldown.get().unwrap_or(ERA_DURATION)
+    }
+
+    /// Get the block time a maintenance task last ran
+    pub fn keeper_last_run(&self, task: String) -> Option<u64> {
+        self.keeper_last_run.get(&task)
+    }
+
+    /// Set the keeper reward and cooldown (admin only)
+    pub fn set_keeper_config(&mut self, reward: U256, cooldown: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_keeper_config(reward, cooldown);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    fn _set_keeper_config(&mut self, reward: U256, cooldown: u64) {
+        let old_reward = self.keeper_reward();
+        let old_cooldown = self.keeper_cooldown();
+        self.keeper_reward.set(reward);
+        self.keeper_cooldown.set(cooldown);
+        self.emit_config_changed("keeper_reward", old_reward, reward);
+        self.emit_config_changed("keeper_cooldown", U256::from(old_cooldown), U256::from(cooldown));
+    }
+
+    /// Check whether only allowlisted addresses can receive transfers
+    pub fn transfers_restricted(&self) -> bool {
+        self.transfers_restricted.get_or_default()
+    }
+
+    /// Check whether an address may receive transfers while they are restricted
+    pub fn is_transfer_allowlisted(&self, account: &Address) -> bool {
+        self.transfer_allowlist.get(account).unwrap_or_default()
+    }
+
+    /// Restrict transfers to allowlisted recipients for the launch phase (admin only)
+    ///
+    /// Staking and unstaking are unaffected. Not possible once transfers were opened.
+    pub fn restrict_transfers(&mut self) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._restrict_transfers()?;
+        self.check_invariants()
+    }
+
+    fn _restrict_transfers(&mut self) -> Result<(), Error> {
+        self.require_feature(FEATURE_COMPLIANCE)?;
+        if self.transfers_opened.get_or_default() {
+            return Err(Error::TransfersAlreadyOpen);
+        }
+        
+        self.transfers_restricted.set(true);
+        self.env().emit_event(TransfersRestricted {
+            account: self.env().caller(),
+        });
+        Ok(())
+    }
+
+    /// Add or remove a transfer recipient from the allowlist (admin only)
+    pub fn set_transfer_allowlist(&mut self, account: &Address, allowed: bool) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_transfer_allowlist(account, allowed);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    fn _set_transfer_allowlist(&mut self, account: &Address, allowed: bool) {
+        self.transfer_allowlist.set(account, allowed);
+        self.env().emit_event(TransferAllowlistUpdated {
+            account: *account,
+            allowed,
+        });
+    }
+
+    /// Lift the transfer restriction permanently (admin only)
+    pub fn open_transfers(&mut self) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._open_transfers()?;
+        self.check_invariants()
+    }
+
+    fn _open_transfers(&mut self) -> Result<(), Error> {
+        if self.transfers_opened.get_or_default() {
+            return Err(Error::TransfersAlreadyOpen);
+        }
+        
+        self.transfers_restricted.set(false);
+        self.transfers_opened.set(true);
+        self.env().emit_event(TransfersOpened {
+            account: self.env().caller(),
+        });
+        Ok(())
+    }
+
+    /// Post the merkle root of a new stCSPR drop (admin only)
+    ///
+    /// Claims of earlier drops can no longer be made; unclaimed stCSPR stays in
+    /// the drop balance for the next one.
+    pub fn set_merkle_root(&mut self, root: [u8; 32]) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self.merkle_drop.set_root(root);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Move `amount` of the caller's stCSPR into the drop balance
+    pub fn fund_merkle_drop(&mut self, amount: U256) -> Result<(), Error> {
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+        
+        self._transfer(&caller, &self_address, amount)?;
+        self.merkle_drop.fund(caller, amount)?;
+        
+        self.env().emit_event(Transfer {
+            sender: caller,
+            recipient: self_address,
+            amount,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Claim the caller's allocation at leaf `index` of the current drop
+    ///
+    /// Each leaf can be claimed once; the stCSPR comes from the funded balance.
+    pub fn claim(&mut self, index: u64, amount: U256, proof: Vec<[u8; 32]>) -> Result<(), Error> {
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+        
+        // CHECKS and EFFECTS: Verify the proof and mark the leaf claimed
+        self.merkle_drop.claim(index, caller, amount, proof)?;
+        self._transfer(&self_address, &caller, amount)?;
+        
+        self.env().emit_event(Transfer {
+            sender: self_address,
+            recipient: caller,
+            amount,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Check whether leaf `index` of the current drop has been claimed
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.merkle_drop.is_claimed(index)
+    }
+
+    /// Get the merkle root of the current drop
+    pub fn merkle_root(&self) -> Option<[u8; 32]> {
+        self.merkle_drop.root()
+    }
+
+    /// Get the funded stCSPR not yet claimed
+    pub fn merkle_drop_balance(&self) -> U256 {
+        self.merkle_drop.balance()
+    }
+
+    /// Get the leaf hash for an allocation, for building trees off-chain
+    pub fn merkle_leaf(&self, index: u64, account: Address, amount: U256) -> [u8; 32] {
+        self.merkle_drop.leaf(index, &account, amount)
+    }
+
+    /// Lock `amount` of the caller's stCSPR in a vesting schedule for `beneficiary`
+    ///
+    /// Nothing vests before `cliff` milliseconds have passed; everything has vested
+    /// after `duration`. An admin can revoke the unvested part of a `revocable`
+    /// schedule, which returns it to the caller. Returns the schedule id.
+    pub fn create_vesting(
+        &mut self,
+        beneficiary: Address,
+        amount: U256,
+        cliff: u64,
+        duration: u64,
+        revocable: bool,
+    ) -> Result<u64, Error> {
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+        
+        self._transfer(&caller, &self_address, amount)?;
+        let id = self.vesting.create(caller, beneficiary, amount, cliff, duration, revocable)?;
+        
+        self.env().emit_event(Transfer {
+            sender: caller,
+            recipient: self_address,
+            amount,
+        });
+        self.check_invariants()?;
+        Ok(id)
+    }
+
+    /// Release the vested stCSPR of schedule `id` to its beneficiary (beneficiary only)
+    pub fn release(&mut self, id: u64) -> Result<U256, Error> {
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+        
+        // CHECKS: Only the beneficiary withdraws
+        let schedule = self.vesting.get(id).ok_or(Error::VestingNotFound)?;
+        if schedule.beneficiary != caller {
+            return Err(Error::NotAuthorized);
+        }
+        
+        // EFFECTS: Record the release, then pay it out
+        let (beneficiary, amount) = self.vesting.release(id)?;
+        self._transfer(&self_address, &beneficiary, amount)?;
+        
+        self.env().emit_event(Transfer {
+            sender: self_address,
+            recipient: beneficiary,
+            amount,
+        });
+        self.check_invariants()?;
+        Ok(amount)
+    }
+
+    /// Revoke the unvested part of schedule `id` and return it to the funder (admin only)
+    ///
+    /// What has vested by now stays releasable by the beneficiary.
+    pub fn revoke_vesting(&mut self, id: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._revoke_vesting(id)?;
+        self.check_invariants()
+    }
+
+    fn _revoke_vesting(&mut self, id: u64) -> Result<(), Error> {
+        let self_address = self.env().self_address();
+        
+        let (funder, refunded) = self.vesting.revoke(id)?;
+        if refunded > U256::zero() {
+            self._transfer(&self_address, &funder, refunded)?;
+            self.env().emit_event(Transfer {
+                sender: self_address,
+                recipient: funder,
+                amount: refunded,
+            });
+        }
+        Ok(())
+    }
+
+    /// Get the stCSPR schedule `id` lets its beneficiary release now
+    pub fn releasable(&self, id: u64) -> U256 {
+        self.vesting.releasable(id)
+    }
+
+    /// Get a vesting schedule by id
+    pub fn vesting_schedule(&self, id: u64) -> Option<VestingSchedule> {
+        self.vesting.get(id)
+    }
+
+    /// Get the stCSPR locked in vesting schedules and not yet released
+    pub fn vesting_locked(&self) -> U256 {
+        self.vesting.locked()
+    }
+
+    /// Flash mint `amount` stCSPR to the `receiver` contract
+    ///
+    /// The receiver's `on_flash_mint` hook is called with the minted stCSPR. When
+    /// it returns, `amount` is burned from the receiver and the fee is paid to the
+    /// treasury (or burned if none is set); a receiver that cannot repay reverts
+    /// the whole call. Staking and unstaking are locked during the hook because
+    /// the minted stCSPR is not backed by pooled CSPR, and the minted amount is
+    /// left out of the exchange rate until it is repaid.
+    pub fn flash_mint(&mut self, amount: U256, receiver: Address, data: Bytes) -> Result<(), Error> {
+        // CHECKS: Flash mints must be enabled and go to a contract
+        self.lock()?;
+        self.require_not_paused()?;
+        self.validate_amount(amount)?;
+        if !self.flash.enabled() {
+            return Err(Error::FlashMintDisabled);
+        }
+        if !receiver.is_contract() {
+            return Err(Error::InvalidAddress);
+        }
+        let initiator = self.env().caller();
+        let fee = if self.sunset.is_active() { U256::zero() } else { self.flash.fee(amount) };
+        let owed = self.safe_add(amount, fee)?;
+        
+        // EFFECTS: Mint to the receiver, outside the shares backing the rate
+        self._mint(&receiver, amount)?;
+        self.flash_outstanding.set(amount);
+        
+        // INTERACTIONS: Hand the stCSPR to the receiver
+        let accepted = FlashBorrowerContractRef::new(self.env(), receiver).on_flash_mint(initiator, amount, fee, data);
+        if !accepted {
+            return Err(Error::TransferRejected);
+        }
+        
+        // EFFECTS: Collect the amount and the fee
+        if self.balance_of(&receiver) < owed {
+            return Err(Error::FlashMintNotRepaid);
+        }
+        self.flash_outstanding.set(U256::zero());
+        let burned = match self.treasury.get() {
+            Some(treasury) if fee > U256::zero() && treasury != receiver => {
+                self._transfer(&receiver, &treasury, fee)?;
+                self.env().emit_event(Transfer {
+                    sender: receiver,
+                    recipient: treasury,
+                    amount: fee,
+                });
+                amount
+            }
+            _ => owed,
+        };
+        self._burn(&receiver, burned)?;
+        self.publish_rate();
+        self.validate_state_consistency()?;
+        self.unlock();
+        
+        self.env().emit_event(FlashMinted {
+            initiator,
+            borrower: receiver,
+            amount,
+            fee,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Enable or disable flash mints and set their fee in basis points (admin only)
+    pub fn set_flash_mint_config(&mut self, enabled: bool, fee_bps: u16) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_flash_mint_config(enabled, fee_bps)?;
+        self.check_invariants()
+    }
+
+    fn _set_flash_mint_config(&mut self, enabled: bool, fee_bps: u16) -> Result<(), Error> {
+        if fee_bps > BPS_DENOMINATOR {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        let old_fee_bps = self.flash.fee_bps();
+        self.flash.configure(enabled, fee_bps);
+        self.emit_config_changed("flash_mint_fee_bps", U256::from(old_fee_bps), U256::from(fee_bps));
+        Ok(())
+    }
+
+    /// Check whether flash mints are enabled
+    pub fn flash_mint_enabled(&self) -> bool {
+        self.flash.enabled()
+    }
+
+    /// Get the fee owed for flash minting `amount`
+    pub fn flash_fee(&self, amount: U256) -> U256 {
+        self.flash.fee(amount)
+    }
+
+    /// Get the per-era unstake limit in basis points of the pool (zero means no limit)
+    pub fn unstake_limit_bps(&self) -> u16 {
+        self.unstake_limit_bps.get_or_default()
+    }
+
+    /// Set the per-era unstake limit (admin only); zero disables it
+    pub fn set_unstake_limit_bps(&mut self, limit_bps: u16) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_unstake_limit_bps(limit_bps)?;
+        self.check_invariants()
+    }
+
+    fn _set_unstake_limit_bps(&mut self, limit_bps: u16) -> Result<(), Error> {
+        if limit_bps > BPS_DENOMINATOR {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        let old_limit_bps = self.unstake_limit_bps();
+        self.unstake_limit_bps.set(limit_bps);
+        self.emit_config_changed("unstake_limit_bps", U256::from(old_limit_bps), U256::from(limit_bps));
+        Ok(())
+    }
+
+    /// Set the large-withdrawal review policy (admin only); a zero threshold disables it
+    ///
+    /// Requests for more than `threshold_bps` of the pooled CSPR are held for
+    /// `window`, during which a guardian can flag them to wait `flag_delay`
+    /// from the flag. Instant unstakes above the threshold are refused.
+    pub fn set_withdrawal_review(&mut self, threshold_bps: u16, window: u64, flag_delay: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self.review.configure(threshold_bps, window, flag_delay)?;
+        self.check_invariants()
+    }
+
+    /// Get the large-withdrawal review threshold, window and flag delay
+    pub fn withdrawal_review(&self) -> (u16, u64, u64) {
+        (self.review.threshold_bps(), self.review.window(), self.review.flag_delay())
+    }
+
+    /// Flag a withdrawal request under review for delayed processing (guardian only)
+    pub fn flag_withdrawal(&mut self, id: u64) -> Result<(), Error> {
+        self.require_role(Role::Guardian)?;
+        let until = self.review.flag(id)?;
+        let claimable_at = self.withdrawals.delay(id, until)?;
+        
+        self.env().emit_event(WithdrawalFlagged {
+            id,
+            guardian: self.env().caller(),
+            claimable_at,
+        });
+        self.check_invariants()
+    }
+
+    /// Get the block time a withdrawal request's review window ends, if it was reviewed
+    pub fn review_ends_at(&self, id: u64) -> Option<u64> {
+        self.review.review_ends_at(id)
+    }
+
+    /// Check whether a guardian flagged a withdrawal request
+    pub fn withdrawal_flagged(&self, id: u64) -> bool {
+        self.review.is_flagged(id)
+    }
+
+    /// Get the minimum time between staking and unstaking, in milliseconds (zero means disabled)
+    pub fn stake_cooldown(&self) -> u64 {
+        self.stake_cooldown.get_or_default()
+    }
+
+    /// Set the minimum time between staking and unstaking (admin only); zero disables it
+    pub fn set_stake_cooldown(&mut self, cooldown: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_stake_cooldown(cooldown);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    fn _set_stake_cooldown(&mut self, cooldown: u64) {
+        let old_cooldown = self.stake_cooldown();
+        self.stake_cooldown.set(cooldown);
+        self.emit_config_changed("stake_cooldown", U256::from(old_cooldown), U256::from(cooldown));
+    }
+
+    /// Get the time left before `account`'s cooling stCSPR can be unstaked, in milliseconds
+    pub fn stake_cooldown_remaining(&self, account: Address) -> u64 {
+        let cooldown = self.stake_cooldown();
+        let staked_at = match self.last_stake_time.get(&account) {
+            Some(staked_at) if cooldown > 0 => staked_at,
+            _ => return 0,
+        };
+        staked_at
+            .saturating_add(cooldown)
+            .saturating_sub(self.env().block_time())
+    }
+
+    /// Get the stCSPR `account` cannot unstake yet because of the stake cooldown
+    pub fn cooling_balance(&self, account: Address) -> U256 {
+        if self.stake_cooldown_remaining(account) == 0 {
+            return U256::zero();
+        }
+        let balance = self.balances.get(&account).unwrap_or_default();
+        self.cooling_shares.get(&account).unwrap_or_default().min(balance)
+    }
+
+    /// Get the number of the current era
+    pub fn current_era(&self) -> u64 {
+        self.eras.era_at(self.env().block_time())
+    }
+
+    /// Get the length of an era in milliseconds
+    pub fn era_length(&self) -> u64 {
+        self.eras.era_length()
+    }
+
+    /// Get the auction unbonding delay in eras
+    pub fn unbonding_delay_eras(&self) -> u64 {
+        self.eras.unbonding_delay()
+    }
+
+    /// Set the era length, the auction unbonding delay and a known era (operator only)
+    ///
+    /// `anchor_era` is the id of the era that started at `anchor_timestamp`, as read
+    /// from the chain. Era buckets used by the unstake rate limit follow the new
+    /// numbering from the next call.
+    pub fn set_era_config(
+        &mut self,
+        era_length: u64,
+        unbonding_delay: u64,
+        anchor_era: u64,
+        anchor_timestamp: u64,
+    ) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        self.eras.set_config(era_length, unbonding_delay, anchor_era, anchor_timestamp)?;
+        self.check_invariants()
+    }
+
+    /// Estimate when a withdrawal request for `amount` stCSPR made now could be claimed
+    ///
+    /// The request itself becomes claimable after the unbonding period. If the
+    /// liquid CSPR cannot cover it, the CSPR first has to be undelegated, which
+    /// takes until the end of the auction unbonding delay. Returns a block time.
+    pub fn estimate_withdrawal_time(&self, amount: U256) -> u64 {
+        let now = self.env().block_time();
+        let claimable_at = now.saturating_add(self.withdrawals.unbonding_period());
+        if motes::to_motes(self.preview_unstake(amount)) <= self.current_buffer() {
+            return claimable_at;
+        }
+        claimable_at.max(self.eras.unbonded_at(now))
+    }
+
+    /// Get the CSPR unstaked or queued for withdrawal in an era that has started
+    pub fn unstaked_in_era(&self, era: u64) -> Result<U256, Error> {
+        if era > self.current_era() {
+            return Err(Error::InvalidEra);
+        }
+        Ok(self.unstaked_per_era.get(&era).unwrap_or_default())
+    }
+
+    /// Get the CSPR that can still be unstaked in the current era
+    ///
+    /// The cap is measured against the pool as it was before this era's unstakes,
+    /// so earlier exits in the era do not shrink it. Returns `U256::MAX` when no
+    /// limit is set.
+    pub fn remaining_unstake_capacity(&self) -> U256 {
+        let limit_bps = self.unstake_limit_bps();
+        if limit_bps == 0 {
+            return U256::MAX;
+        }
+        
+        let unstaked = self.unstaked_per_era.get(&self.current_era()).unwrap_or_default();
+        let pool = self.contract_cspr_balance().saturating_add(unstaked);
+        let cap = pool * U256::from(limit_bps) / U256::from(BPS_DENOMINATOR);
+        cap.saturating_sub(unstaked)
+    }
+
+    /// Get the storage layout version of the stored state
+    pub fn version(&self) -> u32 {
+        self.version.get().unwrap_or(1)
+    }
+
+    /// Get the owner this wasm assigns when migrating version 1 state, if it was built with one
+    pub fn migration_owner(&self) -> Option<Address> {
+        MIGRATION_OWNER.and_then(|owner| owner.parse().ok())
+    }
+
+    /// Migrate stored state to `CONTRACT_VERSION` after installing a new wasm
+    ///
+    /// Version 1 -> 2: the original layout minted stCSPR 1:1 and had no access
+    /// control. Its balances are valid shares at an exchange rate of 1.0 because
+    /// `total_staked` and `contract_cspr_balance` live under the same keys. The
+    /// migration checks that the pool backs all shares and, since version 1 had
+    /// no owner, makes `migration_owner()` the owner. The caller plays no part in
+    /// that, so anyone may run this step. Later versions require the admin role.
+    pub fn migrate(&mut self) -> Result<(), Error> {
+        let from_version = self.version();
+        if from_version >= CONTRACT_VERSION {
+            return Err(Error::MigrationNotNeeded);
+        }
+        
+        if self.access.owner().is_some() {
+            self.require_role(Role::Admin)?;
+        }
+        
+        if from_version < 2 {
+            // Every 1:1 share must still be backed by its CSPR
+            if self.contract_cspr_balance() < self.total_supply() {
+                return Err(Error::InsufficientBalance);
+            }
+            if self.access.owner().is_none() {
+                let owner = self.migration_owner().ok_or(Error::MigrationOwnerNotSet)?;
+                self.access.init(owner);
+            }
+        }
+        
+        self.version.set(CONTRACT_VERSION);
+        self.env().emit_event(Migrated {
+            from_version,
+            to_version: CONTRACT_VERSION,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Get the lifetime staking statistics of an address
+    pub fn get_user_stats(&self, address: &Address) -> UserStats {
+        self.user_stats.get(address).unwrap_or(UserStats {
+            total_ever_staked: U256::zero(),
+            total_ever_unstaked: U256::zero(),
+            first_stake_timestamp: 0,
+            last_action_timestamp: 0,
+        })
+    }
+
+    /// Get an address's stCSPR balance as of a block time (in milliseconds)
+    pub fn balance_of_at(&self, address: &Address, block_time: u64) -> U256 {
+        self.checkpoints.balance_at(address, block_time)
+    }
+
+    /// Get the stCSPR total supply as of a block time (in milliseconds)
+    pub fn total_supply_at(&self, block_time: u64) -> U256 {
+        self.checkpoints.total_supply_at(block_time)
+    }
+
+    /// Create a governance proposal; returns its id
+    ///
+    /// Votes are weighted by stCSPR balances just before the proposal was created,
+    /// so tokens acquired afterwards carry no weight. The proposer must hold stCSPR.
+    pub fn create_proposal(&mut self, description_hash: [u8; 32], actions: Vec<ProposalAction>) -> Result<u64, Error> {
+        let caller = self.env().caller();
+        if actions.is_empty() {
+            return Err(Error::InvalidAmount);
+        }
+        if self.balance_of(&caller) == U256::zero() {
+            return Err(Error::NoVotingPower);
+        }
+        
+        let snapshot = self.env().block_time().saturating_sub(1);
+        let id = self.governance.create(caller, description_hash, actions, snapshot);
+        self.check_invariants()?;
+        Ok(id)
+    }
+
+    /// Vote on an open proposal with the caller's balance at its snapshot
+    pub fn vote(&mut self, proposal_id: u64, support: bool) -> Result<(), Error> {
+        let caller = self.env().caller();
+        let proposal = self.governance.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+        
+        let weight = self.balance_of_at(&caller, proposal.snapshot);
+        if weight == U256::zero() {
+            return Err(Error::NoVotingPower);
+        }
+        
+        self.governance.vote(proposal_id, caller, support, weight)?;
+        self.check_invariants()
+    }
+
+    /// Execute the actions of a proposal that passed (callable by anyone)
+    ///
+    /// While the timelock is enabled the actions are queued with an eta one delay
+    /// away, like every other admin change, and run through `execute_action`.
+    pub fn execute(&mut self, proposal_id: u64) -> Result<(), Error> {
+        let proposal = self.governance.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+        let snapshot_supply = self.total_supply_at(proposal.snapshot);
+        
+        for action in self.governance.execute(proposal_id, snapshot_supply)? {
+            self.apply_or_queue(action)?;
+        }
+        
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Apply an approved action, or queue it in the timelock if a delay is set
+    ///
+    /// Cancellations are never queued, or they could not stop what they target.
+    fn apply_or_queue(&mut self, action: ProposalAction) -> Result<(), Error> {
+        let delay = self.timelock_delay();
+        if delay > 0 && !matches!(action, ProposalAction::CancelQueuedAction(_)) {
+            let eta = self.env().block_time().saturating_add(delay);
+            let caller = self.env().caller();
+            self.timelock.queue(action, eta, caller)?;
+            return Ok(());
+        }
+        self.apply_action(action)
+    }
+
+    /// Apply an action of a passed proposal or an executed timelock entry
+    fn apply_action(&mut self, action: ProposalAction) -> Result<(), Error> {
+        match action {
+            ProposalAction::SetFeeBps(fee_bps) => self._set_fee_bps(fee_bps),
+            ProposalAction::SetTreasury(treasury) => self._set_treasury(treasury),
+            ProposalAction::AddValidator(validator) => self.delegation.add_validator(validator),
+            ProposalAction::RemoveValidator(validator) => self.delegation.remove_validator(&validator),
+            ProposalAction::SetTimelockDelay(delay) => {
+                self._set_timelock_delay(delay);
+                Ok(())
+            }
+            ProposalAction::SetMultisig(signers, threshold) => self.multisig.configure(signers, threshold),
+            ProposalAction::GrantRole(role, account) => {
+                self.access.grant(role, account, self.env().caller());
+                Ok(())
+            }
+            ProposalAction::RevokeRole(role, account) => {
+                self.access.revoke(role, account, self.env().caller());
+                Ok(())
+            }
+            ProposalAction::SetOracleCommittee(members, quorum) => self.oracle.configure(members, quorum),
+            ProposalAction::CoverLoss(amount) => self._cover_loss(amount),
+            ProposalAction::SetLimits(min_stake, max_stake_per_tx, max_total_staked) => {
+                self._set_limits(min_stake, max_stake_per_tx, max_total_staked)
+            }
+            ProposalAction::SetKeeperConfig(reward, cooldown) => {
+                self._set_keeper_config(reward, cooldown);
+                Ok(())
+            }
+            ProposalAction::SetFlashMintConfig(enabled, fee_bps) => self._set_flash_mint_config(enabled, fee_bps),
+            ProposalAction::SetBufferTargetBps(target_bps) => self._set_buffer_target_bps(target_bps),
+            ProposalAction::SetFlushThreshold(threshold) => self._set_flush_threshold(threshold),
+            ProposalAction::SetBufferPolicy(ewma_alpha_bps, withdrawal_cover_bps) => {
+                self._set_buffer_policy(ewma_alpha_bps, withdrawal_cover_bps)
+            }
+            ProposalAction::SetSwapPool(pool, registered) => self.swaps.set_pool(pool, registered),
+            ProposalAction::SetTreasurySwapLimit(limit) => {
+                self._set_treasury_swap_limit(limit);
+                Ok(())
+            }
+            ProposalAction::ApproveApplication(id) => self._approve_application(id),
+            ProposalAction::RejectApplication(id) => self._reject_application(id),
+            ProposalAction::SetApplicationBond(bond) => self._set_application_bond(bond),
+            ProposalAction::SetValidatorRebate(validator, rebate_bps) => {
+                self.delegation.set_rebate_bps(&validator, rebate_bps)
+            }
+            ProposalAction::SetIntegrator(integrator, share_bps, cap) => {
+                self.integrators.set(integrator, share_bps, cap)
+            }
+            ProposalAction::SetBridgeDailyCap(operator, daily_cap) => {
+                self.bridge.set_daily_cap(operator, daily_cap);
+                Ok(())
+            }
+            ProposalAction::SetRecovery(council, period) => {
+                let caller = self.env().caller();
+                self.recovery.configure(caller, council, period)
+            }
+            ProposalAction::SetCompoundInterval(interval) => {
+                self._set_compound_interval(interval);
+                Ok(())
+            }
+            ProposalAction::SetInsuranceShareBps(share_bps) => self._set_insurance_share_bps(share_bps),
+            ProposalAction::SetUnstakeLimitBps(limit_bps) => self._set_unstake_limit_bps(limit_bps),
+            ProposalAction::SetWithdrawalReview(threshold_bps, window, flag_delay) => {
+                self.review.configure(threshold_bps, window, flag_delay)
+            }
+            ProposalAction::StartSunset(exit_window) => self.sunset.start(exit_window),
+            ProposalAction::SetStakeCooldown(cooldown) => {
+                self._set_stake_cooldown(cooldown);
+                Ok(())
+            }
+            ProposalAction::SetGovernanceParams(voting_period, quorum_bps) => {
+                self._set_governance_params(voting_period, quorum_bps)
+            }
+            ProposalAction::RestrictTransfers => self._restrict_transfers(),
+            ProposalAction::SetTransferAllowlist(account, allowed) => {
+                self._set_transfer_allowlist(&account, allowed);
+                Ok(())
+            }
+            ProposalAction::OpenTransfers => self._open_transfers(),
+            ProposalAction::SetMerkleRoot(root) => {
+                self.merkle_drop.set_root(root);
+                Ok(())
+            }
+            ProposalAction::RevokeVesting(id) => self._revoke_vesting(id),
+            ProposalAction::CancelQueuedAction(id) => self.timelock.cancel(id),
+        }
+    }
+
+    /// Queue an admin action to run once `eta` is reached (admin only)
+    ///
+    /// `eta` must be at least the timelock delay away. Returns the action id.
+    pub fn queue_action(&mut self, action: ProposalAction, eta: u64) -> Result<u64, Error> {
+        self.require_role(Role::Admin)?;
+        self.require_multisig_disabled()?;
+        let caller = self.env().caller();
+        let id = self.timelock.queue(action, eta, caller)?;
+        self.check_invariants()?;
+        Ok(id)
+    }
+
+    /// Execute a queued action whose eta has passed (callable by anyone)
+    ///
+    /// Every queued action was authorised when it was queued, so running it is
+    /// open to anyone and an admin cannot hold back a passed proposal.
+    pub fn execute_action(&mut self, id: u64) -> Result<(), Error> {
+        let action = self.timelock.execute(id)?;
+        self.apply_action(action)?;
+        self.check_invariants()
+    }
+
+    /// Cancel a queued action (admin only, while the multisig is disabled)
+    ///
+    /// Once the multisig is enabled, a single admin cannot veto queued actions;
+    /// the signers cancel them with a `CancelQueuedAction` action instead.
+    pub fn cancel_action(&mut self, id: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_multisig_disabled()?;
+        self.timelock.cancel(id)?;
+        self.check_invariants()
+    }
+
+    /// Get a queued action by id
+    pub fn get_queued_action(&self, id: u64) -> Option<QueuedAction> {
+        self.timelock.get(id)
+    }
+
+    /// Get the timelock delay in milliseconds (zero means disabled)
+    pub fn timelock_delay(&self) -> u64 {
+        self.timelock.delay()
+    }
+
+    /// Raise the timelock delay (admin only)
+    ///
+    /// Lowering the delay while the timelock is enabled must itself be queued as
+    /// a `SetTimelockDelay` action.
+    pub fn set_timelock_delay(&mut self, delay: u64) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_multisig_disabled()?;
+        if delay < self.timelock_delay() {
+            return Err(Error::TimelockRequired);
+        }
+        self._set_timelock_delay(delay);
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    fn _set_timelock_delay(&mut self, delay: u64) {
+        let old_delay = self.timelock.delay();
+        self.timelock.set_delay(delay);
+        self.emit_config_changed("timelock_delay", U256::from(old_delay), U256::from(delay));
+    }
+
+    /// Ensure a protected setting may be changed directly by a single admin
+    fn require_direct_admin_change(&self) -> Result<(), Error> {
+        self.require_multisig_disabled()?;
+        if self.timelock_delay() > 0 {
+            return Err(Error::TimelockRequired);
+        }
+        Ok(())
+    }
+
+    /// Ensure admin actions are not reserved for the multisig
+    fn require_multisig_disabled(&self) -> Result<(), Error> {
+        if self.multisig.threshold() > 0 {
+            return Err(Error::MultisigRequired);
+        }
+        Ok(())
+    }
+
+    /// Enable the admin multisig with `threshold` of `signers` (admin only)
+    ///
+    /// Once enabled, every admin setting, role grant and timelock cancellation
+    /// needs `threshold` signer approvals, and the multisig can only be
+    /// reconfigured by a `SetMultisig` action.
+    pub fn set_multisig(&mut self, signers: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_multisig_disabled()?;
+        self.multisig.configure(signers, threshold)?;
+        self.check_invariants()
+    }
+
+    /// Propose an admin action as a signer; counts as the proposer's approval
+    pub fn propose_admin_action(&mut self, action: ProposalAction) -> Result<u64, Error> {
+        let caller = self.env().caller();
+        let id = self.multisig.propose(caller, action)?;
+        self.approve_action(id)?;
+        self.check_invariants()?;
+        Ok(id)
+    }
+
+    /// Approve a proposed admin action as a signer
+    ///
+    /// The approval that reaches the threshold executes the action, or queues it
+    /// in the timelock if a delay is set. Cancellations are never queued.
+    pub fn approve_action(&mut self, id: u64) -> Result<(), Error> {
+        let caller = self.env().caller();
+        if let Some(action) = self.multisig.approve(id, caller)? {
+            self.apply_or_queue(action)?;
+        }
+        self.check_invariants()
+    }
+
+    /// Get a proposed multisig action by id
+    pub fn get_multisig_action(&self, id: u64) -> Option<MultisigAction> {
+        self.multisig.get(id)
+    }
+
+    /// Get the multisig signers
+    pub fn multisig_signers(&self) -> Vec<Address> {
+        self.multisig.signers()
+    }
+
+    /// Get the approvals needed for admin actions (zero means the multisig is disabled)
+    pub fn multisig_threshold(&self) -> u32 {
+        self.multisig.threshold()
+    }
+
+    /// Get a proposal by id
+    pub fn get_proposal(&self, proposal_id: u64) -> Option<Proposal> {
+        self.governance.get(proposal_id)
+    }
+
+    /// Check whether an address has voted on a proposal
+    pub fn has_voted(&self, proposal_id: u64, voter: Address) -> bool {
+        self.governance.has_voted(proposal_id, &voter)
+    }
+
+    /// Get the voting period in milliseconds
+    pub fn voting_period(&self) -> u64 {
+        self.governance.voting_period()
+    }
+
+    /// Get the quorum in basis points of the snapshot supply
+    pub fn quorum_bps(&self) -> u16 {
+        self.governance.quorum_bps()
+    }
+
+    /// Set the voting period and quorum for new votes (admin only)
+    pub fn set_governance_params(&mut self, voting_period: u64, quorum_bps: u16) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self._set_governance_params(voting_period, quorum_bps)?;
+        self.check_invariants()
+    }
+
+    fn _set_governance_params(&mut self, voting_period: u64, quorum_bps: u16) -> Result<(), Error> {
+        if quorum_bps > BPS_DENOMINATOR {
+            return Err(Error::ExceedsMaximum);
+        }
+        
+        let old_voting_period = self.governance.voting_period();
+        let old_quorum_bps = self.governance.quorum_bps();
+        self.governance.set_params(voting_period, quorum_bps);
+        self.emit_config_changed("voting_period", U256::from(old_voting_period), U256::from(voting_period));
+        self.emit_config_changed("quorum_bps", U256::from(old_quorum_bps), U256::from(quorum_bps));
+        Ok(())
+    }
+
+    /// Get the number of the last reported rewards epoch
+    pub fn current_epoch(&self) -> u64 {
+        self.rewards.current_epoch()
+    }
+
+    /// Mint stCSPR shares without adding CSPR to the pool (minter role only)
+    ///
+    /// For extension contracts such as bridges that back the shares elsewhere,
+    /// e.g. by locking them on another chain. Minters are trusted not to dilute
+    /// holders; shares cannot be minted while the pool is empty.
+    pub fn mint_shares(&mut self, recipient: &Address, shares: U256) -> Result<(), Error> {
+        self.require_role(Role::Minter)?;
+        self.require_not_paused()?;
+        self.validate_amount(shares)?;
+        self.validate_address(recipient)?;
+        
+        self._mint(recipient, shares)?;
+        self.validate_state_consistency()?;
+        
+        self.env().emit_event(ExtensionMinted {
+            minter: self.env().caller(),
+            recipient: *recipient,
+            shares,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Burn stCSPR shares without removing CSPR from the pool (minter role only)
+    ///
+    /// Burning another owner's shares spends the minter's allowance. Shares still
+    /// in the stake cooldown cannot be burned, just as they cannot be unstaked.
+    pub fn burn_shares(&mut self, owner: &Address, shares: U256) -> Result<(), Error> {
+        self.require_role(Role::Minter)?;
+        self.require_not_paused()?;
+        self.validate_amount(shares)?;
+        
+        let minter = self.env().caller();
+        self.validate_unlocked_balance(owner, shares)?;
+        
+        if *owner != minter {
+            let current_allowance = self.allowance(owner, &minter);
+            self.validate_sufficient_allowance(current_allowance, shares)?;
+            self.allowances.set(&(*owner, minter), current_allowance - shares);
+        }
+        
+        self._burn(owner, shares)?;
+        self.validate_state_consistency()?;
+        
+        self.env().emit_event(ExtensionBurned {
+            minter,
+            owner: *owner,
+            shares,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Mint stCSPR returning from another chain (bridge minter role only)
+    ///
+    /// At most what was burned by `bridge_burn` can be minted back, each
+    /// `origin_tx` once, and each operator is held to its daily cap.
+    pub fn bridge_mint(&mut self, to: Address, amount: U256, origin_tx: String) -> Result<(), Error> {
+        self.require_role(Role::BridgeMinter)?;
+        self.require_not_paused()?;
+        self.validate_amount(amount)?;
+        self.validate_address(&to)?;
+        let operator = self.env().caller();
+        let day = self.env().block_time() / BRIDGE_DAY;
+        
+        // CHECKS and EFFECTS: Cap, replay and supply checks, then mint
+        let bridged_out = self.bridge.record_mint(operator, &origin_tx, amount, day)?;
+        self._mint(&to, amount)?;
+        self.validate_state_consistency()?;
+        
+        self.env().emit_event(BridgeMinted {
+            operator,
+            recipient: to,
+            amount,
+            origin_tx,
+            bridged_out,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Burn stCSPR leaving for another chain (bridge minter role only)
+    ///
+    /// The burned stCSPR keeps counting in the exchange rate as bridged-out
+    /// supply. Burning another owner's stCSPR spends the operator's allowance.
+    pub fn bridge_burn(&mut self, from: Address, amount: U256, destination: String) -> Result<(), Error> {
+        self.require_role(Role::BridgeMinter)?;
+        self.require_not_paused()?;
+        self.validate_amount(amount)?;
+        let operator = self.env().caller();
+        self.validate_unlocked_balance(&from, amount)?;
+        
+        if from != operator {
+            let current_allowance = self.allowance(&from, &operator);
+            self.validate_sufficient_allowance(current_allowance, amount)?;
+            self.allowances.set(&(from, operator), current_allowance - amount);
+        }
+        
+        let bridged_out = self.bridge.record_burn(&destination, amount)?;
+        self._burn(&from, amount)?;
+        self.validate_state_consistency()?;
+        
+        self.env().emit_event(BridgeBurned {
+            operator,
+            owner: from,
+            amount,
+            destination,
+            bridged_out,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Set the most stCSPR a bridge operator can mint per day (admin only)
+    pub fn set_bridge_daily_cap(&mut self, operator: Address, daily_cap: U256) -> Result<(), Error> {
+        self.require_role(Role::Admin)?;
+        self.require_direct_admin_change()?;
+        self.bridge.set_daily_cap(operator, daily_cap);
+        self.check_invariants()
+    }
+
+    /// Get the most stCSPR a bridge operator can mint per day
+    pub fn bridge_daily_cap(&self, operator: Address) -> U256 {
+        self.bridge.daily_cap(&operator)
+    }
+
+    /// Get the stCSPR a bridge operator has minted today
+    pub fn bridge_minted_today(&self, operator: Address) -> U256 {
+        self.bridge.minted_on(&operator, self.env().block_time() / BRIDGE_DAY)
+    }
+
+    /// Get the stCSPR burned to other chains and not yet minted back
+    pub fn bridged_out_supply(&self) -> U256 {
+        self.bridge.bridged_out()
+    }
+
+    /// Get the rewards report recorded for an epoch
+    pub fn get_reward_report(&self, epoch: u64) -> Option<RewardReport> {
+        self.rewards.report(epoch)
+    }
+
+    /// Publish an incident status code and a URI with the details (operator only)
+    ///
+    /// Codes are the `INCIDENT_*` constants in `incidents`; `INCIDENT_NONE`
+    /// with an empty URI clears the status.
+    pub fn set_incident_status(&mut self, code: u8, message_uri: String) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        let caller = self.env().caller();
+        self.incidents.set_status(caller, code, message_uri)?;
+        self.check_invariants()
+    }
+
+    /// Publish where to reach the security team, e.g. a signed security.txt URI (operator only)
+    pub fn set_security_contact(&mut self, contact: String) -> Result<(), Error> {
+        self.require_role(Role::Operator)?;
+        let caller = self.env().caller();
+        self.incidents.set_contact(caller, contact)?;
+        self.check_invariants()
+    }
+
+    /// Get the incident status, security contact and pool health in a single call
+    pub fn health_check(&self) -> HealthCheck {
+        let (incident_code, incident_message_uri, incident_updated_at) = self.incidents.status();
+        HealthCheck {
+            paused: self.paused(),
+            incident_code,
+            incident_message_uri,
+            incident_updated_at,
+            security_contact: self.incidents.contact(),
+            exchange_rate: self.exchange_rate(),
+            withdrawal_shortfall: self.get_obligations().shortfall,
+            version: self.version(),
+        }
+    }
+
+    /// Get the pool-wide statistics in a single call
+    pub fn get_protocol_stats(&self) -> ProtocolStats {
+        ProtocolStats {
+            total_pooled_cspr: self.total_pooled_cspr(),
+            total_shares: self.total_supply(),
+            exchange_rate: self.exchange_rate(),
+            current_apr_estimate: self.rewards.apr_estimate_bps(),
+            validator_count: self.delegation.validators().len() as u32,
+            pending_withdrawals: self.pending_withdrawals(),
+        }
+    }
+
+    /// Get the CSPR owed to withdrawal requests and the CSPR available to pay it
+    ///
+    /// The purse outside the insurance fund and CSPR returning from the auction
+    /// are set against all pending withdrawals, matured or not, so a shortfall
+    /// means the operator has to undelegate more before every request can be
+    /// claimed. `buffered` is what instant unstakes and delegations may spend.
+    pub fn get_obligations(&self) -> Obligations {
+        let pending_withdrawals = self.withdrawals.total_pending_motes();
+        let unbonding = self.unbonding();
+        let total_unbonding = self.total_unbonding();
+        let available = self.withdrawable_cspr().saturating_add(total_unbonding);
+        Obligations {
+            pending_withdrawals,
+            unbonding,
+            total_unbonding,
+            buffered: self.available_liquidity(),
+            shortfall: pending_withdrawals.saturating_sub(available),
+            surplus: available.saturating_sub(pending_withdrawals),
+        }
+    }
+
+    /// Get the figures the pool invariants are checked against
+    ///
+    /// `InvariantReport::check` tells which invariant, if any, is broken.
+    pub fn invariant_report(&self) -> InvariantReport {
+        let probe = U256::from(RATE_PRECISION);
+        let last_report = self.rewards.report(self.rewards.current_epoch());
+        InvariantReport {
+            total_shares: self.total_supply(),
+            pooled_cspr: self.contract_cspr_motes(),
+            purse: self.env().self_balance(),
+            delegated: self.delegation.total_delegated(),
+            unbonding: self.total_unbonding(),
+            pending_deposits: self.pending_deposits(),
+            pending_withdrawals: self.withdrawals.total_pending_motes(),
+            insurance: self.insurance.balance_motes(),
+            application_bonds: self.applications.bonds_held(),
+            unsynced_rewards: self.unsynced_rewards(),
+            fee_bps: self.fee_bps(),
+            insurance_share_bps: self.insurance.share_bps(),
+            last_rewards: last_report.as_ref().map(|report| report.amount).unwrap_or_default(),
+            last_fee: last_report.map(|report| report.fee).unwrap_or_default(),
+            round_trip_cspr: self
+                .shares_for_cspr(probe)
+                .and_then(|shares| self.cspr_for_shares(shares))
+                .unwrap_or(U256::MAX),
+            round_trip_shares: self
+                .cspr_for_shares(probe)
+                .and_then(|cspr| self.shares_for_cspr(cspr))
+                .unwrap_or(U256::MAX),
+        }
+    }
+
+    /// Gate a maintenance entry point and pay the caller's keeper reward
+    ///
+    /// Anyone may run a task once the cooldown since its last run has passed, and
+    /// is then paid the keeper reward out of the treasury's stCSPR. Operators may
+    /// also run it earlier, unpaid. The reward is skipped if the treasury is unset
+    /// or cannot cover it, so maintenance never fails for lack of fees.
+    fn run_keeper_task(&mut self, task: &str) -> Result<(), Error> {
+        let caller = self.env().caller();
+        let now = self.env().block_time();
+        let key = task.to_string();
+        let ready = match self.keeper_last_run.get(&key) {
+            Some(last_run) => now >= last_run.saturating_add(self.keeper_cooldown()),
+            None => true,
+        };
+        if !ready && !self.access.has_role(Role::Operator, &caller) {
+            return Err(Error::KeeperCooldown);
+        }
+        
+        self.keeper_last_run.set(&key, now);
+        
+        let reward = self.keeper_reward();
+        let treasury = match self.treasury.get() {
+            Some(treasury) if ready && reward > U256::zero() && treasury != caller => treasury,
+            _ => return Ok(()),
+        };
+        let treasury_balance = self.balance_of(&treasury);
+        if treasury_balance < reward {
+            return Ok(());
+        }
+        
+        let new_keeper_balance = self.safe_add(self.balance_of(&caller), reward)?;
+        self.set_balance(&treasury, treasury_balance - reward);
+        self.set_balance(&caller, new_keeper_balance);
+        
+        self.env().emit_event(Transfer {
+            sender: treasury,
+            recipient: caller,
+            amount: reward,
+        });
+        self.env().emit_event(KeeperRewarded {
+            keeper: caller,
+            task: key,
+            amount: reward,
+        });
+        Ok(())
+    }
+
+    /// Call the receiver hook of `to` after `amount` stCSPR was moved from `from`
+    fn notify_receiver(&self, to: &Address, from: &Address, amount: U256, data: Bytes) -> Result<(), Error> {
+        if !to.is_contract() {
+            return Err(Error::InvalidAddress);
+        }
+        
+        let operator = self.env().caller();
+        let accepted = TokenReceiverContractRef::new(self.env(), *to).on_token_received(operator, *from, amount, data);
+        if !accepted {
+            return Err(Error::TransferRejected);
+        }
+        Ok(())
+    }
+
+    /// Mint `shares` to `to` without adding CSPR to the pool
+    fn _mint(&mut self, to: &Address, shares: U256) -> Result<(), Error> {
+        let new_balance = self.safe_add(self.balance_of(to), shares)?;
+        let new_total_supply = self.safe_add(self.total_staked.get_or_default(), shares)?;
+        
+        self.set_balance(to, new_balance);
+        self.set_total_supply(new_total_supply);
+        
+        self.env().emit_event(Mint {
+            recipient: *to,
+            amount: shares,
+        });
+        
+        Ok(())
+    }
+
+    /// Burn `shares` from `owner` without removing CSPR from the pool
+    fn _burn(&mut self, owner: &Address, shares: U256) -> Result<(), Error> {
+        let new_balance = self.safe_sub(self.balance_of(owner), shares)?;
+        let new_total_supply = self.safe_sub(self.total_staked.get_or_default(), shares)?;
+        
+        self.set_balance(owner, new_balance);
+        self.set_total_supply(new_total_supply);
+        
+        self.env().emit_event(Burn {
+            owner: *owner,
+            amount: shares,
+        });
+        
+        Ok(())
+    }
+
+    /// Internal stake function - pools the caller's attached CSPR and mints shares to `beneficiary`
+    /// Follows checks-effects-interactions pattern for atomic execution.
+    fn _stake(&mut self, beneficiary: &Address, amount: U256) -> Result<(), Error> {
+        // CHECKS: Comprehensive input validation and state checks
+        self.lock()?;
+        self.require_not_paused()?;
+        if self.sunset.is_active() {
+            return Err(Error::SunsetActive);
+        }
+        self.validate_amount(amount)?;
+        self.validate_stake_limits(amount)?;
+        self.validate_attached_value(amount)?;
+        self.validate_state_consistency()?;
+
+        let payer = self.env().caller();
+        
+        // Deposits too small to mint a single share are rejected
+        let rate = self.exchange_rate();
+        let shares = self.shares_for_cspr(amount)?;
+        if shares == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+        
+        // Get current state values
+        let current_balance = self.balances.get(beneficiary).unwrap_or_default();
+        let current_total_supply = self.total_staked.get_or_default();
+        let current_contract_balance = self.contract_cspr_balance();
+        let cooling = self.cooling_balance(*beneficiary);
+        
+        // Pre-calculate all new values to ensure they're valid before any state changes
+        let new_balance = self.safe_add(current_balance, shares)?;
+        let new_total_supply = self.safe_add(current_total_supply, shares)?;
+        let new_contract_balance = self.safe_add(current_contract_balance, amount)?;
+        let deposit = motes::to_motes(amount);
+        let new_pending = self
+            .pending_deposits()
+            .checked_add(deposit)
+            .ok_or(Error::ArithmeticOverflow)?;
+        
+        // EFFECTS: Update all state variables atomically
+        // All state changes happen together - if any fail, the entire transaction reverts
+        self.set_balance(beneficiary, new_balance);
+        self.set_total_supply(new_total_supply);
+        self.set_pooled_cspr(new_contract_balance);
+        self.pending_deposits.set(new_pending);
+        self.record_user_stats(beneficiary, amount, U256::zero())?;
+        if self.stake_cooldown() > 0 {
+            self.cooling_shares.set(beneficiary, self.safe_add(cooling, shares)?);
+            self.last_stake_time.set(beneficiary, self.env().block_time());
+        }
+        
+        // Validate state consistency after changes
+        self.validate_state_consistency()?;
+        
+        // INTERACTIONS: External effects (events) happen last
+        let timestamp = self.env().block_time();
+        self.env().emit_event(StakeEvent {
+            user: *beneficiary,
+            payer,
+            cspr_amount: amount,
+            stcspr_minted: shares,
+            timestamp,
+            rate,
+        });
+        
+        self.env().emit_event(Mint {
+            recipient: *beneficiary,
+            amount: shares,
+        });
+        
+        self.env().emit_event(DepositBuffered {
+            staker: payer,
+            amount: deposit,
+            pending: new_pending,
+        });
+        
+        self.unlock();
+        Ok(())
+    }
+
+    /// Internal unstake function - burns the owner's stCSPR and pays the CSPR out to `recipient`
+    /// Follows checks-effects-interactions pattern for atomic execution.
+    fn _unstake(&mut self, owner: &Address, recipient: &Address, amount: U256) -> Result<(), Error> {
+        // CHECKS: Validate the burn and that the payout is covered by liquid CSPR
+        self.require_feature(FEATURE_INSTANT_UNSTAKE)?;
+        self.lock()?;
+        let cspr_amount = self.validate_burn(owner, amount)?;
+        if self.review.requires_review(cspr_amount, self.contract_cspr_balance()) {
+            return Err(Error::ReviewRequired);
+        }
+        let payout = motes::to_motes(cspr_amount);
+        if self.available_liquidity() < payout {
+            return Err(Error::InsufficientLiquidity);
+        }
+        
+        // EFFECTS: Burn the shares and release the CSPR from the pool. The payout
+        // leaves the buffer, so deposits it spent are no longer waiting to be flushed
+        self._burn_shares(owner, recipient, amount, cspr_amount)?;
+        let pending = self.pending_deposits();
+        self.pending_deposits.set(pending - pending.min(payout));
+        
+        // INTERACTIONS: Pay out CSPR
+        self.env().transfer_tokens(recipient, &payout);
+        
+        self.unlock();
+        Ok(())
+    }
+
+    /// Internal request_unstake function - burns the owner's stCSPR into a withdrawal request
+    fn _request_unstake(&mut self, owner: &Address, amount: U256) -> Result<u64, Error> {
+        // CHECKS: Validate the burn
+        self.lock()?;
+        let cspr_amount = self.validate_burn(owner, amount)?;
+        let reviewed = self.review.requires_review(cspr_amount, self.contract_cspr_balance());
+        
+        // EFFECTS: Burn the shares and queue the CSPR owed, holding large requests for review
+        self._burn_shares(owner, owner, amount, cspr_amount)?;
+        let id = self.withdrawals.create(*owner, cspr_amount, amount)?;
+        self.batches.enqueue(id);
+        if reviewed {
+            let review_ends_at = self.review.open(id, cspr_amount);
+            self.withdrawals.delay(id, review_ends_at)?;
+        }
+        
+        self.unlock();
+        Ok(id)
+    }
+
+    /// Validate that `owner` can burn `shares` and return the CSPR they are worth
+    fn validate_burn(&self, owner: &Address, shares: U256) -> Result<U256, Error> {
+        self.require_not_paused()?;
+        self.validate_amount(shares)?;
+        self.validate_state_consistency()?;
+        
+        self.validate_unlocked_balance(owner, shares)?;
+        
+        // Shares too small to be worth a single mote are rejected
+        let cspr_amount = self.cspr_for_shares(shares)?;
+        if cspr_amount == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+        if cspr_amount > self.remaining_unstake_capacity() {
+            return Err(Error::UnstakeRateLimited);
+        }
+        
+        Ok(cspr_amount)
+    }
+
+    /// Check that `owner` holds `shares` outside the stake cooldown
+    fn validate_unlocked_balance(&self, owner: &Address, shares: U256) -> Result<(), Error> {
+        let current_balance = self.balances.get(owner).unwrap_or_default();
+        self.validate_sufficient_balance(current_balance, shares)?;
+        if shares > current_balance.saturating_sub(self.cooling_balance(*owner)) {
+            return Err(Error::CooldownActive);
+        }
+        Ok(())
+    }
+
+    /// Burn `shares` from `owner` and remove `cspr_amount` from the pool
+    fn _burn_shares(&mut self, owner: &Address, recipient: &Address, shares: U256, cspr_amount: U256) -> Result<(), Error> {
+        let rate = self.exchange_rate();
+        let current_balance = self.balances.get(owner).unwrap_or_default();
+        let current_total_supply = self.total_staked.get_or_default();
+        let current_contract_balance = self.contract_cspr_balance();
+        
+        // Pre-calculate all new values to ensure they're valid before any state changes
+        let new_balance = self.safe_sub(current_balance, shares)?;
+        let new_total_supply = self.safe_sub(current_total_supply, shares)?;
+        let new_contract_balance = self.safe_sub(current_contract_balance, cspr_amount)?;
+        let era = self.current_era();
+        let unstaked_this_era = self.safe_add(self.unstaked_per_era.get(&era).unwrap_or_default(), cspr_amount)?;
+        
+        // All state changes happen together - if any fail, the entire transaction reverts
+        self.set_balance(owner, new_balance);
+        self.set_total_supply(new_total_supply);
+        self.set_pooled_cspr(new_contract_balance);
+        self.unstaked_per_era.set(&era, unstaked_this_era);
+        self.record_user_stats(owner, U256::zero(), cspr_amount)?;
+        
+        // Validate state consistency after changes
+        self.validate_state_consistency()?;
+        
+        let timestamp = self.env().block_time();
+        self.env().emit_event(UnstakeEvent {
+            user: *owner,
+            recipient: *recipient,
+            stcspr_burned: shares,
+            cspr_returned: cspr_amount,
+            timestamp,
+            rate,
+        });
+        
+        self.env().emit_event(Burn {
+            owner: *owner,
+            amount: shares,
+        });
+        
+        Ok(())
+    }
+
+    /// Write a balance, record its checkpoint and follow it for the holder's referrer
+    fn set_balance(&mut self, address: &Address, amount: U256) {
+        let old_amount = self.balance_of(address);
+        self.integrators.track_balance(address, old_amount, amount);
+        self.balances.set(address, amount);
+        self.checkpoints.record_balance(address, amount);
+    }
+
+    /// Write the total supply and record its checkpoint
+    fn set_total_supply(&mut self, amount: U256) {
+        self.total_staked.set(amount);
+        self.checkpoints.record_total_supply(amount);
+    }
+
+    /// Add a stake or unstake to an address's lifetime statistics
+    fn record_user_stats(&mut self, user: &Address, staked: U256, unstaked: U256) -> Result<(), Error> {
+        let now = self.env().block_time();
+        let mut stats = self.get_user_stats(user);
+        
+        stats.total_ever_staked = self.safe_add(stats.total_ever_staked, staked)?;
+        stats.total_ever_unstaked = self.safe_add(stats.total_ever_unstaked, unstaked)?;
+        if stats.first_stake_timestamp == 0 && staked > U256::zero() {
+            stats.first_stake_timestamp = now;
+        }
+        stats.last_action_timestamp = now;
+        
+        self.user_stats.set(user, stats);
+        Ok(())
+    }
+
+    /// CSPR in the contract purse outside the insurance fund and application
+    /// bonds, which pays withdrawal requests
+    fn withdrawable_cspr(&self) -> U512 {
+        self.env()
+            .self_balance()
+            .saturating_sub(self.insurance.balance_motes())
+            .saturating_sub(self.applications.bonds_held())
+    }
+
+    /// CSPR free for instant unstakes and delegation: the purse outside the
+    /// insurance fund, less what is reserved for withdrawal requests
+    fn available_liquidity(&self) -> U512 {
+        self.withdrawable_cspr()
+            .saturating_sub(self.withdrawals.total_pending_motes())
+    }
+
+    /// Delegate CSPR that is free to leave the purse to a validator
+    fn _delegate(&mut self, validator: PublicKey, amount: U512) -> Result<(), Error> {
+        if self.available_liquidity() < amount {
+            return Err(Error::InsufficientLiquidity);
+        }
+        self.delegation.delegate(validator, amount)
+    }
+
+    /// Undelegate from a validator and record the CSPR under the era it returns in
+    fn _undelegate(&mut self, validator: PublicKey, amount: U512) -> Result<(), Error> {
+        self.delegation.undelegate(validator, amount)?;
+        self.track_unbonding(amount);
+        Ok(())
+    }
+
+    /// Record `amount` as unbonding until the era the auction returns it in
+    fn track_unbonding(&mut self, amount: U512) {
+        // Eras whose CSPR is back in the purse are dropped, keeping the list short
+        let current_era = self.current_era();
+        let return_era = self.eras.era_at(self.eras.unbonded_at(self.env().block_time()));
+        let mut eras = self.unbonding_eras.get_or_default();
+        for era in eras.iter().filter(|era| **era <= current_era) {
+            self.unbonding_per_era.set(era, U512::zero());
+        }
+        eras.retain(|era| *era > current_era);
+        if !eras.contains(&return_era) {
+            eras.push(return_era);
+        }
+        let unbonding = self.unbonding_per_era.get(&return_era).unwrap_or_default();
+        self.unbonding_per_era.set(&return_era, unbonding.saturating_add(amount));
+        self.unbonding_eras.set(eras);
+        self.batches.record(return_era, amount);
+    }
+    
+    /// CSPR still unbonding at the auction by the era it returns in, oldest first
+    fn unbonding(&self) -> Vec<(u64, U512)> {
+        let current_era = self.current_era();
+        self.unbonding_eras
+            .get_or_default()
+            .into_iter()
+            .filter(|era| *era > current_era)
+            .map(|era| (era, self.unbonding_per_era.get(&era).unwrap_or_default()))
+            .collect()
+    }
+
+    /// CSPR still unbonding at the auction across all return eras
+    fn total_unbonding(&self) -> U512 {
+        self.unbonding()
+            .iter()
+            .fold(U512::zero(), |total, (_, amount)| total.saturating_add(*amount))
+    }
+
+    /// Internal transfer function with validation - callers emit the matching CEP-18 event
+    /// Follows checks-effects-interactions pattern for atomic execution.
+    fn _transfer(&mut self, from: &Address, to: &Address, amount: U256) -> Result<(), Error> {
+        // CHECKS: Comprehensive input validation
+        self.require_not_paused()?;
+        self.validate_amount(amount)?;
+        self.validate_address(from)?;
+        self.validate_address(to)?;
+        
+        if from == to {
+            return Err(Error::SelfTransfer);
+        }
+        if self.transfers_restricted() && !self.is_transfer_allowlisted(to) {
+            return Err(Error::RecipientNotAllowlisted);
+        }
+        
+        // Check sender balance with proper validation
+        let from_balance = self.balances.get(from).unwrap_or_default();
+        self.validate_sufficient_balance(from_balance, amount)?;
+        
+        // Pre-calculate new balances to ensure they're valid before any state changes
+        let new_from_balance = self.safe_sub(from_balance, amount)?;
+        let to_balance = self.balances.get(to).unwrap_or_default();
+        let new_to_balance = self.safe_add(to_balance, amount)?;
+        
+        // Unlocked stCSPR leaves first; only the cooling part beyond it moves its
+        // cooldown along, so a transfer never locks the recipient's other stCSPR
+        let from_cooling = self.cooling_balance(*from);
+        let moved_cooling = amount.saturating_sub(from_balance - from_cooling);
+        let to_cooling = self.cooling_balance(*to);
+        // stCSPR escrowed by the contract (drops, vesting) is paid out unlocked
+        if moved_cooling > U256::zero() && *to == self.env().self_address() {
+            return Err(Error::CooldownActive);
+        }
+        
+        // EFFECTS: Update balances atomically
+        // Both balance updates happen together - if any fail, the entire transaction reverts
+        self.set_balance(from, new_from_balance);
+        self.set_balance(to, new_to_balance);
+        
+        if moved_cooling > U256::zero() {
+            let staked_at = self.last_stake_time.get(from).unwrap_or_default();
+            let to_staked_at = if to_cooling > U256::zero() {
+                self.last_stake_time.get(to).unwrap_or_default()
+            } else {
+                0
+            };
+            self.cooling_shares.set(from, from_cooling - moved_cooling);
+            self.cooling_shares.set(to, to_cooling + moved_cooling);
+            self.last_stake_time.set(to, staked_at.max(to_staked_at));
+        }
+        
+        Ok(())
+    }
+
+    /// Internal approve function - sets the allowance and emits the SetAllowance event
+    fn _approve(&mut self, owner: &Address, spender: &Address, amount: U256) {
+        self.allowances.set(&(*owner, *spender), amount);
+        
+        self.env().emit_event(SetAllowance {
+            owner: *owner,
+            spender: *spender,
+            allowance: amount,
+        });
+    }
+
+    /// Validate supply consistency - ensures total supply equals sum of all balances
+    /// This is a view function that performs internal consistency checks
+    pub fn validate_supply_consistency(&self) -> bool {
+        // In a real implementation, we would iterate through all balances
+        // For this simplified version, we check that total_supply equals contract_cspr_balance
+        // since we maintain a 1:1 ratio between stCSPR tokens and CSPR custody
+        let total_supply = self.total_supply();
+        let pooled = self.contract_cspr_balance();
+        
+        // Supply consistency: outstanding stCSPR shares must be backed by pooled CSPR
+        total_supply == U256::zero() || pooled > U256::zero()
+    }
+
+    /// Hand `FAUCET_AMOUNT` stCSPR from the faucet balance to the caller (testnet builds only)
+    ///
+    /// Each address can claim once per `FAUCET_PERIOD`. The stCSPR was staked and
+    /// moved into the faucet with `fund_faucet`, so claims mint nothing and leave
+    /// the pool and the exchange rate untouched. The entry point is compiled out
+    /// unless the `testnet-faucet` feature is enabled, so mainnet wasm never exposes it.
+    #[cfg(feature = "testnet-faucet")]
+    pub fn faucet_stake(&mut self) -> Result<(), Error> {
+        self.require_not_paused()?;
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+        let now = self.env().block_time();
+        
+        // CHECKS: Enforce one claim per address per period, paid from the funded balance
+        if let Some(last_claim) = self.faucet_last_claim.get(&caller) {
+            if now < last_claim.saturating_add(FAUCET_PERIOD) {
+                return Err(Error::FaucetCooldown);
+            }
+        }
+        let amount = U256::from(FAUCET_AMOUNT);
+        let faucet_balance = self.faucet_balance();
+        if faucet_balance < amount {
+            return Err(Error::InsufficientBalance);
+        }
+        
+        // EFFECTS: Pay out of the faucet balance
+        self.faucet_balance.set(faucet_balance - amount);
+        self.faucet_last_claim.set(&caller, now);
+        self._transfer(&self_address, &caller, amount)?;
+        
+        self.env().emit_event(Transfer {
+            sender: self_address,
+            recipient: caller,
+            amount,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Move `amount` of the caller's stCSPR into the faucet balance (testnet builds only)
+    #[cfg(feature = "testnet-faucet")]
+    pub fn fund_faucet(&mut self, amount: U256) -> Result<(), Error> {
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+        let new_faucet_balance = self.safe_add(self.faucet_balance(), amount)?;
+        
+        self._transfer(&caller, &self_address, amount)?;
+        self.faucet_balance.set(new_faucet_balance);
+        
+        self.env().emit_event(Transfer {
+            sender: caller,
+            recipient: self_address,
+            amount,
+        });
+        self.check_invariants()?;
+        Ok(())
+    }
+
+    /// Get the stCSPR left for faucet claims (testnet builds only)
+    #[cfg(feature = "testnet-faucet")]
+    pub fn faucet_balance(&self) -> U256 {
+        self.faucet_balance.get_or_default()
+    }
+
+    /// Test-only method to set balances directly (for testing purposes)
+    #[cfg(test)]
+    pub fn set_balance_for_testing(&mut self, address: &Address, amount: U256) {
+        self.balances.set(address, amount);
+    }
+
+    /// Test-only method to set the pooled CSPR directly (simulates rewards or losses)
+    #[cfg(test)]
+    pub fn set_total_pooled_cspr_for_testing(&mut self, amount: U256) {
+        self.set_pooled_cspr(amount);
+    }
+
+    /// Test-only method to let the auction stake run ahead of the tracked delegation,
+    /// as compounded rewards do
+    ///
+    /// The mock auction cannot grow a stake, so the attached CSPR stands in for the
+    /// rewards and the tracked delegation is lowered by the same amount instead.
+    #[cfg(test)]
+    #[odra(payable)]
+    pub fn simulate_auction_rewards_for_testing(&mut self, validator: PublicKey) {
+        let amount = self.env().attached_value();
+        self.delegation.write_off(&validator, amount).unwrap();
+    }
+
+    /// Test-only method to hold or release the reentrancy guard
+    #[cfg(test)]
+    pub fn set_locked_for_testing(&mut self, locked: bool) {
+        self.locked.set(locked);
+    }
+
+    /// Test-only method to roll the stored layout version back
+    #[cfg(test)]
+    pub fn set_version_for_testing(&mut self, version: u32) {
+        self.version.set(version);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{assert_events_since, event, event_count};
+    use odra::host::{Deployer, HostRef};
+    use proptest::prelude::*;
+
+    // Helper to deploy with the default metadata and configuration
+    fn default_init_args() -> CasperLiquidInitArgs {
+        CasperLiquidInitArgs {
+            name: None,
+            symbol: None,
+            decimals: None,
+            owner: None,
+            fee_bps: None,
+            treasury: None,
+            features: None,
+        }
+    }
+
+    // Helper to stake with the matching amount of CSPR attached, as a wallet would
+    fn stake_with_cspr(contract: &mut CasperLiquid, amount: U256) -> Result<(), Error> {
+        contract.with_tokens(U512::from(amount.as_u128())).stake(amount)
+    }
+
+    #[test]
+    fn test_contract_initialization() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        
+        // Test contract deploys with zero total supply
+        assert_eq!(contract.total_supply(), U256::zero());
+        
+        // Test metadata functions return correct values
+        assert_eq!(contract.name(), "Staked CSPR");
+        assert_eq!(contract.symbol(), "stCSPR");
+        assert_eq!(contract.decimals(), 9);
+    }
+
+    #[test]
+    fn test_initial_balances() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        // Test that initial balance is zero for any address
+        assert_eq!(contract.balance_of(&user), U256::zero());
+    }
+
+    #[test]
+    fn test_metadata_consistency() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        
+        // Test that metadata is consistent across multiple calls
+        assert_eq!(contract.name(), contract.name());
+        assert_eq!(contract.symbol(), contract.symbol());
+        assert_eq!(contract.decimals(), contract.decimals());
+        
+        // Test that decimals match CSPR (9 decimals)
+        assert_eq!(contract.decimals(), 9u8);
+    }
+
+    #[test]
+    fn test_error_code_round_trip() {
+        // Every code the contract can revert with must decode back to the same error
+        for code in 0u16..1000 {
+            if let Some(error) = Error::from_code(code) {
+                assert_eq!(error.code(), code);
+                assert!(!error.describe().is_empty());
+            }
+        }
+
+        // Unknown codes are not decoded
+        assert!(Error::from_code(0).is_none());
+        assert!(Error::from_code(11).is_none());
+        assert!(Error::from_code(u16::MAX).is_none());
+        assert_eq!(Error::from_code(2).unwrap().describe(), "Insufficient allowance");
+        
+        // The exported table agrees with the lookups and lists every variant once
+        assert_eq!(Error::TABLE.len(), 85);
+        for &(code, name, message) in Error::TABLE {
+            let error = Error::from_code(code).unwrap();
+            assert_eq!(format!("{:?}", error), name);
+            assert_eq!(error.describe(), message);
+        }
+        
+        // Codes are stable for frontends that map them
+        assert_eq!(Error::NotAuthorized.code(), 12);
+        assert_eq!(Error::UnbondingNotFinished.code(), 19);
+        assert_eq!(Error::StateInconsistency.code(), 38);
+        assert_eq!(Error::InvalidEra.code(), 39);
+    }
+
+    #[cfg(feature = "offchain")]
+    #[test]
+    fn test_offchain_types_round_trip_through_json() {
+        let test_env = odra_test::env();
+        let user = test_env.get_account(0);
+        
+        let event = StakeEvent {
+            user,
+            payer: user,
+            cspr_amount: U256::from(1_000u64),
+            stcspr_minted: U256::from(990u64),
+            timestamp: 42,
+            rate: U256::from(1_010_101_010u64),
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(serde_json::from_str::<StakeEvent>(&json).unwrap(), event);
+        
+        // Errors serialize by name, so JSON consumers do not depend on the numeric code
+        let json = serde_json::to_string(&Error::InsufficientBalance).unwrap();
+        assert_eq!(json, "\"InsufficientBalance\"");
+        assert!(matches!(serde_json::from_str::<Error>(&json).unwrap(), Error::InsufficientBalance));
+        
+        let request = withdrawals::WithdrawalRequest {
+            id: 1,
+            owner: user,
+            amount: U256::from(500u64),
+            claimable_at: 20,
+            claimed: false,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(serde_json::from_str::<withdrawals::WithdrawalRequest>(&json).unwrap(), request);
+    }
+
+    #[cfg(feature = "testnet-faucet")]
+    #[test]
+    fn test_faucet_stake_daily_cap() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        
+        // Nothing to hand out until the faucet is funded with staked stCSPR
+        test_env.set_caller(user);
+        match contract.faucet_stake().unwrap_err() {
+            Error::InsufficientBalance => {},
+            _ => panic!("Expected InsufficientBalance error"),
+        }
+        test_env.set_caller(admin);
+        stake_with_cspr(&mut contract, U256::from(FAUCET_AMOUNT * 2)).unwrap();
+        contract.fund_faucet(U256::from(FAUCET_AMOUNT * 2)).unwrap();
+        assert_eq!(contract.faucet_balance(), U256::from(FAUCET_AMOUNT * 2));
+        
+        // Claims move funded stCSPR without minting or touching the pool
+        test_env.set_caller(user);
+        assert!(contract.faucet_stake().is_ok());
+        assert_eq!(contract.balance_of(&user), U256::from(FAUCET_AMOUNT));
+        assert_eq!(contract.faucet_balance(), U256::from(FAUCET_AMOUNT));
+        assert_eq!(contract.total_supply(), U256::from(FAUCET_AMOUNT * 2));
+        assert_eq!(contract.contract_cspr_balance(), U256::from(FAUCET_AMOUNT * 2));
+        assert_eq!(contract.exchange_rate(), U256::from(RATE_PRECISION));
+        
+        // A second claim within the period is rejected
+        match contract.faucet_stake().unwrap_err() {
+            Error::FaucetCooldown => {},
+            _ => panic!("Expected FaucetCooldown error"),
+        }
+        
+        // After the period elapses the address can claim again, unless paused
+        test_env.advance_block_time(FAUCET_PERIOD);
+        test_env.set_caller(admin);
+        contract.pause().unwrap();
+        test_env.set_caller(user);
+        match contract.faucet_stake().unwrap_err() {
+            Error::ContractPaused => {},
+            _ => panic!("Expected ContractPaused error"),
+        }
+        test_env.set_caller(admin);
+        contract.unpause().unwrap();
+        test_env.set_caller(user);
+        assert!(contract.faucet_stake().is_ok());
+        assert_eq!(contract.balance_of(&user), U256::from(FAUCET_AMOUNT * 2));
+        assert_eq!(contract.faucet_balance(), U256::zero());
+    }
+
+    // Helper function to set up a contract with initial balances for testing
+    fn setup_contract_with_balances(sender_balance: u64, recipient_balance: u64) -> (odra_test::TestEnv, CasperLiquid, Address, Address) {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let sender = test_env.get_account(0);
+        let recipient = test_env.get_account(1);
+        
+        // Set balances for testing using the test helper method
+        if sender_balance > 0 {
+            contract.set_balance_for_testing(&sender, U256::from(sender_balance));
+        }
+        if recipient_balance > 0 {
+            contract.set_balance_for_testing(&recipient, U256::from(recipient_balance));
+        }
+        
+        (test_env, contract, sender, recipient)
+    }
+
+    // Feature: casper-liquid-staking, Property 4: CEP-18 Transfer Conservation
+    proptest! {
+        #[test]
+        fn test_transfer_conservation(
+            sender_balance in 1u64..1_000_000u64,
+            recipient_balance in 0u64..1_000_000u64,
+            transfer_amount in 1u64..1_000_000u64
+        ) {
+            // Only test valid transfers (amount <= sender_balance)
+            prop_assume!(transfer_amount <= sender_balance);
+            
+            let (test_env, mut contract, sender, recipient) = setup_contract_with_balances(sender_balance, recipient_balance);
+            
+            // Record initial balances and total supply
+            let initial_sender_balance = contract.balance_of(&sender);
+            let initial_recipient_balance = contract.balance_of(&recipient);
+            let initial_total_supply = contract.total_supply();
+            let initial_sum = initial_sender_balance + initial_recipient_balance;
+            
+            // Set the caller to sender for the transfer
+            test_env.set_caller(sender);
+            
+            // Perform transfer
+            let result = contract.transfer(&recipient, U256::from(transfer_amount));
+            
+            // Transfer should succeed for valid amounts
+            prop_assert!(result.is_ok());
+            
+            // Check final balances
+            let final_sender_balance = contract.balance_of(&sender);
+            let final_recipient_balance = contract.balance_of(&recipient);
+            let final_total_supply = contract.total_supply();
+            let final_sum = final_sender_balance + final_recipient_balance;
+            
+            // Property: Sum of sender and recipient balances should remain constant
+            prop_assert_eq!(initial_sum, final_sum);
+            
+            // Property: Total supply should remain unchanged
+            prop_assert_eq!(initial_total_supply, final_total_supply);
+            
+            // Property: Balances should change by exactly the transfer amount
+            prop_assert_eq!(final_sender_balance, initial_sender_balance - U256::from(transfer_amount));
+            prop_assert_eq!(final_recipient_balance, initial_recipient_balance + U256::from(transfer_amount));
+        }
+    }
+
+    // Unit tests for CEP-18 edge cases
+    
+    #[test]
+    fn test_transfer_insufficient_balance() {
+        let (test_env, mut contract, sender, recipient) = setup_contract_with_balances(100, 0);
+        test_env.set_caller(sender);
+        
+        // Try to transfer more than balance
+        let result = contract.transfer(&recipient, U256::from(101));
+        
+        // Should fail with insufficient balance error
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::InsufficientBalance => {},
+            _ => panic!("Expected InsufficientBalance error"),
+        }
+        
+        // Balances should remain unchanged
+        assert_eq!(contract.balance_of(&sender), U256::from(100));
+        assert_eq!(contract.balance_of(&recipient), U256::zero());
+    }
+
+    #[test]
+    fn test_transfer_zero_amount() {
+        let (test_env, mut contract, sender, recipient) = setup_contract_with_balances(100, 0);
+        test_env.set_caller(sender);
+        
+        // Try to transfer zero amount
+        let result = contract.transfer(&recipient, U256::zero());
+        
+        // Should fail with invalid amount error
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
+        
+        // Balances should remain unchanged
+        assert_eq!(contract.balance_of(&sender), U256::from(100));
+        assert_eq!(contract.balance_of(&recipient), U256::zero());
+    }
+
+    #[test]
+    fn test_transfer_to_self() {
+        let (test_env, mut contract, sender, _) = setup_contract_with_balances(100, 0);
+        test_env.set_caller(sender);
+        
+        // Try to transfer to self
+        let result = contract.transfer(&sender, U256::from(50));
+        
+        // Should fail with self transfer error
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::SelfTransfer => {},
+            _ => panic!("Expected SelfTransfer error"),
+        }
+        
+        // Balance should remain unchanged
+        assert_eq!(contract.balance_of(&sender), U256::from(100));
+    }
+
+    #[test]
+    fn test_restricted_transfers_launch_mode() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let partner = test_env.get_account(1);
+        let stranger = test_env.get_account(2);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        contract.restrict_transfers().unwrap();
+        contract.set_transfer_allowlist(&partner, true).unwrap();
+        
+        // Only allowlisted recipients can receive stCSPR
+        contract.transfer(&partner, U256::from(100)).unwrap();
+        match contract.transfer(&stranger, U256::from(100)).unwrap_err() {
+            Error::RecipientNotAllowlisted => {},
+            _ => panic!("Expected RecipientNotAllowlisted error"),
+        }
+        
+        // Staking is unaffected
+        test_env.set_caller(stranger);
+        stake_with_cspr(&mut contract, U256::from(50)).unwrap();
+        assert_eq!(contract.balance_of(&stranger), U256::from(50));
+        
+        // Opening transfers is permanent
+        test_env.set_caller(admin);
+        contract.open_transfers().unwrap();
+        contract.transfer(&stranger, U256::from(100)).unwrap();
+        match contract.restrict_transfers().unwrap_err() {
+            Error::TransfersAlreadyOpen => {},
+            _ => panic!("Expected TransfersAlreadyOpen error"),
+        }
+        assert!(!contract.transfers_restricted());
+    }
+
+    #[test]
+    fn test_merkle_drop_claims_once() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let alice = test_env.get_account(1);
+        let bob = test_env.get_account(2);
+        
+        // Two-leaf tree: the root hashes the sorted pair of leaves
+        let leaf_alice = contract.merkle_leaf(0, alice, U256::from(300));
+        let leaf_bob = contract.merkle_leaf(1, bob, U256::from(200));
+        let (low, high) = if leaf_alice <= leaf_bob { (leaf_alice, leaf_bob) } else { (leaf_bob, leaf_alice) };
+        let root = odra::casper_types::crypto::blake2b([low, high].concat());
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        contract.fund_merkle_drop(U256::from(500)).unwrap();
+        contract.set_merkle_root(root).unwrap();
+        assert_eq!(contract.merkle_drop_balance(), U256::from(500));
+        assert_eq!(contract.balance_of(&admin), U256::from(500));
+        
+        test_env.set_caller(alice);
+        contract.claim(0, U256::from(300), vec![leaf_bob]).unwrap();
+        assert_eq!(contract.balance_of(&alice), U256::from(300));
+        assert!(contract.is_claimed(0));
+        assert!(!contract.is_claimed(1));
+        
+        // A leaf pays out once
+        match contract.claim(0, U256::from(300), vec![leaf_bob]).unwrap_err() {
+            Error::AlreadyClaimed => {},
+            _ => panic!("Expected AlreadyClaimed error"),
+        }
+        
+        // The proof binds the account and the amount
+        test_env.set_caller(bob);
+        match contract.claim(1, U256::from(250), vec![leaf_alice]).unwrap_err() {
+            Error::InvalidMerkleProof => {},
+            _ => panic!("Expected InvalidMerkleProof error"),
+        }
+        contract.claim(1, U256::from(200), vec![leaf_alice]).unwrap();
+        assert_eq!(contract.balance_of(&bob), U256::from(200));
+        assert_eq!(contract.merkle_drop_balance(), U256::zero());
+        
+        // Only admins post roots
+        match contract.set_merkle_root([0u8; 32]).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_vesting_releases_linearly_after_cliff() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let team = test_env.get_account(1);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        let id = contract.create_vesting(team, U256::from(1_000), 1_000, 4_000, true).unwrap();
+        assert_eq!(contract.balance_of(&admin), U256::zero());
+        assert_eq!(contract.vesting_locked(), U256::from(1_000));
+        
+        // Nothing vests before the cliff
+        test_env.advance_block_time(999);
+        assert_eq!(contract.releasable(id), U256::zero());
+        test_env.set_caller(team);
+        match contract.release(id).unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
+        
+        // Linear from the start once the cliff has passed
+        test_env.advance_block_time(1_001);
+        assert_eq!(contract.releasable(id), U256::from(500));
+        assert_eq!(contract.release(id).unwrap(), U256::from(500));
+        assert_eq!(contract.balance_of(&team), U256::from(500));
+        
+        // Only the beneficiary releases, only admins revoke
+        test_env.set_caller(admin);
+        match contract.release(id).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        test_env.set_caller(team);
+        match contract.revoke_vesting(id).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        // Revoking returns the unvested part and leaves the vested part releasable
+        test_env.advance_block_time(1_000);
+        test_env.set_caller(admin);
+        contract.revoke_vesting(id).unwrap();
+        assert_eq!(contract.balance_of(&admin), U256::from(250));
+        match contract.revoke_vesting(id).unwrap_err() {
+            Error::VestingNotRevocable => {},
+            _ => panic!("Expected VestingNotRevocable error"),
+        }
+        test_env.advance_block_time(10_000);
+        test_env.set_caller(team);
+        assert_eq!(contract.release(id).unwrap(), U256::from(250));
+        assert_eq!(contract.balance_of(&team), U256::from(750));
+        assert_eq!(contract.vesting_locked(), U256::zero());
+        
+        // The cliff cannot outlast the schedule
+        match contract.create_vesting(admin, U256::from(100), 10, 5, false).unwrap_err() {
+            Error::InvalidVestingSchedule => {},
+            _ => panic!("Expected InvalidVestingSchedule error"),
+        }
+    }
+
+    #[test]
+    fn test_flash_mint_requires_repayment_with_fee() {
+        use crate::flash::mock::{MockBorrower, MockBorrowerInitArgs};
+        
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        let borrower = MockBorrower::deploy(&test_env, MockBorrowerInitArgs { accept: true });
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        
+        // Disabled until an admin turns it on
+        match contract.flash_mint(U256::from(5_000), *borrower.address(), Bytes::new()).unwrap_err() {
+            Error::FlashMintDisabled => {},
+            _ => panic!("Expected FlashMintDisabled error"),
+        }
+        contract.set_flash_mint_config(true, 100).unwrap();
+        assert_eq!(contract.flash_fee(U256::from(5_000)), U256::from(50));
+        
+        // A borrower that only holds the minted amount cannot pay the fee
+        match contract.flash_mint(U256::from(5_000), *borrower.address(), Bytes::new()).unwrap_err() {
+            Error::FlashMintNotRepaid => {},
+            _ => panic!("Expected FlashMintNotRepaid error"),
+        }
+        assert_eq!(contract.total_supply(), U256::from(1_000));
+        
+        // With the fee on hand the loan is repaid; without a treasury the fee is burned
+        contract.transfer(borrower.address(), U256::from(50)).unwrap();
+        contract.flash_mint(U256::from(5_000), *borrower.address(), Bytes::new()).unwrap();
+        assert_eq!(borrower.borrowed(), U256::from(5_000));
+        assert_eq!(borrower.last_fee(), U256::from(50));
+        assert_eq!(borrower.last_initiator(), Some(user));
+        assert_eq!(contract.balance_of(borrower.address()), U256::zero());
+        assert_eq!(contract.total_supply(), U256::from(950));
+        
+        // The minted stCSPR does not move the rate seen or published during the hook
+        let rate_before = contract.exchange_rate();
+        let feed_before = contract.rate_feed();
+        let treasury = test_env.get_account(1);
+        contract.set_treasury(treasury).unwrap();
+        contract.transfer(borrower.address(), U256::from(50)).unwrap();
+        contract.flash_mint(U256::from(5_000), *borrower.address(), Bytes::new()).unwrap();
+        assert_eq!(borrower.observed_rate(), rate_before);
+        assert_eq!(contract.rate_feed(), feed_before);
+        assert_eq!(contract.balance_of(&treasury), U256::from(50));
+        
+        // Borrowers can reject, and only contracts can borrow
+        let picky = MockBorrower::deploy(&test_env, MockBorrowerInitArgs { accept: false });
+        match contract.flash_mint(U256::from(5_000), *picky.address(), Bytes::new()).unwrap_err() {
+            Error::TransferRejected => {},
+            _ => panic!("Expected TransferRejected error"),
+        }
+        match contract.flash_mint(U256::from(5_000), user, Bytes::new()).unwrap_err() {
+            Error::InvalidAddress => {},
+            _ => panic!("Expected InvalidAddress error"),
+        }
+        
+        // The minted stCSPR cannot be queued for withdrawal during the hook
+        let mut reentrant = MockBorrower::deploy(&test_env, MockBorrowerInitArgs { accept: true });
+        reentrant.set_reenter(true);
+        match contract.flash_mint(U256::from(5_000), *reentrant.address(), Bytes::new()).unwrap_err() {
+            Error::Reentrancy => {},
+            _ => panic!("Expected Reentrancy error"),
+        }
+        assert_eq!(contract.pending_withdrawals(), U256::zero());
+    }
+
+    #[test]
+    fn test_treasury_swap_through_registered_pool() {
+        use crate::swaps::mock::{MockSwapPool, MockSwapPoolInitArgs};
+        
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let treasurer = test_env.get_account(1);
+        let treasury = test_env.get_account(2);
+        let pool = MockSwapPool::deploy(&test_env, MockSwapPoolInitArgs { price_bps: 9_900 });
+        
+        contract.set_fee_bps(1_000).unwrap();
+        contract.set_treasury(treasury).unwrap();
+        stake_with_cspr(&mut contract, U256::from(1_000_000)).unwrap();
+        contract.report_rewards(U256::from(100_000)).unwrap();
+        contract.grant_role(Role::Treasurer, treasurer).unwrap();
+        let treasury_shares = contract.balance_of(&treasury);
+        
+        // Only registered pools, and only the treasurer
+        test_env.set_caller(treasurer);
+        match contract.treasury_swap(*pool.address(), U256::from(1_000), U256::zero()).unwrap_err() {
+            Error::SwapPoolNotRegistered => {},
+            _ => panic!("Expected SwapPoolNotRegistered error"),
+        }
+        test_env.set_caller(admin);
+        match contract.set_swap_pool(treasurer, true).unwrap_err() {
+            Error::InvalidAddress => {},
+            _ => panic!("Expected InvalidAddress error"),
+        }
+        contract.set_swap_pool(*pool.address(), true).unwrap();
+        contract.set_treasury_swap_limit(U256::from(2_000)).unwrap();
+        match contract.treasury_swap(*pool.address(), U256::from(1_000), U256::zero()).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        // The pool must pay at least min_out to the treasury
+        test_env.set_caller(treasurer);
+        match contract.treasury_swap(*pool.address(), U256::from(1_000), U256::from(995)).unwrap_err() {
+            Error::SlippageExceeded => {},
+            _ => panic!("Expected SlippageExceeded error"),
+        }
+        let amount_out = contract.treasury_swap(*pool.address(), U256::from(1_000), U256::from(990)).unwrap();
+        assert_eq!(amount_out, U256::from(990));
+        assert_eq!(contract.balance_of(&treasury), treasury_shares - U256::from(1_000));
+        assert_eq!(contract.balance_of(pool.address()), U256::from(1_000));
+        assert_eq!(pool.last_recipient(), Some(treasury));
+        assert_eq!(contract.treasury_swapped(contract.current_epoch()), U256::from(1_000));
+        
+        // The epoch budget caps the stCSPR sold until the next rewards report
+        match contract.treasury_swap(*pool.address(), U256::from(1_001), U256::zero()).unwrap_err() {
+            Error::SwapLimitReached => {},
+            _ => panic!("Expected SwapLimitReached error"),
+        }
+        test_env.set_caller(admin);
+        contract.report_rewards(U256::from(100_000)).unwrap();
+        test_env.set_caller(treasurer);
+        contract.treasury_swap(*pool.address(), U256::from(2_000), U256::zero()).unwrap();
+        assert_eq!(pool.received(), U256::from(3_000));
+    }
+
+    #[test]
+    fn test_rate_feed_publishes_rate_changes() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        assert_eq!(contract.rate_feed(), None);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        test_env.advance_block_time(1_000);
+        contract.update_rate_feed();
+        let published_at = test_env.block_time();
+        assert_eq!(contract.rate_feed(), Some((U256::from(RATE_PRECISION), published_at)));
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &RateUpdated {
+                old_rate: U256::zero(),
+                new_rate: U256::from(RATE_PRECISION),
+                timestamp: published_at,
+            }
+        ));
+        
+        // An unchanged rate is not republished
+        let since = event_count(&test_env, contract.address());
+        test_env.advance_block_time(1_000);
+        contract.update_rate_feed();
+        assert_events_since(&test_env, contract.address(), since, &[]);
+        assert_eq!(contract.rate_feed(), Some((U256::from(RATE_PRECISION), published_at)));
+        
+        // Rewards publish the new rate on their own
+        contract.report_rewards(U256::from(100)).unwrap();
+        let rewarded_at = test_env.block_time();
+        assert_eq!(contract.rate_feed(), Some((U256::from(1_100_000_000u64), rewarded_at)));
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &RateUpdated {
+                old_rate: U256::from(RATE_PRECISION),
+                new_rate: U256::from(1_100_000_000u64),
+                timestamp: rewarded_at,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_rate_with_meta_reports_the_last_epoch() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        assert_eq!(
+            contract.rate_with_meta(),
+            RateWithMeta { rate: U256::from(RATE_PRECISION), updated_at: 0, epoch: 0 }
+        );
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        test_env.advance_block_time(1_000);
+        contract.report_rewards(U256::from(100)).unwrap();
+        let reported_at = test_env.block_time();
+        
+        // Later blocks do not refresh the rate; only the next report does
+        test_env.advance_block_time(5_000);
+        assert_eq!(
+            contract.rate_with_meta(),
+            RateWithMeta { rate: U256::from(1_100_000_000u64), updated_at: reported_at, epoch: 1 }
+        );
+    }
+
+    #[test]
+    fn test_custody_is_tracked_in_motes() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        assert_eq!(contract.contract_cspr_motes(), U512::from(1_000));
+        assert_eq!(contract.contract_cspr_motes(), test_env.balance_of(contract.address()));
+        assert_eq!(contract.contract_cspr_balance(), U256::from(1_000));
+        
+        // Withdrawal requests reserve their CSPR in motes as well
+        contract.request_unstake(U256::from(400)).unwrap();
+        assert_eq!(contract.contract_cspr_motes(), U512::from(600));
+        assert_eq!(contract.pending_withdrawals(), U256::from(400));
+        
+        // Thresholds beyond the token range cannot be reported as config changes
+        match contract.set_flush_threshold(U512::MAX).unwrap_err() {
+            Error::MotesOutOfRange => {},
+            _ => panic!("Expected MotesOutOfRange error"),
+        }
+    }
+
+    #[test]
+    fn test_invariant_report_identifies_broken_invariant() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        contract.report_rewards(U256::from(500)).unwrap();
+        let report = contract.invariant_report();
+        assert_eq!(report.total_shares, U256::from(1_000));
+        assert_eq!(report.pooled_cspr, U512::from(1_500));
+        assert_eq!(report.purse, U512::from(1_000));
+        assert_eq!(report.pending_deposits, U512::from(1_000));
+        assert_eq!(report.holdings(), U512::from(1_000));
+        assert_eq!(report.unsynced_rewards, U512::from(500));
+        assert!(report.round_trip_cspr <= U256::from(RATE_PRECISION));
+        assert!(report.check().is_ok());
+        
+        // Each broken invariant reports its own error
+        let mut fees = report.clone();
+        fees.last_fee = fees.last_rewards + 1;
+        match fees.check().unwrap_err() {
+            Error::InvariantFeeAccounting => {},
+            _ => panic!("Expected InvariantFeeAccounting error"),
+        }
+        let mut rounding = report.clone();
+        rounding.round_trip_shares = U256::from(RATE_PRECISION) + 1;
+        match rounding.check().unwrap_err() {
+            Error::InvariantShareRoundTrip => {},
+            _ => panic!("Expected InvariantShareRoundTrip error"),
+        }
+        
+        // Pooled CSPR that is neither held nor reported breaks custody
+        let mut custody = report.clone();
+        custody.unsynced_rewards = U512::zero();
+        match custody.check().unwrap_err() {
+            Error::InvariantCustody => {},
+            _ => panic!("Expected InvariantCustody error"),
+        }
+        contract.set_total_pooled_cspr_for_testing(U256::from(2_000));
+        match contract.invariant_report().check().unwrap_err() {
+            Error::InvariantCustody => {},
+            _ => panic!("Expected InvariantCustody error"),
+        }
+        
+        contract.set_total_pooled_cspr_for_testing(U256::zero());
+        match contract.invariant_report().check().unwrap_err() {
+            Error::StateInconsistency => {},
+            _ => panic!("Expected StateInconsistency error"),
+        }
+    }
+
+    #[test]
+    fn test_oracle_reports_need_quorum_and_fresh_nonce() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let (alice, bob, carol) = (test_env.get_account(1), test_env.get_account(2), test_env.get_account(3));
+        let members = vec![test_env.public_key(&alice), test_env.public_key(&bob), test_env.public_key(&carol)];
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        
+        // A member listed twice would inflate the committee without adding a signer
+        let repeated = vec![members[0].clone(), members[0].clone(), members[1].clone()];
+        match contract.set_oracle_committee(repeated, 3).unwrap_err() {
+            Error::InvalidAddress => {},
+            _ => panic!("Expected InvalidAddress error"),
+        }
+        contract.set_oracle_committee(members.clone(), 2).unwrap();
+        
+        // The operator alone can no longer move the rate
+        match contract.report_rewards(U256::from(100)).unwrap_err() {
+            Error::OracleReportRequired => {},
+            _ => panic!("Expected OracleReportRequired error"),
+        }
+        
+        let report = OracleReport::Rewards(U256::from(100));
+        let message = contract.oracle_report_message(report.clone(), 1);
+        let alice_signature = test_env.sign_message(&message, &alice);
+        let bob_signature = test_env.sign_message(&message, &bob);
+        
+        // One key, even submitted twice, is not a quorum
+        let single = vec![(members[0].clone(), alice_signature.clone()), (members[0].clone(), alice_signature.clone())];
+        match contract.submit_oracle_report(report.clone(), 1, single).unwrap_err() {
+            Error::OracleQuorumNotReached => {},
+            _ => panic!("Expected OracleQuorumNotReached error"),
+        }
+        
+        // A signature does not count for another member's key
+        let forged = vec![(members[0].clone(), alice_signature.clone()), (members[1].clone(), alice_signature.clone())];
+        match contract.submit_oracle_report(report.clone(), 1, forged).unwrap_err() {
+            Error::OracleQuorumNotReached => {},
+            _ => panic!("Expected OracleQuorumNotReached error"),
+        }
+        
+        // Anyone can submit a quorum-signed report
+        let signed = vec![(members[0].clone(), alice_signature), (members[1].clone(), bob_signature)];
+        test_env.set_caller(carol);
+        contract.submit_oracle_report(report.clone(), 1, signed.clone()).unwrap();
+        assert_eq!(contract.total_pooled_cspr(), U256::from(1_100));
+        assert_eq!(contract.oracle_nonce(), 1);
+        
+        // Reports cannot be replayed
+        match contract.submit_oracle_report(report, 1, signed).unwrap_err() {
+            Error::OracleNonceUsed => {},
+            _ => panic!("Expected OracleNonceUsed error"),
+        }
+    }
+
+    #[test]
+    fn test_approval_mechanism() {
+        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(100, 0);
+        test_env.set_caller(owner);
+        
+        // Initially no allowance
+        assert_eq!(contract.allowance(&owner, &spender), U256::zero());
+        
+        // Approve spender
+        let result = contract.approve(&spender, U256::from(50));
+        assert!(result.is_ok());
+        
+        // Check allowance was set
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(50));
+        
+        // Approve different amount (should overwrite)
+        let result = contract.approve(&spender, U256::from(75));
+        assert!(result.is_ok());
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(75));
+    }
+
+    #[test]
+    fn test_set_allowances_batch() {
+        let (test_env, mut contract, owner, spender1) = setup_contract_with_balances(100, 0);
+        let spender2 = test_env.get_account(2);
+        test_env.set_caller(owner);
+        
+        // All entries are applied in one call
+        let result = contract.set_allowances(vec![(spender1, U256::from(10)), (spender2, U256::from(20))]);
+        assert!(result.is_ok());
+        assert_eq!(contract.allowance(&owner, &spender1), U256::from(10));
+        assert_eq!(contract.allowance(&owner, &spender2), U256::from(20));
+        
+        // A single invalid entry rejects the whole batch
+        let result = contract.set_allowances(vec![(spender1, U256::from(50)), (owner, U256::from(5))]);
+        match result.unwrap_err() {
+            Error::SelfTransfer => {},
+            _ => panic!("Expected SelfTransfer error"),
+        }
+        assert_eq!(contract.allowance(&owner, &spender1), U256::from(10));
+    }
+
+    #[test]
+    fn test_transfer_from_success() {
+        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(100, 0);
+        let recipient = test_env.get_account(2);
+        
+        // Owner approves spender
+        test_env.set_caller(owner);
+        contract.approve(&spender, U256::from(50)).unwrap();
+        
+        // Spender transfers from owner to recipient
+        test_env.set_caller(spender);
+        let result = contract.transfer_from(&owner, &recipient, U256::from(30));
+        assert!(result.is_ok());
+        
+        // Check balances
+        assert_eq!(contract.balance_of(&owner), U256::from(70));
+        assert_eq!(contract.balance_of(&recipient), U256::from(30));
+        
+        // Check remaining allowance
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(20));
+    }
+
+    #[test]
+    fn test_allowance_usage_tracking() {
+        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(100, 0);
+        let recipient = test_env.get_account(2);
+        
+        // Owner approves spender and asks to be alerted every 25 tokens
+        test_env.set_caller(owner);
+        contract.approve(&spender, U256::from(100)).unwrap();
+        contract.set_allowance_usage_threshold(&spender, U256::from(25)).unwrap();
+        assert_eq!(contract.allowance_usage_threshold(&owner, &spender), U256::from(25));
+        
+        // Usage below the threshold raises no alert
+        test_env.set_caller(spender);
+        let since = event_count(&test_env, contract.address());
+        contract.transfer_from(&owner, &recipient, U256::from(20)).unwrap();
+        assert_events_since(&test_env, contract.address(), since, &[
+            event(TransferFrom { spender, owner, recipient, amount: U256::from(20) }),
+        ]);
+        
+        // Crossing 25 raises exactly one alert, and usage accumulates across transfers
+        let since = event_count(&test_env, contract.address());
+        contract.transfer_from(&owner, &recipient, U256::from(10)).unwrap();
+        assert_events_since(&test_env, contract.address(), since, &[
+            event(AllowanceUsageThresholdCrossed {
+                owner,
+                spender,
+                threshold: U256::from(25),
+                cumulative_usage: U256::from(30),
+            }),
+            event(TransferFrom { spender, owner, recipient, amount: U256::from(10) }),
+        ]);
+        assert_eq!(contract.allowance_usage(&owner, &spender), U256::from(30));
+        
+        // Usage still below 50 stays within the same multiple, so no further alert
+        let since = event_count(&test_env, contract.address());
+        contract.transfer_from(&owner, &recipient, U256::from(5)).unwrap();
+        assert_events_since(&test_env, contract.address(), since, &[
+            event(TransferFrom { spender, owner, recipient, amount: U256::from(5) }),
+        ]);
+        
+        // Usage survives re-approval
+        test_env.set_caller(owner);
+        contract.approve(&spender, U256::from(50)).unwrap();
+        assert_eq!(contract.allowance_usage(&owner, &spender), U256::from(35));
+        
+        // Usage is tracked per (owner, spender) pair
+        assert_eq!(contract.allowance_usage(&spender, &owner), U256::zero());
+    }
+
+    #[test]
+    fn test_transfer_from_insufficient_allowance() {
+        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(100, 0);
+        let recipient = test_env.get_account(2);
+        
+        // Owner approves spender for less than transfer amount
+        test_env.set_caller(owner);
+        contract.approve(&spender, U256::from(30)).unwrap();
+        
+        // Spender tries to transfer more than allowance
+        test_env.set_caller(spender);
+        let result = contract.transfer_from(&owner, &recipient, U256::from(50));
+        
+        // Should fail with insufficient allowance
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::InsufficientAllowance => {},
+            _ => panic!("Expected InsufficientAllowance error"),
+        }
+        
+        // Balances should remain unchanged
+        assert_eq!(contract.balance_of(&owner), U256::from(100));
+        assert_eq!(contract.balance_of(&recipient), U256::zero());
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(30));
+    }
+
+    #[test]
+    fn test_transfer_from_insufficient_balance() {
+        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(50, 0);
+        let recipient = test_env.get_account(2);
+        
+        // Owner approves spender for more than balance
+        test_env.set_caller(owner);
+        contract.approve(&spender, U256::from(100)).unwrap();
+        
+        // Spender tries to transfer more than owner's balance
+        test_env.set_caller(spender);
+        let result = contract.transfer_from(&owner, &recipient, U256::from(75));
+        
+        // Should fail with insufficient balance
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::InsufficientBalance => {},
+            _ => panic!("Expected InsufficientBalance error"),
+        }
+        
+        // Balances and allowance should remain unchanged
+        assert_eq!(contract.balance_of(&owner), U256::from(50));
+        assert_eq!(contract.balance_of(&recipient), U256::zero());
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(100));
+    }
+
+    // Feature: casper-liquid-staking, Property 1: Stake/Unstake Round Trip Consistency (Complete)
+    proptest! {
+        #[test]
+        fn test_stake_unstake_round_trip_consistency(
+            stake_amount in 1u64..1_000_000u64
+        ) {
+            let test_env = odra_test::env();
+            let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+            let user = test_env.get_account(0);
+            
+            // Set caller to user
+            test_env.set_caller(user);
+            
+            // Record initial state
+            let initial_balance = contract.balance_of(&user);
+            let initial_total_supply = contract.total_supply();
+            let initial_contract_balance = contract.contract_cspr_balance();
+            
+            // Perform stake operation
+            let stake_result = stake_with_cspr(&mut contract, U256::from(stake_amount));
+            prop_assert!(stake_result.is_ok());
+            
+            // Record state after staking
+            let after_stake_balance = contract.balance_of(&user);
+            let after_stake_total_supply = contract.total_supply();
+            let after_stake_contract_balance = contract.contract_cspr_balance();
+            
+            // Verify staking worked correctly
+            prop_assert_eq!(after_stake_balance, initial_balance + U256::from(stake_amount));
+            prop_assert_eq!(after_stake_total_supply, initial_total_supply + U256::from(stake_amount));
+            prop_assert_eq!(after_stake_contract_balance, initial_contract_balance + U256::from(stake_amount));
+            
+            // Now unstake the same amount
+            let unstake_result = contract.unstake(U256::from(stake_amount));
+            prop_assert!(unstake_result.is_ok());
+            
+            // Record final state
+            let final_balance = contract.balance_of(&user);
+            let final_total_supply = contract.total_supply();
+            let final_contract_balance = contract.contract_cspr_balance();
+            
+            // Property: Round trip should return to original state
+            prop_assert_eq!(final_balance, initial_balance);
+            prop_assert_eq!(final_total_supply, initial_total_supply);
+            prop_assert_eq!(final_contract_balance, initial_contract_balance);
+            
+            // Property: Stake then unstake should be identity operation
+            prop_assert_eq!(final_balance, initial_balance);
+            prop_assert_eq!(final_total_supply, initial_total_supply);
+        }
+    }
+
+    // Unit tests for stake function edge cases
+    
+    #[test]
+    fn test_stake_zero_amount() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        test_env.set_caller(user);
+        
+        // Try to stake zero amount
+        let result = stake_with_cspr(&mut contract, U256::zero());
+        
+        // Should fail with invalid amount error
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
+        
+        // Balance and total supply should remain unchanged
+        assert_eq!(contract.balance_of(&user), U256::zero());
+        assert_eq!(contract.total_supply(), U256::zero());
+    }
+
+    #[test]
+    fn test_stake_requires_matching_cspr() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        test_env.set_caller(user);
+        
+        // Attaching less CSPR than requested is rejected
+        let result = contract.with_tokens(U512::from(50)).stake(U256::from(100));
+        match result.unwrap_err() {
+            Error::AttachedValueMismatch => {},
+            _ => panic!("Expected AttachedValueMismatch error"),
+        }
+        
+        // Staking without attaching CSPR is rejected
+        assert!(contract.stake(U256::from(100)).is_err());
+        assert_eq!(contract.balance_of(&user), U256::zero());
+        
+        // With matching CSPR attached the motes move into the contract purse
+        assert!(stake_with_cspr(&mut contract, U256::from(100)).is_ok());
+        assert_eq!(contract.balance_of(&user), U256::from(100));
+        assert_eq!(test_env.balance_of(contract.address()), U512::from(100));
+    }
+
+    #[test]
+    fn test_exchange_rate_views() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        // Empty pool starts at 1:1
+        assert_eq!(contract.exchange_rate(), U256::from(RATE_PRECISION));
+        assert_eq!(contract.preview_stake(U256::from(100)), U256::from(100));
+        assert_eq!(contract.preview_unstake(U256::from(100)), U256::from(100));
+        
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        
+        // Simulate rewards doubling the pool
+        contract.set_total_pooled_cspr_for_testing(U256::from(200));
+        assert_eq!(contract.exchange_rate(), U256::from(2 * RATE_PRECISION));
+        assert_eq!(contract.preview_stake(U256::from(100)), U256::from(50));
+        assert_eq!(contract.preview_unstake(U256::from(50)), U256::from(100));
+    }
+
+    #[test]
+    fn test_stake_and_unstake_at_exchange_rate() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user1 = test_env.get_account(0);
+        let user2 = test_env.get_account(1);
+        
+        test_env.set_caller(user1);
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        contract.report_rewards(U256::from(100)).unwrap();
+        
+        // Later stakers get fewer shares for the same CSPR
+        test_env.set_caller(user2);
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        assert_eq!(contract.balance_of(&user2), U256::from(50));
+        assert_eq!(contract.total_supply(), U256::from(150));
+        assert_eq!(contract.total_pooled_cspr(), U256::from(300));
+        
+        // Burning shares returns their proportional CSPR
+        let user2_cspr_before = test_env.balance_of(&user2);
+        contract.unstake(U256::from(50)).unwrap();
+        assert_eq!(test_env.balance_of(&user2), user2_cspr_before + U512::from(100));
+        assert_eq!(contract.total_supply(), U256::from(100));
+        assert_eq!(contract.total_pooled_cspr(), U256::from(200));
+        
+        // Amounts that round down to zero are rejected
+        match stake_with_cspr(&mut contract, U256::from(1)).unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
+    }
+
+    #[test]
+    fn test_report_rewards_appreciates_rate() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        
+        // Rewards cannot be reported before anyone has staked
+        assert!(contract.report_rewards(U256::from(10)).is_err());
+        
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        
+        // Only the operator can report rewards
+        match contract.report_rewards(U256::from(100)).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(operator);
+        contract.report_rewards(U256::from(100)).unwrap();
+        
+        // Shares are unchanged but each one is worth more CSPR
+        assert_eq!(contract.total_supply(), U256::from(1_000));
+        assert_eq!(contract.total_pooled_cspr(), U256::from(1_100));
+        assert_eq!(contract.exchange_rate(), U256::from(1_100_000_000u64));
+        assert_eq!(contract.preview_unstake(U256::from(1_000)), U256::from(1_100));
+        
+        // The report is kept in the history
+        assert_eq!(contract.current_epoch(), 1);
+        let report = contract.get_reward_report(1).unwrap();
+        assert_eq!(report.amount, U256::from(100));
+        assert_eq!(report.exchange_rate, U256::from(1_100_000_000u64));
+        assert!(contract.get_reward_report(2).is_none());
+    }
+
+    #[test]
+    fn test_incident_status_surfaces_in_health_check() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(1);
+        
+        let health = contract.health_check();
+        assert_eq!(health.incident_code, incidents::INCIDENT_NONE);
+        assert!(health.security_contact.is_empty());
+        assert!(!health.paused);
+        
+        contract.set_security_contact("https://casperliquid.io/.well-known/security.txt".to_string()).unwrap();
+        contract
+            .set_incident_status(incidents::INCIDENT_DEGRADED, "https://status.casperliquid.io/1".to_string())
+            .unwrap();
+        contract.pause().unwrap();
+        
+        let health = contract.health_check();
+        assert_eq!(health.incident_code, incidents::INCIDENT_DEGRADED);
+        assert_eq!(health.incident_message_uri, "https://status.casperliquid.io/1");
+        assert_eq!(health.incident_updated_at, test_env.block_time());
+        assert_eq!(health.security_contact, "https://casperliquid.io/.well-known/security.txt");
+        assert!(health.paused);
+        
+        // Incidents need a message, codes are bounded, and only operators publish
+        match contract.set_incident_status(incidents::INCIDENT_HALTED, String::new()).unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
+        match contract.set_incident_status(incidents::INCIDENT_RESOLVED + 1, "uri".to_string()).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
+        }
+        test_env.set_caller(user);
+        match contract.set_incident_status(incidents::INCIDENT_NONE, String::new()).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_get_protocol_stats() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        
+        let stats = contract.get_protocol_stats();
+        assert_eq!(stats.total_pooled_cspr, U256::zero());
+        assert_eq!(stats.exchange_rate, U256::from(RATE_PRECISION));
+        assert_eq!(stats.current_apr_estimate, U256::zero());
+        assert_eq!(stats.validator_count, 0);
+        
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        contract.request_unstake(U256::from(100)).unwrap();
+        
+        test_env.set_caller(operator);
+        contract.add_validator(test_env.public_key(&test_env.get_account(5))).unwrap();
+        contract.report_rewards(U256::from(90)).unwrap();
+        
+        // One report is not enough to estimate a return
+        assert_eq!(contract.get_protocol_stats().current_apr_estimate, U256::zero());
+        
+        // 10% growth over a tenth of a year annualizes to 100%
+        test_env.advance_block_time(rewards::YEAR_MILLIS / 10);
+        contract.report_rewards(U256::from(99)).unwrap();
+        
+        let stats = contract.get_protocol_stats();
+        assert_eq!(stats.total_pooled_cspr, U256::from(1_089));
+        assert_eq!(stats.total_shares, U256::from(900));
+        assert_eq!(stats.exchange_rate, U256::from(1_210_000_000u64));
+        assert_eq!(stats.current_apr_estimate, U256::from(10_000));
+        assert_eq!(stats.validator_count, 1);
+        assert_eq!(stats.pending_withdrawals, U256::from(100));
+    }
+
+    #[test]
+    fn test_protocol_fee_on_rewards() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let treasury = test_env.get_account(2);
+        
+        // Fee is bounded and operator-only
+        match contract.set_fee_bps(MAX_FEE_BPS + 1).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
+        }
+        test_env.set_caller(user);
+        match contract.set_fee_bps(1_000).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(operator);
+        contract.set_fee_bps(1_000).unwrap(); // 10%
+        contract.set_treasury(treasury).unwrap();
+        assert_eq!(contract.fee_bps(), 1_000);
+        assert_eq!(contract.treasury(), Some(treasury));
+        
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000_000)).unwrap();
+        
+        test_env.set_caller(operator);
+        contract.report_rewards(U256::from(100_000)).unwrap();
+        
+        // Treasury shares are worth the 10% fee (rounded down), holders keep the rest
+        let treasury_shares = contract.balance_of(&treasury);
+        let treasury_value = contract.preview_unstake(treasury_shares);
+        assert!(treasury_value >= U256::from(9_999) && treasury_value <= U256::from(10_000));
+        assert_eq!(contract.preview_unstake(U256::from(1_000_000)), U256::from(1_090_000));
+        assert_eq!(contract.get_reward_report(1).unwrap().fee, U256::from(10_000));
+    }
+
+    #[test]
+    fn test_keeper_reward_for_maintenance() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let keeper = test_env.get_account(1);
+        let treasury = test_env.get_account(2);
+        let validator = test_env.get_validator(0);
+        
+        contract.set_fee_bps(1_000).unwrap();
+        contract.set_treasury(treasury).unwrap();
+        contract.add_validator(validator.clone()).unwrap();
+        contract.set_validator_weight(validator, 1).unwrap();
+        contract.set_flush_threshold(U512::one()).unwrap();
+        stake_with_cspr(&mut contract, U256::from(1_000_000)).unwrap();
+        contract.report_rewards(U256::from(100_000)).unwrap();
+        contract.set_keeper_config(U256::from(100), 1_000).unwrap();
+        let treasury_shares = contract.balance_of(&treasury);
+        
+        // Anyone can run a maintenance task and is paid from the treasury's fees
+        test_env.set_caller(keeper);
+        contract.flush_deposits().unwrap();
+        assert_eq!(contract.balance_of(&keeper), U256::from(100));
+        assert_eq!(contract.balance_of(&treasury), treasury_shares - U256::from(100));
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &KeeperRewarded {
+                keeper,
+                task: "flush_deposits".to_string(),
+                amount: U256::from(100),
+            }
+        ));
+        
+        // Repeating the task within the cooldown is rejected
+        test_env.set_caller(operator);
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        test_env.set_caller(keeper);
+        match contract.flush_deposits().unwrap_err() {
+            Error::KeeperCooldown => {},
+            _ => panic!("Expected KeeperCooldown error"),
+        }
+        
+        // Operators can still run it, but are not paid before the cooldown ends
+        test_env.set_caller(operator);
+        let operator_shares = contract.balance_of(&operator);
+        contract.flush_deposits().unwrap();
+        assert_eq!(contract.balance_of(&operator), operator_shares);
+        
+        test_env.advance_block_time(1_000);
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        test_env.set_caller(keeper);
+        contract.flush_deposits().unwrap();
+        assert_eq!(contract.balance_of(&keeper), U256::from(200));
+        assert_eq!(contract.keeper_last_run("flush_deposits".to_string()), Some(test_env.block_time()));
+        
+        // Moving delegations between validators stays with the operator
+        match contract.rebalance().unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_compound_enforces_interval() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let validator = test_env.get_validator(0);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(800_000_000_000u64)).unwrap();
+        assert_eq!(contract.last_compound_timestamp(), None);
+        
+        // Without auction rewards the delegations and the rate are unchanged
+        contract.compound().unwrap();
+        assert_eq!(contract.last_compound_timestamp(), Some(test_env.block_time()));
+        assert_eq!(contract.delegated_amount(validator), U512::from(800_000_000_000u64));
+        assert_eq!(contract.exchange_rate(), U256::from(RATE_PRECISION));
+        
+        match contract.compound().unwrap_err() {
+            Error::CompoundTooSoon => {},
+            _ => panic!("Expected CompoundTooSoon error"),
+        }
+        
+        test_env.advance_block_time(contract.compound_interval());
+        contract.compound().unwrap();
+    }
+
+    #[test]
+    fn test_compound_does_not_recredit_reported_rewards() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let validator = test_env.get_validator(0);
+        let (alice, bob) = (test_env.get_account(1), test_env.get_account(2));
+        let members = vec![test_env.public_key(&alice), test_env.public_key(&bob)];
+        
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(800_000_000_000u64)).unwrap();
+        
+        // The operator reports the rewards the auction has compounded
+        contract.with_tokens(U512::from(10_000_000_000u64)).simulate_auction_rewards_for_testing(validator.clone());
+        contract.report_rewards(U256::from(10_000_000_000u64)).unwrap();
+        let rate = contract.exchange_rate();
+        assert_eq!(rate, U256::from(1_010_000_000u64));
+        assert_eq!(contract.unsynced_rewards(), U512::from(10_000_000_000u64));
+        
+        // Compounding the same growth only syncs the delegation
+        contract.compound().unwrap();
+        assert_eq!(contract.exchange_rate(), rate);
+        assert_eq!(contract.delegated_amount(validator.clone()), U512::from(800_000_000_000u64));
+        assert_eq!(contract.unsynced_rewards(), U512::zero());
+        
+        // Growth nobody reported is credited once
+        contract.with_tokens(U512::from(10_000_000_000u64)).simulate_auction_rewards_for_testing(validator.clone());
+        test_env.advance_block_time(contract.compound_interval());
+        contract.compound().unwrap();
+        assert_eq!(contract.exchange_rate(), U256::from(1_020_000_000u64));
+        
+        // With the oracle enabled, unreported growth waits for a signed report
+        contract.set_oracle_committee(members, 2).unwrap();
+        contract.with_tokens(U512::from(10_000_000_000u64)).simulate_auction_rewards_for_testing(validator.clone());
+        test_env.advance_block_time(contract.compound_interval());
+        contract.compound().unwrap();
+        assert_eq!(contract.exchange_rate(), U256::from(1_020_000_000u64));
+        assert_eq!(contract.delegated_amount(validator), U512::from(790_000_000_000u64));
+    }
+
+    #[test]
+    fn test_validator_rebate_paid_in_stcspr() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let validator = test_env.get_validator(0);
+        let operator = Address::from(validator.clone());
+        
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(800_000_000_000u64)).unwrap();
+        
+        // Rebates are capped
+        match contract.set_validator_rebate(validator.clone(), 1_001).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
+        }
+        contract.set_validator_rebate(validator.clone(), 500).unwrap();
+        assert_eq!(contract.validator_rebate_bps(validator.clone()), 500);
+        
+        // Compounding 10 CSPR of rewards accrues a 0.5 CSPR rebate
+        contract.with_tokens(U512::from(10_000_000_000u64)).simulate_auction_rewards_for_testing(validator.clone());
+        contract.compound().unwrap();
+        assert_eq!(contract.validator_rewards(validator.clone()), U512::from(10_000_000_000u64));
+        assert_eq!(contract.rebate_owed(validator.clone()), U512::from(500_000_000u64));
+        
+        // Only the validator's account can claim
+        match contract.claim_rebate(validator.clone()).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        // The rebate is minted at the post-mint rate
+        test_env.set_caller(operator);
+        let minted = contract.claim_rebate(validator.clone()).unwrap();
+        assert_eq!(minted, U256::from(495_294_700u64));
+        assert_eq!(contract.balance_of(operator), minted);
+        assert_eq!(contract.rebate_owed(validator.clone()), U512::zero());
+        match contract.claim_rebate(validator).unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
+    }
+
+    #[test]
+    fn test_compound_reads_stake_through_the_backend() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let validator = test_env.get_validator(0);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(800_000_000_000u64)).unwrap();
+        
+        // The backend reports the stake grown by 10 CSPR of rewards
+        crate::staking::mock::MockStaking::add_rewards(&validator, U512::from(10_000_000_000u64));
+        contract.compound().unwrap();
+        assert_eq!(contract.exchange_rate(), U256::from(1_010_000_000u64));
+        assert_eq!(contract.delegated_amount(validator), U512::from(810_000_000_000u64));
+    }
+
+    #[test]
+    fn test_stake_multiple_users() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user1 = test_env.get_account(0);
+        let user2 = test_env.get_account(1);
+        
+        // User 1 stakes 100 CSPR
+        test_env.set_caller(user1);
+        let result1 = stake_with_cspr(&mut contract, U256::from(100));
+        assert!(result1.is_ok());
+        
+        // User 2 stakes 200 CSPR
+        test_env.set_caller(user2);
+        let result2 = stake_with_cspr(&mut contract, U256::from(200));
+        assert!(result2.is_ok());
+        
+        // Check individual balances
+        assert_eq!(contract.balance_of(&user1), U256::from(100));
+        assert_eq!(contract.balance_of(&user2), U256::from(200));
+        
+        // Check total supply
+        assert_eq!(contract.total_supply(), U256::from(300));
+    }
+
+    #[test]
+    fn test_stake_accumulation() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        test_env.set_caller(user);
+        
+        // Stake multiple times
+        stake_with_cspr(&mut contract, U256::from(50)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(75)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(25)).unwrap();
+        
+        // Check accumulated balance
+        assert_eq!(contract.balance_of(&user), U256::from(150));
+        assert_eq!(contract.total_supply(), U256::from(150));
+    }
+
+    // Unit tests for unstake function edge cases
+    
+    #[test]
+    fn test_unstake_zero_amount() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        test_env.set_caller(user);
+        
+        // First stake some tokens
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        
+        // Try to unstake zero amount
+        let result = contract.unstake(U256::zero());
+        
+        // Should fail with invalid amount error
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
+        
+        // Balance and total supply should remain unchanged
+        assert_eq!(contract.balance_of(&user), U256::from(100));
+        assert_eq!(contract.total_supply(), U256::from(100));
+    }
+
+    #[test]
+    fn test_unstake_insufficient_balance() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        test_env.set_caller(user);
+        
+        // Stake some tokens
+        stake_with_cspr(&mut contract, U256::from(50)).unwrap();
+        
+        // Try to unstake more than balance
+        let result = contract.unstake(U256::from(75));
+        
+        // Should fail with insufficient balance error
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::InsufficientBalance => {},
+            _ => panic!("Expected InsufficientBalance error"),
+        }
+        
+        // Balance and total supply should remain unchanged
+        assert_eq!(contract.balance_of(&user), U256::from(50));
+        assert_eq!(contract.total_supply(), U256::from(50));
+    }
+
+    #[test]
+    fn test_unstake_exact_balance() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        test_env.set_caller(user);
+        
+        // Stake tokens
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        
+        // Unstake exact balance
+        let result = contract.unstake(U256::from(100));
+        assert!(result.is_ok());
+        
+        // Balance should be zero
+        assert_eq!(contract.balance_of(&user), U256::zero());
+        assert_eq!(contract.total_supply(), U256::zero());
+        assert_eq!(contract.contract_cspr_balance(), U256::zero());
+    }
+
+    #[test]
+    fn test_unstake_partial_balance() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        test_env.set_caller(user);
+        
+        // Stake tokens
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        
+        // Unstake partial balance
+        let result = contract.unstake(U256::from(30));
+        assert!(result.is_ok());
+        
+        // Check remaining balance
+        assert_eq!(contract.balance_of(&user), U256::from(70));
+        assert_eq!(contract.total_supply(), U256::from(70));
+        assert_eq!(contract.contract_cspr_balance(), U256::from(70));
+    }
+
+    #[test]
+    fn test_unstake_pays_out_cspr() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        assert_eq!(test_env.balance_of(contract.address()), U512::from(100));
+        
+        // Unstaking moves CSPR from the contract purse back to the user
+        let user_balance_before = test_env.balance_of(&user);
+        contract.unstake(U256::from(40)).unwrap();
+        assert_eq!(test_env.balance_of(contract.address()), U512::from(60));
+        assert_eq!(test_env.balance_of(&user), user_balance_before + U512::from(40));
+    }
+
+    #[test]
+    fn test_delegation_operator_only() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let validator = test_env.get_validator(0);
+        
+        // The deployer is the operator
+        assert!(contract.has_role(Role::Operator, operator));
+        
+        // Other accounts cannot manage the validator set
+        test_env.set_caller(user);
+        match contract.add_validator(validator.clone()).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(operator);
+        assert!(contract.add_validator(validator.clone()).is_ok());
+        assert_eq!(contract.validators(), vec![validator.clone()]);
+        
+        // Registering twice is rejected
+        match contract.add_validator(validator.clone()).unwrap_err() {
+            Error::ValidatorAlreadyExists => {},
+            _ => panic!("Expected ValidatorAlreadyExists error"),
+        }
+    }
+
+    #[test]
+    fn test_roles_and_ownership_transfer() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let owner = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let new_owner = test_env.get_account(2);
+        
+        // The deployer owns the contract and holds every role
+        assert_eq!(contract.owner(), Some(owner));
+        assert!(contract.has_role(Role::Admin, owner));
+        assert!(contract.has_role(Role::Pauser, owner));
+        assert!(!contract.has_role(Role::Operator, user));
+        
+        // Only the owner or an admin can grant roles
+        test_env.set_caller(user);
+        match contract.grant_role(Role::Operator, user).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(owner);
+        contract.grant_role(Role::Operator, user).unwrap();
+        assert!(contract.has_role(Role::Operator, user));
+        
+        // Operators cannot change admin settings
+        test_env.set_caller(user);
+        match contract.set_fee_bps(500).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(owner);
+        contract.revoke_role(Role::Operator, user).unwrap();
+        assert!(!contract.has_role(Role::Operator, user));
+        
+        // Ownership moves only once the nominee accepts
+        contract.transfer_ownership(new_owner).unwrap();
+        assert_eq!(contract.pending_owner(), Some(new_owner));
+        assert_eq!(contract.owner(), Some(owner));
+        
+        test_env.set_caller(user);
+        match contract.accept_ownership().unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(new_owner);
+        contract.accept_ownership().unwrap();
+        assert_eq!(contract.owner(), Some(new_owner));
+        assert_eq!(contract.pending_owner(), None);
+    }
+
+    #[test]
+    fn test_recovery_council_takes_over_after_missed_heartbeats() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let owner = test_env.get_account(0);
+        let council = test_env.get_account(1);
+        let period = recovery::MIN_RECOVERY_PERIOD;
+        
+        match contract.set_recovery(Some(council), period - 1).unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
+        contract.set_recovery(Some(council), period).unwrap();
+        assert_eq!(contract.recovery_available_at(), Some(test_env.block_time() + period));
+        
+        // A heartbeat pushes the takeover back
+        test_env.advance_block_time(period - 1);
+        contract.heartbeat().unwrap();
+        test_env.advance_block_time(period - 1);
+        test_env.set_caller(council);
+        match contract.claim_admin().unwrap_err() {
+            Error::RecoveryNotDue => {},
+            _ => panic!("Expected RecoveryNotDue error"),
+        }
+        
+        // Only the council can take over once the heartbeats stop
+        test_env.advance_block_time(1);
+        test_env.set_caller(test_env.get_account(2));
+        match contract.claim_admin().unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        test_env.set_caller(council);
+        contract.claim_admin().unwrap();
+        assert_eq!(contract.owner(), Some(council));
+        assert!(contract.has_role(Role::Admin, council));
+        assert!(!contract.has_role(Role::Admin, owner));
+        assert_eq!(contract.last_heartbeat(), test_env.block_time());
+        
+        // The lost key can no longer administer or keep the switch alive
+        test_env.set_caller(owner);
+        match contract.heartbeat().unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_minter_extension_mint_and_burn() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let bridge = test_env.get_account(1);
+        let user = test_env.get_account(2);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        
+        // Only whitelisted minters can mint
+        test_env.set_caller(bridge);
+        match contract.mint_shares(&user, U256::from(100)).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(admin);
+        contract.grant_role(Role::Minter, bridge).unwrap();
+        
+        test_env.set_caller(bridge);
+        contract.mint_shares(&user, U256::from(100)).unwrap();
+        assert_eq!(contract.balance_of(&user), U256::from(100));
+        assert_eq!(contract.total_supply(), U256::from(1_100));
+        assert_eq!(contract.total_pooled_cspr(), U256::from(1_000));
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &ExtensionMinted { minter: bridge, recipient: user, shares: U256::from(100) }
+        ));
+        
+        // Burning another owner's shares needs an allowance
+        match contract.burn_shares(&user, U256::from(40)).unwrap_err() {
+            Error::InsufficientAllowance => {},
+            _ => panic!("Expected InsufficientAllowance error"),
+        }
+        test_env.set_caller(user);
+        contract.approve(&bridge, U256::from(40)).unwrap();
+        test_env.set_caller(bridge);
+        contract.burn_shares(&user, U256::from(40)).unwrap();
+        assert_eq!(contract.balance_of(&user), U256::from(60));
+        assert_eq!(contract.allowance(&user, &bridge), U256::zero());
+        assert_eq!(contract.total_supply(), U256::from(1_060));
+        
+        // Freshly staked shares are held back by the stake cooldown
+        test_env.set_caller(admin);
+        contract.set_stake_cooldown(1_000).unwrap();
+        contract.grant_role(Role::Minter, admin).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        match contract.burn_shares(&admin, U256::from(1_001)).unwrap_err() {
+            Error::CooldownActive => {},
+            _ => panic!("Expected CooldownActive error"),
+        }
+        contract.burn_shares(&admin, U256::from(1_000)).unwrap();
+        contract.revoke_role(Role::Minter, admin).unwrap();
+        
+        // Revoking the role disables the hooks
+        test_env.set_caller(admin);
+        contract.revoke_role(Role::Minter, bridge).unwrap();
+        test_env.set_caller(bridge);
+        assert!(contract.mint_shares(&user, U256::from(1)).is_err());
+    }
+
+    #[test]
+    fn test_bridge_mint_and_burn_keep_supply_auditable() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let bridge = test_env.get_account(1);
+        let user = test_env.get_account(2);
+        
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        contract.approve(&bridge, U256::from(400)).unwrap();
+        
+        test_env.set_caller(admin);
+        contract.grant_role(Role::BridgeMinter, bridge).unwrap();
+        contract.set_bridge_daily_cap(bridge, U256::from(300)).unwrap();
+        
+        // Bridging out burns the stCSPR but leaves the rate where it was
+        let rate = contract.exchange_rate();
+        test_env.set_caller(bridge);
+        contract.bridge_burn(user, U256::from(400), "0xdestination".to_string()).unwrap();
+        assert_eq!(contract.balance_of(&user), U256::from(600));
+        assert_eq!(contract.bridged_out_supply(), U256::from(400));
+        assert_eq!(contract.exchange_rate(), rate);
+        
+        // Mints are held to the daily cap and each origin transaction is minted once
+        contract.bridge_mint(user, U256::from(250), "0xorigin1".to_string()).unwrap();
+        match contract.bridge_mint(user, U256::from(50), "0xorigin1".to_string()).unwrap_err() {
+            Error::BridgeTxProcessed => {},
+            _ => panic!("Expected BridgeTxProcessed error"),
+        }
+        match contract.bridge_mint(user, U256::from(100), "0xorigin2".to_string()).unwrap_err() {
+            Error::BridgeCapReached => {},
+            _ => panic!("Expected BridgeCapReached error"),
+        }
+        assert_eq!(contract.bridge_minted_today(bridge), U256::from(250));
+        
+        // The next day the cap resets, but no more than was bridged out comes back
+        test_env.advance_block_time(bridge::BRIDGE_DAY);
+        match contract.bridge_mint(user, U256::from(200), "0xorigin2".to_string()).unwrap_err() {
+            Error::BridgeSupplyExceeded => {},
+            _ => panic!("Expected BridgeSupplyExceeded error"),
+        }
+        contract.bridge_mint(user, U256::from(150), "0xorigin2".to_string()).unwrap();
+        assert_eq!(contract.balance_of(&user), U256::from(1_000));
+        assert_eq!(contract.bridged_out_supply(), U256::zero());
+        assert_eq!(contract.exchange_rate(), rate);
+        
+        // Minter role holders cannot use the bridge entry points
+        test_env.set_caller(admin);
+        contract.grant_role(Role::Minter, admin).unwrap();
+        match contract.bridge_mint(admin, U256::from(1), "0xorigin3".to_string()).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_pause_blocks_state_changes() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let owner = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        
+        stake_with_cspr(&mut contract, U256::from(1000)).unwrap();
+        
+        // Only pausers can pause
+        test_env.set_caller(user);
+        match contract.pause().unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(owner);
+        contract.pause().unwrap();
+        assert!(contract.paused());
+        
+        match stake_with_cspr(&mut contract, U256::from(100)).unwrap_err() {
+            Error::ContractPaused => {},
+            _ => panic!("Expected ContractPaused error"),
+        }
+        match contract.unstake(U256::from(100)).unwrap_err() {
+            Error::ContractPaused => {},
+            _ => panic!("Expected ContractPaused error"),
+        }
+        match contract.transfer(&user, U256::from(100)).unwrap_err() {
+            Error::ContractPaused => {},
+            _ => panic!("Expected ContractPaused error"),
+        }
+        match contract.approve(&user, U256::from(100)).unwrap_err() {
+            Error::ContractPaused => {},
+            _ => panic!("Expected ContractPaused error"),
+        }
+        
+        // Views keep working and unpausing restores normal operation
+        assert_eq!(contract.balance_of(&owner), U256::from(1000));
+        contract.unpause().unwrap();
+        assert!(!contract.paused());
+        assert!(contract.transfer(&user, U256::from(100)).is_ok());
+    }
+
+    #[test]
+    fn test_reentrancy_guard() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        
+        stake_with_cspr(&mut contract, U256::from(1000)).unwrap();
+        
+        // The guard is released after each successful call
+        assert!(contract.unstake(U256::from(100)).is_ok());
+        assert!(stake_with_cspr(&mut contract, U256::from(100)).is_ok());
+        
+        // A call entering while the guard is held is rejected
+        contract.set_locked_for_testing(true);
+        match contract.unstake(U256::from(100)).unwrap_err() {
+            Error::Reentrancy => {},
+            _ => panic!("Expected Reentrancy error"),
+        }
+        match stake_with_cspr(&mut contract, U256::from(100)).unwrap_err() {
+            Error::Reentrancy => {},
+            _ => panic!("Expected Reentrancy error"),
+        }
+    }
+
+    #[test]
+    fn test_cep18_events() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let owner = test_env.get_account(0);
+        let spender = test_env.get_account(1);
+        let recipient = test_env.get_account(2);
+        
+        stake_with_cspr(&mut contract, U256::from(1000)).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &Mint { recipient: owner, amount: U256::from(1000) }
+        ));
+        
+        contract.transfer(&recipient, U256::from(100)).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &Transfer { sender: owner, recipient, amount: U256::from(100) }
+        ));
+        
+        contract.approve(&spender, U256::from(200)).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &SetAllowance { owner, spender, allowance: U256::from(200) }
+        ));
+        
+        test_env.set_caller(spender);
+        contract.transfer_from(&owner, &recipient, U256::from(50)).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &TransferFrom { spender, owner, recipient, amount: U256::from(50) }
+        ));
+        
+        test_env.set_caller(owner);
+        contract.unstake(U256::from(300)).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &Burn { owner, amount: U256::from(300) }
+        ));
+    }
+
+    #[test]
+    fn test_increase_and_decrease_allowance() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let owner = test_env.get_account(0);
+        let spender = test_env.get_account(1);
+        
+        contract.approve(&spender, U256::from(100)).unwrap();
+        contract.increase_allowance(&spender, U256::from(50)).unwrap();
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(150));
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &IncreaseAllowance { owner, spender, allowance: U256::from(150), inc_by: U256::from(50) }
+        ));
+        
+        contract.decrease_allowance(&spender, U256::from(120)).unwrap();
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(30));
+        
+        // Cannot decrease below zero
+        match contract.decrease_allowance(&spender, U256::from(31)).unwrap_err() {
+            Error::InsufficientAllowance => {},
+            _ => panic!("Expected InsufficientAllowance error"),
+        }
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(30));
+        
+        // Overflow is rejected
+        match contract.increase_allowance(&spender, U256::MAX).unwrap_err() {
+            Error::ArithmeticOverflow => {},
+            _ => panic!("Expected ArithmeticOverflow error"),
+        }
+    }
+
+    #[test]
+    fn test_staking_limits() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        
+        // Only admins can configure limits
+        test_env.set_caller(user);
+        match contract.set_limits(U256::from(10), U256::zero(), U256::zero()).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(admin);
+        contract.set_limits(U256::from(10), U256::from(500), U256::from(800)).unwrap();
+        assert_eq!(contract.min_stake(), U256::from(10));
+        assert_eq!(contract.max_stake_per_tx(), U256::from(500));
+        assert_eq!(contract.max_total_staked(), U256::from(800));
+        
+        match stake_with_cspr(&mut contract, U256::from(9)).unwrap_err() {
+            Error::BelowMinimumStake => {},
+            _ => panic!("Expected BelowMinimumStake error"),
+        }
+        match stake_with_cspr(&mut contract, U256::from(501)).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
+        }
+        
+        stake_with_cspr(&mut contract, U256::from(500)).unwrap();
+        match stake_with_cspr(&mut contract, U256::from(301)).unwrap_err() {
+            Error::StakingCapReached => {},
+            _ => panic!("Expected StakingCapReached error"),
+        }
+        assert!(stake_with_cspr(&mut contract, U256::from(300)).is_ok());
+    }
+
+    #[test]
+    fn test_unstake_rate_limit_per_era() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        assert_eq!(contract.remaining_unstake_capacity(), U256::MAX);
+        
+        match contract.set_unstake_limit_bps(BPS_DENOMINATOR + 1).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
+        }
+        contract.set_unstake_limit_bps(1_000).unwrap();
+        assert_eq!(contract.remaining_unstake_capacity(), U256::from(100));
+        
+        // Earlier exits in the era do not shrink the cap
+        contract.unstake(U256::from(60)).unwrap();
+        assert_eq!(contract.remaining_unstake_capacity(), U256::from(40));
+        
+        // Queued withdrawals count towards the same cap
+        match contract.request_unstake(U256::from(41)).unwrap_err() {
+            Error::UnstakeRateLimited => {},
+            _ => panic!("Expected UnstakeRateLimited error"),
+        }
+        contract.request_unstake(U256::from(40)).unwrap();
+        assert_eq!(contract.remaining_unstake_capacity(), U256::zero());
+        assert_eq!(contract.unstaked_in_era(0).unwrap(), U256::from(100));
+        match contract.unstaked_in_era(1).unwrap_err() {
+            Error::InvalidEra => {},
+            _ => panic!("Expected InvalidEra error"),
+        }
+        
+        // A new era resets the bucket against the smaller pool
+        test_env.advance_block_time(ERA_DURATION);
+        assert_eq!(contract.remaining_unstake_capacity(), U256::from(90));
+        contract.unstake(U256::from(90)).unwrap();
+    }
+
+    #[test]
+    fn test_stake_cooldown_blocks_early_unstake() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let staker = test_env.get_account(0);
+        let other = test_env.get_account(1);
+        
+        // Disabled by default
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        assert_eq!(contract.stake_cooldown_remaining(staker), 0);
+        assert_eq!(contract.cooling_balance(staker), U256::zero());
+        contract.unstake(U256::from(100)).unwrap();
+        
+        // Only the freshly staked stCSPR is held back
+        contract.set_stake_cooldown(1_000).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        assert_eq!(contract.stake_cooldown(), 1_000);
+        assert_eq!(contract.stake_cooldown_remaining(staker), 1_000);
+        assert_eq!(contract.cooling_balance(staker), U256::from(100));
+        match contract.unstake(U256::from(901)).unwrap_err() {
+            Error::CooldownActive => {},
+            _ => panic!("Expected CooldownActive error"),
+        }
+        match contract.request_unstake(U256::from(901)).unwrap_err() {
+            Error::CooldownActive => {},
+            _ => panic!("Expected CooldownActive error"),
+        }
+        contract.unstake(U256::from(900)).unwrap();
+        
+        // Cooling stCSPR cannot be parked in the contract's escrow
+        match contract.fund_merkle_drop(U256::from(1)).unwrap_err() {
+            Error::CooldownActive => {},
+            _ => panic!("Expected CooldownActive error"),
+        }
+        
+        // Transferring does not skip the cooldown
+        contract.transfer(&other, U256::from(100)).unwrap();
+        assert_eq!(contract.cooling_balance(other), U256::from(100));
+        test_env.advance_block_time(400);
+        assert_eq!(contract.stake_cooldown_remaining(other), 600);
+        test_env.set_caller(other);
+        match contract.unstake(U256::from(100)).unwrap_err() {
+            Error::CooldownActive => {},
+            _ => panic!("Expected CooldownActive error"),
+        }
+        
+        test_env.advance_block_time(600);
+        assert_eq!(contract.stake_cooldown_remaining(other), 0);
+        assert_eq!(contract.cooling_balance(other), U256::zero());
+        contract.unstake(U256::from(100)).unwrap();
+    }
+
+    #[test]
+    fn test_stake_cooldown_dust_transfer_does_not_lock_recipient() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let attacker = test_env.get_account(0);
+        let victim = test_env.get_account(1);
+        
+        test_env.set_caller(victim);
+        stake_with_cspr(&mut contract, U256::from(500)).unwrap();
+        
+        test_env.set_caller(attacker);
+        contract.set_stake_cooldown(1_000).unwrap();
+        stake_with_cspr(&mut contract, U256::from(10)).unwrap();
+        contract.transfer(&victim, U256::from(1)).unwrap();
+        
+        // Only the dust inherits the cooldown; the victim's own stCSPR stays free
+        test_env.set_caller(victim);
+        assert_eq!(contract.cooling_balance(victim), U256::from(1));
+        match contract.unstake(U256::from(501)).unwrap_err() {
+            Error::CooldownActive => {},
+            _ => panic!("Expected CooldownActive error"),
+        }
+        contract.unstake(U256::from(500)).unwrap();
+    }
+
+    #[test]
+    fn test_era_math_and_withdrawal_estimates() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let validator = test_env.get_validator(0);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000_000)).unwrap();
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator, U512::from(800_000)).unwrap();
+        assert_eq!(contract.current_era(), 0);
+        assert_eq!(contract.era_length(), ERA_DURATION);
+        
+        // Covered by the buffer: only the unbonding period applies
+        let now = test_env.block_time();
+        assert_eq!(
+            contract.estimate_withdrawal_time(U256::from(100_000)),
+            now + contract.unbonding_period()
+        );
+        // Needs undelegation: wait for the end of the auction unbonding delay
+        assert_eq!(
+            contract.estimate_withdrawal_time(U256::from(500_000)),
+            (contract.unbonding_delay_eras() + 1) * ERA_DURATION
+        );
+        
+        // Anchoring to the chain's era numbering
+        match contract.set_era_config(3_600_000, 2, 100, now + 1).unwrap_err() {
+            Error::InvalidEra => {},
+            _ => panic!("Expected InvalidEra error"),
+        }
+        contract.set_era_config(3_600_000, 2, 100, now).unwrap();
+        assert_eq!(contract.current_era(), 100);
+        test_env.advance_block_time(3 * 3_600_000 + 1);
+        assert_eq!(contract.current_era(), 103);
+        
+        test_env.set_caller(test_env.get_account(1));
+        match contract.set_era_config(3_600_000, 2, 100, now).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_obligations_summary() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let validator = test_env.get_validator(0);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(900_000_000_000u64)).unwrap();
+        contract.request_unstake(U256::from(300_000_000_000u64)).unwrap();
+        
+        // The purse alone cannot pay the queued withdrawal
+        let obligations = contract.get_obligations();
+        assert_eq!(obligations.pending_withdrawals, U512::from(300_000_000_000u64));
+        assert!(obligations.unbonding.is_empty());
+        assert_eq!(obligations.buffered, U512::zero());
+        assert_eq!(obligations.shortfall, U512::from(200_000_000_000u64));
+        assert_eq!(obligations.surplus, U512::zero());
+        
+        // Undelegated CSPR counts towards coverage until its return era
+        contract.undelegate_from(validator, U512::from(250_000_000_000u64)).unwrap();
+        let return_era = contract.current_era() + contract.unbonding_delay_eras() + 1;
+        let obligations = contract.get_obligations();
+        assert_eq!(obligations.unbonding, vec![(return_era, U512::from(250_000_000_000u64))]);
+        assert_eq!(obligations.total_unbonding, U512::from(250_000_000_000u64));
+        assert_eq!(obligations.shortfall, U512::zero());
+        assert_eq!(obligations.surplus, U512::from(50_000_000_000u64));
+        
+        test_env.advance_block_time((contract.unbonding_delay_eras() + 1) * ERA_DURATION);
+        assert!(contract.get_obligations().unbonding.is_empty());
+    }
+
+    #[test]
+    fn test_withdrawal_queue_position_and_eta() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let validator = test_env.get_validator(0);
+        
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        test_env.set_caller(operator);
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(900_000_000_000u64)).unwrap();
+        
+        test_env.set_caller(user);
+        let first = contract.request_unstake(U256::from(60_000_000_000u64)).unwrap();
+        let second = contract.request_unstake(U256::from(80_000_000_000u64)).unwrap();
+        assert_eq!(contract.queue_position(first), Some(0));
+        assert_eq!(contract.queue_position(second), Some(1));
+        assert_eq!(contract.queue_position(second + 1), None);
+        
+        // The purse covers the first request, so only its unbonding period applies
+        assert_eq!(contract.withdrawal_eta(first), contract.claimable_at(first));
+        
+        // The second one waits for CSPR the operator still has to undelegate
+        let undelegated_by = (contract.unbonding_delay_eras() + 1) * ERA_DURATION;
+        assert_eq!(contract.withdrawal_eta(second), Some(undelegated_by));
+        
+        // Once undelegated, it waits for the era the CSPR returns in
+        test_env.advance_block_time(ERA_DURATION);
+        test_env.set_caller(operator);
+        contract.undelegate_from(validator, U512::from(100_000_000_000u64)).unwrap();
+        assert_eq!(contract.withdrawal_eta(second), Some(undelegated_by + ERA_DURATION));
+        
+        // Claiming the first request moves the second to the front
+        test_env.advance_block_time(contract.unbonding_period());
+        test_env.set_caller(user);
+        contract.claim_withdrawal(first).unwrap();
+        assert_eq!(contract.queue_position(first), None);
+        assert_eq!(contract.withdrawal_eta(first), None);
+        assert_eq!(contract.queue_position(second), Some(0));
+    }
+
+    #[test]
+    fn test_init_with_custom_configuration() {
+        let test_env = odra_test::env();
+        let owner = test_env.get_account(1);
+        let treasury = test_env.get_account(2);
+        let contract = CasperLiquid::deploy(
+            &test_env,
+            CasperLiquidInitArgs {
+                name: Some("Test Staked CSPR".to_string()),
+                symbol: Some("tstCSPR".to_string()),
+                decimals: Some(6),
+                owner: Some(owner),
+                fee_bps: Some(500),
+                treasury: Some(treasury),
+                features: None,
+            },
+        );
+        
+        assert_eq!(contract.name(), "Test Staked CSPR");
+        assert_eq!(contract.symbol(), "tstCSPR");
+        assert_eq!(contract.decimals(), 6);
+        assert_eq!(contract.owner(), Some(owner));
+        assert!(contract.has_role(Role::Admin, owner));
+        assert!(!contract.has_role(Role::Admin, test_env.get_account(0)));
+        assert_eq!(contract.fee_bps(), 500);
+        assert_eq!(contract.treasury(), Some(treasury));
+        assert_eq!(contract.features(), DEFAULT_FEATURES);
+    }
+
+    #[test]
+    fn test_init_features_switch_off_entry_points() {
+        let test_env = odra_test::env();
+        let referrer = test_env.get_account(1);
+        let mut contract = CasperLiquid::deploy(
+            &test_env,
+            CasperLiquidInitArgs {
+                features: Some(FEATURE_COMPLIANCE),
+                ..default_init_args()
+            },
+        );
+        assert!(contract.feature_enabled(FEATURE_COMPLIANCE));
+        assert!(!contract.feature_enabled(FEATURE_INSTANT_UNSTAKE | FEATURE_COMPLIANCE));
+        
+        // Staking and the withdrawal queue are always on
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        contract.request_unstake(U256::from(100)).unwrap();
+        match contract.unstake(U256::from(100)).unwrap_err() {
+            Error::FeatureDisabled => {},
+            _ => panic!("Expected FeatureDisabled error"),
+        }
+        match contract.with_tokens(U512::from(100)).stake_with_referral(U256::from(100), &referrer).unwrap_err() {
+            Error::FeatureDisabled => {},
+            _ => panic!("Expected FeatureDisabled error"),
+        }
+        contract.restrict_transfers().unwrap();
+        
+        // Without the compliance bit transfers cannot be restricted
+        let mut contract = CasperLiquid::deploy(
+            &test_env,
+            CasperLiquidInitArgs {
+                features: Some(FEATURE_INSTANT_UNSTAKE | FEATURE_REFERRALS),
+                ..default_init_args()
+            },
+        );
+        match contract.restrict_transfers().unwrap_err() {
+            Error::FeatureDisabled => {},
+            _ => panic!("Expected FeatureDisabled error"),
+        }
+    }
+
+    #[test]
+    fn test_migrate_from_one_to_one_layout() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        
+        // Freshly installed contracts are already at the current version
+        assert_eq!(contract.version(), CONTRACT_VERSION);
+        match contract.migrate().unwrap_err() {
+            Error::MigrationNotNeeded => {},
+            _ => panic!("Expected MigrationNotNeeded error"),
+        }
+        
+        // Simulate state written by the 1:1 version
+        stake_with_cspr(&mut contract, U256::from(1000)).unwrap();
+        contract.set_version_for_testing(1);
+        assert_eq!(contract.version(), 1);
+        
+        // Only admins can migrate once an owner exists
+        test_env.set_caller(user);
+        match contract.migrate().unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(admin);
+        contract.migrate().unwrap();
+        assert_eq!(contract.version(), CONTRACT_VERSION);
+        
+        // Legacy balances are shares at a 1.0 exchange rate
+        assert_eq!(contract.exchange_rate(), U256::from(RATE_PRECISION));
+        assert_eq!(contract.preview_unstake(U256::from(1000)), U256::from(1000));
+    }
+
+    #[test]
+    fn test_user_stats() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        
+        let stats = contract.get_user_stats(&user);
+        assert_eq!(stats.total_ever_staked, U256::zero());
+        assert_eq!(stats.first_stake_timestamp, 0);
+        
+        test_env.advance_block_time(1_000);
+        stake_with_cspr(&mut contract, U256::from(500)).unwrap();
+        let first_stake = contract.get_user_stats(&user).first_stake_timestamp;
+        assert!(first_stake > 0);
+        
+        test_env.advance_block_time(1_000);
+        stake_with_cspr(&mut contract, U256::from(300)).unwrap();
+        contract.unstake(U256::from(200)).unwrap();
+        contract.request_unstake(U256::from(100)).unwrap();
+        
+        let stats = contract.get_user_stats(&user);
+        assert_eq!(stats.total_ever_staked, U256::from(800));
+        assert_eq!(stats.total_ever_unstaked, U256::from(300));
+        assert_eq!(stats.first_stake_timestamp, first_stake);
+        assert!(stats.last_action_timestamp > first_stake);
+    }
+
+    #[test]
+    fn test_transfer_batch() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let sender = test_env.get_account(0);
+        let alice = test_env.get_account(1);
+        let bob = test_env.get_account(2);
+        
+        stake_with_cspr(&mut contract, U256::from(1000)).unwrap();
+        
+        match contract.transfer_batch(vec![alice, bob], vec![U256::from(100)]).unwrap_err() {
+            Error::LengthMismatch => {},
+            _ => panic!("Expected LengthMismatch error"),
+        }
+        
+        contract.transfer_batch(vec![alice, bob], vec![U256::from(100), U256::from(200)]).unwrap();
+        assert_eq!(contract.balance_of(&sender), U256::from(700));
+        assert_eq!(contract.balance_of(&alice), U256::from(100));
+        assert_eq!(contract.balance_of(&bob), U256::from(200));
+        
+        // A failing leg reverts the whole batch
+        match contract.transfer_batch(vec![alice, bob], vec![U256::from(100), U256::from(1000)]).unwrap_err() {
+            Error::InsufficientBalance => {},
+            _ => panic!("Expected InsufficientBalance error"),
+        }
+        assert_eq!(contract.balance_of(&sender), U256::from(700));
+        assert_eq!(contract.balance_of(&alice), U256::from(100));
+    }
+
+    #[test]
+    fn test_permit() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let owner = test_env.get_account(0);
+        let spender = test_env.get_account(1);
+        let owner_key = test_env.public_key(&owner);
+        let deadline = 1_000_000;
+        
+        let message = contract.permit_message(owner, spender, U256::from(500), deadline);
+        let signature = test_env.sign_message(&message, &owner);
+        
+        // A signature for a different amount is rejected
+        test_env.set_caller(spender);
+        match contract.permit(owner_key.clone(), spender, U256::from(600), deadline, signature.clone()).unwrap_err() {
+            Error::InvalidSignature => {},
+            _ => panic!("Expected InvalidSignature error"),
+        }
+        
+        // Anyone can submit the owner's permit
+        contract.permit(owner_key.clone(), spender, U256::from(500), deadline, signature.clone()).unwrap();
+        assert_eq!(contract.allowance(&owner, &spender), U256::from(500));
+        assert_eq!(contract.nonces(owner), 1);
+        
+        // The nonce moved on, so the signature cannot be replayed
+        match contract.permit(owner_key.clone(), spender, U256::from(500), deadline, signature).unwrap_err() {
+            Error::InvalidSignature => {},
+            _ => panic!("Expected InvalidSignature error"),
+        }
+        
+        // Expired permits are rejected
+        let message = contract.permit_message(owner, spender, U256::from(500), deadline);
+        let signature = test_env.sign_message(&message, &owner);
+        test_env.advance_block_time(deadline + 1);
+        match contract.permit(owner_key, spender, U256::from(500), deadline, signature).unwrap_err() {
+            Error::PermitExpired => {},
+            _ => panic!("Expected PermitExpired error"),
+        }
+    }
+
+    #[test]
+    fn test_stake_for_and_unstake_to() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let payer = test_env.get_account(0);
+        let beneficiary = test_env.get_account(1);
+        let recipient = test_env.get_account(2);
+        
+        // The payer funds the stake, the beneficiary receives the stCSPR
+        contract.with_tokens(U512::from(500)).stake_for(&beneficiary, U256::from(500)).unwrap();
+        assert_eq!(contract.balance_of(&payer), U256::zero());
+        assert_eq!(contract.balance_of(&beneficiary), U256::from(500));
+        assert_eq!(contract.get_user_stats(&beneficiary).total_ever_staked, U256::from(500));
+        
+        // The beneficiary sends the unstaked CSPR to another address
+        test_env.set_caller(beneficiary);
+        let recipient_balance_before = test_env.balance_of(&recipient);
+        contract.unstake_to(&recipient, U256::from(200)).unwrap();
+        assert_eq!(contract.balance_of(&beneficiary), U256::from(300));
+        assert_eq!(test_env.balance_of(&recipient), recipient_balance_before + U512::from(200));
+    }
+
+    #[test]
+    fn test_stake_and_transfer_into_contract() {
+        use crate::receiver::mock::{MockReceiver, MockReceiverInitArgs};
+        
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        let friend = test_env.get_account(1);
+        let market = MockReceiver::deploy(&test_env, MockReceiverInitArgs { accept: true });
+        
+        // Without a payload the stCSPR is simply transferred
+        let minted = contract
+            .with_tokens(U512::from(100))
+            .stake_and_transfer(&friend, U256::from(100), Bytes::new())
+            .unwrap();
+        assert_eq!(minted, U256::from(100));
+        assert_eq!(contract.balance_of(&friend), U256::from(100));
+        assert_eq!(contract.balance_of(&user), U256::zero());
+        
+        // With a payload the target's receiver hook is called
+        let payload = Bytes::from(vec![1, 2, 3]);
+        contract
+            .with_tokens(U512::from(250))
+            .stake_and_transfer(market.address(), U256::from(250), payload.clone())
+            .unwrap();
+        assert_eq!(contract.balance_of(market.address()), U256::from(250));
+        assert_eq!(market.received(), U256::from(250));
+        assert_eq!(market.last_from(), Some(user));
+        assert_eq!(market.last_data(), payload);
+        
+        // Receivers can reject, reverting the whole deposit
+        let picky = MockReceiver::deploy(&test_env, MockReceiverInitArgs { accept: false });
+        match contract
+            .with_tokens(U512::from(50))
+            .stake_and_transfer(picky.address(), U256::from(50), payload)
+            .unwrap_err()
+        {
+            Error::TransferRejected => {},
+            _ => panic!("Expected TransferRejected error"),
+        }
+        assert_eq!(contract.total_supply(), U256::from(350));
+    }
+
+    #[test]
+    fn test_transfer_and_call() {
+        use crate::receiver::mock::{MockReceiver, MockReceiverInitArgs};
+        
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(0);
+        let friend = test_env.get_account(1);
+        let vault = MockReceiver::deploy(&test_env, MockReceiverInitArgs { accept: true });
+        let data = Bytes::from(vec![7]);
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        
+        // Accounts are not called back
+        contract.transfer_and_call(&friend, U256::from(100), data.clone()).unwrap();
+        assert_eq!(contract.balance_of(&friend), U256::from(100));
+        
+        contract.transfer_and_call(vault.address(), U256::from(300), data.clone()).unwrap();
+        assert_eq!(contract.balance_of(vault.address()), U256::from(300));
+        assert_eq!(vault.received(), U256::from(300));
+        assert_eq!(vault.last_from(), Some(user));
+        assert_eq!(vault.last_data(), data);
+        
+        // A rejecting contract reverts the transfer
+        let picky = MockReceiver::deploy(&test_env, MockReceiverInitArgs { accept: false });
+        match contract.transfer_and_call(picky.address(), U256::from(50), data).unwrap_err() {
+            Error::TransferRejected => {},
+            _ => panic!("Expected TransferRejected error"),
+        }
+        assert_eq!(contract.balance_of(&user), U256::from(600));
+        assert_eq!(contract.balance_of(picky.address()), U256::zero());
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use odra::host::{Deployer, HostRef};
-    use proptest::prelude::*;
 
     #[test]
-    fn test_contract_initialization() {
+    fn test_admin_events() {
+        use crate::delegation::{ValidatorAdded, ValidatorRemoved};
+        
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let treasury = test_env.get_account(1);
+        let validator = test_env.get_validator(0);
         
-        // Test contract deploys with zero total supply
-        assert_eq!(contract.total_supply(), U256::zero());
+        contract.set_fee_bps(1_000).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &ConfigChanged {
+                key: "fee_bps".to_string(),
+                old_value: U256::zero(),
+                new_value: U256::from(1_000),
+                changed_by: admin,
+            }
+        ));
         
-        // Test metadata functions return correct values
-        assert_eq!(contract.name(), "Staked CSPR");
-        assert_eq!(contract.symbol(), "stCSPR");
-        assert_eq!(contract.decimals(), 9);
+        contract.set_treasury(treasury).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &TreasuryChanged { previous_treasury: None, new_treasury: treasury, changed_by: admin }
+        ));
+        
+        contract.add_validator(validator.clone()).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &ValidatorAdded { validator: validator.clone() }
+        ));
+        
+        contract.remove_validator(validator.clone()).unwrap();
+        assert!(test_env.emitted_event(contract.address(), &ValidatorRemoved { validator }));
+        
+        contract.pause().unwrap();
+        assert!(test_env.emitted_event(contract.address(), &Paused { account: admin }));
     }
 
     #[test]
-    fn test_initial_balances() {
+    fn test_balance_checkpoints() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let user = test_env.get_account(0);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let alice = test_env.get_account(0);
+        let bob = test_env.get_account(1);
         
-        // Test that initial balance is zero for any address
-        assert_eq!(contract.balance_of(&user), U256::zero());
+        test_env.advance_block_time(1_000);
+        stake_with_cspr(&mut contract, U256::from(1000)).unwrap();
+        test_env.advance_block_time(1_000);
+        contract.transfer(&bob, U256::from(400)).unwrap();
+        test_env.advance_block_time(1_000);
+        contract.unstake(U256::from(100)).unwrap();
+        let now = contract.get_user_stats(&alice).last_action_timestamp;
+        
+        // Walk back through the history: unstake, transfer, stake
+        assert_eq!(contract.balance_of_at(&alice, now), U256::from(500));
+        assert_eq!(contract.balance_of_at(&alice, now - 1), U256::from(600));
+        assert_eq!(contract.balance_of_at(&bob, now - 1), U256::from(400));
+        assert_eq!(contract.balance_of_at(&alice, now - 1_001), U256::from(1000));
+        assert_eq!(contract.balance_of_at(&bob, now - 1_001), U256::zero());
+        assert_eq!(contract.balance_of_at(&alice, now - 2_001), U256::zero());
+        
+        assert_eq!(contract.total_supply_at(now), U256::from(900));
+        assert_eq!(contract.total_supply_at(now - 1), U256::from(1000));
+        assert_eq!(contract.total_supply_at(now - 2_001), U256::zero());
     }
 
     #[test]
-    fn test_metadata_consistency() {
+    fn test_governance_proposal_lifecycle() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let alice = test_env.get_account(0);
+        let bob = test_env.get_account(1);
+        let carol = test_env.get_account(2);
         
-        // Test that metadata is consistent across multiple calls
-        assert_eq!(contract.name(), contract.name());
-        assert_eq!(contract.symbol(), contract.symbol());
-        assert_eq!(contract.decimals(), contract.decimals());
+        stake_with_cspr(&mut contract, U256::from(600)).unwrap();
+        contract.transfer(&bob, U256::from(200)).unwrap();
+        test_env.advance_block_time(1_000);
         
-        // Test that decimals match CSPR (9 decimals)
-        assert_eq!(contract.decimals(), 9u8);
+        // Holders without stCSPR cannot propose
+        test_env.set_caller(carol);
+        match contract.create_proposal([1u8; 32], vec![ProposalAction::SetFeeBps(500)]).unwrap_err() {
+            Error::NoVotingPower => {},
+            _ => panic!("Expected NoVotingPower error"),
+        }
+        
+        test_env.set_caller(alice);
+        let id = contract.create_proposal([1u8; 32], vec![ProposalAction::SetFeeBps(500)]).unwrap();
+        
+        // Tokens received after the snapshot carry no weight
+        contract.transfer(&carol, U256::from(100)).unwrap();
+        test_env.set_caller(carol);
+        match contract.vote(id, true).unwrap_err() {
+            Error::NoVotingPower => {},
+            _ => panic!("Expected NoVotingPower error"),
+        }
+        
+        test_env.set_caller(alice);
+        contract.vote(id, true).unwrap();
+        match contract.vote(id, true).unwrap_err() {
+            Error::AlreadyVoted => {},
+            _ => panic!("Expected AlreadyVoted error"),
+        }
+        test_env.set_caller(bob);
+        contract.vote(id, false).unwrap();
+        
+        let proposal = contract.get_proposal(id).unwrap();
+        assert_eq!(proposal.for_votes, U256::from(400));
+        assert_eq!(proposal.against_votes, U256::from(200));
+        
+        // Execution waits for the end of the voting period
+        match contract.execute(id).unwrap_err() {
+            Error::VotingInProgress => {},
+            _ => panic!("Expected VotingInProgress error"),
+        }
+        
+        test_env.advance_block_time(contract.voting_period() + 1);
+        contract.execute(id).unwrap();
+        assert_eq!(contract.fee_bps(), 500);
+        
+        match contract.execute(id).unwrap_err() {
+            Error::ProposalAlreadyExecuted => {},
+            _ => panic!("Expected ProposalAlreadyExecuted error"),
+        }
     }
 
-    // Helper function to set up a contract with initial balances for testing
-    fn setup_contract_with_balances(sender_balance: u64, recipient_balance: u64) -> (odra_test::TestEnv, CasperLiquid, Address, Address) {
+    #[test]
+    fn test_timelocked_admin_actions() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let sender = test_env.get_account(0);
-        let recipient = test_env.get_account(1);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(1);
+        let delay = 86_400_000;
         
-        // Set balances for testing using the test helper method
-        if sender_balance > 0 {
-            contract.set_balance_for_testing(&sender, U256::from(sender_balance));
+        contract.set_timelock_delay(delay).unwrap();
+        
+        // Timelocked settings can no longer be changed directly
+        match contract.set_fee_bps(500).unwrap_err() {
+            Error::TimelockRequired => {},
+            _ => panic!("Expected TimelockRequired error"),
         }
-        if recipient_balance > 0 {
-            contract.set_balance_for_testing(&recipient, U256::from(recipient_balance));
+        match contract.set_timelock_delay(0).unwrap_err() {
+            Error::TimelockRequired => {},
+            _ => panic!("Expected TimelockRequired error"),
         }
         
-        (test_env, contract, sender, recipient)
+        // Queued actions must respect the delay
+        let now = test_env.block_time();
+        match contract.queue_action(ProposalAction::SetFeeBps(500), now + delay - 1).unwrap_err() {
+            Error::TimelockEtaTooEarly => {},
+            _ => panic!("Expected TimelockEtaTooEarly error"),
+        }
+        test_env.set_caller(user);
+        assert!(contract.queue_action(ProposalAction::SetFeeBps(500), now + delay).is_err());
+        test_env.set_caller(test_env.get_account(0));
+        
+        let id = contract.queue_action(ProposalAction::SetFeeBps(500), now + delay).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &timelock::ActionQueued { id, action: ProposalAction::SetFeeBps(500), eta: now + delay }
+        ));
+        match contract.execute_action(id).unwrap_err() {
+            Error::TimelockNotReady => {},
+            _ => panic!("Expected TimelockNotReady error"),
+        }
+        
+        test_env.advance_block_time(delay);
+        contract.execute_action(id).unwrap();
+        assert_eq!(contract.fee_bps(), 500);
+        assert!(contract.get_queued_action(id).unwrap().executed);
+        match contract.execute_action(id).unwrap_err() {
+            Error::TimelockActionNotPending => {},
+            _ => panic!("Expected TimelockActionNotPending error"),
+        }
+        
+        // Cancelled actions never run
+        let now = test_env.block_time();
+        let id = contract.queue_action(ProposalAction::SetFeeBps(1_000), now + delay).unwrap();
+        contract.cancel_action(id).unwrap();
+        test_env.advance_block_time(delay);
+        assert!(contract.execute_action(id).is_err());
+        assert_eq!(contract.fee_bps(), 500);
+        
+        // Lowering the delay goes through the queue as well
+        let now = test_env.block_time();
+        let id = contract.queue_action(ProposalAction::SetTimelockDelay(0), now + delay).unwrap();
+        test_env.advance_block_time(delay);
+        contract.execute_action(id).unwrap();
+        assert_eq!(contract.timelock_delay(), 0);
+        contract.set_fee_bps(100).unwrap();
     }
 
-    // Feature: casper-liquid-staking, Property 4: CEP-18 Transfer Conservation
-    proptest! {
-        #[test]
-        fn test_transfer_conservation(
-            sender_balance in 1u64..1_000_000u64,
-            recipient_balance in 0u64..1_000_000u64,
-            transfer_amount in 1u64..1_000_000u64
-        ) {
-            // Only test valid transfers (amount <= sender_balance)
-            prop_assume!(transfer_amount <= sender_balance);
-            
-            let (test_env, mut contract, sender, recipient) = setup_contract_with_balances(sender_balance, recipient_balance);
-            
-            // Record initial balances and total supply
-            let initial_sender_balance = contract.balance_of(&sender);
-            let initial_recipient_balance = contract.balance_of(&recipient);
-            let initial_total_supply = contract.total_supply();
-            let initial_sum = initial_sender_balance + initial_recipient_balance;
-            
-            // Set the caller to sender for the transfer
-            test_env.set_caller(sender);
-            
-            // Perform transfer
-            let result = contract.transfer(&recipient, U256::from(transfer_amount));
-            
-            // Transfer should succeed for valid amounts
-            prop_assert!(result.is_ok());
-            
-            // Check final balances
-            let final_sender_balance = contract.balance_of(&sender);
-            let final_recipient_balance = contract.balance_of(&recipient);
-            let final_total_supply = contract.total_supply();
-            let final_sum = final_sender_balance + final_recipient_balance;
-            
-            // Property: Sum of sender and recipient balances should remain constant
-            prop_assert_eq!(initial_sum, final_sum);
-            
-            // Property: Total supply should remain unchanged
-            prop_assert_eq!(initial_total_supply, final_total_supply);
-            
-            // Property: Balances should change by exactly the transfer amount
-            prop_assert_eq!(final_sender_balance, initial_sender_balance - U256::from(transfer_amount));
-            prop_assert_eq!(final_recipient_balance, initial_recipient_balance + U256::from(transfer_amount));
+    #[test]
+    fn test_timelock_delays_setters_and_proposals() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let anyone = test_env.get_account(1);
+        let delay = 86_400_000;
+        
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        test_env.advance_block_time(1_000);
+        contract.set_timelock_delay(delay).unwrap();
+        
+        // Direct changes are rejected while the delay applies
+        match contract.set_limits(U256::from(10), U256::zero(), U256::zero()).unwrap_err() {
+            Error::TimelockRequired => {},
+            _ => panic!("Expected TimelockRequired error"),
+        }
+        match contract.grant_role(Role::Minter, admin).unwrap_err() {
+            Error::TimelockRequired => {},
+            _ => panic!("Expected TimelockRequired error"),
+        }
+        
+        // A passed proposal is queued instead of applied
+        let proposal = contract.create_proposal([2u8; 32], vec![ProposalAction::SetFeeBps(500)]).unwrap();
+        contract.vote(proposal, true).unwrap();
+        test_env.advance_block_time(contract.voting_period() + 1);
+        contract.execute(proposal).unwrap();
+        assert_eq!(contract.fee_bps(), 0);
+        let queued = contract.get_queued_action(0).unwrap();
+        assert_eq!(queued.action, ProposalAction::SetFeeBps(500));
+        
+        // Anyone runs it once the delay has passed
+        test_env.set_caller(anyone);
+        match contract.execute_action(0).unwrap_err() {
+            Error::TimelockNotReady => {},
+            _ => panic!("Expected TimelockNotReady error"),
         }
+        test_env.advance_block_time(delay);
+        contract.execute_action(0).unwrap();
+        assert_eq!(contract.fee_bps(), 500);
     }
 
-    // Unit tests for CEP-18 edge cases
-    
     #[test]
-    fn test_transfer_insufficient_balance() {
-        let (test_env, mut contract, sender, recipient) = setup_contract_with_balances(100, 0);
-        test_env.set_caller(sender);
+    fn test_multisig_admin_actions() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let signers = vec![test_env.get_account(1), test_env.get_account(2), test_env.get_account(3)];
         
-        // Try to transfer more than balance
-        let result = contract.transfer(&recipient, U256::from(101));
+        match contract.set_multisig(signers.clone(), 4).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
+        }
+        contract.set_multisig(signers.clone(), 2).unwrap();
+        assert_eq!(contract.multisig_threshold(), 2);
         
-        // Should fail with insufficient balance error
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            Error::InsufficientBalance => {},
-            _ => panic!("Expected InsufficientBalance error"),
+        // A single admin can no longer change protected settings or grant roles
+        match contract.set_fee_bps(500).unwrap_err() {
+            Error::MultisigRequired => {},
+            _ => panic!("Expected MultisigRequired error"),
+        }
+        for result in [
+            contract.grant_role(Role::Minter, admin),
+            contract.set_oracle_committee(Vec::new(), 0),
+            contract.cover_loss(U256::from(1)),
+            contract.set_limits(U256::from(10), U256::zero(), U256::zero()),
+            contract.set_flash_mint_config(true, 100),
+        ] {
+            match result.unwrap_err() {
+                Error::MultisigRequired => {},
+                _ => panic!("Expected MultisigRequired error"),
+            }
         }
+        assert!(!contract.has_role(Role::Minter, admin));
+        assert!(contract.propose_admin_action(ProposalAction::SetFeeBps(500)).is_err());
         
-        // Balances should remain unchanged
-        assert_eq!(contract.balance_of(&sender), U256::from(100));
-        assert_eq!(contract.balance_of(&recipient), U256::zero());
+        test_env.set_caller(signers[0]);
+        let id = contract.propose_admin_action(ProposalAction::SetFeeBps(500)).unwrap();
+        assert_eq!(contract.get_multisig_action(id).unwrap().approvals, 1);
+        assert_eq!(contract.fee_bps(), 0);
+        match contract.approve_action(id).unwrap_err() {
+            Error::AlreadyApproved => {},
+            _ => panic!("Expected AlreadyApproved error"),
+        }
+        
+        // The approval reaching the threshold executes the action
+        test_env.set_caller(signers[1]);
+        contract.approve_action(id).unwrap();
+        assert_eq!(contract.fee_bps(), 500);
+        assert!(contract.get_multisig_action(id).unwrap().executed);
+        test_env.set_caller(signers[2]);
+        match contract.approve_action(id).unwrap_err() {
+            Error::MultisigActionNotPending => {},
+            _ => panic!("Expected MultisigActionNotPending error"),
+        }
+        
+        // Roles are granted by the signers as well
+        let id = contract.propose_admin_action(ProposalAction::GrantRole(Role::Minter, signers[2])).unwrap();
+        test_env.set_caller(signers[0]);
+        contract.approve_action(id).unwrap();
+        assert!(contract.has_role(Role::Minter, signers[2]));
+        test_env.set_caller(signers[2]);
+        
+        // Actions expire if the threshold is not reached in time
+        let id = contract.propose_admin_action(ProposalAction::SetFeeBps(1_000)).unwrap();
+        test_env.advance_block_time(multisig::ACTION_EXPIRY + 1);
+        test_env.set_caller(signers[0]);
+        match contract.approve_action(id).unwrap_err() {
+            Error::MultisigActionExpired => {},
+            _ => panic!("Expected MultisigActionExpired error"),
+        }
+        
+        // Disabling the multisig also needs the signers
+        let id = contract.propose_admin_action(ProposalAction::SetMultisig(Vec::new(), 0)).unwrap();
+        test_env.set_caller(signers[2]);
+        contract.approve_action(id).unwrap();
+        test_env.set_caller(admin);
+        contract.set_fee_bps(100).unwrap();
     }
 
     #[test]
-    fn test_transfer_zero_amount() {
-        let (test_env, mut contract, sender, recipient) = setup_contract_with_balances(100, 0);
-        test_env.set_caller(sender);
+    fn test_multisig_cancels_queued_actions() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let signers = vec![test_env.get_account(1), test_env.get_account(2)];
+        contract.set_timelock_delay(1_000).unwrap();
+        contract.set_multisig(signers.clone(), 2).unwrap();
         
-        // Try to transfer zero amount
-        let result = contract.transfer(&recipient, U256::zero());
+        // The approved fee change waits in the timelock
+        test_env.set_caller(signers[0]);
+        let id = contract.propose_admin_action(ProposalAction::SetFeeBps(300)).unwrap();
+        test_env.set_caller(signers[1]);
+        contract.approve_action(id).unwrap();
+        let queued = contract.get_queued_action(0).unwrap();
+        assert_eq!(queued.action, ProposalAction::SetFeeBps(300));
         
-        // Should fail with invalid amount error
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            Error::InvalidAmount => {},
-            _ => panic!("Expected InvalidAmount error"),
+        // A single admin cannot veto it
+        test_env.set_caller(admin);
+        match contract.cancel_action(0).unwrap_err() {
+            Error::MultisigRequired => {},
+            _ => panic!("Expected MultisigRequired error"),
         }
         
-        // Balances should remain unchanged
-        assert_eq!(contract.balance_of(&sender), U256::from(100));
-        assert_eq!(contract.balance_of(&recipient), U256::zero());
+        // The signers cancel it right away, without waiting for the delay
+        test_env.set_caller(signers[1]);
+        let id = contract.propose_admin_action(ProposalAction::CancelQueuedAction(0)).unwrap();
+        test_env.set_caller(signers[0]);
+        contract.approve_action(id).unwrap();
+        assert!(contract.get_queued_action(0).unwrap().cancelled);
+        test_env.advance_block_time(1_000);
+        test_env.set_caller(admin);
+        match contract.execute_action(0).unwrap_err() {
+            Error::TimelockActionNotPending => {},
+            _ => panic!("Expected TimelockActionNotPending error"),
+        }
+        assert_eq!(contract.fee_bps(), 0);
     }
 
     #[test]
-    fn test_transfer_to_self() {
-        let (test_env, mut contract, sender, _) = setup_contract_with_balances(100, 0);
-        test_env.set_caller(sender);
+    fn test_stake_with_referral() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let staker = test_env.get_account(0);
+        let partner = test_env.get_account(1);
         
-        // Try to transfer to self
-        let result = contract.transfer(&sender, U256::from(50));
+        contract.with_tokens(U512::from(300)).stake_with_referral(U256::from(300), &partner).unwrap();
+        contract.with_tokens(U512::from(200)).stake_with_referral(U256::from(200), &partner).unwrap();
         
-        // Should fail with self transfer error
-        assert!(result.is_err());
-        match result.unwrap_err() {
+        assert_eq!(contract.balance_of(&staker), U256::from(500));
+        assert_eq!(contract.referred_volume(&partner), U256::from(500));
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &ReferralRecorded {
+                referrer: partner,
+                staker,
+                cspr_amount: U256::from(200),
+                total_referred: U256::from(500),
+            }
+        ));
+        
+        // Self-referral is rejected
+        match contract.with_tokens(U512::from(100)).stake_with_referral(U256::from(100), &staker).unwrap_err() {
             Error::SelfTransfer => {},
             _ => panic!("Expected SelfTransfer error"),
         }
+    }
+
+    #[test]
+    fn test_integrator_earns_fee_share_on_referred_stake() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let wallet = test_env.get_account(1);
+        let staker = test_env.get_account(2);
+        let treasury = test_env.get_account(3);
+        contract.set_fee_bps(1_000).unwrap();
+        contract.set_treasury(treasury).unwrap();
+        
+        match contract.set_integrator(wallet, 5_001, U256::zero()).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
+        }
+        contract.set_integrator(wallet, 5_000, U256::from(10_000_000_000u64)).unwrap();
+        assert_eq!(contract.integrators(), vec![wallet]);
         
-        // Balance should remain unchanged
-        assert_eq!(contract.balance_of(&sender), U256::from(100));
+        // Half the stCSPR is held by the wallet's staker
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        test_env.set_caller(staker);
+        contract
+            .with_tokens(U512::from(1_000_000_000_000u64))
+            .stake_with_referral(U256::from(1_000_000_000_000u64), &wallet)
+            .unwrap();
+        assert_eq!(contract.referrer_of(staker), Some(wallet));
+        assert_eq!(contract.referred_shares(wallet), U256::from(1_000_000_000_000u64));
+        
+        // A 100 CSPR reward takes a 10 CSPR fee; the wallet earns half of the half on its stakers
+        test_env.set_caller(admin);
+        contract.report_rewards(U256::from(100_000_000_000u64)).unwrap();
+        let fee_shares = contract.balance_of(treasury) + contract.integrator_fees_owed(wallet);
+        assert_eq!(contract.integrator_fees_owed(wallet), fee_shares / 4);
+        
+        // Moving stCSPR away from referred stakers moves it out of the referred total
+        test_env.set_caller(staker);
+        contract.transfer(&admin, U256::from(400_000_000_000u64)).unwrap();
+        assert_eq!(contract.referred_shares(wallet), U256::from(600_000_000_000u64));
+        
+        // Earnings stop at the cap
+        test_env.set_caller(admin);
+        contract.report_rewards(U256::from(2_000_000_000_000u64)).unwrap();
+        assert_eq!(contract.integrator_fees_owed(wallet), U256::from(10_000_000_000u64));
+        
+        test_env.set_caller(wallet);
+        assert_eq!(contract.claim_integrator_fees().unwrap(), U256::from(10_000_000_000u64));
+        assert_eq!(contract.balance_of(wallet), U256::from(10_000_000_000u64));
+        match contract.claim_integrator_fees().unwrap_err() {
+            Error::InvalidAmount => {},
+            _ => panic!("Expected InvalidAmount error"),
+        }
     }
 
     #[test]
-    fn test_approval_mechanism() {
-        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(100, 0);
-        test_env.set_caller(owner);
+    fn test_validator_application_queue() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let applicant = test_env.get_account(1);
+        let validator = test_env.get_validator(0);
+        let other_validator = test_env.get_validator(1);
+        let uri = "ipfs://validator-profile".to_string();
+        let bond = contract.application_bond();
+        assert_eq!(bond, U512::from(applications::DEFAULT_APPLICATION_BOND));
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
         
-        // Initially no allowance
-        assert_eq!(contract.allowance(&owner, &spender), U256::zero());
+        // Applications carry exactly the bond
+        test_env.set_caller(applicant);
+        match contract.with_tokens(U512::one()).apply_as_validator(validator.clone(), uri.clone()).unwrap_err() {
+            Error::AttachedValueMismatch => {},
+            _ => panic!("Expected AttachedValueMismatch error"),
+        }
+        let id = contract.with_tokens(bond).apply_as_validator(validator.clone(), uri.clone()).unwrap();
+        match contract.with_tokens(bond).apply_as_validator(validator.clone(), uri.clone()).unwrap_err() {
+            Error::ValidatorAlreadyExists => {},
+            _ => panic!("Expected ValidatorAlreadyExists error"),
+        }
         
-        // Approve spender
-        let result = contract.approve(&spender, U256::from(50));
-        assert!(result.is_ok());
+        // Bonds are held apart from the liquidity buffer
+        assert_eq!(contract.current_buffer(), U512::from(1_000_000_000_000u64));
         
-        // Check allowance was set
-        assert_eq!(contract.allowance(&owner, &spender), U256::from(50));
+        // Approval registers the validator and refunds the bond
+        match contract.approve_application(id).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        test_env.set_caller(admin);
+        contract.approve_application(id).unwrap();
+        assert!(contract.validators().contains(&validator));
+        assert_eq!(test_env.balance_of(contract.address()), U512::from(1_000_000_000_000u64));
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &applications::ApplicationApproved { id, validator: validator.clone() }
+        ));
+        match contract.approve_application(id).unwrap_err() {
+            Error::ApplicationNotPending => {},
+            _ => panic!("Expected ApplicationNotPending error"),
+        }
         
-        // Approve different amount (should overwrite)
-        let result = contract.approve(&spender, U256::from(75));
-        assert!(result.is_ok());
-        assert_eq!(contract.allowance(&owner, &spender), U256::from(75));
+        // A rejected application's bond goes to the insurance fund
+        test_env.set_caller(applicant);
+        let id = contract.with_tokens(bond).apply_as_validator(other_validator.clone(), uri).unwrap();
+        test_env.set_caller(admin);
+        contract.reject_application(id).unwrap();
+        assert!(!contract.validators().contains(&other_validator));
+        assert_eq!(contract.insurance_balance(), U256::from(applications::DEFAULT_APPLICATION_BOND));
+        match contract.reject_application(id + 1).unwrap_err() {
+            Error::ApplicationNotFound => {},
+            _ => panic!("Expected ApplicationNotFound error"),
+        }
     }
 
     #[test]
-    fn test_transfer_from_success() {
-        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(100, 0);
-        let recipient = test_env.get_account(2);
+    fn test_delegate_and_undelegate() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let validator = test_env.get_validator(0);
+        let stake_amount = 1_000_000_000_000u64; // 1,000 CSPR
+        let delegate_amount = U512::from(600_000_000_000u64); // 600 CSPR
         
-        // Owner approves spender
-        test_env.set_caller(owner);
-        contract.approve(&spender, U256::from(50)).unwrap();
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(stake_amount)).unwrap();
         
-        // Spender transfers from owner to recipient
-        test_env.set_caller(spender);
-        let result = contract.transfer_from(&owner, &recipient, U256::from(30));
-        assert!(result.is_ok());
+        test_env.set_caller(operator);
+        contract.add_validator(validator.clone()).unwrap();
         
-        // Check balances
-        assert_eq!(contract.balance_of(&owner), U256::from(70));
-        assert_eq!(contract.balance_of(&recipient), U256::from(30));
+        // Delegating to an unknown validator fails
+        let other_validator = test_env.get_validator(1);
+        match contract.delegate_to(other_validator, delegate_amount).unwrap_err() {
+            Error::ValidatorNotFound => {},
+            _ => panic!("Expected ValidatorNotFound error"),
+        }
         
-        // Check remaining allowance
-        assert_eq!(contract.allowance(&owner, &spender), U256::from(20));
+        // Delegation moves CSPR out of the purse but not out of custody
+        contract.delegate_to(validator.clone(), delegate_amount).unwrap();
+        assert_eq!(contract.delegated_amount(validator.clone()), delegate_amount);
+        assert_eq!(contract.total_delegated(), delegate_amount);
+        assert_eq!(test_env.balance_of(contract.address()), U512::from(400_000_000_000u64));
+        assert_eq!(contract.contract_cspr_balance(), U256::from(stake_amount));
+        
+        // Instant unstake is limited to the liquid CSPR left in the purse
+        test_env.set_caller(user);
+        match contract.unstake(U256::from(500_000_000_000u64)).unwrap_err() {
+            Error::InsufficientLiquidity => {},
+            _ => panic!("Expected InsufficientLiquidity error"),
+        }
+        
+        // CSPR reserved for withdrawal requests cannot be delegated
+        contract.request_unstake(U256::from(300_000_000_000u64)).unwrap();
+        test_env.set_caller(operator);
+        match contract.delegate_to(validator.clone(), U512::from(200_000_000_000u64)).unwrap_err() {
+            Error::InsufficientLiquidity => {},
+            _ => panic!("Expected InsufficientLiquidity error"),
+        }
+        
+        // A validator with delegations cannot be removed
+        test_env.set_caller(operator);
+        match contract.remove_validator(validator.clone()).unwrap_err() {
+            Error::ValidatorHasDelegations => {},
+            _ => panic!("Expected ValidatorHasDelegations error"),
+        }
+        
+        contract.undelegate_from(validator.clone(), delegate_amount).unwrap();
+        assert_eq!(contract.delegated_amount(validator.clone()), U512::zero());
+        assert!(contract.remove_validator(validator).is_ok());
     }
 
     #[test]
-    fn test_transfer_from_insufficient_allowance() {
-        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(100, 0);
-        let recipient = test_env.get_account(2);
+    fn test_redelegate_between_validators() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let (from, to) = (test_env.get_validator(0), test_env.get_validator(1));
         
-        // Owner approves spender for less than transfer amount
-        test_env.set_caller(owner);
-        contract.approve(&spender, U256::from(30)).unwrap();
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
         
-        // Spender tries to transfer more than allowance
-        test_env.set_caller(spender);
-        let result = contract.transfer_from(&owner, &recipient, U256::from(50));
+        test_env.set_caller(operator);
+        contract.add_validator(from.clone()).unwrap();
+        contract.delegate_to(from.clone(), U512::from(600_000_000_000u64)).unwrap();
         
-        // Should fail with insufficient allowance
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            Error::InsufficientAllowance => {},
-            _ => panic!("Expected InsufficientAllowance error"),
+        // The validators must differ and the target must be registered
+        match contract.redelegate(from.clone(), to.clone(), U512::from(1_000_000_000u64)).unwrap_err() {
+            Error::ValidatorNotFound => {},
+            _ => panic!("Expected ValidatorNotFound error"),
+        }
+        contract.add_validator(to.clone()).unwrap();
+        match contract.redelegate(from.clone(), from.clone(), U512::from(1_000_000_000u64)).unwrap_err() {
+            Error::InvalidAddress => {},
+            _ => panic!("Expected InvalidAddress error"),
         }
         
-        // Balances should remain unchanged
-        assert_eq!(contract.balance_of(&owner), U256::from(100));
-        assert_eq!(contract.balance_of(&recipient), U256::zero());
-        assert_eq!(contract.allowance(&owner, &spender), U256::from(30));
+        // Only the operator can move delegations
+        test_env.set_caller(user);
+        match contract.redelegate(from.clone(), to.clone(), U512::from(100_000_000_000u64)).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        // The new delegation is paid from free CSPR while the old one unbonds
+        test_env.set_caller(operator);
+        match contract.redelegate(from.clone(), to.clone(), U512::from(500_000_000_000u64)).unwrap_err() {
+            Error::InsufficientLiquidity => {},
+            _ => panic!("Expected InsufficientLiquidity error"),
+        }
+        contract.redelegate(from.clone(), to.clone(), U512::from(300_000_000_000u64)).unwrap();
+        assert_eq!(contract.delegated_amount(from.clone()), U512::from(300_000_000_000u64));
+        assert_eq!(contract.delegated_amount(to.clone()), U512::from(300_000_000_000u64));
+        assert_eq!(contract.total_delegated(), U512::from(600_000_000_000u64));
+        assert_eq!(contract.current_buffer(), U512::from(100_000_000_000u64));
+        assert_eq!(contract.invariant_report().unbonding, U512::from(300_000_000_000u64));
     }
 
     #[test]
-    fn test_transfer_from_insufficient_balance() {
-        let (test_env, mut contract, owner, spender) = setup_contract_with_balances(50, 0);
-        let recipient = test_env.get_account(2);
+    fn test_liquidity_buffer_rebalance() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let validator = test_env.get_validator(0);
         
-        // Owner approves spender for more than balance
-        test_env.set_caller(owner);
-        contract.approve(&spender, U256::from(100)).unwrap();
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
         
-        // Spender tries to transfer more than owner's balance
-        test_env.set_caller(spender);
-        let result = contract.transfer_from(&owner, &recipient, U256::from(75));
+        test_env.set_caller(operator);
+        contract.add_validator(validator.clone()).unwrap();
+        contract.set_buffer_target_bps(1_000).unwrap(); // keep 10% liquid
+        assert_eq!(contract.target_buffer(), U512::from(100_000_000_000u64));
         
-        // Should fail with insufficient balance
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            Error::InsufficientBalance => {},
-            _ => panic!("Expected InsufficientBalance error"),
+        match contract.set_buffer_target_bps(BPS_DENOMINATOR + 1).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
         }
         
-        // Balances and allowance should remain unchanged
-        assert_eq!(contract.balance_of(&owner), U256::from(50));
-        assert_eq!(contract.balance_of(&recipient), U256::zero());
-        assert_eq!(contract.allowance(&owner, &spender), U256::from(100));
-    }
-
-    // Feature: casper-liquid-staking, Property 1: Stake/Unstake Round Trip Consistency (Complete)
-    proptest! {
-        #[test]
-        fn test_stake_unstake_round_trip_consistency(
-            stake_amount in 1u64..1_000_000u64
-        ) {
-            let test_env = odra_test::env();
-            let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-            let user = test_env.get_account(0);
-            
-            // Set caller to user
-            test_env.set_caller(user);
-            
-            // Record initial state
-            let initial_balance = contract.balance_of(&user);
-            let initial_total_supply = contract.total_supply();
-            let initial_contract_balance = contract.contract_cspr_balance();
-            
-            // Perform stake operation
-            let stake_result = contract.stake(U256::from(stake_amount));
-            prop_assert!(stake_result.is_ok());
-            
-            // Record state after staking
-            let after_stake_balance = contract.balance_of(&user);
-            let after_stake_total_supply = contract.total_supply();
-            let after_stake_contract_balance = contract.contract_cspr_balance();
-            
-            // Verify staking worked correctly
-            prop_assert_eq!(after_stake_balance, initial_balance + U256::from(stake_amount));
-            prop_assert_eq!(after_stake_total_supply, initial_total_supply + U256::from(stake_amount));
-            prop_assert_eq!(after_stake_contract_balance, initial_contract_balance + U256::from(stake_amount));
-            
-            // Now unstake the same amount
-            let unstake_result = contract.unstake(U256::from(stake_amount));
-            prop_assert!(unstake_result.is_ok());
-            
-            // Record final state
-            let final_balance = contract.balance_of(&user);
-            let final_total_supply = contract.total_supply();
-            let final_contract_balance = contract.contract_cspr_balance();
-            
-            // Property: Round trip should return to original state
-            prop_assert_eq!(final_balance, initial_balance);
-            prop_assert_eq!(final_total_supply, initial_total_supply);
-            prop_assert_eq!(final_contract_balance, initial_contract_balance);
-            
-            // Property: Stake then unstake should be identity operation
-            prop_assert_eq!(final_balance, initial_balance);
-            prop_assert_eq!(final_total_supply, initial_total_supply);
+        // Everything above the target is delegated
+        contract.rebalance_buffer(validator.clone()).unwrap();
+        assert_eq!(contract.current_buffer(), U512::from(100_000_000_000u64));
+        assert_eq!(contract.delegated_amount(validator.clone()), U512::from(900_000_000_000u64));
+        
+        // Unstakes up to the buffer are paid instantly, larger ones must be queued
+        test_env.set_caller(user);
+        assert!(contract.unstake(U256::from(50_000_000_000u64)).is_ok());
+        match contract.unstake(U256::from(60_000_000_000u64)).unwrap_err() {
+            Error::InsufficientLiquidity => {},
+            _ => panic!("Expected InsufficientLiquidity error"),
         }
+        assert!(contract.request_unstake(U256::from(60_000_000_000u64)).is_ok());
+        
+        // A shortfall is undelegated from the validator
+        test_env.set_caller(operator);
+        contract.rebalance_buffer(validator.clone()).unwrap();
+        assert!(contract.delegated_amount(validator) < U512::from(900_000_000_000u64));
     }
 
-    // Unit tests for stake function edge cases
-    
     #[test]
-    fn test_stake_zero_amount() {
+    fn test_weighted_validator_rebalance() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let user = test_env.get_account(0);
-        
-        test_env.set_caller(user);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let validator_a = test_env.get_validator(0);
+        let validator_b = test_env.get_validator(1);
         
-        // Try to stake zero amount
-        let result = contract.stake(U256::zero());
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        contract.add_validator(validator_a.clone()).unwrap();
+        contract.add_validator(validator_b.clone()).unwrap();
+        contract.set_buffer_target_bps(1_000).unwrap(); // keep 10% liquid
         
-        // Should fail with invalid amount error
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            Error::InvalidAmount => {},
-            _ => panic!("Expected InvalidAmount error"),
+        // Weights must target registered validators
+        match contract.set_validator_weight(test_env.get_validator(2), 1).unwrap_err() {
+            Error::ValidatorNotFound => {},
+            _ => panic!("Expected ValidatorNotFound error"),
         }
         
-        // Balance and total supply should remain unchanged
-        assert_eq!(contract.balance_of(&user), U256::zero());
-        assert_eq!(contract.total_supply(), U256::zero());
+        contract.set_validator_weight(validator_a.clone(), 2).unwrap();
+        contract.set_validator_weight(validator_b.clone(), 1).unwrap();
+        
+        let allocation = contract.current_allocation();
+        assert_eq!(allocation[0].target, U512::from(600_000_000_000u64));
+        assert_eq!(allocation[1].target, U512::from(300_000_000_000u64));
+        
+        contract.rebalance().unwrap();
+        assert_eq!(contract.delegated_amount(validator_a.clone()), U512::from(600_000_000_000u64));
+        assert_eq!(contract.delegated_amount(validator_b.clone()), U512::from(300_000_000_000u64));
+        assert_eq!(contract.current_buffer(), U512::from(100_000_000_000u64));
+        
+        // Shifting weight undelegates from the validator now above target
+        contract.set_validator_weight(validator_a.clone(), 1).unwrap();
+        contract.rebalance().unwrap();
+        assert_eq!(contract.delegated_amount(validator_a.clone()), U512::from(450_000_000_000u64));
+        
+        // The unbonding CSPR still counts, so a second run leaves the delegations alone
+        contract.rebalance().unwrap();
+        assert_eq!(contract.delegated_amount(validator_a), U512::from(450_000_000_000u64));
+        assert_eq!(contract.delegated_amount(validator_b), U512::from(300_000_000_000u64));
     }
 
     #[test]
-    fn test_stake_multiple_users() {
+    fn test_flush_deposits_batches_delegations() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let user1 = test_env.get_account(0);
-        let user2 = test_env.get_account(1);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let staker = test_env.get_account(0);
+        let validator_a = test_env.get_validator(0);
+        let validator_b = test_env.get_validator(1);
         
-        // User 1 stakes 100 CSPR
-        test_env.set_caller(user1);
-        let result1 = contract.stake(U256::from(100));
-        assert!(result1.is_ok());
+        contract.add_validator(validator_a.clone()).unwrap();
+        contract.add_validator(validator_b.clone()).unwrap();
+        contract.set_validator_weight(validator_a.clone(), 2).unwrap();
+        contract.set_validator_weight(validator_b.clone(), 1).unwrap();
+        assert_eq!(contract.flush_threshold(), U512::from(MIN_DELEGATION));
         
-        // User 2 stakes 200 CSPR
-        test_env.set_caller(user2);
-        let result2 = contract.stake(U256::from(200));
-        assert!(result2.is_ok());
+        // Stakes are buffered without touching the auction
+        stake_with_cspr(&mut contract, U256::from(300_000_000_000u64)).unwrap();
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &DepositBuffered {
+                staker,
+                amount: U512::from(300_000_000_000u64),
+                pending: U512::from(300_000_000_000u64),
+            }
+        ));
+        assert_eq!(contract.total_delegated(), U512::zero());
+        match contract.flush_deposits().unwrap_err() {
+            Error::BelowFlushThreshold => {},
+            _ => panic!("Expected BelowFlushThreshold error"),
+        }
         
-        // Check individual balances
-        assert_eq!(contract.balance_of(&user1), U256::from(100));
-        assert_eq!(contract.balance_of(&user2), U256::from(200));
+        stake_with_cspr(&mut contract, U256::from(300_000_000_000u64)).unwrap();
+        assert_eq!(contract.pending_deposits(), U512::from(600_000_000_000u64));
         
-        // Check total supply
-        assert_eq!(contract.total_supply(), U256::from(300));
+        contract.flush_deposits().unwrap();
+        assert_eq!(contract.delegated_amount(validator_a), U512::from(400_000_000_000u64));
+        assert_eq!(contract.delegated_amount(validator_b), U512::from(200_000_000_000u64));
+        assert_eq!(contract.pending_deposits(), U512::zero());
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &DepositsFlushed {
+                amount: U512::from(600_000_000_000u64),
+                pending: U512::zero(),
+            }
+        ));
+        
+        // Instant unstakes are paid from the buffer and stop counting as pending
+        stake_with_cspr(&mut contract, U256::from(300_000_000_000u64)).unwrap();
+        contract.unstake(U256::from(100_000_000_000u64)).unwrap();
+        assert_eq!(contract.pending_deposits(), U512::from(200_000_000_000u64));
     }
 
     #[test]
-    fn test_stake_accumulation() {
+    fn test_buffer_policy_follows_withdrawal_volume() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let user = test_env.get_account(0);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let validator = test_env.get_validator(0);
         
-        test_env.set_caller(user);
+        contract.add_validator(validator.clone()).unwrap();
+        contract.set_validator_weight(validator.clone(), 1).unwrap();
+        contract.set_flush_threshold(U512::one()).unwrap();
+        match contract.set_buffer_policy(BPS_DENOMINATOR + 1, 0).unwrap_err() {
+            Error::ExceedsMaximum => {},
+            _ => panic!("Expected ExceedsMaximum error"),
+        }
+        // Half-weight average, with one era of withdrawals kept liquid
+        contract.set_buffer_policy(5_000, 10_000).unwrap();
         
-        // Stake multiple times
-        contract.stake(U256::from(50)).unwrap();
-        contract.stake(U256::from(75)).unwrap();
-        contract.stake(U256::from(25)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(1_000_000_000_000u64)).unwrap();
+        contract.unstake(U256::from(200_000_000_000u64)).unwrap();
+        assert_eq!(contract.target_buffer(), U512::zero());
         
-        // Check accumulated balance
-        assert_eq!(contract.balance_of(&user), U256::from(150));
-        assert_eq!(contract.total_supply(), U256::from(150));
+        // The next upkeep folds the finished era into the average and keeps it liquid
+        test_env.advance_block_time(ERA_DURATION);
+        contract.flush_deposits().unwrap();
+        assert_eq!(contract.withdrawal_ewma(), U512::from(100_000_000_000u64));
+        assert_eq!(contract.target_buffer(), U512::from(100_000_000_000u64));
+        assert_eq!(contract.delegated_amount(validator.clone()), U512::from(700_000_000_000u64));
+        assert_eq!(contract.current_buffer(), U512::from(100_000_000_000u64));
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &BufferRebalanced {
+                validator,
+                delegated: U512::from(700_000_000_000u64),
+                undelegated: U512::zero(),
+                buffer: U512::from(100_000_000_000u64),
+            }
+        ));
+        
+        // Quiet eras decay the average again
+        test_env.advance_block_time(ERA_DURATION);
+        stake_with_cspr(&mut contract, U256::from(100_000_000_000u64)).unwrap();
+        contract.flush_deposits().unwrap();
+        assert_eq!(contract.withdrawal_ewma(), U512::from(50_000_000_000u64));
     }
 
-    // Unit tests for unstake function edge cases
-    
     #[test]
-    fn test_unstake_zero_amount() {
+    fn test_report_loss_lowers_rate() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let user = test_env.get_account(0);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let operator = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let validator = test_env.get_validator(0);
         
         test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000_000)).unwrap();
         
-        // First stake some tokens
-        contract.stake(U256::from(100)).unwrap();
-        
-        // Try to unstake zero amount
-        let result = contract.unstake(U256::zero());
+        test_env.set_caller(operator);
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(800_000)).unwrap();
         
-        // Should fail with invalid amount error
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            Error::InvalidAmount => {},
-            _ => panic!("Expected InvalidAmount error"),
+        // Losses cannot exceed the validator's delegation
+        match contract.report_loss(U256::from(900_000), validator.clone()).unwrap_err() {
+            Error::InsufficientBalance => {},
+            _ => panic!("Expected InsufficientBalance error"),
         }
         
-        // Balance and total supply should remain unchanged
-        assert_eq!(contract.balance_of(&user), U256::from(100));
-        assert_eq!(contract.total_supply(), U256::from(100));
+        contract.report_loss(U256::from(100_000), validator.clone()).unwrap();
+        assert_eq!(contract.total_pooled_cspr(), U256::from(900_000));
+        assert_eq!(contract.delegated_amount(validator), U512::from(700_000));
+        assert_eq!(contract.exchange_rate(), U256::from(RATE_PRECISION) * 9 / 10);
+        assert_eq!(contract.preview_unstake(U256::from(1_000)), U256::from(900));
     }
 
     #[test]
-    fn test_unstake_insufficient_balance() {
+    fn test_insurance_covers_loss() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let user = test_env.get_account(0);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let treasury = test_env.get_account(2);
+        let validator = test_env.get_validator(0);
+        
+        contract.set_fee_bps(1_000).unwrap(); // 10% of rewards
+        contract.set_treasury(treasury).unwrap();
+        contract.set_insurance_share_bps(5_000).unwrap(); // half of the fee
         
         test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000_000)).unwrap();
         
-        // Stake some tokens
-        contract.stake(U256::from(50)).unwrap();
+        // 100,000 rewards: 10,000 fee, 5,000 of which funds insurance
+        test_env.set_caller(admin);
+        contract.report_rewards(U256::from(100_000)).unwrap();
+        assert_eq!(contract.insurance_balance(), U256::from(5_000));
+        assert_eq!(contract.total_pooled_cspr(), U256::from(1_095_000));
+        assert_eq!(contract.coverage_ratio(), U256::from(45)); // 0.45%
         
-        // Try to unstake more than balance
-        let result = contract.unstake(U256::from(75));
+        // A loss the insurance fund can absorb leaves the rate alone
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(500_000)).unwrap();
+        let rate_before = contract.exchange_rate();
+        contract.report_loss(U256::from(4_000), validator.clone()).unwrap();
+        assert_eq!(contract.exchange_rate(), rate_before);
+        assert_eq!(contract.insurance_balance(), U256::from(1_000));
+        assert_eq!(contract.total_pooled_cspr(), U256::from(1_095_000));
         
-        // Should fail with insufficient balance error
-        assert!(result.is_err());
-        match result.unwrap_err() {
+        // Only the part beyond the fund lowers the rate
+        contract.report_loss(U256::from(3_000), validator).unwrap();
+        assert_eq!(contract.insurance_balance(), U256::zero());
+        assert_eq!(contract.total_pooled_cspr(), U256::from(1_093_000));
+        assert!(contract.exchange_rate() < rate_before);
+        match contract.cover_loss(U256::from(1)).unwrap_err() {
             Error::InsufficientBalance => {},
             _ => panic!("Expected InsufficientBalance error"),
         }
         
-        // Balance and total supply should remain unchanged
-        assert_eq!(contract.balance_of(&user), U256::from(50));
-        assert_eq!(contract.total_supply(), U256::from(50));
+        // Instant unstakes cannot spend the insurance fund's CSPR in the purse
+        contract.report_rewards(U256::from(100_000)).unwrap();
+        assert_eq!(contract.insurance_balance(), U256::from(5_000));
+        assert_eq!(contract.current_buffer(), U512::from(495_000));
+        assert_eq!(contract.get_obligations().buffered, U512::from(495_000));
+        test_env.set_caller(user);
+        match contract.unstake(contract.preview_stake(U256::from(495_500))).unwrap_err() {
+            Error::InsufficientLiquidity => {},
+            _ => panic!("Expected InsufficientLiquidity error"),
+        }
+        contract.unstake(contract.preview_stake(U256::from(494_000))).unwrap();
     }
 
     #[test]
-    fn test_unstake_exact_balance() {
+    fn test_unbonding_shortfall_draws_insurance_then_cuts_the_batch() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let user = test_env.get_account(0);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let user = test_env.get_account(1);
+        let treasury = test_env.get_account(2);
+        let validator = test_env.get_validator(0);
         
+        contract.set_fee_bps(1_000).unwrap();
+        contract.set_treasury(treasury).unwrap();
+        contract.set_insurance_share_bps(5_000).unwrap();
         test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000_000)).unwrap();
+        test_env.set_caller(admin);
+        contract.report_rewards(U256::from(100_000)).unwrap();
+        assert_eq!(contract.insurance_balance(), U256::from(5_000));
+        contract.add_validator(validator.clone()).unwrap();
+        contract.delegate_to(validator.clone(), U512::from(500_000)).unwrap();
         
-        // Stake tokens
-        contract.stake(U256::from(100)).unwrap();
+        // Both requests wait for the next undelegation, which pays them
+        test_env.set_caller(user);
+        let first = contract.request_unstake(U256::from(100_000)).unwrap();
+        let second = contract.request_unstake(U256::from(300_000)).unwrap();
+        test_env.set_caller(admin);
+        contract.undelegate_from(validator, U512::from(300_000)).unwrap();
+        let era = contract.current_era() + contract.unbonding_delay_eras() + 1;
+        let batch = contract.unbonding_batch(era).unwrap();
+        assert_eq!(batch.expected, U512::from(300_000));
+        assert_eq!(batch.requests, vec![first, second]);
+        match contract.settle_unbonding_batch(era, U512::from(280_000)).unwrap_err() {
+            Error::UnbondingNotFinished => {},
+            _ => panic!("Expected UnbondingNotFinished error"),
+        }
         
-        // Unstake exact balance
-        let result = contract.unstake(U256::from(100));
-        assert!(result.is_ok());
+        // 20,000 was slashed while unbonding: insurance covers 5,000, and the
+        // requests bear the rest in proportion to their share of the batch
+        test_env.advance_block_time((contract.unbonding_delay_eras() + 1) * ERA_DURATION);
+        let (a, b) = (
+            contract.get_withdrawal(first).unwrap().amount,
+            contract.get_withdrawal(second).unwrap().amount,
+        );
+        let requests_share = U256::from(15_000) * (a + b) / U256::from(300_000);
+        let (cut_a, cut_b) = (requests_share * a / (a + b), requests_share * b / (a + b));
+        let pooled = contract.total_pooled_cspr();
+        contract.settle_unbonding_batch(era, U512::from(280_000)).unwrap();
         
-        // Balance should be zero
-        assert_eq!(contract.balance_of(&user), U256::zero());
+        assert_eq!(contract.insurance_balance(), U256::zero());
+        assert_eq!(contract.get_withdrawal(first).unwrap().amount, a - cut_a);
+        assert_eq!(contract.get_withdrawal(second).unwrap().amount, b - cut_b);
+        assert_eq!(contract.pending_withdrawals(), a + b - cut_a - cut_b);
+        let written_off = U256::from(15_000) - cut_a - cut_b;
+        assert_eq!(contract.total_pooled_cspr(), pooled - written_off);
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &BatchSettled {
+                era,
+                expected: U512::from(300_000),
+                returned: U512::from(280_000),
+                covered: U256::from(5_000),
+                haircut: cut_a + cut_b,
+                written_off,
+            }
+        ));
+        assert_eq!(contract.unbonding_batch(era).unwrap().returned, U512::from(280_000));
+        match contract.settle_unbonding_batch(era, U512::from(280_000)).unwrap_err() {
+            Error::BatchNotFound => {},
+            _ => panic!("Expected BatchNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_sunset_forces_positions_out_before_retirement() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let alice = test_env.get_account(0);
+        let bob = test_env.get_account(1);
+        let keeper = test_env.get_account(2);
+        let exit_window = 7 * 86_400_000;
+        
+        stake_with_cspr(&mut contract, U256::from(600)).unwrap();
+        contract.transfer(&bob, U256::from(200)).unwrap();
+        test_env.advance_block_time(1_000);
+        match contract.retire().unwrap_err() {
+            Error::SunsetNotDue => {},
+            _ => panic!("Expected SunsetNotDue error"),
+        }
+        
+        // Only a passed proposal starts the sunset
+        let id = contract.create_proposal([1u8; 32], vec![ProposalAction::StartSunset(exit_window)]).unwrap();
+        contract.vote(id, true).unwrap();
+        test_env.advance_block_time(contract.voting_period() + 1);
+        contract.execute(id).unwrap();
+        let started_at = contract.sunset_started_at().unwrap();
+        assert_eq!(contract.forced_exit_at(), Some(started_at + exit_window));
+        
+        test_env.set_caller(bob);
+        match stake_with_cspr(&mut contract, U256::from(100)).unwrap_err() {
+            Error::SunsetActive => {},
+            _ => panic!("Expected SunsetActive error"),
+        }
+        
+        // Holders leave on their own during the exit window; the rest is forced out after it
+        test_env.set_caller(keeper);
+        match contract.force_exit(&bob).unwrap_err() {
+            Error::SunsetNotDue => {},
+            _ => panic!("Expected SunsetNotDue error"),
+        }
+        test_env.set_caller(alice);
+        let alice_request = contract.request_unstake(U256::from(400)).unwrap();
+        test_env.advance_block_time(exit_window);
+        test_env.set_caller(keeper);
+        let bob_request = contract.force_exit(&bob).unwrap();
+        assert_eq!(contract.get_withdrawal(bob_request).unwrap().owner, bob);
         assert_eq!(contract.total_supply(), U256::zero());
-        assert_eq!(contract.contract_cspr_balance(), U256::zero());
+        assert!(test_env.emitted_event(
+            contract.address(),
+            &ForcedExit {
+                holder: bob,
+                request_id: bob_request,
+                shares: U256::from(200),
+                keeper,
+            }
+        ));
+        
+        // Retirement waits for every request to be claimed
+        test_env.set_caller(alice);
+        match contract.retire().unwrap_err() {
+            Error::SupplyOutstanding => {},
+            _ => panic!("Expected SupplyOutstanding error"),
+        }
+        test_env.advance_block_time(contract.unbonding_period());
+        contract.claim_withdrawal(alice_request).unwrap();
+        test_env.set_caller(bob);
+        contract.claim_withdrawal(bob_request).unwrap();
+        
+        test_env.set_caller(alice);
+        contract.retire().unwrap();
+        assert!(contract.retired());
+        assert!(contract.paused());
+        match contract.unpause().unwrap_err() {
+            Error::ContractRetired => {},
+            _ => panic!("Expected ContractRetired error"),
+        }
+        match contract.retire().unwrap_err() {
+            Error::ContractRetired => {},
+            _ => panic!("Expected ContractRetired error"),
+        }
     }
 
     #[test]
-    fn test_unstake_partial_balance() {
+    fn test_sunset_waives_protocol_fees() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
-        let user = test_env.get_account(0);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let treasury = test_env.get_account(1);
         
-        test_env.set_caller(user);
+        contract.set_fee_bps(1_000).unwrap();
+        contract.set_treasury(treasury).unwrap();
+        contract.set_insurance_share_bps(5_000).unwrap();
+        stake_with_cspr(&mut contract, U256::from(1_000_000)).unwrap();
+        test_env.advance_block_time(1_000);
         
-        // Stake tokens
-        contract.stake(U256::from(100)).unwrap();
+        let id = contract.create_proposal([1u8; 32], vec![ProposalAction::StartSunset(0)]).unwrap();
+        contract.vote(id, true).unwrap();
+        test_env.advance_block_time(contract.voting_period() + 1);
+        contract.execute(id).unwrap();
         
-        // Unstake partial balance
-        let result = contract.unstake(U256::from(30));
-        assert!(result.is_ok());
+        // The whole report goes to the remaining holders
+        contract.report_rewards(U256::from(100_000)).unwrap();
+        assert_eq!(contract.balance_of(&treasury), U256::zero());
+        assert_eq!(contract.insurance_balance(), U256::zero());
+        assert_eq!(contract.total_pooled_cspr(), U256::from(1_100_000));
         
-        // Check remaining balance
-        assert_eq!(contract.balance_of(&user), U256::from(70));
-        assert_eq!(contract.total_supply(), U256::from(70));
-        assert_eq!(contract.contract_cspr_balance(), U256::from(70));
+        // A sunset cannot be restarted to move the exit window
+        let id = contract.create_proposal([2u8; 32], vec![ProposalAction::StartSunset(0)]).unwrap();
+        contract.vote(id, true).unwrap();
+        test_env.advance_block_time(contract.voting_period() + 1);
+        match contract.execute(id).unwrap_err() {
+            Error::SunsetActive => {},
+            _ => panic!("Expected SunsetActive error"),
+        }
     }
 
     #[test]
     fn test_unstake_multiple_users() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user1 = test_env.get_account(0);
         let user2 = test_env.get_account(1);
         
         // Both users stake
         test_env.set_caller(user1);
-        contract.stake(U256::from(100)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
         
         test_env.set_caller(user2);
-        contract.stake(U256::from(200)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(200)).unwrap();
         
         // User1 unstakes
         test_env.set_caller(user1);
@@ -921,10 +8511,220 @@ mod tests {
         assert_eq!(contract.contract_cspr_balance(), U256::from(250));
     }
 
+    #[test]
+    fn test_unstake_from_with_approval() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let owner = test_env.get_account(0);
+        let operator = test_env.get_account(1);
+        
+        test_env.set_caller(owner);
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
+        contract.approve_unstake(&operator, U256::from(60)).unwrap();
+        
+        // Operator exits part of the owner's position within budget
+        test_env.set_caller(operator);
+        assert!(contract.unstake_from(&owner, U256::from(40)).is_ok());
+        assert_eq!(contract.balance_of(&owner), U256::from(60));
+        assert_eq!(contract.balance_of(&operator), U256::zero());
+        assert_eq!(contract.unstake_allowance(&owner, &operator), U256::from(20));
+        assert_eq!(contract.total_supply(), U256::from(60));
+        
+        // Exceeding the remaining budget fails
+        match contract.unstake_from(&owner, U256::from(30)).unwrap_err() {
+            Error::InsufficientAllowance => {},
+            _ => panic!("Expected InsufficientAllowance error"),
+        }
+        
+        // A transfer allowance does not grant unstake rights
+        test_env.set_caller(owner);
+        contract.approve(&operator, U256::from(60)).unwrap();
+        contract.approve_unstake(&operator, U256::zero()).unwrap();
+        test_env.set_caller(operator);
+        assert!(contract.unstake_from(&owner, U256::from(10)).is_err());
+    }
+
+    #[test]
+    fn test_withdrawal_request_and_claim() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let user = test_env.get_account(1);
+        let other = test_env.get_account(2);
+        
+        test_env.set_caller(user);
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        
+        // Requesting burns the shares and queues the CSPR owed
+        let id = contract.request_unstake(U256::from(400)).unwrap();
+        assert_eq!(contract.balance_of(&user), U256::from(600));
+        assert_eq!(contract.total_supply(), U256::from(600));
+        assert_eq!(contract.pending_withdrawals(), U256::from(400));
+        assert_eq!(contract.list_withdrawals(&user).len(), 1);
+        assert!(contract.claimable_at(id).is_some());
+        
+        // Claiming before the unbonding period elapses fails
+        match contract.claim_withdrawal(id).unwrap_err() {
+            Error::UnbondingNotFinished => {},
+            _ => panic!("Expected UnbondingNotFinished error"),
+        }
+        
+        test_env.advance_block_time(contract.unbonding_period());
+        
+        // Only the owner can claim
+        test_env.set_caller(other);
+        match contract.claim_withdrawal(id).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        
+        test_env.set_caller(user);
+        let user_cspr_before = test_env.balance_of(&user);
+        contract.claim_withdrawal(id).unwrap();
+        assert_eq!(test_env.balance_of(&user), user_cspr_before + U512::from(400));
+        assert_eq!(contract.pending_withdrawals(), U256::zero());
+        assert!(contract.list_withdrawals(&user).is_empty());
+        assert!(contract.get_withdrawal(id).unwrap().claimed);
+        
+        // A request can only be claimed once
+        match contract.claim_withdrawal(id).unwrap_err() {
+            Error::WithdrawalAlreadyClaimed => {},
+            _ => panic!("Expected WithdrawalAlreadyClaimed error"),
+        }
+    }
+
+    #[test]
+    fn test_large_withdrawals_are_held_for_review() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let admin = test_env.get_account(0);
+        let guardian = test_env.get_account(1);
+        let whale = test_env.get_account(2);
+        let day = 86_400_000;
+        
+        contract.grant_role(Role::Guardian, guardian).unwrap();
+        contract.set_withdrawal_review(2_000, day, 7 * day).unwrap();
+        test_env.set_caller(whale);
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        
+        // Small requests are not reviewed, large instant unstakes are refused
+        let small = contract.request_unstake(U256::from(100)).unwrap();
+        assert_eq!(contract.review_ends_at(small), None);
+        match contract.unstake(U256::from(300)).unwrap_err() {
+            Error::ReviewRequired => {},
+            _ => panic!("Expected ReviewRequired error"),
+        }
+        
+        // Unflagged requests are released when the window closes
+        let released = contract.request_unstake(U256::from(300)).unwrap();
+        let flagged = contract.request_unstake(U256::from(250)).unwrap();
+        let review_ends_at = test_env.block_time() + day;
+        assert_eq!(contract.review_ends_at(released), Some(review_ends_at));
+        assert_eq!(contract.claimable_at(released), Some(review_ends_at));
+        
+        // Only guardians flag, and only while the window is open
+        match contract.flag_withdrawal(flagged).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        test_env.set_caller(guardian);
+        match contract.flag_withdrawal(small).unwrap_err() {
+            Error::NotUnderReview => {},
+            _ => panic!("Expected NotUnderReview error"),
+        }
+        contract.flag_withdrawal(flagged).unwrap();
+        assert!(contract.withdrawal_flagged(flagged));
+        assert_eq!(contract.claimable_at(flagged), Some(test_env.block_time() + 7 * day));
+        
+        test_env.advance_block_time(day);
+        test_env.set_caller(whale);
+        contract.claim_withdrawal(released).unwrap();
+        match contract.claim_withdrawal(flagged).unwrap_err() {
+            Error::UnbondingNotFinished => {},
+            _ => panic!("Expected UnbondingNotFinished error"),
+        }
+        
+        // Disabling the review lets large instant unstakes through again
+        test_env.set_caller(admin);
+        contract.set_withdrawal_review(0, 0, 0).unwrap();
+        test_env.set_caller(whale);
+        contract.unstake(U256::from(100)).unwrap();
+    }
+
+    #[test]
+    fn test_withdrawal_requests_are_transferable() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let seller = test_env.get_account(1);
+        let market = test_env.get_account(2);
+        let buyer = test_env.get_account(3);
+        
+        test_env.set_caller(seller);
+        stake_with_cspr(&mut contract, U256::from(1_000)).unwrap();
+        let id = contract.request_unstake(U256::from(400)).unwrap();
+        let second = contract.request_unstake(U256::from(100)).unwrap();
+        let third = contract.request_unstake(U256::from(100)).unwrap();
+        
+        // Requests can be listed a page at a time
+        let page = contract.list_withdrawals_page(&seller, 1, 5);
+        assert_eq!(page.iter().map(|request| request.id).collect::<Vec<_>>(), vec![second, third]);
+        assert_eq!(contract.list_withdrawals_page(&seller, 0, 1)[0].id, id);
+        assert!(contract.list_withdrawals_page(&seller, 3, 5).is_empty());
+        
+        // Approvals cannot change while the contract is paused
+        test_env.set_caller(test_env.get_account(0));
+        contract.pause().unwrap();
+        test_env.set_caller(seller);
+        match contract.approve_withdrawal(id, Some(market)).unwrap_err() {
+            Error::ContractPaused => {},
+            _ => panic!("Expected ContractPaused error"),
+        }
+        test_env.set_caller(test_env.get_account(0));
+        contract.unpause().unwrap();
+        
+        // Only the owner approves, and only the owner or the approved address transfers
+        test_env.set_caller(market);
+        match contract.transfer_withdrawal(id, buyer).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        match contract.approve_withdrawal(id, Some(market)).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        test_env.set_caller(seller);
+        contract.approve_withdrawal(id, Some(market)).unwrap();
+        assert_eq!(contract.withdrawal_approved(id), Some(market));
+        
+        test_env.set_caller(market);
+        contract.transfer_withdrawal(id, buyer).unwrap();
+        assert_eq!(contract.get_withdrawal(id).unwrap().owner, buyer);
+        assert_eq!(contract.withdrawal_approved(id), None);
+        assert_eq!(contract.list_withdrawals(&seller).len(), 2);
+        assert_eq!(contract.list_withdrawals(&buyer).len(), 1);
+        
+        // The new owner claims the CSPR once unbonded
+        test_env.advance_block_time(contract.unbonding_period());
+        test_env.set_caller(seller);
+        match contract.claim_withdrawal(id).unwrap_err() {
+            Error::NotAuthorized => {},
+            _ => panic!("Expected NotAuthorized error"),
+        }
+        test_env.set_caller(buyer);
+        let buyer_cspr_before = test_env.balance_of(&buyer);
+        contract.claim_withdrawal(id).unwrap();
+        assert_eq!(test_env.balance_of(&buyer), buyer_cspr_before + U512::from(400));
+        
+        // Claimed requests cannot change hands
+        match contract.transfer_withdrawal(id, seller).unwrap_err() {
+            Error::WithdrawalAlreadyClaimed => {},
+            _ => panic!("Expected WithdrawalAlreadyClaimed error"),
+        }
+    }
+
     #[test]
     fn test_supply_consistency_validation() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         // Initially, supply should be consistent (both zero)
@@ -932,7 +8732,7 @@ mod tests {
         
         // After staking, supply should still be consistent
         test_env.set_caller(user);
-        contract.stake(U256::from(100)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
         assert!(contract.validate_supply_consistency());
         
         // After unstaking, supply should still be consistent
@@ -947,7 +8747,7 @@ mod tests {
     #[test]
     fn test_total_supply_accuracy() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user1 = test_env.get_account(0);
         let user2 = test_env.get_account(1);
         
@@ -956,12 +8756,12 @@ mod tests {
         
         // After user1 stakes
         test_env.set_caller(user1);
-        contract.stake(U256::from(100)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
         assert_eq!(contract.total_supply(), U256::from(100));
         
         // After user2 stakes
         test_env.set_caller(user2);
-        contract.stake(U256::from(200)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(200)).unwrap();
         assert_eq!(contract.total_supply(), U256::from(300));
         
         // After user1 unstakes partially
@@ -978,7 +8778,7 @@ mod tests {
     #[test]
     fn test_balance_tracking_accuracy() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user1 = test_env.get_account(0);
         let user2 = test_env.get_account(1);
         let user3 = test_env.get_account(2);
@@ -990,14 +8790,14 @@ mod tests {
         
         // User1 stakes
         test_env.set_caller(user1);
-        contract.stake(U256::from(100)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
         assert_eq!(contract.balance_of(&user1), U256::from(100));
         assert_eq!(contract.balance_of(&user2), U256::zero());
         assert_eq!(contract.balance_of(&user3), U256::zero());
         
         // User2 stakes
         test_env.set_caller(user2);
-        contract.stake(U256::from(200)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(200)).unwrap();
         assert_eq!(contract.balance_of(&user1), U256::from(100));
         assert_eq!(contract.balance_of(&user2), U256::from(200));
         assert_eq!(contract.balance_of(&user3), U256::zero());
@@ -1024,7 +8824,7 @@ mod tests {
             )
         ) {
             let test_env = odra_test::env();
-            let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+            let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
             let user1 = test_env.get_account(0);
             let user2 = test_env.get_account(1);
             let user3 = test_env.get_account(2);
@@ -1042,7 +8842,7 @@ mod tests {
                 match op_type % 3 {
                     0 => {
                         // Stake operation
-                        let result = contract.stake(U256::from(amount));
+                        let result = stake_with_cspr(&mut contract, U256::from(amount));
                         if result.is_ok() {
                             expected_balances[user_idx] += U256::from(amount);
                             expected_total_supply += U256::from(amount);
@@ -1118,13 +8918,13 @@ mod tests {
             view_calls in 1u32..100u32 // Number of view function calls to make
         ) {
             let test_env = odra_test::env();
-            let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+            let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
             let users: Vec<Address> = (0..initial_stakes.len()).map(|i| test_env.get_account(i)).collect();
             
             // Set up initial state with some stakes
             for (i, &stake_amount) in initial_stakes.iter().enumerate() {
                 test_env.set_caller(users[i]);
-                let _ = contract.stake(U256::from(stake_amount));
+                let _ = stake_with_cspr(&mut contract, U256::from(stake_amount));
             }
             
             // Record the complete state before view function calls
@@ -1212,7 +9012,7 @@ mod tests {
             prop_assume!(unstake_amount <= stake_amount);
             
             let test_env = odra_test::env();
-            let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+            let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
             let user = test_env.get_account(0);
             
             // Set caller to user
@@ -1222,7 +9022,7 @@ mod tests {
             let initial_contract_balance = contract.contract_cspr_balance();
             
             // Perform stake operation
-            let stake_result = contract.stake(U256::from(stake_amount));
+            let stake_result = stake_with_cspr(&mut contract, U256::from(stake_amount));
             prop_assert!(stake_result.is_ok());
             
             // Check contract CSPR balance after staking
@@ -1259,18 +9059,18 @@ mod tests {
             balance_amount in 1u64..1000u64,
         ) {
             let test_env = odra_test::env();
-            let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+            let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
             let user1 = test_env.get_account(0);
             let user2 = test_env.get_account(1);
             
             // Set up initial state
             test_env.set_caller(user1);
             if balance_amount > 0 {
-                let _ = contract.stake(U256::from(balance_amount));
+                let _ = stake_with_cspr(&mut contract, U256::from(balance_amount));
             }
             
             // Property: Zero amounts should always be rejected for stake operations
-            let zero_stake_result = contract.stake(U256::from(zero_amount));
+            let zero_stake_result = stake_with_cspr(&mut contract, U256::from(zero_amount));
             prop_assert!(zero_stake_result.is_err());
             match zero_stake_result.unwrap_err() {
                 Error::InvalidAmount => {}, // Expected error
@@ -1364,13 +9164,13 @@ mod tests {
             )
         ) {
             let test_env = odra_test::env();
-            let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+            let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
             let user1 = test_env.get_account(0);
             let user2 = test_env.get_account(1);
             
             // Set up initial state
             test_env.set_caller(user1);
-            let _ = contract.stake(U256::from(initial_stake));
+            let _ = stake_with_cspr(&mut contract, U256::from(initial_stake));
             
             for (op_type, amount) in operations {
                 // Record state before operation
@@ -1385,7 +9185,7 @@ mod tests {
                     0 => {
                         // Stake operation - might fail if amount is too large
                         test_env.set_caller(user1);
-                        contract.stake(U256::from(amount))
+                        stake_with_cspr(&mut contract, U256::from(amount))
                     },
                     1 => {
                         // Unstake operation - might fail if insufficient balance
@@ -1490,36 +9290,69 @@ mod tests {
             prop_assume!(transfer_amount <= stake_amount);
             
             let test_env = odra_test::env();
-            let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+            let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
             let user1 = test_env.get_account(0);
             let user2 = test_env.get_account(1);
             
+            let address = *contract.address();
+            
             // Test stake operation event emission
             test_env.set_caller(user1);
-            let stake_result = contract.stake(U256::from(stake_amount));
+            let since = event_count(&test_env, &address);
+            let stake_result = stake_with_cspr(&mut contract, U256::from(stake_amount));
             prop_assert!(stake_result.is_ok(), "Stake operation should succeed");
             
-            // Property: Successful stake should emit both StakeEvent and Transfer event
-            // Note: In a real test environment, we would check the emitted events
-            // For this property test, we verify the operation succeeded and state is consistent
+            // Property: Successful stake emits StakeEvent, Mint and DepositBuffered, in that order
+            assert_events_since(&test_env, &address, since, &[
+                event(StakeEvent {
+                    user: user1,
+                    payer: user1,
+                    cspr_amount: U256::from(stake_amount),
+                    stcspr_minted: U256::from(stake_amount),
+                    timestamp: test_env.block_time(),
+                    rate: U256::from(RATE_PRECISION),
+                }),
+                event(Mint { recipient: user1, amount: U256::from(stake_amount) }),
+                event(DepositBuffered {
+                    staker: user1,
+                    amount: U512::from(stake_amount),
+                    pending: U512::from(stake_amount),
+                }),
+            ]);
             prop_assert_eq!(contract.balance_of(&user1), U256::from(stake_amount));
             prop_assert_eq!(contract.total_supply(), U256::from(stake_amount));
             
             // Test unstake operation event emission
+            let since = event_count(&test_env, &address);
             let unstake_result = contract.unstake(U256::from(unstake_amount));
             prop_assert!(unstake_result.is_ok(), "Unstake operation should succeed");
             
-            // Property: Successful unstake should emit both UnstakeEvent and Transfer event
+            // Property: Successful unstake emits UnstakeEvent then Burn
+            assert_events_since(&test_env, &address, since, &[
+                event(UnstakeEvent {
+                    user: user1,
+                    recipient: user1,
+                    stcspr_burned: U256::from(unstake_amount),
+                    cspr_returned: U256::from(unstake_amount),
+                    timestamp: test_env.block_time(),
+                    rate: U256::from(RATE_PRECISION),
+                }),
+                event(Burn { owner: user1, amount: U256::from(unstake_amount) }),
+            ]);
             let expected_remaining = stake_amount - unstake_amount;
             prop_assert_eq!(contract.balance_of(&user1), U256::from(expected_remaining));
             prop_assert_eq!(contract.total_supply(), U256::from(expected_remaining));
             
             // Test transfer operation event emission (if user has sufficient balance)
             if transfer_amount <= expected_remaining && transfer_amount > 0 {
+                let since = event_count(&test_env, &address);
                 let transfer_result = contract.transfer(&user2, U256::from(transfer_amount));
                 prop_assert!(transfer_result.is_ok(), "Transfer operation should succeed");
                 
-                // Property: Successful transfer should emit Transfer event
+                // Property: Successful transfer emits exactly one Transfer event
+                assert_events_since(&test_env, &address, since, &[
+                    event(Transfer { sender: user1, recipient: user2, amount: U256::from(transfer_amount) }),
+                ]);
                 let expected_user1_balance = expected_remaining - transfer_amount;
                 prop_assert_eq!(contract.balance_of(&user1), U256::from(expected_user1_balance));
                 prop_assert_eq!(contract.balance_of(&user2), U256::from(transfer_amount));
@@ -1527,19 +9360,32 @@ mod tests {
             }
             
             // Test approval operation event emission
+            let since = event_count(&test_env, &address);
             let approval_result = contract.approve(&user2, U256::from(approval_amount));
             prop_assert!(approval_result.is_ok(), "Approval operation should succeed");
             
-            // Property: Successful approval should emit Approval event
+            // Property: Successful approval emits exactly one SetAllowance event
+            assert_events_since(&test_env, &address, since, &[
+                event(SetAllowance { owner: user1, spender: user2, allowance: U256::from(approval_amount) }),
+            ]);
             prop_assert_eq!(contract.allowance(&user1, &user2), U256::from(approval_amount));
             
             // Test transfer_from operation event emission (if allowance and balance sufficient)
             if approval_amount > 0 && approval_amount <= contract.balance_of(&user1) {
                 test_env.set_caller(user2);
+                let since = event_count(&test_env, &address);
                 let transfer_from_result = contract.transfer_from(&user1, &user2, U256::from(approval_amount));
                 prop_assert!(transfer_from_result.is_ok(), "Transfer from operation should succeed");
                 
-                // Property: Successful transfer_from should emit Transfer event
+                // Property: Successful transfer_from emits exactly one TransferFrom event
+                assert_events_since(&test_env, &address, since, &[
+                    event(TransferFrom {
+                        spender: user2,
+                        owner: user1,
+                        recipient: user2,
+                        amount: U256::from(approval_amount),
+                    }),
+                ]);
                 let remaining_allowance = contract.allowance(&user1, &user2);
                 prop_assert_eq!(remaining_allowance, U256::zero()); // Allowance should be consumed
             }
@@ -0,0 +1,141 @@
+use odra::casper_types::bytesrepr::ToBytes;
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::Error;
+
+/// Event emitted when a new merkle root is posted, starting a new drop
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct MerkleRootSet {
+    pub drop_id: u64,
+    pub root: [u8; 32],
+}
+
+/// Event emitted when stCSPR is set aside for claims
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropFunded {
+    pub funder: Address,
+    pub amount: U256,
+    pub balance: U256,
+}
+
+/// Event emitted when an account claims its drop allocation
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropClaimed {
+    pub drop_id: u64,
+    pub index: u64,
+    pub account: Address,
+    pub amount: U256,
+}
+
+/// MerkleDrop - one-time claims of pre-funded stCSPR against a merkle root
+///
+/// Leaves are `blake2b(index ++ account ++ amount)` over their bytesrepr
+/// encodings, and each proof step hashes the sorted pair of nodes, so proofs do
+/// not need left/right flags. Posting a new root starts a new drop with fresh
+/// claim flags. Moving the stCSPR is the responsibility of the parent contract.
+#[odra::module]
+pub struct MerkleDrop {
+    /// Root of the current drop
+    root: Var<[u8; 32]>,
+    /// Id of the current drop, incremented by every new root
+    drop_id: Var<u64>,
+    /// Whether a leaf index has been claimed, by (drop id, index)
+    claimed: Mapping<(u64, u64), bool>,
+    /// stCSPR funded and not yet claimed
+    balance: Var<U256>,
+}
+
+#[odra::module]
+impl MerkleDrop {
+    /// Start a new drop with `root`
+    pub fn set_root(&mut self, root: [u8; 32]) {
+        let drop_id = self.drop_id.get_or_default() + 1;
+        self.drop_id.set(drop_id);
+        self.root.set(root);
+
+        self.env().emit_event(MerkleRootSet { drop_id, root });
+    }
+
+    /// Add funded stCSPR to the claimable balance
+    pub fn fund(&mut self, funder: Address, amount: U256) -> Result<(), Error> {
+        let balance = self
+            .balance()
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        self.balance.set(balance);
+
+        self.env().emit_event(DropFunded { funder, amount, balance });
+        Ok(())
+    }
+
+    /// Verify a claim of `amount` for `account` at leaf `index` and mark it claimed
+    pub fn claim(&mut self, index: u64, account: Address, amount: U256, proof: Vec<[u8; 32]>) -> Result<(), Error> {
+        // CHECKS: The leaf must be in the current drop, unclaimed and funded
+        let root = self.root.get().ok_or(Error::InvalidMerkleProof)?;
+        let drop_id = self.drop_id();
+        if self.is_claimed(index) {
+            return Err(Error::AlreadyClaimed);
+        }
+        if self.compute_root(self.leaf(index, &account, amount), &proof) != root {
+            return Err(Error::InvalidMerkleProof);
+        }
+        let balance = self
+            .balance()
+            .checked_sub(amount)
+            .ok_or(Error::InsufficientBalance)?;
+
+        // EFFECTS: Mark the leaf claimed before the parent pays it out
+        self.claimed.set(&(drop_id, index), true);
+        self.balance.set(balance);
+
+        self.env().emit_event(DropClaimed {
+            drop_id,
+            index,
+            account,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Check whether leaf `index` of the current drop has been claimed
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.claimed.get(&(self.drop_id(), index)).unwrap_or_default()
+    }
+
+    /// Get the root of the current drop
+    pub fn root(&self) -> Option<[u8; 32]> {
+        self.root.get()
+    }
+
+    /// Get the id of the current drop (zero before the first root)
+    pub fn drop_id(&self) -> u64 {
+        self.drop_id.get_or_default()
+    }
+
+    /// Get the funded stCSPR not yet claimed
+    pub fn balance(&self) -> U256 {
+        self.balance.get_or_default()
+    }
+
+    /// Hash a leaf the way the off-chain tree builder must
+    pub fn leaf(&self, index: u64, account: &Address, amount: U256) -> [u8; 32] {
+        // Serializing these types cannot fail
+        let bytes = (index, *account, amount).to_bytes().unwrap_or_default();
+        self.env().hash(bytes)
+    }
+
+    /// Fold a proof into the root it implies for `leaf`
+    fn compute_root(&self, leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+        proof.iter().fold(leaf, |node, sibling| {
+            let (low, high) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+            let mut pair = Vec::with_capacity(64);
+            pair.extend_from_slice(&low);
+            pair.extend_from_slice(&high);
+            self.env().hash(pair)
+        })
+    }
+}
@@ -0,0 +1,58 @@
+//! Conversions between token amounts and native motes
+//!
+//! Share and exchange rate math runs in `U256`, while the host's purse API
+//! counts CSPR in `U512` motes. Custody amounts (pooled CSPR, CSPR reserved for
+//! withdrawals, the insurance fund) are stored in `U512` so they compare
+//! directly with what the purse holds, and are converted at the boundary. A
+//! motes value too large for a `U256` is an error, never silently clamped.
+//!
+//! `U256` and `U512` share the same bytesrepr encoding, so values stored as
+//! `U256` by earlier versions read back unchanged as `U512`.
+
+use odra::casper_types::{U256, U512};
+
+use crate::Error;
+
+/// Convert a token amount to motes (every `U256` fits into a `U512`)
+pub fn to_motes(amount: U256) -> U512 {
+    let mut bytes = [0u8; 32];
+    amount.to_little_endian(&mut bytes);
+    U512::from_little_endian(&bytes)
+}
+
+/// Convert motes to a token amount, failing if they do not fit into a `U256`
+pub fn to_amount(motes: U512) -> Result<U256, Error> {
+    let mut bytes = [0u8; 64];
+    motes.to_little_endian(&mut bytes);
+    if bytes[32..].iter().any(|byte| *byte != 0) {
+        return Err(Error::MotesOutOfRange);
+    }
+    Ok(U256::from_little_endian(&bytes[..32]))
+}
+
+/// Add a token amount to a motes balance
+pub fn add(motes: U512, amount: U256) -> Result<U512, Error> {
+    motes.checked_add(to_motes(amount)).ok_or(Error::ArithmeticOverflow)
+}
+
+/// Subtract a token amount from a motes balance
+pub fn sub(motes: U512, amount: U256) -> Result<U512, Error> {
+    motes.checked_sub(to_motes(amount)).ok_or(Error::ArithmeticUnderflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_round_trip_and_reject_out_of_range() {
+        assert_eq!(to_motes(U256::from(1_000u64)), U512::from(1_000u64));
+        assert_eq!(to_amount(U512::from(1_000u64)).unwrap(), U256::from(1_000u64));
+        assert_eq!(to_amount(to_motes(U256::MAX)).unwrap(), U256::MAX);
+        assert!(matches!(to_amount(to_motes(U256::MAX) + U512::one()), Err(Error::MotesOutOfRange)));
+
+        assert_eq!(add(U512::from(5u64), U256::from(3u64)).unwrap(), U512::from(8u64));
+        assert!(matches!(sub(U512::from(5u64), U256::from(6u64)), Err(Error::ArithmeticUnderflow)));
+        assert!(matches!(add(U512::MAX, U256::one()), Err(Error::ArithmeticOverflow)));
+    }
+}
@@ -0,0 +1,181 @@
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::governance::ProposalAction;
+use crate::Error;
+
+/// Time signers have to approve an action (7 days, in milliseconds)
+pub const ACTION_EXPIRY: u64 = 604_800_000;
+
+/// An admin action waiting for signer approvals
+#[odra::odra_type]
+pub struct MultisigAction {
+    pub id: u64,
+    pub action: ProposalAction,
+    pub proposer: Address,
+    pub approvals: u32,
+    /// Block time after which the action can no longer be approved
+    pub expires_at: u64,
+    pub executed: bool,
+}
+
+/// Event emitted when the signer set or threshold changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultisigConfigured {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Event emitted when a signer proposes an admin action
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultisigActionProposed {
+    pub id: u64,
+    pub proposer: Address,
+    pub action: ProposalAction,
+    pub expires_at: u64,
+}
+
+/// Event emitted when a signer approves an admin action
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultisigActionApproved {
+    pub id: u64,
+    pub signer: Address,
+    pub approvals: u32,
+}
+
+/// Event emitted when an action reaches its threshold and is executed
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultisigActionExecuted {
+    pub id: u64,
+}
+
+/// Multisig - m-of-n approval of admin actions
+///
+/// A threshold of zero disables the multisig. Applying approved actions is the
+/// responsibility of the parent contract.
+#[odra::module]
+pub struct Multisig {
+    /// Accounts allowed to propose and approve actions
+    signers: Var<Vec<Address>>,
+    /// Approvals needed to execute an action
+    threshold: Var<u32>,
+    /// Id assigned to the next proposed action
+    next_id: Var<u64>,
+    /// All proposed actions by id
+    actions: Mapping<u64, MultisigAction>,
+    /// Whether a signer has approved an action
+    approved: Mapping<(u64, Address), bool>,
+}
+
+#[odra::module]
+impl Multisig {
+    /// Replace the signer set and threshold; a threshold of zero disables the multisig
+    pub fn configure(&mut self, signers: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        if threshold as usize > signers.len() {
+            return Err(Error::ExceedsMaximum);
+        }
+        for (index, signer) in signers.iter().enumerate() {
+            if signers[..index].contains(signer) {
+                return Err(Error::InvalidAddress);
+            }
+        }
+
+        self.signers.set(signers.clone());
+        self.threshold.set(threshold);
+        self.env().emit_event(MultisigConfigured { signers, threshold });
+        Ok(())
+    }
+
+    /// Propose an action on behalf of a signer; returns its id
+    pub fn propose(&mut self, proposer: Address, action: ProposalAction) -> Result<u64, Error> {
+        if !self.is_signer(&proposer) {
+            return Err(Error::NotAuthorized);
+        }
+
+        let id = self.next_id.get_or_default();
+        let expires_at = self.env().block_time().saturating_add(ACTION_EXPIRY);
+        self.next_id.set(id + 1);
+        self.actions.set(
+            &id,
+            MultisigAction {
+                id,
+                action: action.clone(),
+                proposer,
+                approvals: 0,
+                expires_at,
+                executed: false,
+            },
+        );
+
+        self.env().emit_event(MultisigActionProposed {
+            id,
+            proposer,
+            action,
+            expires_at,
+        });
+        Ok(id)
+    }
+
+    /// Record a signer's approval; returns the action once the threshold is reached
+    pub fn approve(&mut self, id: u64, signer: Address) -> Result<Option<ProposalAction>, Error> {
+        // CHECKS: Signer must not have approved and the action must still be open
+        if !self.is_signer(&signer) {
+            return Err(Error::NotAuthorized);
+        }
+        let mut pending = self.actions.get(&id).ok_or(Error::MultisigActionNotFound)?;
+        if pending.executed {
+            return Err(Error::MultisigActionNotPending);
+        }
+        if self.env().block_time() > pending.expires_at {
+            return Err(Error::MultisigActionExpired);
+        }
+        if self.has_approved(id, &signer) {
+            return Err(Error::AlreadyApproved);
+        }
+
+        // EFFECTS: Count the approval and mark the action executed at the threshold
+        pending.approvals += 1;
+        let approvals = pending.approvals;
+        let ready = approvals >= self.threshold();
+        pending.executed = ready;
+        let action = pending.action.clone();
+        self.actions.set(&id, pending);
+        self.approved.set(&(id, signer), true);
+
+        self.env().emit_event(MultisigActionApproved { id, signer, approvals });
+        if !ready {
+            return Ok(None);
+        }
+        self.env().emit_event(MultisigActionExecuted { id });
+        Ok(Some(action))
+    }
+
+    /// Get a proposed action by id
+    pub fn get(&self, id: u64) -> Option<MultisigAction> {
+        self.actions.get(&id)
+    }
+
+    /// Check whether a signer has approved an action
+    pub fn has_approved(&self, id: u64, signer: &Address) -> bool {
+        self.approved.get(&(id, *signer)).unwrap_or_default()
+    }
+
+    /// Check whether an account is a signer
+    pub fn is_signer(&self, account: &Address) -> bool {
+        self.signers().contains(account)
+    }
+
+    /// Get the signers
+    pub fn signers(&self) -> Vec<Address> {
+        self.signers.get_or_default()
+    }
+
+    /// Get the approvals needed to execute an action (zero means disabled)
+    pub fn threshold(&self) -> u32 {
+        self.threshold.get_or_default()
+    }
+}
@@ -0,0 +1,132 @@
+use odra::casper_types::bytesrepr::{Bytes, ToBytes};
+use odra::casper_types::PublicKey;
+use odra::prelude::*;
+use odra::{module::Module, Var};
+
+use crate::Error;
+
+/// Domain tag prefixed to every oracle report message
+pub const ORACLE_DOMAIN: &str = "CasperLiquid:oracle:v1";
+
+/// A pool update the oracle committee signs off on
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub enum OracleReport {
+    /// Staking rewards earned by the pool's delegations
+    Rewards(U256),
+    /// CSPR lost at a validator
+    Loss(U256, PublicKey),
+}
+
+/// Event emitted when the committee members or quorum change
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct OracleCommitteeConfigured {
+    pub members: Vec<PublicKey>,
+    pub quorum: u32,
+}
+
+/// Event emitted when a signed report is accepted
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct OracleReportAccepted {
+    pub nonce: u64,
+    pub report: OracleReport,
+    /// Number of distinct members whose signature verified
+    pub signatures: u32,
+}
+
+/// OracleCommittee - quorum-signed, nonced reward and loss reports
+///
+/// A quorum of zero disables the committee. Applying an accepted report to the
+/// pool is the responsibility of the parent contract.
+#[odra::module]
+pub struct OracleCommittee {
+    /// Public keys allowed to sign reports
+    members: Var<Vec<PublicKey>>,
+    /// Distinct member signatures a report needs
+    quorum: Var<u32>,
+    /// Nonce of the last accepted report
+    nonce: Var<u64>,
+}
+
+#[odra::module]
+impl OracleCommittee {
+    /// Replace the members and quorum; a quorum of zero disables the committee
+    pub fn configure(&mut self, members: Vec<PublicKey>, quorum: u32) -> Result<(), Error> {
+        if quorum as usize > members.len() {
+            return Err(Error::ExceedsMaximum);
+        }
+        for (index, member) in members.iter().enumerate() {
+            if members[..index].contains(member) {
+                return Err(Error::InvalidAddress);
+            }
+        }
+
+        self.members.set(members.clone());
+        self.quorum.set(quorum);
+        self.env().emit_event(OracleCommitteeConfigured { members, quorum });
+        Ok(())
+    }
+
+    /// Verify a report's nonce and signatures and consume the nonce
+    pub fn accept(&mut self, report: OracleReport, nonce: u64, signatures: Vec<(PublicKey, Bytes)>) -> Result<(), Error> {
+        // CHECKS: The nonce must be new and a quorum of members must have signed
+        if nonce <= self.nonce() {
+            return Err(Error::OracleNonceUsed);
+        }
+
+        let message = self.message(&report, nonce);
+        let members = self.members();
+        let mut signers: Vec<PublicKey> = Vec::new();
+        for (signer, signature) in signatures {
+            if !members.contains(&signer) || signers.contains(&signer) {
+                continue;
+            }
+            if self.env().verify_signature(&message, &signature, &signer) {
+                signers.push(signer);
+            }
+        }
+        if (signers.len() as u32) < self.quorum() {
+            return Err(Error::OracleQuorumNotReached);
+        }
+
+        // EFFECTS: Consume the nonce before the parent applies the report
+        self.nonce.set(nonce);
+        self.env().emit_event(OracleReportAccepted {
+            nonce,
+            report,
+            signatures: signers.len() as u32,
+        });
+        Ok(())
+    }
+
+    /// Build the message members sign for `report` under `nonce`
+    ///
+    /// The message binds the domain tag, this contract, the nonce and the report.
+    pub fn message(&self, report: &OracleReport, nonce: u64) -> Bytes {
+        let fields = (ORACLE_DOMAIN.to_string(), self.env().self_address(), nonce, report.clone());
+        // Serializing these types cannot fail; an empty message would never verify anyway
+        Bytes::from(fields.to_bytes().unwrap_or_default())
+    }
+
+    /// Check whether reports must go through the committee
+    pub fn is_enabled(&self) -> bool {
+        self.quorum() > 0
+    }
+
+    /// Get the committee members
+    pub fn members(&self) -> Vec<PublicKey> {
+        self.members.get_or_default()
+    }
+
+    /// Get the distinct member signatures a report needs
+    pub fn quorum(&self) -> u32 {
+        self.quorum.get_or_default()
+    }
+
+    /// Get the nonce of the last accepted report (zero before the first)
+    pub fn nonce(&self) -> u64 {
+        self.nonce.get_or_default()
+    }
+}
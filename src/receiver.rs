@@ -0,0 +1,63 @@
+//! Receiver hook for contracts that accept stCSPR
+//!
+//! A contract implementing `on_token_received` can be sent stCSPR together with
+//! a payload and act on it in the same deploy, e.g. a lending market crediting
+//! the deposit to the sender. `transfer_and_call` requires the hook on every
+//! contract recipient, so tokens cannot be stranded in a contract that does not
+//! expect them.
+
+use odra::casper_types::bytesrepr::Bytes;
+use odra::prelude::*;
+use odra::Address;
+
+/// Interface of a contract that accepts stCSPR with a payload
+#[odra::external_contract]
+pub trait TokenReceiver {
+    /// Called after `amount` stCSPR has been credited to the receiver.
+    /// `operator` made the call and `from` was debited; returning `false` rejects
+    /// the transfer and reverts it.
+    fn on_token_received(&mut self, operator: Address, from: Address, amount: U256, data: Bytes) -> bool;
+}
+
+/// Receiver used by the contract tests
+#[cfg(test)]
+pub(crate) mod mock {
+    use odra::casper_types::bytesrepr::Bytes;
+    use odra::prelude::*;
+    use odra::{module::Module, Address, Var};
+
+    /// Records the last stCSPR it was sent and accepts or rejects it as configured
+    #[odra::module]
+    pub struct MockReceiver {
+        accept: Var<bool>,
+        received: Var<U256>,
+        last_from: Var<Option<Address>>,
+        last_data: Var<Bytes>,
+    }
+
+    #[odra::module]
+    impl MockReceiver {
+        pub fn init(&mut self, accept: bool) {
+            self.accept.set(accept);
+        }
+
+        pub fn on_token_received(&mut self, _operator: Address, from: Address, amount: U256, data: Bytes) -> bool {
+            self.received.set(self.received.get_or_default() + amount);
+            self.last_from.set(Some(from));
+            self.last_data.set(data);
+            self.accept.get_or_default()
+        }
+
+        pub fn received(&self) -> U256 {
+            self.received.get_or_default()
+        }
+
+        pub fn last_from(&self) -> Option<Address> {
+            self.last_from.get().flatten()
+        }
+
+        pub fn last_data(&self) -> Bytes {
+            self.last_data.get_or_default()
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Dead-man's switch on the admin keys
+//!
+//! Admins show they still hold their keys by calling `heartbeat`. Once a
+//! recovery council is set, and no heartbeat has been seen for the recovery
+//! period, the council can take ownership and the admin role, so a lost owner
+//! key does not freeze the protocol's configuration for good. The council is
+//! usually a multisig account; governance can replace it with a `SetRecovery`
+//! proposal.
+
+use odra::prelude::*;
+use odra::{module::Module, Address, Var};
+
+use crate::Error;
+
+/// Shortest recovery period that can be configured (7 days, in milliseconds)
+pub const MIN_RECOVERY_PERIOD: u64 = 7 * 86_400_000;
+
+/// Event emitted when an admin records a heartbeat
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heartbeat {
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
+/// Event emitted when the recovery council or period changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecoveryConfigured {
+    pub council: Option<Address>,
+    pub period: u64,
+}
+
+/// Event emitted when the recovery council takes over from inactive admins
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdminRecovered {
+    pub council: Address,
+    pub previous_owner: Address,
+    /// Block time of the last heartbeat before the takeover
+    pub last_heartbeat: u64,
+}
+
+/// Recovery - admin heartbeats and the council that takes over when they stop
+///
+/// Moving ownership and roles is the responsibility of the parent contract.
+#[odra::module]
+pub struct Recovery {
+    /// Account that can take over once the heartbeats stop (none disables the switch)
+    council: Var<Option<Address>>,
+    /// Time without a heartbeat after which the council can take over, in milliseconds
+    period: Var<u64>,
+    /// Block time of the last heartbeat
+    last_heartbeat: Var<u64>,
+}
+
+#[odra::module]
+impl Recovery {
+    /// Get the recovery council, if the switch is armed
+    pub fn council(&self) -> Option<Address> {
+        self.council.get().flatten()
+    }
+
+    /// Get the time without a heartbeat after which the council can take over
+    pub fn period(&self) -> u64 {
+        self.period.get_or_default()
+    }
+
+    /// Get the block time of the last heartbeat
+    pub fn last_heartbeat(&self) -> u64 {
+        self.last_heartbeat.get_or_default()
+    }
+
+    /// Get the block time from which the council can take over, if the switch is armed
+    pub fn available_at(&self) -> Option<u64> {
+        self.council().map(|_| self.last_heartbeat().saturating_add(self.period()))
+    }
+
+    /// Set the council and period; `None` disarms the switch
+    ///
+    /// Arming the switch counts as a heartbeat of `admin`.
+    pub fn configure(&mut self, admin: Address, council: Option<Address>, period: u64) -> Result<(), Error> {
+        if council.is_some() && period < MIN_RECOVERY_PERIOD {
+            return Err(Error::InvalidAmount);
+        }
+
+        self.council.set(council);
+        self.period.set(period);
+        self.env().emit_event(RecoveryConfigured { council, period });
+        if council.is_some() {
+            self.beat(admin);
+        }
+        Ok(())
+    }
+
+    /// Record a heartbeat of `admin`
+    pub fn beat(&mut self, admin: Address) {
+        let timestamp = self.env().block_time();
+        self.last_heartbeat.set(timestamp);
+        self.env().emit_event(Heartbeat { admin, timestamp });
+    }
+
+    /// Check that `caller` is the council and the heartbeats have stopped
+    ///
+    /// Returns the block time of the last heartbeat. The period restarts from
+    /// now, so the council has to keep up the heartbeats in turn.
+    pub fn take_over(&mut self, caller: &Address) -> Result<u64, Error> {
+        if self.council() != Some(*caller) {
+            return Err(Error::NotAuthorized);
+        }
+        let last_heartbeat = self.last_heartbeat();
+        if self.env().block_time() < last_heartbeat.saturating_add(self.period()) {
+            return Err(Error::RecoveryNotDue);
+        }
+
+        self.last_heartbeat.set(self.env().block_time());
+        Ok(last_heartbeat)
+    }
+}
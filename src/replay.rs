@@ -0,0 +1,206 @@
+//! Replays of recorded event logs for regression tests
+//!
+//! A log written by the indexer (`--jsonl`, live or `backfill`) holds the
+//! stake, unstake, transfer and allowance events of a production contract.
+//! `replay` sends the calls that emitted them to a fresh contract, and
+//! `assert_snapshot` compares the outcome with a snapshot of the production
+//! state, so an incident can be turned into a test without hand-writing it.
+//!
+//! Recorded addresses are mapped to the test accounts in order of first
+//! appearance, starting at account 1; account 0 deploys the contract and acts
+//! as operator. Rewards are not in the log, so before each stake or unstake
+//! the rewards that move the rate to the recorded one are reported. A recorded
+//! rate below the current one would need a loss report and is rejected, as are
+//! transfers the contract made itself (e.g. merkle drop claims).
+
+use std::collections::BTreeMap;
+
+use odra::host::HostEnv;
+use odra::prelude::*;
+use serde_json::Value;
+
+use crate::{CasperLiquidHostRef, RATE_PRECISION};
+
+/// Maps recorded addresses to test accounts
+pub struct Replay<'a> {
+    env: &'a HostEnv,
+    accounts: BTreeMap<String, Address>,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(env: &'a HostEnv) -> Self {
+        Replay {
+            env,
+            accounts: BTreeMap::new(),
+        }
+    }
+
+    /// Get the test account standing in for a recorded address
+    pub fn account(&mut self, recorded: &str) -> Address {
+        let next = self.accounts.len() + 1;
+        *self
+            .accounts
+            .entry(recorded.to_string())
+            .or_insert_with(|| self.env.get_account(next))
+    }
+
+    /// Send the calls behind every event of a JSON lines log, in log order
+    pub fn replay(&mut self, contract: &mut CasperLiquidHostRef, log: &str) -> Result<(), String> {
+        for (number, line) in log.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let event: Value = serde_json::from_str(line).map_err(|e| format!("line {}: {}", number + 1, e))?;
+            self.apply(contract, &event)
+                .map_err(|e| format!("line {} ({}): {}", number + 1, event["event"], e))?;
+        }
+        Ok(())
+    }
+
+    /// Assert the contract matches a snapshot of `total_supply`,
+    /// `total_pooled_cspr` and the `balances` of recorded addresses
+    pub fn assert_snapshot(&mut self, contract: &CasperLiquidHostRef, snapshot: &str) {
+        let snapshot: Value = serde_json::from_str(snapshot).expect("Snapshot is JSON");
+        assert_eq!(contract.total_supply(), amount(&snapshot, "total_supply").unwrap(), "total_supply");
+        assert_eq!(
+            contract.total_pooled_cspr(),
+            amount(&snapshot, "total_pooled_cspr").unwrap(),
+            "total_pooled_cspr"
+        );
+        for (recorded, balance) in snapshot["balances"].as_object().into_iter().flatten() {
+            let expected = U256::from_dec_str(balance.as_str().unwrap_or_default()).expect("Balance is a decimal string");
+            let account = self.account(recorded);
+            assert_eq!(contract.balance_of(&account), expected, "balance of {}", recorded);
+        }
+    }
+
+    fn apply(&mut self, contract: &mut CasperLiquidHostRef, event: &Value) -> Result<(), String> {
+        let data = &event["data"];
+        match event["event"].as_str().unwrap_or_default() {
+            "StakeEvent" => {
+                self.align_rate(contract, amount(data, "rate")?)?;
+                let (user, payer) = (self.address(data, "user")?, self.address(data, "payer")?);
+                let cspr = amount(data, "cspr_amount")?;
+                let attached = U512::from_dec_str(&cspr.to_string()).map_err(|e| format!("{:?}", e))?;
+                self.env.set_caller(payer);
+                let staked = if user == payer {
+                    contract.with_tokens(attached).stake(cspr)
+                } else {
+                    contract.with_tokens(attached).stake_for(&user, cspr)
+                };
+                staked.map_err(|e| format!("{:?}", e))?;
+            }
+            "UnstakeEvent" => {
+                self.align_rate(contract, amount(data, "rate")?)?;
+                let (user, recipient) = (self.address(data, "user")?, self.address(data, "recipient")?);
+                let shares = amount(data, "stcspr_burned")?;
+                self.env.set_caller(user);
+                let unstaked = if user == recipient {
+                    contract.unstake(shares)
+                } else {
+                    contract.unstake_to(&recipient, shares)
+                };
+                unstaked.map_err(|e| format!("{:?}", e))?;
+            }
+            "Transfer" => {
+                let (sender, recipient) = (self.address(data, "sender")?, self.address(data, "recipient")?);
+                self.env.set_caller(sender);
+                contract
+                    .transfer(&recipient, amount(data, "amount")?)
+                    .map_err(|e| format!("{:?}", e))?;
+            }
+            "TransferFrom" => {
+                let spender = self.address(data, "spender")?;
+                let (owner, recipient) = (self.address(data, "owner")?, self.address(data, "recipient")?);
+                self.env.set_caller(spender);
+                contract
+                    .transfer_from(&owner, &recipient, amount(data, "amount")?)
+                    .map_err(|e| format!("{:?}", e))?;
+            }
+            "SetAllowance" => {
+                let (owner, spender) = (self.address(data, "owner")?, self.address(data, "spender")?);
+                self.env.set_caller(owner);
+                contract
+                    .approve(&spender, amount(data, "allowance")?)
+                    .map_err(|e| format!("{:?}", e))?;
+            }
+            other => return Err(format!("cannot replay {} events", other)),
+        }
+        Ok(())
+    }
+
+    // Report the rewards that bring the exchange rate up to `rate`
+    fn align_rate(&mut self, contract: &mut CasperLiquidHostRef, rate: U256) -> Result<(), String> {
+        let supply = contract.total_supply();
+        if supply.is_zero() || contract.exchange_rate() == rate {
+            return Ok(());
+        }
+
+        let target = rate * supply / U256::from(RATE_PRECISION);
+        let pooled = contract.total_pooled_cspr();
+        if target <= pooled {
+            return Err(format!("rate {} is below the replayed rate {}", rate, contract.exchange_rate()));
+        }
+        self.env.set_caller(self.env.get_account(0));
+        contract.report_rewards(target - pooled).map_err(|e| format!("{:?}", e))
+    }
+
+    fn address(&mut self, data: &Value, field: &str) -> Result<Address, String> {
+        let recorded = data[field].as_str().ok_or_else(|| format!("missing {}", field))?;
+        Ok(self.account(recorded))
+    }
+}
+
+fn amount(data: &Value, field: &str) -> Result<U256, String> {
+    let raw = data[field].as_str().ok_or_else(|| format!("missing {}", field))?;
+    U256::from_dec_str(raw).map_err(|_| format!("{} is not a decimal amount: {}", field, raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CasperLiquid, CasperLiquidInitArgs};
+    use odra::host::Deployer;
+
+    #[test]
+    fn test_replay_matches_the_recorded_snapshot() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(
+            &test_env,
+            CasperLiquidInitArgs {
+                name: None,
+                symbol: None,
+                decimals: None,
+                owner: None,
+                fee_bps: None,
+                treasury: None,
+                features: None,
+            },
+        );
+
+        let mut replay = Replay::new(&test_env);
+        replay
+            .replay(&mut contract, include_str!("../tests/fixtures/replay_events.jsonl"))
+            .unwrap();
+        replay.assert_snapshot(&contract, include_str!("../tests/fixtures/replay_snapshot.json"));
+    }
+
+    #[test]
+    fn test_replay_rejects_rates_it_cannot_reach() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(
+            &test_env,
+            CasperLiquidInitArgs {
+                name: None,
+                symbol: None,
+                decimals: None,
+                owner: None,
+                fee_bps: None,
+                treasury: None,
+                features: None,
+            },
+        );
+        let log = r#"{"event":"StakeEvent","data":{"user":"a","payer":"a","cspr_amount":"1000000000000","rate":"1000000000"}}
+{"event":"StakeEvent","data":{"user":"a","payer":"a","cspr_amount":"1000000000000","rate":"900000000"}}"#;
+
+        let error = Replay::new(&test_env).replay(&mut contract, log).unwrap_err();
+        assert!(error.starts_with("line 2"), "{}", error);
+    }
+}
@@ -0,0 +1,140 @@
+//! Review of large withdrawal requests
+//!
+//! Once enabled, a withdrawal request for more than `threshold_bps` of the
+//! pooled CSPR is held for a review window. During the window an account with
+//! the `Guardian` role can flag it, which pushes its claim back by the flag
+//! delay so governance has time to look at it. Requests nobody flags are
+//! released when the window closes, without anyone having to act. Instant
+//! unstakes above the threshold are refused, so large exits cannot skip the
+//! review by draining the buffer directly.
+
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::{Error, BPS_DENOMINATOR};
+
+/// Longest review window or flag delay that can be configured (30 days, in milliseconds)
+pub const MAX_REVIEW_DELAY: u64 = 30 * 86_400_000;
+
+/// Event emitted when a withdrawal request is held for review
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalUnderReview {
+    pub id: u64,
+    pub cspr_amount: U256,
+    /// Block time the request is released at unless flagged
+    pub review_ends_at: u64,
+}
+
+/// Event emitted when a guardian flags a withdrawal request under review
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalFlagged {
+    pub id: u64,
+    pub guardian: Address,
+    /// Block time from which the request can now be claimed
+    pub claimable_at: u64,
+}
+
+/// Event emitted when the review policy changes
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalReviewSet {
+    pub threshold_bps: u16,
+    pub window: u64,
+    pub flag_delay: u64,
+}
+
+/// WithdrawalReview - review windows and guardian flags of large withdrawal requests
+///
+/// Delaying the claims is the responsibility of the parent contract.
+#[odra::module]
+pub struct WithdrawalReview {
+    /// Share of the pooled CSPR above which a request is reviewed, in basis points (zero disables it)
+    threshold_bps: Var<u16>,
+    /// Time a request is held for review, in milliseconds
+    window: Var<u64>,
+    /// Time a flagged request waits from the flag, in milliseconds
+    flag_delay: Var<u64>,
+    /// Block time each reviewed request's window ends
+    review_ends: Mapping<u64, u64>,
+    /// Requests a guardian flagged
+    flagged: Mapping<u64, bool>,
+}
+
+#[odra::module]
+impl WithdrawalReview {
+    /// Set the threshold, window and flag delay; a zero threshold disables reviews
+    pub fn configure(&mut self, threshold_bps: u16, window: u64, flag_delay: u64) -> Result<(), Error> {
+        if threshold_bps > BPS_DENOMINATOR || window > MAX_REVIEW_DELAY || flag_delay > MAX_REVIEW_DELAY {
+            return Err(Error::ExceedsMaximum);
+        }
+
+        self.threshold_bps.set(threshold_bps);
+        self.window.set(window);
+        self.flag_delay.set(flag_delay);
+        self.env().emit_event(WithdrawalReviewSet {
+            threshold_bps,
+            window,
+            flag_delay,
+        });
+        Ok(())
+    }
+
+    /// Get the share of the pooled CSPR above which a request is reviewed, in basis points
+    pub fn threshold_bps(&self) -> u16 {
+        self.threshold_bps.get_or_default()
+    }
+
+    /// Get the time a request is held for review
+    pub fn window(&self) -> u64 {
+        self.window.get_or_default()
+    }
+
+    /// Get the time a flagged request waits from the flag
+    pub fn flag_delay(&self) -> u64 {
+        self.flag_delay.get_or_default()
+    }
+
+    /// Check whether withdrawing `cspr_amount` out of `pooled` CSPR needs a review
+    pub fn requires_review(&self, cspr_amount: U256, pooled: U256) -> bool {
+        let threshold_bps = self.threshold_bps();
+        threshold_bps > 0
+            && cspr_amount.saturating_mul(U256::from(BPS_DENOMINATOR))
+                > pooled.saturating_mul(U256::from(threshold_bps))
+    }
+
+    /// Hold request `id` for review; returns the block time its window ends
+    pub fn open(&mut self, id: u64, cspr_amount: U256) -> u64 {
+        let review_ends_at = self.env().block_time().saturating_add(self.window());
+        self.review_ends.set(&id, review_ends_at);
+        self.env().emit_event(WithdrawalUnderReview {
+            id,
+            cspr_amount,
+            review_ends_at,
+        });
+        review_ends_at
+    }
+
+    /// Get the block time the review window of request `id` ends, if it was reviewed
+    pub fn review_ends_at(&self, id: u64) -> Option<u64> {
+        self.review_ends.get(&id)
+    }
+
+    /// Check whether a guardian flagged request `id`
+    pub fn is_flagged(&self, id: u64) -> bool {
+        self.flagged.get(&id).unwrap_or_default()
+    }
+
+    /// Flag request `id` while its window is open; returns the block time it can be claimed from
+    pub fn flag(&mut self, id: u64) -> Result<u64, Error> {
+        let review_ends_at = self.review_ends_at(id).ok_or(Error::NotUnderReview)?;
+        let now = self.env().block_time();
+        if now >= review_ends_at || self.is_flagged(id) {
+            return Err(Error::NotUnderReview);
+        }
+
+        self.flagged.set(&id, true);
+        Ok(now.saturating_add(self.flag_delay()))
+    }
+}
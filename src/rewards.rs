@@ -0,0 +1,104 @@
+use odra::prelude::*;
+use odra::{module::Module, Mapping, Var};
+
+/// Milliseconds in a 365-day year, used to annualize reward growth
+pub const YEAR_MILLIS: u64 = 31_536_000_000;
+
+/// A single rewards report, as recorded for an epoch
+#[odra::odra_type]
+pub struct RewardReport {
+    pub epoch: u64,
+    /// Gross rewards added to the pool
+    pub amount: U256,
+    /// Part of `amount` taken as protocol fee
+    pub fee: U256,
+    pub exchange_rate: U256,
+    pub timestamp: u64,
+}
+
+/// Event emitted when staking rewards are added to the pool
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RewardsAccrued {
+    pub epoch: u64,
+    pub amount: U256,
+    pub exchange_rate: U256,
+}
+
+/// Rewards - keeps the history of reward reports
+///
+/// Each report opens a new epoch. Updating the pooled CSPR is the
+/// responsibility of the parent contract.
+#[odra::module]
+pub struct Rewards {
+    /// Number of the last reported epoch (zero before the first report)
+    current_epoch: Var<u64>,
+    /// Report recorded for each epoch
+    reports: Mapping<u64, RewardReport>,
+}
+
+#[odra::module]
+impl Rewards {
+    /// Record a rewards report and emit `RewardsAccrued`; returns the new epoch
+    pub fn record(&mut self, amount: U256, fee: U256, exchange_rate: U256) -> u64 {
+        let epoch = self.current_epoch.get_or_default() + 1;
+        let timestamp = self.env().block_time();
+
+        self.current_epoch.set(epoch);
+        self.reports.set(
+            &epoch,
+            RewardReport {
+                epoch,
+                amount,
+                fee,
+                exchange_rate,
+                timestamp,
+            },
+        );
+
+        self.env().emit_event(RewardsAccrued {
+            epoch,
+            amount,
+            exchange_rate,
+        });
+
+        epoch
+    }
+
+    /// Get the number of the last reported epoch
+    pub fn current_epoch(&self) -> u64 {
+        self.current_epoch.get_or_default()
+    }
+
+    /// Get the report recorded for an epoch
+    pub fn report(&self, epoch: u64) -> Option<RewardReport> {
+        self.reports.get(&epoch)
+    }
+
+    /// Estimate the annual rate of return in basis points
+    ///
+    /// Annualizes the exchange rate growth between the last two reports. Returns
+    /// zero until two reports exist or if the rate did not grow between them.
+    pub fn apr_estimate_bps(&self) -> U256 {
+        let epoch = self.current_epoch();
+        if epoch < 2 {
+            return U256::zero();
+        }
+        let (previous, latest) = match (self.report(epoch - 1), self.report(epoch)) {
+            (Some(previous), Some(latest)) => (previous, latest),
+            _ => return U256::zero(),
+        };
+
+        let elapsed = latest.timestamp.saturating_sub(previous.timestamp);
+        if elapsed == 0
+            || previous.exchange_rate == U256::zero()
+            || latest.exchange_rate <= previous.exchange_rate
+        {
+            return U256::zero();
+        }
+
+        let growth = latest.exchange_rate - previous.exchange_rate;
+        growth * U256::from(crate::BPS_DENOMINATOR) * U256::from(YEAR_MILLIS)
+            / (previous.exchange_rate * U256::from(elapsed))
+    }
+}
@@ -0,0 +1,153 @@
+//! Rounding policy of the share conversions
+//!
+//! Conversions between CSPR and stCSPR shares rarely divide evenly. By default
+//! each one rounds in the pool's favour, so no sequence of stakes and unstakes
+//! takes out more than it put in:
+//!
+//! - shares minted for staked CSPR round down (`MINT_ROUNDING`)
+//! - shares burned for CSPR paid out round up (`BURN_ROUNDING`), so the CSPR
+//!   paid for a number of burned shares rounds down
+//!
+//! The policy is fixed at compile time. The `rounding-mint-up` and
+//! `rounding-burn-down` features flip a direction, e.g. to reproduce another
+//! protocol's math on a test network; either lets stakers round value out of
+//! the pool. `rounding_policy` returns the policy a build was compiled with.
+
+use odra::prelude::*;
+
+/// Direction a share conversion rounds in
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+impl Rounding {
+    /// Get the other direction
+    pub fn opposite(&self) -> Rounding {
+        match self {
+            Rounding::Down => Rounding::Up,
+            Rounding::Up => Rounding::Down,
+        }
+    }
+}
+
+/// Rounding directions a build was compiled with
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundingPolicy {
+    /// Shares minted for staked CSPR
+    pub mint: Rounding,
+    /// Shares burned for CSPR paid out
+    pub burn: Rounding,
+}
+
+/// Rounding of the shares minted for staked CSPR
+#[cfg(not(feature = "rounding-mint-up"))]
+pub const MINT_ROUNDING: Rounding = Rounding::Down;
+/// Rounding of the shares minted for staked CSPR
+#[cfg(feature = "rounding-mint-up")]
+pub const MINT_ROUNDING: Rounding = Rounding::Up;
+
+/// Rounding of the shares burned for CSPR paid out
+#[cfg(not(feature = "rounding-burn-down"))]
+pub const BURN_ROUNDING: Rounding = Rounding::Up;
+/// Rounding of the shares burned for CSPR paid out
+#[cfg(feature = "rounding-burn-down")]
+pub const BURN_ROUNDING: Rounding = Rounding::Down;
+
+/// Get the policy this build was compiled with
+pub fn policy() -> RoundingPolicy {
+    RoundingPolicy {
+        mint: MINT_ROUNDING,
+        burn: BURN_ROUNDING,
+    }
+}
+
+/// Compute `value * numerator / denominator`, rounded as `rounding` says
+///
+/// Returns `None` on overflow or a zero denominator.
+pub fn mul_div(value: U256, numerator: U256, denominator: U256, rounding: Rounding) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+    let product = value.checked_mul(numerator)?;
+    let quotient = product / denominator;
+    match rounding {
+        Rounding::Up if !(product % denominator).is_zero() => quotient.checked_add(U256::one()),
+        _ => Some(quotient),
+    }
+}
+
+/// Shares minted for `amount` CSPR staked into `pooled` CSPR backing `total_shares`
+pub fn shares_for_cspr(amount: U256, total_shares: U256, pooled: U256) -> Option<U256> {
+    // The first deposit (or a fully drained pool) mints shares 1:1
+    if total_shares.is_zero() || pooled.is_zero() {
+        return Some(amount);
+    }
+    mul_div(amount, total_shares, pooled, MINT_ROUNDING)
+}
+
+/// Shares burned to pay out `amount` CSPR from `pooled` CSPR backing `total_shares`
+pub fn shares_to_burn(amount: U256, total_shares: U256, pooled: U256) -> Option<U256> {
+    if total_shares.is_zero() || pooled.is_zero() {
+        return Some(amount);
+    }
+    mul_div(amount, total_shares, pooled, BURN_ROUNDING)
+}
+
+/// CSPR paid out for burning `shares` of `total_shares` backed by `pooled` CSPR
+pub fn cspr_for_shares(shares: U256, total_shares: U256, pooled: U256) -> Option<U256> {
+    if total_shares.is_zero() {
+        return Some(shares);
+    }
+    mul_div(shares, pooled, total_shares, BURN_ROUNDING.opposite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every pool of up to 24 shares and 24 CSPR, against every amount up to 48
+    #[test]
+    #[cfg(not(any(feature = "rounding-mint-up", feature = "rounding-burn-down")))]
+    fn test_default_policy_never_rounds_value_out_of_the_pool() {
+        assert_eq!(policy(), RoundingPolicy { mint: Rounding::Down, burn: Rounding::Up });
+
+        for total_shares in (1u64..=24).map(U256::from) {
+            for pooled in (1u64..=24).map(U256::from) {
+                for amount in (0u64..=48).map(U256::from) {
+                    // Minted shares are never worth more than the CSPR staked
+                    let minted = shares_for_cspr(amount, total_shares, pooled).unwrap();
+                    assert!(minted * pooled <= amount * total_shares);
+                    assert!((minted + 1) * pooled > amount * total_shares);
+
+                    // Burned shares are always worth at least the CSPR paid out
+                    let burned = shares_to_burn(amount, total_shares, pooled).unwrap();
+                    assert!(burned * pooled >= amount * total_shares);
+                    assert!(burned.is_zero() || (burned - 1) * pooled < amount * total_shares);
+
+                    // Paying out for shares rounds the same way as burning for CSPR
+                    let paid = cspr_for_shares(amount, total_shares, pooled).unwrap();
+                    assert!(paid * total_shares <= amount * pooled);
+                    assert!(shares_to_burn(paid, total_shares, pooled).unwrap() <= amount);
+
+                    // A round trip through either conversion gains nothing
+                    assert!(cspr_for_shares(minted, total_shares, pooled).unwrap() <= amount);
+                    assert!(shares_for_cspr(paid, total_shares, pooled).unwrap() <= amount);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_div_rounds_as_asked() {
+        let (seven, two) = (U256::from(7), U256::from(2));
+        assert_eq!(mul_div(seven, U256::one(), two, Rounding::Down), Some(U256::from(3)));
+        assert_eq!(mul_div(seven, U256::one(), two, Rounding::Up), Some(U256::from(4)));
+        assert_eq!(mul_div(U256::from(8), U256::one(), two, Rounding::Up), Some(U256::from(4)));
+        assert_eq!(mul_div(seven, U256::one(), U256::zero(), Rounding::Down), None);
+        assert_eq!(mul_div(U256::MAX, two, two, Rounding::Down), None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Staking backends the pool can delegate through
+//!
+//! `Delegation` keeps the pool's bookkeeping and calls the backend for the
+//! actual staking. The backend is chosen when the modules are composed, with
+//! the `Backend` alias, so a future Casper staking mechanism only needs a new
+//! `StakingBackend` implementation, not changes to the pool logic.
+
+use odra::casper_types::PublicKey;
+use odra::prelude::*;
+use odra::ContractEnv;
+
+/// Where the pool's CSPR is staked
+pub trait StakingBackend {
+    /// Stake `amount` from the contract purse with `validator`
+    fn delegate(env: &ContractEnv, validator: PublicKey, amount: U512);
+
+    /// Start unstaking `amount` from `validator`; it returns to the contract
+    /// purse once the backend's unbonding period has elapsed
+    fn undelegate(env: &ContractEnv, validator: PublicKey, amount: U512);
+
+    /// Get the stake held with `validator`, rewards included
+    fn delegated_amount(env: &ContractEnv, validator: PublicKey) -> U512;
+}
+
+/// Delegation through the Casper auction contract
+pub struct CasperAuction;
+
+impl StakingBackend for CasperAuction {
+    fn delegate(env: &ContractEnv, validator: PublicKey, amount: U512) {
+        env.delegate(validator, amount);
+    }
+
+    fn undelegate(env: &ContractEnv, validator: PublicKey, amount: U512) {
+        env.undelegate(validator, amount);
+    }
+
+    fn delegated_amount(env: &ContractEnv, validator: PublicKey) -> U512 {
+        env.delegated_amount(validator)
+    }
+}
+
+/// Backend the pool is composed with
+#[cfg(not(test))]
+pub type Backend = CasperAuction;
+
+/// Backend the pool is composed with
+#[cfg(test)]
+pub type Backend = mock::MockStaking;
+
+/// Backend used by the contract tests
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    use odra::casper_types::PublicKey;
+    use odra::prelude::*;
+    use odra::ContractEnv;
+
+    use super::{CasperAuction, StakingBackend};
+
+    thread_local! {
+        // Rewards added to each validator's stake; each test runs on its own thread
+        static REWARDS: RefCell<BTreeMap<PublicKey, U512>> = RefCell::new(BTreeMap::new());
+    }
+
+    /// The mock VM's auction, plus stake growth tests can add
+    ///
+    /// The mock VM's auction cannot grow a stake on its own, so rewards are
+    /// added with `add_rewards` and reported on top of the auction's stake.
+    pub struct MockStaking;
+
+    impl MockStaking {
+        /// Let `validator`'s stake grow by `amount`, as compounded rewards do
+        pub fn add_rewards(validator: &PublicKey, amount: U512) {
+            REWARDS.with(|rewards| {
+                let mut rewards = rewards.borrow_mut();
+                let grown = rewards.get(validator).copied().unwrap_or_default() + amount;
+                rewards.insert(validator.clone(), grown);
+            });
+        }
+    }
+
+    impl StakingBackend for MockStaking {
+        fn delegate(env: &ContractEnv, validator: PublicKey, amount: U512) {
+            CasperAuction::delegate(env, validator, amount);
+        }
+
+        fn undelegate(env: &ContractEnv, validator: PublicKey, amount: U512) {
+            CasperAuction::undelegate(env, validator, amount);
+        }
+
+        fn delegated_amount(env: &ContractEnv, validator: PublicKey) -> U512 {
+            let rewards = REWARDS.with(|rewards| rewards.borrow().get(&validator).copied().unwrap_or_default());
+            CasperAuction::delegated_amount(env, validator) + rewards
+        }
+    }
+}
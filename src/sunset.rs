@@ -0,0 +1,125 @@
+//! Sunset mode and retirement
+//!
+//! Governance can start a sunset to wind the pool down. From then on no new
+//! stake is taken and no protocol fees are charged, while holders leave
+//! through the withdrawal queue. Holders get the exit window to request their
+//! withdrawals themselves; once it has passed, anyone can force the remaining
+//! positions into the queue. When the supply and the pending withdrawals have
+//! both reached zero, an operator can retire the contract, which pauses it for
+//! good.
+
+use odra::prelude::*;
+use odra::{module::Module, Address, Var};
+
+use crate::Error;
+
+/// Longest exit window a sunset can give holders (365 days, in milliseconds)
+pub const MAX_EXIT_WINDOW: u64 = 365 * 86_400_000;
+
+/// Event emitted when governance starts the sunset
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct SunsetStarted {
+    pub started_at: u64,
+    /// Block time from which remaining positions can be forced into the queue
+    pub forced_exit_at: u64,
+}
+
+/// Event emitted when a position is forced into the withdrawal queue
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForcedExit {
+    pub holder: Address,
+    /// Withdrawal request the position was moved into, owned by the holder
+    pub request_id: u64,
+    pub shares: U256,
+    pub keeper: Address,
+}
+
+/// Event emitted when an operator retires the contract
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct Retired {
+    pub operator: Address,
+    pub timestamp: u64,
+}
+
+/// Sunset - the wind-down schedule and the retired flag
+///
+/// Refusing stake, waiving fees and moving positions into the queue is the responsibility of the parent contract.
+#[odra::module]
+pub struct Sunset {
+    /// Block time the sunset started at (unset while the pool runs normally)
+    started_at: Var<u64>,
+    /// Block time from which remaining positions can be forced into the queue
+    forced_exit_at: Var<u64>,
+    retired: Var<bool>,
+}
+
+#[odra::module]
+impl Sunset {
+    /// Start the sunset, giving holders `exit_window` to leave on their own
+    pub fn start(&mut self, exit_window: u64) -> Result<(), Error> {
+        if self.is_active() {
+            return Err(Error::SunsetActive);
+        }
+        if exit_window > MAX_EXIT_WINDOW {
+            return Err(Error::ExceedsMaximum);
+        }
+
+        let started_at = self.env().block_time();
+        let forced_exit_at = started_at.saturating_add(exit_window);
+        self.started_at.set(started_at);
+        self.forced_exit_at.set(forced_exit_at);
+        self.env().emit_event(SunsetStarted {
+            started_at,
+            forced_exit_at,
+        });
+        Ok(())
+    }
+
+    /// Check whether the sunset has started
+    pub fn is_active(&self) -> bool {
+        self.started_at.get().is_some()
+    }
+
+    /// Get the block time the sunset started at
+    pub fn started_at(&self) -> Option<u64> {
+        self.started_at.get()
+    }
+
+    /// Get the block time from which remaining positions can be forced into the queue
+    pub fn forced_exit_at(&self) -> Option<u64> {
+        self.forced_exit_at.get()
+    }
+
+    /// Ensure the exit window has passed
+    pub fn require_forced_exit_open(&self) -> Result<(), Error> {
+        match self.forced_exit_at() {
+            Some(forced_exit_at) if self.env().block_time() >= forced_exit_at => Ok(()),
+            _ => Err(Error::SunsetNotDue),
+        }
+    }
+
+    /// Check whether the contract was retired
+    pub fn is_retired(&self) -> bool {
+        self.retired.get_or_default()
+    }
+
+    /// Mark the contract retired on behalf of `operator`
+    pub fn retire(&mut self, operator: Address) -> Result<(), Error> {
+        if !self.is_active() {
+            return Err(Error::SunsetNotDue);
+        }
+        if self.is_retired() {
+            return Err(Error::ContractRetired);
+        }
+
+        self.retired.set(true);
+        self.env().emit_event(Retired {
+            operator,
+            timestamp: self.env().block_time(),
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+//! Treasury diversification through registered swap pools
+//!
+//! The treasurer can sell some of the treasury's stCSPR fees for CSPR, or for
+//! another asset governance approved, to pay the protocol's running costs.
+//! Only pools registered by an admin can be used, and the stCSPR sold per
+//! rewards epoch is capped, so a compromised treasurer key cannot drain the
+//! treasury in one go.
+
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::Error;
+
+/// Interface of a pool the treasury can sell stCSPR to
+#[odra::external_contract]
+pub trait SwapPool {
+    /// Called after `amount_in` stCSPR has been credited to the pool. The pool
+    /// pays its output to `recipient` and returns the amount paid; the swap is
+    /// reverted if that is below `min_out`.
+    fn swap(&mut self, amount_in: U256, min_out: U256, recipient: Address) -> U256;
+}
+
+/// Event emitted when the treasury sold stCSPR through a pool
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreasurySwapped {
+    pub pool: Address,
+    pub treasurer: Address,
+    pub amount_in: U256,
+    pub amount_out: U256,
+    pub epoch: u64,
+}
+
+/// Event emitted when a swap pool is registered or removed
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwapPoolUpdated {
+    pub pool: Address,
+    pub registered: bool,
+}
+
+/// TreasurySwaps - registered pools and the per-epoch swap budget
+///
+/// Moving the stCSPR and calling the pool is the responsibility of the parent
+/// contract.
+#[odra::module]
+pub struct TreasurySwaps {
+    /// Pools the treasury may swap through
+    pools: Mapping<Address, bool>,
+    /// Most stCSPR swapped per rewards epoch (zero disables swaps)
+    epoch_limit: Var<U256>,
+    /// stCSPR swapped in each rewards epoch
+    swapped: Mapping<u64, U256>,
+}
+
+#[odra::module]
+impl TreasurySwaps {
+    /// Check whether a pool is registered
+    pub fn is_pool(&self, pool: &Address) -> bool {
+        self.pools.get(pool).unwrap_or_default()
+    }
+
+    /// Register or remove a pool
+    pub fn set_pool(&mut self, pool: Address, registered: bool) -> Result<(), Error> {
+        if registered && !pool.is_contract() {
+            return Err(Error::InvalidAddress);
+        }
+
+        self.pools.set(&pool, registered);
+        self.env().emit_event(SwapPoolUpdated { pool, registered });
+        Ok(())
+    }
+
+    /// Get the most stCSPR that can be swapped per epoch
+    pub fn epoch_limit(&self) -> U256 {
+        self.epoch_limit.get_or_default()
+    }
+
+    /// Set the most stCSPR that can be swapped per epoch
+    pub fn set_epoch_limit(&mut self, limit: U256) {
+        self.epoch_limit.set(limit);
+    }
+
+    /// Get the stCSPR swapped in an epoch
+    pub fn swapped_in(&self, epoch: u64) -> U256 {
+        self.swapped.get(&epoch).unwrap_or_default()
+    }
+
+    /// Count `amount` against the budget of `epoch`
+    pub fn record(&mut self, epoch: u64, amount: U256) -> Result<(), Error> {
+        let swapped = self
+            .swapped_in(epoch)
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        if swapped > self.epoch_limit() {
+            return Err(Error::SwapLimitReached);
+        }
+
+        self.swapped.set(&epoch, swapped);
+        Ok(())
+    }
+}
+
+/// Pool used by the contract tests
+#[cfg(test)]
+pub(crate) mod mock {
+    use odra::prelude::*;
+    use odra::{module::Module, Address, Var};
+
+    /// Quotes a fixed price in basis points of the stCSPR sold and records the swaps
+    #[odra::module]
+    pub struct MockSwapPool {
+        price_bps: Var<u16>,
+        received: Var<U256>,
+        last_recipient: Var<Option<Address>>,
+    }
+
+    #[odra::module]
+    impl MockSwapPool {
+        pub fn init(&mut self, price_bps: u16) {
+            self.price_bps.set(price_bps);
+        }
+
+        pub fn swap(&mut self, amount_in: U256, _min_out: U256, recipient: Address) -> U256 {
+            self.received.set(self.received.get_or_default() + amount_in);
+            self.last_recipient.set(Some(recipient));
+            amount_in * U256::from(self.price_bps.get_or_default()) / U256::from(crate::BPS_DENOMINATOR)
+        }
+
+        pub fn received(&self) -> U256 {
+            self.received.get_or_default()
+        }
+
+        pub fn last_recipient(&self) -> Option<Address> {
+            self.last_recipient.get().flatten()
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Helpers for asserting emitted events in the contract tests
+//!
+//! `HostEnv::emitted_event` only checks that an event exists somewhere in the
+//! contract's history. These helpers also check how many events a call
+//! emitted and in which order, across event types.
+
+use odra::casper_types::bytesrepr::ToBytes;
+use odra::host::HostEnv;
+use odra::Address;
+
+/// Serialize an event the way the host stores it, so events of different types
+/// can be compared in one list
+pub fn event<T: ToBytes>(event: T) -> Vec<u8> {
+    event.to_bytes().expect("Events always serialize")
+}
+
+/// Get the number of events `contract` has emitted so far
+pub fn event_count(env: &HostEnv, contract: &Address) -> u32 {
+    env.events_count(contract)
+}
+
+/// Assert that `contract` emitted exactly `expected`, in order, since the
+/// history had `since` events
+///
+/// Take `since` with `event_count` before the call under test.
+pub fn assert_events_since(env: &HostEnv, contract: &Address, since: u32, expected: &[Vec<u8>]) {
+    let count = env.events_count(contract);
+    assert_eq!(
+        (count - since) as usize,
+        expected.len(),
+        "Expected {} events, {} were emitted",
+        expected.len(),
+        count - since
+    );
+
+    for (offset, expected_event) in expected.iter().enumerate() {
+        let index = since + offset as u32;
+        let actual = env
+            .get_event_bytes(contract, index)
+            .expect("Event index is within the history");
+        assert_eq!(
+            &actual[..],
+            expected_event.as_slice(),
+            "Event {} does not match the expected event {}",
+            index,
+            offset
+        );
+    }
+}
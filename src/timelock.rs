@@ -0,0 +1,132 @@
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::governance::ProposalAction;
+use crate::Error;
+
+/// An admin action waiting in the timelock
+#[odra::odra_type]
+pub struct QueuedAction {
+    pub id: u64,
+    pub action: ProposalAction,
+    /// Block time from which the action can be executed
+    pub eta: u64,
+    pub queued_by: Address,
+    pub executed: bool,
+    pub cancelled: bool,
+}
+
+/// Event emitted when an admin action is queued
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionQueued {
+    pub id: u64,
+    pub action: ProposalAction,
+    pub eta: u64,
+}
+
+/// Event emitted when a queued action is executed
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionExecuted {
+    pub id: u64,
+}
+
+/// Event emitted when a queued action is cancelled
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionCancelled {
+    pub id: u64,
+}
+
+/// Timelock - delays admin actions so users can react before they take effect
+///
+/// A delay of zero disables the timelock. Authorization and applying executed
+/// actions are the responsibility of the parent contract.
+#[odra::module]
+pub struct Timelock {
+    /// Minimum time between queueing an action and executing it, in milliseconds
+    delay: Var<u64>,
+    /// Id assigned to the next queued action
+    next_id: Var<u64>,
+    /// All queued actions by id
+    actions: Mapping<u64, QueuedAction>,
+}
+
+#[odra::module]
+impl Timelock {
+    /// Queue `action` for execution at `eta`; returns its id
+    pub fn queue(&mut self, action: ProposalAction, eta: u64, queued_by: Address) -> Result<u64, Error> {
+        // CHECKS: The action must wait at least the configured delay
+        let earliest = self.env().block_time().saturating_add(self.delay());
+        if eta < earliest {
+            return Err(Error::TimelockEtaTooEarly);
+        }
+
+        let id = self.next_id.get_or_default();
+        self.next_id.set(id + 1);
+        self.actions.set(
+            &id,
+            QueuedAction {
+                id,
+                action: action.clone(),
+                eta,
+                queued_by,
+                executed: false,
+                cancelled: false,
+            },
+        );
+
+        self.env().emit_event(ActionQueued { id, action, eta });
+        Ok(id)
+    }
+
+    /// Mark a ready action as executed and return it
+    pub fn execute(&mut self, id: u64) -> Result<ProposalAction, Error> {
+        let mut queued = self.pending(id)?;
+        if self.env().block_time() < queued.eta {
+            return Err(Error::TimelockNotReady);
+        }
+
+        queued.executed = true;
+        let action = queued.action.clone();
+        self.actions.set(&id, queued);
+
+        self.env().emit_event(ActionExecuted { id });
+        Ok(action)
+    }
+
+    /// Cancel a pending action
+    pub fn cancel(&mut self, id: u64) -> Result<(), Error> {
+        let mut queued = self.pending(id)?;
+        queued.cancelled = true;
+        self.actions.set(&id, queued);
+
+        self.env().emit_event(ActionCancelled { id });
+        Ok(())
+    }
+
+    /// Get a queued action by id
+    pub fn get(&self, id: u64) -> Option<QueuedAction> {
+        self.actions.get(&id)
+    }
+
+    /// Get the timelock delay in milliseconds
+    pub fn delay(&self) -> u64 {
+        self.delay.get_or_default()
+    }
+
+    /// Set the timelock delay
+    pub fn set_delay(&mut self, delay: u64) {
+        self.delay.set(delay);
+    }
+
+    /// Get an action that is neither executed nor cancelled
+    fn pending(&self, id: u64) -> Result<QueuedAction, Error> {
+        let queued = self.actions.get(&id).ok_or(Error::TimelockActionNotFound)?;
+        if queued.executed || queued.cancelled {
+            return Err(Error::TimelockActionNotPending);
+        }
+        Ok(queued)
+    }
+}
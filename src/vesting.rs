@@ -0,0 +1,208 @@
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::Error;
+
+/// A stCSPR vesting schedule
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct VestingSchedule {
+    pub id: u64,
+    /// Account that locked the stCSPR and receives it back on revocation
+    pub funder: Address,
+    pub beneficiary: Address,
+    /// stCSPR locked by the schedule (reduced to the vested part on revocation)
+    pub amount: U256,
+    /// stCSPR already released to the beneficiary
+    pub released: U256,
+    /// Block time the schedule was created
+    pub start: u64,
+    /// Time after `start` before anything vests, in milliseconds
+    pub cliff: u64,
+    /// Time after `start` until everything has vested, in milliseconds
+    pub duration: u64,
+    /// Whether an admin may revoke the unvested part
+    pub revocable: bool,
+    pub revoked: bool,
+}
+
+/// Event emitted when stCSPR is locked in a vesting schedule
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct VestingCreated {
+    pub id: u64,
+    pub funder: Address,
+    pub beneficiary: Address,
+    pub amount: U256,
+    pub cliff: u64,
+    pub duration: u64,
+    pub revocable: bool,
+}
+
+/// Event emitted when vested stCSPR is released to the beneficiary
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct VestingReleased {
+    pub id: u64,
+    pub beneficiary: Address,
+    pub amount: U256,
+}
+
+/// Event emitted when the unvested part of a schedule is revoked
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct VestingRevoked {
+    pub id: u64,
+    /// Unvested stCSPR returned to the funder
+    pub refunded: U256,
+}
+
+/// Vesting - linear stCSPR vesting schedules with a cliff
+///
+/// Nothing vests before `start + cliff`; after that the vested amount grows
+/// linearly from `start` until `start + duration`. The locked stCSPR is held by
+/// the parent contract, which is responsible for moving it.
+#[odra::module]
+pub struct Vesting {
+    /// Id assigned to the next schedule
+    next_id: Var<u64>,
+    /// All schedules by id
+    schedules: Mapping<u64, VestingSchedule>,
+    /// stCSPR locked by schedules and not yet released
+    locked: Var<U256>,
+}
+
+#[odra::module]
+impl Vesting {
+    /// Create a schedule for `amount` stCSPR locked by `funder`; returns its id
+    pub fn create(
+        &mut self,
+        funder: Address,
+        beneficiary: Address,
+        amount: U256,
+        cliff: u64,
+        duration: u64,
+        revocable: bool,
+    ) -> Result<u64, Error> {
+        // CHECKS: The schedule must lock something and vest by its end
+        if amount == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+        if duration == 0 || cliff > duration {
+            return Err(Error::InvalidVestingSchedule);
+        }
+        let locked = self
+            .locked()
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        // EFFECTS: Store the schedule
+        let id = self.next_id.get_or_default();
+        self.next_id.set(id + 1);
+        self.locked.set(locked);
+        self.schedules.set(
+            &id,
+            VestingSchedule {
+                id,
+                funder,
+                beneficiary,
+                amount,
+                released: U256::zero(),
+                start: self.env().block_time(),
+                cliff,
+                duration,
+                revocable,
+                revoked: false,
+            },
+        );
+
+        self.env().emit_event(VestingCreated {
+            id,
+            funder,
+            beneficiary,
+            amount,
+            cliff,
+            duration,
+            revocable,
+        });
+        Ok(id)
+    }
+
+    /// Mark everything releasable in schedule `id` as released; returns the beneficiary and amount
+    pub fn release(&mut self, id: u64) -> Result<(Address, U256), Error> {
+        let mut schedule = self.schedules.get(&id).ok_or(Error::VestingNotFound)?;
+        let amount = self.releasable_amount(&schedule);
+        if amount == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+
+        // EFFECTS: Record the release before the parent pays it out
+        schedule.released = schedule.released + amount;
+        let beneficiary = schedule.beneficiary;
+        self.schedules.set(&id, schedule);
+        self.locked.set(self.locked() - amount);
+
+        self.env().emit_event(VestingReleased {
+            id,
+            beneficiary,
+            amount,
+        });
+        Ok((beneficiary, amount))
+    }
+
+    /// Cut schedule `id` down to its vested part; returns the funder and the unvested amount
+    pub fn revoke(&mut self, id: u64) -> Result<(Address, U256), Error> {
+        let mut schedule = self.schedules.get(&id).ok_or(Error::VestingNotFound)?;
+        if !schedule.revocable || schedule.revoked {
+            return Err(Error::VestingNotRevocable);
+        }
+
+        // EFFECTS: What has vested stays releasable, the rest goes back
+        let vested = self.vested_amount(&schedule);
+        let refunded = schedule.amount - vested;
+        schedule.amount = vested;
+        schedule.revoked = true;
+        let funder = schedule.funder;
+        self.schedules.set(&id, schedule);
+        self.locked.set(self.locked() - refunded);
+
+        self.env().emit_event(VestingRevoked { id, refunded });
+        Ok((funder, refunded))
+    }
+
+    /// Get a schedule by id
+    pub fn get(&self, id: u64) -> Option<VestingSchedule> {
+        self.schedules.get(&id)
+    }
+
+    /// Get the stCSPR schedule `id` lets its beneficiary release now
+    pub fn releasable(&self, id: u64) -> U256 {
+        self.schedules
+            .get(&id)
+            .map(|schedule| self.releasable_amount(&schedule))
+            .unwrap_or_default()
+    }
+
+    /// Get the stCSPR locked by all schedules and not yet released
+    pub fn locked(&self) -> U256 {
+        self.locked.get_or_default()
+    }
+
+    fn releasable_amount(&self, schedule: &VestingSchedule) -> U256 {
+        self.vested_amount(schedule).saturating_sub(schedule.released)
+    }
+
+    fn vested_amount(&self, schedule: &VestingSchedule) -> U256 {
+        if schedule.revoked {
+            return schedule.amount;
+        }
+        let elapsed = self.env().block_time().saturating_sub(schedule.start);
+        if elapsed < schedule.cliff {
+            return U256::zero();
+        }
+        if elapsed >= schedule.duration {
+            return schedule.amount;
+        }
+        schedule.amount * U256::from(elapsed) / U256::from(schedule.duration)
+    }
+}
@@ -0,0 +1,185 @@
+//! Monitoring of one holder from the event stream
+//!
+//! Lets custodians keep an eye on a cold wallet holding stCSPR without running
+//! an indexer. [`Watch`] is a [`Sink`] that keeps only the events involving one
+//! address and turns each into a line of text. It also tracks how the balance
+//! has changed since the watch started and the allowances the address has
+//! granted. Lines can be appended to a log file as well as printed.
+//!
+//! Only the events the indexer decodes are seen: stCSPR minted or burned by the
+//! contract itself (fees, rebates, queued unstakes) does not show up.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+use odra::prelude::*;
+
+use crate::indexer::{IndexedEvent, IndexerError, Reorg, Sink};
+
+/// Watch - events of one address, with its balance change and granted allowances
+pub struct Watch {
+    address: String,
+    /// stCSPR the address received or minted since the watch started
+    received: U256,
+    /// stCSPR the address sent or burned since the watch started
+    sent: U256,
+    /// Allowance the address granted each spender, as last seen
+    allowances: BTreeMap<String, U256>,
+    log: Option<File>,
+}
+
+impl Watch {
+    /// Watch `address`, formatted as in the event stream (`account-hash-...` or `hash-...`)
+    pub fn new(address: &str) -> Self {
+        Watch {
+            address: address.to_string(),
+            received: U256::zero(),
+            sent: U256::zero(),
+            allowances: BTreeMap::new(),
+            log: None,
+        }
+    }
+
+    /// Also append every line to the file at `path`
+    pub fn with_log(mut self, path: &str) -> Result<Self, IndexerError> {
+        self.log = Some(OpenOptions::new().create(true).append(true).open(path)?);
+        Ok(self)
+    }
+
+    /// Get the balance change since the watch started, and whether it is a decrease
+    pub fn balance_change(&self) -> (U256, bool) {
+        if self.received >= self.sent {
+            (self.received - self.sent, false)
+        } else {
+            (self.sent - self.received, true)
+        }
+    }
+
+    /// Get the allowances the address has granted, as last seen
+    pub fn allowances(&self) -> &BTreeMap<String, U256> {
+        &self.allowances
+    }
+
+    /// Describe an event if it involves the address, updating the tracked state
+    pub fn observe(&mut self, event: &IndexedEvent) -> Option<String> {
+        let field = |name: &str| event.data[name].as_str().unwrap_or_default().to_string();
+        let amount = |name: &str| U256::from_dec_str(&field(name)).unwrap_or_default();
+        let address = self.address.as_str();
+
+        let change = match event.name.as_str() {
+            "StakeEvent" if field("user") == address => {
+                self.received += amount("stcspr_minted");
+                format!("staked {} motes for {} stCSPR", field("cspr_amount"), field("stcspr_minted"))
+            }
+            "UnstakeEvent" if field("user") == address => {
+                self.sent += amount("stcspr_burned");
+                format!(
+                    "unstaked {} stCSPR for {} motes to {}",
+                    field("stcspr_burned"),
+                    field("cspr_returned"),
+                    field("recipient")
+                )
+            }
+            "Transfer" | "TransferFrom" => {
+                let sender = if event.name == "Transfer" { field("sender") } else { field("owner") };
+                let via = if event.name == "TransferFrom" {
+                    format!(" (by spender {})", field("spender"))
+                } else {
+                    String::new()
+                };
+                if sender == address && event.name == "TransferFrom" {
+                    let spender = field("spender");
+                    let remaining = self.allowances.get(&spender).map(|allowance| allowance.saturating_sub(amount("amount")));
+                    if let Some(remaining) = remaining {
+                        self.allowances.insert(spender, remaining);
+                    }
+                }
+                if sender == address {
+                    self.sent += amount("amount");
+                    format!("sent {} stCSPR to {}{}", field("amount"), field("recipient"), via)
+                } else if field("recipient") == address {
+                    self.received += amount("amount");
+                    format!("received {} stCSPR from {}{}", field("amount"), sender, via)
+                } else {
+                    return None;
+                }
+            }
+            "SetAllowance" if field("owner") == address => {
+                self.allowances.insert(field("spender"), amount("allowance"));
+                format!("allowance of {} set to {} stCSPR", field("spender"), field("allowance"))
+            }
+            _ => return None,
+        };
+
+        let (delta, decrease) = self.balance_change();
+        Some(format!(
+            "{} {}: {} (balance {}{} since start)",
+            event.timestamp,
+            event.deploy_hash,
+            change,
+            if decrease { "-" } else { "+" },
+            delta
+        ))
+    }
+}
+
+impl Sink for Watch {
+    fn write(&mut self, event: &IndexedEvent) -> Result<(), IndexerError> {
+        if let Some(line) = self.observe(event) {
+            println!("{}", line);
+            if let Some(log) = self.log.as_mut() {
+                writeln!(log, "{}", line)?;
+            }
+        }
+        Ok(())
+    }
+
+    // The running totals cannot be unwound, so the fork is reported instead
+    fn reorg(&mut self, reorg: &Reorg) -> Result<(), IndexerError> {
+        let line = format!(
+            "fork: events {} to {} of block {} were orphaned; the balance change may include them",
+            reorg.from_index, reorg.to_index, reorg.orphaned_block
+        );
+        println!("{}", line);
+        if let Some(log) = self.log.as_mut() {
+            writeln!(log, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn indexed(index: u32, name: &str, data: Value) -> IndexedEvent {
+        IndexedEvent {
+            index,
+            deploy_hash: format!("deploy-{}", index),
+            block_hash: "bb".to_string(),
+            timestamp: format!("t{}", index),
+            name: name.to_string(),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_watch_follows_one_address() {
+        let mut watch = Watch::new("cold");
+        let events = vec![
+            indexed(0, "Transfer", json!({ "sender": "exchange", "recipient": "cold", "amount": "1000" })),
+            indexed(1, "Transfer", json!({ "sender": "alice", "recipient": "bob", "amount": "5" })),
+            indexed(2, "SetAllowance", json!({ "owner": "cold", "spender": "desk", "allowance": "300" })),
+            indexed(3, "TransferFrom", json!({ "spender": "desk", "owner": "cold", "recipient": "bob", "amount": "200" })),
+        ];
+
+        let lines: Vec<String> = events.iter().filter_map(|event| watch.observe(event)).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "t0 deploy-0: received 1000 stCSPR from exchange (balance +1000 since start)");
+        assert_eq!(lines[2], "t3 deploy-3: sent 200 stCSPR to bob (by spender desk) (balance +800 since start)");
+        assert_eq!(watch.balance_change(), (U256::from(800), false));
+        assert_eq!(watch.allowances().get("desk"), Some(&U256::from(100)));
+    }
+}
@@ -0,0 +1,322 @@
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, Var};
+
+use crate::{motes, Error};
+
+/// Default time between a withdrawal request and its claim (7 eras of 2 hours, in milliseconds)
+pub const DEFAULT_UNBONDING_PERIOD: u64 = 50_400_000;
+
+/// A pending or settled request to withdraw CSPR after the unbonding period
+#[odra::odra_type]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalRequest {
+    pub id: u64,
+    pub owner: Address,
+    /// CSPR owed to the owner, fixed at request time
+    pub amount: U256,
+    /// Block time from which the request can be claimed
+    pub claimable_at: u64,
+    pub claimed: bool,
+}
+
+/// Event emitted when stCSPR is burned into a withdrawal request
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalRequested {
+    pub id: u64,
+    pub owner: Address,
+    pub stcspr_burned: U256,
+    pub cspr_amount: U256,
+    pub claimable_at: u64,
+}
+
+/// Event emitted when a withdrawal request is paid out
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalClaimed {
+    pub id: u64,
+    pub owner: Address,
+    pub cspr_amount: U256,
+}
+
+/// Event emitted when a request is cut to share an undelegation shortfall
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalHaircut {
+    pub id: u64,
+    pub owner: Address,
+    pub cut: U256,
+    /// CSPR still owed to the owner
+    pub cspr_amount: U256,
+}
+
+/// Event emitted when a withdrawal request changes owner
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalTransferred {
+    pub id: u64,
+    pub from: Address,
+    pub to: Address,
+}
+
+/// Event emitted when an owner sets or clears the address approved to transfer a request
+#[odra::event]
+#[cfg_attr(feature = "offchain", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawalApproved {
+    pub id: u64,
+    pub owner: Address,
+    pub approved: Option<Address>,
+}
+
+/// WithdrawalQueue - stores withdrawal requests until their unbonding period elapses
+///
+/// The queue only does the bookkeeping; burning shares and paying out CSPR is
+/// the responsibility of the parent contract.
+#[odra::module]
+pub struct WithdrawalQueue {
+    /// Id assigned to the next request
+    next_id: Var<u64>,
+    /// All requests by id
+    requests: Mapping<u64, WithdrawalRequest>,
+    /// Ids of each owner's unclaimed requests
+    owner_requests: Mapping<Address, Vec<u64>>,
+    /// CSPR owed to all unclaimed requests, in motes
+    total_pending: Var<U512>,
+    /// Time between a request and its claim, in milliseconds
+    unbonding_period: Var<u64>,
+    /// Address each request's owner approved to transfer it
+    approvals: Mapping<u64, Option<Address>>,
+    /// Lowest id that may still be unclaimed
+    head: Var<u64>,
+}
+
+#[odra::module]
+impl WithdrawalQueue {
+    /// Create a request for `amount` CSPR claimable after the unbonding period; returns its id
+    pub fn create(&mut self, owner: Address, amount: U256, stcspr_burned: U256) -> Result<u64, Error> {
+        let id = self.next_id.get_or_default();
+        let claimable_at = self.env().block_time().saturating_add(self.unbonding_period());
+        let new_total_pending = motes::add(self.total_pending_motes(), amount)?;
+
+        self.next_id.set(id + 1);
+        self.requests.set(
+            &id,
+            WithdrawalRequest {
+                id,
+                owner,
+                amount,
+                claimable_at,
+                claimed: false,
+            },
+        );
+        let mut ids = self.owner_requests.get(&owner).unwrap_or_default();
+        ids.push(id);
+        self.owner_requests.set(&owner, ids);
+        self.total_pending.set(new_total_pending);
+
+        self.env().emit_event(WithdrawalRequested {
+            id,
+            owner,
+            stcspr_burned,
+            cspr_amount: amount,
+            claimable_at,
+        });
+
+        Ok(id)
+    }
+
+    /// Mark a request as claimed by `caller` and return the CSPR owed
+    pub fn claim(&mut self, id: u64, caller: &Address) -> Result<U256, Error> {
+        // CHECKS: Request must exist, belong to the caller and be unlocked
+        let mut request = self.requests.get(&id).ok_or(Error::WithdrawalNotFound)?;
+        if request.owner != *caller {
+            return Err(Error::NotAuthorized);
+        }
+        if request.claimed {
+            return Err(Error::WithdrawalAlreadyClaimed);
+        }
+        if self.env().block_time() < request.claimable_at {
+            return Err(Error::UnbondingNotFinished);
+        }
+
+        let new_total_pending = motes::sub(self.total_pending_motes(), request.amount)?;
+
+        // EFFECTS: Settle the request
+        request.claimed = true;
+        self.requests.set(&id, request.clone());
+        let mut ids = self.owner_requests.get(caller).unwrap_or_default();
+        ids.retain(|pending_id| *pending_id != id);
+        self.owner_requests.set(caller, ids);
+        self.total_pending.set(new_total_pending);
+        self.advance_head();
+
+        self.env().emit_event(WithdrawalClaimed {
+            id,
+            owner: request.owner,
+            cspr_amount: request.amount,
+        });
+
+        Ok(request.amount)
+    }
+
+    /// Push the claim of an unclaimed request back to at least `until`; returns its claim time
+    pub fn delay(&mut self, id: u64, until: u64) -> Result<u64, Error> {
+        let mut request = self.requests.get(&id).ok_or(Error::WithdrawalNotFound)?;
+        if request.claimed {
+            return Err(Error::WithdrawalAlreadyClaimed);
+        }
+
+        request.claimable_at = request.claimable_at.max(until);
+        let claimable_at = request.claimable_at;
+        self.requests.set(&id, request);
+        Ok(claimable_at)
+    }
+
+    /// Cut `cut` CSPR from what an unclaimed request is owed; returns what it is still owed
+    pub fn haircut(&mut self, id: u64, cut: U256) -> Result<U256, Error> {
+        let mut request = self.requests.get(&id).ok_or(Error::WithdrawalNotFound)?;
+        if request.claimed {
+            return Err(Error::WithdrawalAlreadyClaimed);
+        }
+        let cspr_amount = request.amount.checked_sub(cut).ok_or(Error::ArithmeticUnderflow)?;
+        let new_total_pending = motes::sub(self.total_pending_motes(), cut)?;
+
+        request.amount = cspr_amount;
+        let owner = request.owner;
+        self.requests.set(&id, request);
+        self.total_pending.set(new_total_pending);
+
+        self.env().emit_event(WithdrawalHaircut {
+            id,
+            owner,
+            cut,
+            cspr_amount,
+        });
+        Ok(cspr_amount)
+    }
+
+    /// Move an unclaimed request to `new_owner`; `caller` must own it or be approved for it
+    pub fn transfer(&mut self, id: u64, caller: &Address, new_owner: Address) -> Result<(), Error> {
+        // CHECKS: Request must be unclaimed and the caller allowed to move it
+        let mut request = self.requests.get(&id).ok_or(Error::WithdrawalNotFound)?;
+        if request.claimed {
+            return Err(Error::WithdrawalAlreadyClaimed);
+        }
+        let from = request.owner;
+        if *caller != from && self.approved(id) != Some(*caller) {
+            return Err(Error::NotAuthorized);
+        }
+        if new_owner == from {
+            return Err(Error::SelfTransfer);
+        }
+
+        // EFFECTS: Re-home the request and clear its approval
+        request.owner = new_owner;
+        self.requests.set(&id, request);
+        self.approvals.set(&id, None);
+        let mut from_ids = self.owner_requests.get(&from).unwrap_or_default();
+        from_ids.retain(|pending_id| *pending_id != id);
+        self.owner_requests.set(&from, from_ids);
+        let mut to_ids = self.owner_requests.get(&new_owner).unwrap_or_default();
+        to_ids.push(id);
+        self.owner_requests.set(&new_owner, to_ids);
+
+        self.env().emit_event(WithdrawalTransferred {
+            id,
+            from,
+            to: new_owner,
+        });
+        Ok(())
+    }
+
+    /// Set or clear the address approved to transfer request `id`; `caller` must own it
+    pub fn approve(&mut self, id: u64, caller: &Address, approved: Option<Address>) -> Result<(), Error> {
+        let request = self.requests.get(&id).ok_or(Error::WithdrawalNotFound)?;
+        if request.owner != *caller {
+            return Err(Error::NotAuthorized);
+        }
+        if request.claimed {
+            return Err(Error::WithdrawalAlreadyClaimed);
+        }
+
+        self.approvals.set(&id, approved);
+        self.env().emit_event(WithdrawalApproved {
+            id,
+            owner: request.owner,
+            approved,
+        });
+        Ok(())
+    }
+
+    /// Get the address approved to transfer request `id`
+    pub fn approved(&self, id: u64) -> Option<Address> {
+        self.approvals.get(&id).flatten()
+    }
+
+    /// Get a request by id
+    pub fn get(&self, id: u64) -> Option<WithdrawalRequest> {
+        self.requests.get(&id)
+    }
+
+    /// Get the number of unclaimed requests created before `id` and the CSPR they are owed, in motes
+    pub fn ahead_of(&self, id: u64) -> (u64, U512) {
+        (self.head.get_or_default()..id)
+            .filter_map(|earlier| self.requests.get(&earlier))
+            .filter(|request| !request.claimed)
+            .fold((0, U512::zero()), |(count, owed), request| {
+                (count + 1, owed.saturating_add(motes::to_motes(request.amount)))
+            })
+    }
+
+    /// Get all unclaimed requests of an owner, oldest first
+    pub fn pending_of(&self, owner: &Address) -> Vec<WithdrawalRequest> {
+        self.owner_requests
+            .get(owner)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| self.requests.get(id))
+            .collect()
+    }
+
+    /// Get up to `limit` unclaimed requests of an owner, skipping the `offset` oldest
+    pub fn pending_page_of(&self, owner: &Address, offset: u32, limit: u32) -> Vec<WithdrawalRequest> {
+        self.owner_requests
+            .get(owner)
+            .unwrap_or_default()
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .filter_map(|id| self.requests.get(id))
+            .collect()
+    }
+
+    /// Get the CSPR owed to all unclaimed requests
+    pub fn total_pending(&self) -> U256 {
+        motes::to_amount(self.total_pending_motes()).unwrap_or_else(|error| self.env().revert(error))
+    }
+
+    /// Get the CSPR owed to all unclaimed requests, in motes
+    pub fn total_pending_motes(&self) -> U512 {
+        self.total_pending.get_or_default()
+    }
+
+    /// Get the unbonding period in milliseconds
+    pub fn unbonding_period(&self) -> u64 {
+        self.unbonding_period.get().unwrap_or(DEFAULT_UNBONDING_PERIOD)
+    }
+
+    /// Set the unbonding period in milliseconds
+    pub fn set_unbonding_period(&mut self, period: u64) {
+        self.unbonding_period.set(period);
+    }
+
+    /// Move the head past requests that have been claimed
+    fn advance_head(&mut self) {
+        let next_id = self.next_id.get_or_default();
+        let mut head = self.head.get_or_default();
+        while head < next_id && self.requests.get(&head).map_or(false, |request| request.claimed) {
+            head += 1;
+        }
+        self.head.set(head);
+    }
+}
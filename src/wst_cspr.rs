@@ -0,0 +1,267 @@
+use odra::prelude::*;
+use odra::{module::Module, Address, Mapping, UnwrapOrRevert, Var};
+
+use crate::cep18::{Burn, Mint, SetAllowance, Transfer, TransferFrom};
+use crate::{Error, RATE_PRECISION};
+
+/// The parts of the stCSPR contract the wrapper calls
+#[odra::external_contract]
+pub trait StCspr {
+    fn balance_of(&self, address: &Address) -> U256;
+    fn transfer(&mut self, recipient: &Address, amount: U256);
+    fn transfer_from(&mut self, owner: &Address, recipient: &Address, amount: U256);
+}
+
+/// WstCspr - non-rebasing wrapper around stCSPR
+///
+/// wstCSPR is a share of the stCSPR locked through `wrap`, so its balance never
+/// changes while its stCSPR value follows the locked amount. stCSPR balances are
+/// already pool shares today, which makes the rate 1:1, but integrations that
+/// need a wrapped token can rely on this contract regardless of how stCSPR
+/// accrues value. The locked amount is tracked internally: stCSPR sent to the
+/// wrapper directly is not counted, so a donation cannot move the rate.
+#[odra::module]
+pub struct WstCspr {
+    /// Address of the wrapped stCSPR contract
+    stcspr: Var<Address>,
+    /// wstCSPR balances
+    balances: Mapping<Address, U256>,
+    /// wstCSPR in circulation
+    total_supply: Var<U256>,
+    /// Amounts spenders may move on behalf of owners
+    allowances: Mapping<(Address, Address), U256>,
+    /// stCSPR locked through `wrap` and not yet released by `unwrap`
+    locked: Var<U256>,
+}
+
+#[odra::module]
+impl WstCspr {
+    /// Initialize the wrapper for the stCSPR contract at `stcspr`
+    pub fn init(&mut self, stcspr: Address) {
+        self.stcspr.set(stcspr);
+    }
+
+    /// Get the token name
+    pub fn name(&self) -> String {
+        "Wrapped Staked CSPR".to_string()
+    }
+
+    /// Get the token symbol
+    pub fn symbol(&self) -> String {
+        "wstCSPR".to_string()
+    }
+
+    /// Get the token decimals (same as stCSPR)
+    pub fn decimals(&self) -> u8 {
+        9
+    }
+
+    /// Get the wstCSPR in circulation
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get_or_default()
+    }
+
+    /// Get the wstCSPR balance of an address
+    pub fn balance_of(&self, address: &Address) -> U256 {
+        self.balances.get(address).unwrap_or_default()
+    }
+
+    /// Get the address of the wrapped stCSPR contract
+    pub fn stcspr_address(&self) -> Option<Address> {
+        self.stcspr.get()
+    }
+
+    /// Lock `amount` stCSPR and mint wstCSPR at the current rate
+    ///
+    /// The caller must first approve this contract to spend the stCSPR.
+    pub fn wrap(&mut self, amount: U256) -> Result<U256, Error> {
+        if amount == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+
+        let caller = self.env().caller();
+        let wst_amount = self.preview_wrap(amount);
+        if wst_amount == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+        let new_balance = self
+            .balance_of(&caller)
+            .checked_add(wst_amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        let new_supply = self
+            .total_supply()
+            .checked_add(wst_amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        let new_locked = self
+            .locked_stcspr()
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        // EFFECTS: Mint wstCSPR
+        self.balances.set(&caller, new_balance);
+        self.total_supply.set(new_supply);
+        self.locked.set(new_locked);
+
+        // INTERACTIONS: Pull the stCSPR from the caller
+        let self_address = self.env().self_address();
+        self.stcspr_ref().transfer_from(&caller, &self_address, amount);
+
+        self.env().emit_event(Mint {
+            recipient: caller,
+            amount: wst_amount,
+        });
+        Ok(wst_amount)
+    }
+
+    /// Burn `wst_amount` wstCSPR and release the stCSPR it is worth
+    pub fn unwrap(&mut self, wst_amount: U256) -> Result<U256, Error> {
+        if wst_amount == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+
+        let caller = self.env().caller();
+        let balance = self.balance_of(&caller);
+        if balance < wst_amount {
+            return Err(Error::InsufficientBalance);
+        }
+        let stcspr_amount = self.preview_unwrap(wst_amount);
+
+        // EFFECTS: Burn wstCSPR
+        self.balances.set(&caller, balance - wst_amount);
+        self.total_supply.set(self.total_supply() - wst_amount);
+        self.locked.set(self.locked_stcspr() - stcspr_amount);
+
+        // INTERACTIONS: Release the stCSPR
+        self.stcspr_ref().transfer(&caller, stcspr_amount);
+
+        self.env().emit_event(Burn {
+            owner: caller,
+            amount: wst_amount,
+        });
+        Ok(stcspr_amount)
+    }
+
+    /// Transfer wstCSPR from the caller to another address
+    pub fn transfer(&mut self, recipient: &Address, amount: U256) -> Result<(), Error> {
+        let caller = self.env().caller();
+        self._transfer(&caller, recipient, amount)?;
+
+        self.env().emit_event(Transfer {
+            sender: caller,
+            recipient: *recipient,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Get the wstCSPR `spender` may move on behalf of `owner`
+    pub fn allowance(&self, owner: &Address, spender: &Address) -> U256 {
+        self.allowances.get(&(*owner, *spender)).unwrap_or_default()
+    }
+
+    /// Let `spender` move up to `amount` of the caller's wstCSPR
+    pub fn approve(&mut self, spender: &Address, amount: U256) -> Result<(), Error> {
+        let caller = self.env().caller();
+        if caller == *spender {
+            return Err(Error::SelfTransfer);
+        }
+
+        self.allowances.set(&(caller, *spender), amount);
+        self.env().emit_event(SetAllowance {
+            owner: caller,
+            spender: *spender,
+            allowance: amount,
+        });
+        Ok(())
+    }
+
+    /// Transfer wstCSPR from `owner` to `recipient` using the caller's allowance
+    pub fn transfer_from(&mut self, owner: &Address, recipient: &Address, amount: U256) -> Result<(), Error> {
+        let spender = self.env().caller();
+        let allowance = self.allowance(owner, &spender);
+        if allowance < amount {
+            return Err(Error::InsufficientAllowance);
+        }
+
+        self.allowances.set(&(*owner, spender), allowance - amount);
+        self._transfer(owner, recipient, amount)?;
+
+        self.env().emit_event(TransferFrom {
+            spender,
+            owner: *owner,
+            recipient: *recipient,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Get the stCSPR value of one wstCSPR, scaled by `RATE_PRECISION`
+    pub fn stcspr_per_wst(&self) -> U256 {
+        let supply = self.total_supply();
+        if supply == U256::zero() {
+            return U256::from(RATE_PRECISION);
+        }
+        self.locked_stcspr() * U256::from(RATE_PRECISION) / supply
+    }
+
+    /// Get the wstCSPR minted for one stCSPR, scaled by `RATE_PRECISION`
+    pub fn wst_per_stcspr(&self) -> U256 {
+        let locked = self.locked_stcspr();
+        if locked == U256::zero() {
+            return U256::from(RATE_PRECISION);
+        }
+        self.total_supply() * U256::from(RATE_PRECISION) / locked
+    }
+
+    /// Preview how much wstCSPR wrapping `amount` stCSPR would mint
+    pub fn preview_wrap(&self, amount: U256) -> U256 {
+        let supply = self.total_supply();
+        let locked = self.locked_stcspr();
+        if supply == U256::zero() || locked == U256::zero() {
+            return amount;
+        }
+        amount * supply / locked
+    }
+
+    /// Preview how much stCSPR unwrapping `wst_amount` wstCSPR would release
+    pub fn preview_unwrap(&self, wst_amount: U256) -> U256 {
+        let supply = self.total_supply();
+        if supply == U256::zero() {
+            return U256::zero();
+        }
+        wst_amount * self.locked_stcspr() / supply
+    }
+
+    /// Get the stCSPR locked through `wrap`, which backs the wstCSPR supply
+    pub fn locked_stcspr(&self) -> U256 {
+        self.locked.get_or_default()
+    }
+
+    /// Move wstCSPR between two addresses
+    fn _transfer(&mut self, from: &Address, to: &Address, amount: U256) -> Result<(), Error> {
+        if amount == U256::zero() {
+            return Err(Error::InvalidAmount);
+        }
+        if from == to {
+            return Err(Error::SelfTransfer);
+        }
+
+        let from_balance = self.balance_of(from);
+        if from_balance < amount {
+            return Err(Error::InsufficientBalance);
+        }
+        let new_to_balance = self
+            .balance_of(to)
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        self.balances.set(from, from_balance - amount);
+        self.balances.set(to, new_to_balance);
+        Ok(())
+    }
+
+    fn stcspr_ref(&self) -> StCsprContractRef {
+        let address = self.stcspr.get().unwrap_or_revert(&self.env());
+        StCsprContractRef::new(self.env(), address)
+    }
+}
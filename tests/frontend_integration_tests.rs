@@ -1,4 +1,4 @@
-use casper_liquid::{CasperLiquid, Error};
+use casper_liquid::{CasperLiquid, CasperLiquidInitArgs, Error};
 use odra::prelude::*;
 use odra::host::{Deployer, HostRef};
 
@@ -8,11 +8,29 @@ use odra::host::{Deployer, HostRef};
 mod frontend_integration_tests {
     use super::*;
 
+    // Helper to deploy with the default metadata and configuration
+    fn default_init_args() -> CasperLiquidInitArgs {
+        CasperLiquidInitArgs {
+            name: None,
+            symbol: None,
+            decimals: None,
+            owner: None,
+            fee_bps: None,
+            treasury: None,
+            features: None,
+        }
+    }
+
+    // Helper to stake with the matching amount of CSPR attached, as a wallet would
+    fn stake_with_cspr(contract: &mut CasperLiquid, amount: U256) -> Result<(), Error> {
+        contract.with_tokens(U512::from(amount.as_u128())).stake(amount)
+    }
+
     /// Simulate the complete user journey from the frontend perspective
     #[test]
     fn test_frontend_user_journey() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         test_env.set_caller(user);
@@ -28,7 +46,7 @@ mod frontend_integration_tests {
         // Step 2: User clicks "Stake 10 CSPR" button
         // Frontend converts 10 CSPR to motes (10 * 10^9)
         let stake_amount_motes = U256::from(10_000_000_000u64); // 10 CSPR in motes
-        let stake_result = contract.stake(stake_amount_motes);
+        let stake_result = stake_with_cspr(&mut contract, stake_amount_motes);
         assert!(stake_result.is_ok(), "Frontend stake operation should succeed");
         
         // Step 3: Frontend updates balance display
@@ -36,7 +54,7 @@ mod frontend_integration_tests {
         assert_eq!(contract.total_supply(), stake_amount_motes);
         
         // Step 4: User stakes another 10 CSPR (simulating multiple stakes)
-        let second_stake = contract.stake(stake_amount_motes);
+        let second_stake = stake_with_cspr(&mut contract, stake_amount_motes);
         assert!(second_stake.is_ok(), "Second frontend stake should succeed");
         
         let total_staked = stake_amount_motes * U256::from(2);
@@ -59,13 +77,13 @@ mod frontend_integration_tests {
     #[test]
     fn test_frontend_error_handling() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         test_env.set_caller(user);
         
         // Test 1: User tries to stake 0 CSPR (frontend validation should catch this)
-        let zero_stake = contract.stake(U256::zero());
+        let zero_stake = stake_with_cspr(&mut contract, U256::zero());
         assert!(zero_stake.is_err(), "Zero stake should fail");
         match zero_stake.unwrap_err() {
             Error::InvalidAmount => {},
@@ -82,7 +100,7 @@ mod frontend_integration_tests {
         
         // Test 3: User stakes some tokens first
         let stake_amount = U256::from(5_000_000_000u64); // 5 CSPR
-        contract.stake(stake_amount).unwrap();
+        stake_with_cspr(&mut contract, stake_amount).unwrap();
         
         // Test 4: User tries to unstake more than they have
         let excessive_unstake = contract.unstake(U256::from(10_000_000_000u64)); // 10 CSPR
@@ -101,7 +119,7 @@ mod frontend_integration_tests {
     #[test]
     fn test_frontend_balance_display() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         test_env.set_caller(user);
@@ -119,7 +137,7 @@ mod frontend_integration_tests {
         
         for amount in test_amounts {
             let stake_amount = U256::from(amount);
-            let stake_result = contract.stake(stake_amount);
+            let stake_result = stake_with_cspr(&mut contract, stake_amount);
             assert!(stake_result.is_ok(), "Stake of {} motes should succeed", amount);
             
             total_staked += stake_amount;
@@ -147,7 +165,7 @@ mod frontend_integration_tests {
     #[test]
     fn test_frontend_transaction_flow() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         test_env.set_caller(user);
@@ -167,7 +185,7 @@ mod frontend_integration_tests {
         let initial_supply = contract.total_supply();
         
         // Execute transaction (simulating successful blockchain submission)
-        let transaction_result = contract.stake(stake_amount);
+        let transaction_result = stake_with_cspr(&mut contract, stake_amount);
         assert!(transaction_result.is_ok(), "Transaction should succeed");
         
         // Verify state after transaction (what frontend would check)
@@ -200,19 +218,19 @@ mod frontend_integration_tests {
     #[test]
     fn test_frontend_multi_user_scenarios() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let alice = test_env.get_account(0);
         let bob = test_env.get_account(1);
         
         // Simulate Alice using the frontend
         test_env.set_caller(alice);
         let alice_stake = U256::from(15_000_000_000u64); // 15 CSPR
-        contract.stake(alice_stake).unwrap();
+        stake_with_cspr(&mut contract, alice_stake).unwrap();
         
         // Simulate Bob using the frontend simultaneously
         test_env.set_caller(bob);
         let bob_stake = U256::from(25_000_000_000u64); // 25 CSPR
-        contract.stake(bob_stake).unwrap();
+        stake_with_cspr(&mut contract, bob_stake).unwrap();
         
         // Verify both users see correct balances
         assert_eq!(contract.balance_of(&alice), alice_stake);
@@ -251,7 +269,7 @@ mod frontend_integration_tests {
     #[test]
     fn test_frontend_approval_workflow() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         let dex_contract = test_env.get_account(1); // Simulating a DEX contract
         
@@ -259,7 +277,7 @@ mod frontend_integration_tests {
         
         // User stakes tokens first
         let stake_amount = U256::from(20_000_000_000u64); // 20 CSPR
-        contract.stake(stake_amount).unwrap();
+        stake_with_cspr(&mut contract, stake_amount).unwrap();
         
         // User approves DEX to spend their stCSPR tokens
         let approval_amount = U256::from(10_000_000_000u64); // 10 CSPR worth
@@ -291,14 +309,14 @@ mod frontend_integration_tests {
     #[test]
     fn test_frontend_edge_cases() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         test_env.set_caller(user);
         
         // Test minimum stake amount (1 mote)
         let min_stake = U256::from(1u64);
-        let min_stake_result = contract.stake(min_stake);
+        let min_stake_result = stake_with_cspr(&mut contract, min_stake);
         assert!(min_stake_result.is_ok(), "Minimum stake should succeed");
         assert_eq!(contract.balance_of(&user), min_stake);
         
@@ -309,7 +327,7 @@ mod frontend_integration_tests {
         
         // Test large stake amount (simulating whale user)
         let large_stake = U256::from(1_000_000_000_000_000_000u64); // 1 billion CSPR
-        let large_stake_result = contract.stake(large_stake);
+        let large_stake_result = stake_with_cspr(&mut contract, large_stake);
         assert!(large_stake_result.is_ok(), "Large stake should succeed");
         assert_eq!(contract.balance_of(&user), large_stake);
         
@@ -325,7 +343,7 @@ mod frontend_integration_tests {
         // Test multiple small operations (simulating frequent user interactions)
         for i in 1..=10 {
             let small_stake = U256::from(i * 1_000_000_000u64); // i CSPR
-            let result = contract.stake(small_stake);
+            let result = stake_with_cspr(&mut contract, small_stake);
             assert!(result.is_ok(), "Small stake {} should succeed", i);
         }
         
@@ -338,7 +356,7 @@ mod frontend_integration_tests {
     #[test]
     fn test_frontend_metadata_queries() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         // Test metadata queries that frontend would make
@@ -353,7 +371,7 @@ mod frontend_integration_tests {
         
         // Perform some operations
         test_env.set_caller(user);
-        contract.stake(U256::from(10_000_000_000u64)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(10_000_000_000u64)).unwrap();
         
         // Test queries after operations
         assert_eq!(contract.balance_of(&user), U256::from(10_000_000_000u64));
@@ -373,14 +391,14 @@ mod frontend_integration_tests {
     #[test]
     fn test_frontend_reconnection_scenarios() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         test_env.set_caller(user);
         
         // Simulate user session 1: stake some tokens
         let initial_stake = U256::from(15_000_000_000u64); // 15 CSPR
-        contract.stake(initial_stake).unwrap();
+        stake_with_cspr(&mut contract, initial_stake).unwrap();
         
         // Simulate user disconnecting and reconnecting
         // Frontend would query current balance to restore state
@@ -389,7 +407,7 @@ mod frontend_integration_tests {
         
         // User continues with more operations after reconnection
         let additional_stake = U256::from(5_000_000_000u64); // 5 CSPR
-        contract.stake(additional_stake).unwrap();
+        stake_with_cspr(&mut contract, additional_stake).unwrap();
         
         let total_balance = initial_stake + additional_stake;
         assert_eq!(contract.balance_of(&user), total_balance);
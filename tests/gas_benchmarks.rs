@@ -0,0 +1,119 @@
+//! Gas regression checks for the hot entry points
+//!
+//! Gas is only metered on the Casper execution engine, so the checks are skipped
+//! on the mock VM. Run them with `ODRA_BACKEND=casper cargo test --test gas_benchmarks`
+//! (after `cargo odra build`). Costs are compared against `tests/gas_baseline.txt`;
+//! set `GAS_BASELINE_UPDATE=1` to rewrite the baseline after an intended change.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+
+use casper_liquid::{CasperLiquid, CasperLiquidInitArgs};
+use odra::host::{Deployer, HostEnv, HostRef};
+use odra::prelude::*;
+
+/// Largest allowed increase over the baseline, in percent
+const MAX_REGRESSION_PERCENT: u64 = 5;
+
+/// Baseline costs, one `<entry point> <gas>` pair per line
+const BASELINE_PATH: &str = "tests/gas_baseline.txt";
+
+/// Number of recipients in the measured transfer_batch call
+const BATCH_SIZE: usize = 5;
+
+fn metered() -> bool {
+    env::var("ODRA_BACKEND").map(|backend| backend == "casper").unwrap_or(false)
+}
+
+fn read_baseline() -> BTreeMap<String, u64> {
+    fs::read_to_string(BASELINE_PATH)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let gas = parts.next()?.parse().ok()?;
+            Some((name.to_string(), gas))
+        })
+        .collect()
+}
+
+fn write_baseline(costs: &BTreeMap<String, u64>) {
+    let contents: String = costs
+        .iter()
+        .map(|(name, gas)| format!("{} {}\n", name, gas))
+        .collect();
+    fs::write(BASELINE_PATH, contents).expect("Failed to write the gas baseline");
+}
+
+/// Run every measured entry point once and collect its gas cost
+fn measure(test_env: &HostEnv) -> BTreeMap<String, u64> {
+    let mut costs = BTreeMap::new();
+    let mut contract = CasperLiquid::deploy(
+        test_env,
+        CasperLiquidInitArgs {
+            name: None,
+            symbol: None,
+            decimals: None,
+            owner: None,
+            fee_bps: None,
+            treasury: None,
+            features: None,
+        },
+    );
+    let owner = test_env.get_account(0);
+    let spender = test_env.get_account(1);
+    let recipient = test_env.get_account(2);
+    
+    contract.with_tokens(U512::from(1_000_000u64)).stake(U256::from(1_000_000u64)).unwrap();
+    costs.insert("stake".to_string(), test_env.last_call_gas_cost());
+    
+    contract.unstake(U256::from(1_000u64)).unwrap();
+    costs.insert("unstake".to_string(), test_env.last_call_gas_cost());
+    
+    contract.transfer(&recipient, U256::from(1_000u64)).unwrap();
+    costs.insert("transfer".to_string(), test_env.last_call_gas_cost());
+    
+    contract.approve(&spender, U256::from(1_000u64)).unwrap();
+    test_env.set_caller(spender);
+    contract.transfer_from(&owner, &recipient, U256::from(1_000u64)).unwrap();
+    costs.insert("transfer_from".to_string(), test_env.last_call_gas_cost());
+    
+    test_env.set_caller(owner);
+    let recipients: Vec<Address> = (2..2 + BATCH_SIZE).map(|index| test_env.get_account(index)).collect();
+    let amounts = vec![U256::from(100u64); BATCH_SIZE];
+    contract.transfer_batch(recipients, amounts).unwrap();
+    costs.insert("transfer_batch".to_string(), test_env.last_call_gas_cost());
+    
+    costs
+}
+
+#[test]
+fn test_gas_costs_within_baseline() {
+    if !metered() {
+        return;
+    }
+    
+    let test_env = odra_test::env();
+    let costs = measure(&test_env);
+    for (name, gas) in &costs {
+        println!("{:<16} {:>14} motes", name, gas);
+    }
+    
+    if env::var("GAS_BASELINE_UPDATE").is_ok() {
+        write_baseline(&costs);
+        return;
+    }
+    
+    let baseline = read_baseline();
+    let regressions: Vec<String> = costs
+        .iter()
+        .filter_map(|(name, gas)| {
+            let base = *baseline.get(name)?;
+            let limit = base + base * MAX_REGRESSION_PERCENT / 100;
+            (*gas > limit).then(|| format!("{}: {} > {} (baseline {})", name, gas, limit, base))
+        })
+        .collect();
+    assert!(regressions.is_empty(), "Gas regressions:\n{}", regressions.join("\n"));
+}
@@ -1,4 +1,5 @@
-use casper_liquid::{CasperLiquid, Error};
+use casper_liquid::wst_cspr::{WstCspr, WstCsprInitArgs};
+use casper_liquid::{CasperLiquid, CasperLiquidInitArgs, Error, RATE_PRECISION};
 use odra::prelude::*;
 use odra::host::{Deployer, HostRef};
 
@@ -8,11 +9,29 @@ use odra::host::{Deployer, HostRef};
 mod integration_tests {
     use super::*;
 
+    // Helper to deploy with the default metadata and configuration
+    fn default_init_args() -> CasperLiquidInitArgs {
+        CasperLiquidInitArgs {
+            name: None,
+            symbol: None,
+            decimals: None,
+            owner: None,
+            fee_bps: None,
+            treasury: None,
+            features: None,
+        }
+    }
+
+    // Helper to stake with the matching amount of CSPR attached, as a wallet would
+    fn stake_with_cspr(contract: &mut CasperLiquid, amount: U256) -> Result<(), Error> {
+        contract.with_tokens(U512::from(amount.as_u128())).stake(amount)
+    }
+
     /// Test end-to-end stake/unstake flow for a single user
     #[test]
     fn test_end_to_end_single_user_flow() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user = test_env.get_account(0);
         
         // Set caller to user
@@ -25,7 +44,7 @@ mod integration_tests {
         
         // Step 1: User stakes 100 CSPR
         let stake_amount = U256::from(100);
-        let stake_result = contract.stake(stake_amount);
+        let stake_result = stake_with_cspr(&mut contract, stake_amount);
         assert!(stake_result.is_ok(), "Stake operation should succeed");
         
         // Verify state after staking
@@ -36,7 +55,7 @@ mod integration_tests {
         
         // Step 2: User stakes additional 50 CSPR
         let additional_stake = U256::from(50);
-        let stake_result2 = contract.stake(additional_stake);
+        let stake_result2 = stake_with_cspr(&mut contract, additional_stake);
         assert!(stake_result2.is_ok(), "Second stake operation should succeed");
         
         let total_staked = stake_amount + additional_stake;
@@ -69,24 +88,24 @@ mod integration_tests {
     #[test]
     fn test_multi_user_concurrent_operations() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user1 = test_env.get_account(0);
         let user2 = test_env.get_account(1);
         let user3 = test_env.get_account(2);
         
         // User 1 stakes 100 CSPR
         test_env.set_caller(user1);
-        let stake1_result = contract.stake(U256::from(100));
+        let stake1_result = stake_with_cspr(&mut contract, U256::from(100));
         assert!(stake1_result.is_ok());
         
         // User 2 stakes 200 CSPR
         test_env.set_caller(user2);
-        let stake2_result = contract.stake(U256::from(200));
+        let stake2_result = stake_with_cspr(&mut contract, U256::from(200));
         assert!(stake2_result.is_ok());
         
         // User 3 stakes 150 CSPR
         test_env.set_caller(user3);
-        let stake3_result = contract.stake(U256::from(150));
+        let stake3_result = stake_with_cspr(&mut contract, U256::from(150));
         assert!(stake3_result.is_ok());
         
         // Verify individual balances
@@ -135,14 +154,14 @@ mod integration_tests {
     #[test]
     fn test_multi_user_approval_flow() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let owner = test_env.get_account(0);
         let spender = test_env.get_account(1);
         let recipient = test_env.get_account(2);
         
         // Owner stakes 200 CSPR
         test_env.set_caller(owner);
-        contract.stake(U256::from(200)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(200)).unwrap();
         
         // Owner approves spender for 100 stCSPR
         let approval_result = contract.approve(&spender, U256::from(100));
@@ -184,13 +203,13 @@ mod integration_tests {
     #[test]
     fn test_multi_user_error_scenarios() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user1 = test_env.get_account(0);
         let user2 = test_env.get_account(1);
         
         // User 1 stakes 100 CSPR
         test_env.set_caller(user1);
-        contract.stake(U256::from(100)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
         
         // User 2 tries to unstake without having any balance
         test_env.set_caller(user2);
@@ -237,7 +256,7 @@ mod integration_tests {
     #[test]
     fn test_complex_multi_user_workflow() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let alice = test_env.get_account(0);
         let bob = test_env.get_account(1);
         let charlie = test_env.get_account(2);
@@ -245,13 +264,13 @@ mod integration_tests {
         
         // Phase 1: Initial staking
         test_env.set_caller(alice);
-        contract.stake(U256::from(500)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(500)).unwrap();
         
         test_env.set_caller(bob);
-        contract.stake(U256::from(300)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(300)).unwrap();
         
         test_env.set_caller(charlie);
-        contract.stake(U256::from(200)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(200)).unwrap();
         
         // Verify initial state
         assert_eq!(contract.total_supply(), U256::from(1000));
@@ -280,7 +299,7 @@ mod integration_tests {
         contract.unstake(U256::from(150)).unwrap(); // Charlie unstakes some
         
         test_env.set_caller(dave);
-        contract.stake(U256::from(50)).unwrap(); // Dave stakes more
+        stake_with_cspr(&mut contract, U256::from(50)).unwrap(); // Dave stakes more
         
         // Verify final state
         assert_eq!(contract.balance_of(&alice), U256::from(300));
@@ -305,7 +324,7 @@ mod integration_tests {
     #[test]
     fn test_contract_metadata_consistency() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user1 = test_env.get_account(0);
         let user2 = test_env.get_account(1);
         
@@ -316,10 +335,10 @@ mod integration_tests {
         
         // Perform various operations
         test_env.set_caller(user1);
-        contract.stake(U256::from(100)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
         
         test_env.set_caller(user2);
-        contract.stake(U256::from(200)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(200)).unwrap();
         
         test_env.set_caller(user1);
         contract.transfer(&user2, U256::from(50)).unwrap();
@@ -344,7 +363,7 @@ mod integration_tests {
     #[test]
     fn test_large_scale_multi_user_operations() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         
         let num_users = 10;
         let stake_amount = U256::from(100);
@@ -353,7 +372,7 @@ mod integration_tests {
         for i in 0..num_users {
             let user = test_env.get_account(i);
             test_env.set_caller(user);
-            let result = contract.stake(stake_amount);
+            let result = stake_with_cspr(&mut contract, stake_amount);
             assert!(result.is_ok(), "User {} stake should succeed", i);
             assert_eq!(contract.balance_of(&user), stake_amount);
         }
@@ -417,7 +436,7 @@ mod integration_tests {
     #[test]
     fn test_multi_user_edge_cases() {
         let test_env = odra_test::env();
-        let mut contract = CasperLiquid::deploy(&test_env, NoArgs);
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
         let user1 = test_env.get_account(0);
         let user2 = test_env.get_account(1);
         
@@ -425,11 +444,11 @@ mod integration_tests {
         test_env.set_caller(user1);
         
         // Zero stake should fail
-        let zero_stake = contract.stake(U256::zero());
+        let zero_stake = stake_with_cspr(&mut contract, U256::zero());
         assert!(zero_stake.is_err());
         
         // Stake some amount first
-        contract.stake(U256::from(100)).unwrap();
+        stake_with_cspr(&mut contract, U256::from(100)).unwrap();
         
         // Zero unstake should fail
         let zero_unstake = contract.unstake(U256::zero());
@@ -460,4 +479,81 @@ mod integration_tests {
         assert_eq!(contract.contract_cspr_balance(), U256::zero());
         assert!(contract.validate_supply_consistency());
     }
+    /// Test wrapping stCSPR into wstCSPR and back through the companion contract
+    #[test]
+    fn test_wrap_and_unwrap_wst_cspr() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let mut wrapper = WstCspr::deploy(
+            &test_env,
+            WstCsprInitArgs {
+                stcspr: *contract.address(),
+            },
+        );
+        let user = test_env.get_account(0);
+        
+        stake_with_cspr(&mut contract, U256::from(1000)).unwrap();
+        
+        // Wrapping pulls stCSPR through an allowance
+        contract.approve(wrapper.address(), U256::from(400)).unwrap();
+        let minted = wrapper.wrap(U256::from(400)).unwrap();
+        assert_eq!(minted, U256::from(400));
+        assert_eq!(wrapper.balance_of(&user), U256::from(400));
+        assert_eq!(contract.balance_of(&user), U256::from(600));
+        assert_eq!(contract.balance_of(wrapper.address()), U256::from(400));
+        assert_eq!(wrapper.stcspr_per_wst(), U256::from(RATE_PRECISION));
+        assert_eq!(wrapper.wst_per_stcspr(), U256::from(RATE_PRECISION));
+        
+        // Unwrapping releases the locked stCSPR
+        let released = wrapper.unwrap(U256::from(150)).unwrap();
+        assert_eq!(released, U256::from(150));
+        assert_eq!(wrapper.total_supply(), U256::from(250));
+        assert_eq!(contract.balance_of(&user), U256::from(750));
+        
+        match wrapper.unwrap(U256::from(300)).unwrap_err() {
+            Error::InsufficientBalance => {},
+            _ => panic!("Expected InsufficientBalance error"),
+        }
+        
+        // stCSPR donated to the wrapper does not move its rate
+        contract.transfer(wrapper.address(), U256::from(500)).unwrap();
+        assert_eq!(wrapper.locked_stcspr(), U256::from(250));
+        assert_eq!(wrapper.stcspr_per_wst(), U256::from(RATE_PRECISION));
+        contract.approve(wrapper.address(), U256::from(100)).unwrap();
+        assert_eq!(wrapper.wrap(U256::from(100)).unwrap(), U256::from(100));
+    }
+    
+    /// Test spending wstCSPR through an allowance
+    #[test]
+    fn test_wst_cspr_allowances() {
+        let test_env = odra_test::env();
+        let mut contract = CasperLiquid::deploy(&test_env, default_init_args());
+        let mut wrapper = WstCspr::deploy(
+            &test_env,
+            WstCsprInitArgs {
+                stcspr: *contract.address(),
+            },
+        );
+        let owner = test_env.get_account(0);
+        let spender = test_env.get_account(1);
+        let recipient = test_env.get_account(2);
+        
+        stake_with_cspr(&mut contract, U256::from(1000)).unwrap();
+        contract.approve(wrapper.address(), U256::from(400)).unwrap();
+        wrapper.wrap(U256::from(400)).unwrap();
+        
+        wrapper.approve(&spender, U256::from(150)).unwrap();
+        assert_eq!(wrapper.allowance(&owner, &spender), U256::from(150));
+        
+        test_env.set_caller(spender);
+        wrapper.transfer_from(&owner, &recipient, U256::from(100)).unwrap();
+        assert_eq!(wrapper.balance_of(&owner), U256::from(300));
+        assert_eq!(wrapper.balance_of(&recipient), U256::from(100));
+        assert_eq!(wrapper.allowance(&owner, &spender), U256::from(50));
+        
+        match wrapper.transfer_from(&owner, &recipient, U256::from(51)).unwrap_err() {
+            Error::InsufficientAllowance => {},
+            _ => panic!("Expected InsufficientAllowance error"),
+        }
+    }
 }
\ No newline at end of file
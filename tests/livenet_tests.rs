@@ -0,0 +1,99 @@
+//! Tests against a real Casper network (NCTL or testnet)
+//!
+//! These deploy the compiled wasm, so they cover what the mock VM cannot:
+//! payable entry points, purse permissions and gas paid by the caller. Build
+//! the wasm with `cargo odra build` and configure the `ODRA_CASPER_LIVENET_*`
+//! environment variables before running
+//! `cargo test --features livenet --test livenet_tests -- --test-threads=1`.
+#![cfg(feature = "livenet")]
+
+use casper_liquid::{CasperLiquid, CasperLiquidHostRef, CasperLiquidInitArgs};
+use odra::host::{Deployer, HostEnv, HostRef};
+use odra::prelude::*;
+
+/// Gas limit for installing the contract, in motes
+const DEPLOY_GAS: u64 = 450_000_000_000;
+
+/// Gas limit for a single entry point call, in motes
+const CALL_GAS: u64 = 10_000_000_000;
+
+/// Amount staked by each test (10 CSPR)
+const STAKE_AMOUNT: u64 = 10_000_000_000;
+
+// Helper to deploy with the default metadata and configuration
+fn deploy(env: &HostEnv) -> CasperLiquidHostRef {
+    env.set_gas(DEPLOY_GAS);
+    let contract = CasperLiquid::deploy(
+        env,
+        CasperLiquidInitArgs {
+            name: None,
+            symbol: None,
+            decimals: None,
+            owner: None,
+            fee_bps: None,
+            treasury: None,
+            features: None,
+        },
+    );
+    env.set_gas(CALL_GAS);
+    contract
+}
+
+/// Staking moves real CSPR into the contract purse and charges gas on top
+#[test]
+fn test_stake_transfers_cspr_and_pays_gas() {
+    let env = odra_casper_livenet_env::env();
+    let mut contract = deploy(&env);
+    let user = env.caller();
+    
+    let balance_before = env.balance_of(&user);
+    contract
+        .with_tokens(U512::from(STAKE_AMOUNT))
+        .stake(U256::from(STAKE_AMOUNT))
+        .unwrap();
+    let balance_after = env.balance_of(&user);
+    
+    assert_eq!(contract.balance_of(&user), U256::from(STAKE_AMOUNT));
+    assert_eq!(env.balance_of(contract.address()), U512::from(STAKE_AMOUNT));
+    
+    // The caller paid the stake plus a non-zero gas fee
+    let spent = balance_before - balance_after;
+    assert!(spent > U512::from(STAKE_AMOUNT));
+    assert!(spent <= U512::from(STAKE_AMOUNT + CALL_GAS));
+}
+
+/// Unstaking pays CSPR out of the contract purse, which needs the purse URef
+/// to be accessible to the installed contract
+#[test]
+fn test_unstake_pays_out_of_contract_purse() {
+    let env = odra_casper_livenet_env::env();
+    let mut contract = deploy(&env);
+    let user = env.caller();
+    
+    contract
+        .with_tokens(U512::from(STAKE_AMOUNT))
+        .stake(U256::from(STAKE_AMOUNT))
+        .unwrap();
+    
+    let half = STAKE_AMOUNT / 2;
+    let balance_before = env.balance_of(&user);
+    contract.unstake(U256::from(half)).unwrap();
+    let balance_after = env.balance_of(&user);
+    
+    assert_eq!(contract.balance_of(&user), U256::from(half));
+    assert_eq!(env.balance_of(contract.address()), U512::from(half));
+    
+    // The payout arrives, less the gas paid for the call
+    assert!(balance_after + U512::from(CALL_GAS) >= balance_before + U512::from(half));
+    assert!(balance_after < balance_before + U512::from(half));
+}
+
+/// A stake without the matching CSPR attached is rejected by the deployed wasm
+#[test]
+fn test_stake_without_attached_value_reverts() {
+    let env = odra_casper_livenet_env::env();
+    let mut contract = deploy(&env);
+    
+    assert!(contract.stake(U256::from(STAKE_AMOUNT)).is_err());
+    assert_eq!(contract.total_supply(), U256::zero());
+}